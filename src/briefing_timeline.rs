@@ -0,0 +1,473 @@
+use crate::{
+    string_table::{StringId, StringTable},
+    MissionBriefing, MissionBriefingAction, NumberModifier, UnitType,
+};
+use serde::Serialize;
+use std::fmt::Write;
+use tracing::instrument;
+
+// Times are milliseconds since the briefing started.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct BriefingEvent {
+    pub time: u64,
+    pub trigger: usize,
+    // Index of the action in the trigger.
+    pub index: usize,
+    pub action: MissionBriefingAction,
+}
+
+// A unit shown in one of the 4 portrait slots, 0 based.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct PortraitSpan {
+    pub slot: i64,
+    pub unit_type: UnitType,
+    pub start: u64,
+    // The end of the briefing if the portrait is never hidden.
+    pub end: u64,
+}
+
+// A portrait slot that is animated as if talking, either on its own or by a transmission.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct SpeakingSpan {
+    pub slot: i64,
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct BriefingSound {
+    pub wave: StringId,
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionKind {
+    // Stays until the next text message or transmission replaces it.
+    TextMessage,
+    Transmission,
+    // Shown in the objectives box until they are set again.
+    Objectives,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct BriefingCaption {
+    pub kind: CaptionKind,
+    pub text: StringId,
+    // Only set for transmissions.
+    pub slot: Option<i64>,
+    pub start: u64,
+    pub end: u64,
+}
+
+// What one player sees during the mission briefing. Briefing triggers of a player run one after another and
+// Wait and Display Transmission block until they are done, the other actions take no time.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct BriefingTimeline {
+    // 0 based.
+    pub player: usize,
+    pub duration: u64,
+    pub events: Vec<BriefingEvent>,
+    pub portraits: Vec<PortraitSpan>,
+    pub speaking: Vec<SpeakingSpan>,
+    pub sounds: Vec<BriefingSound>,
+    pub captions: Vec<BriefingCaption>,
+}
+
+// Player 1 to 8 run the briefing if it is for them, for all players or for their force.
+fn runs_for(briefing: &MissionBriefing, player_forces: &[u8; 8], player: usize) -> bool {
+    let activated = &briefing.activated_for_players;
+    let force = player_forces[player] as usize;
+
+    activated[player] != 0 || activated[17] != 0 || (force < 4 && activated[18 + force] != 0)
+}
+
+fn milliseconds(time: i64) -> u64 {
+    time.max(0) as u64
+}
+
+// The modifier applies the time to the length of the wav.
+fn transmission_duration(time: i64, modifier: &NumberModifier, wave_time: i64) -> u64 {
+    let (time, wave_time) = (milliseconds(time), milliseconds(wave_time));
+
+    match modifier {
+        NumberModifier::SetTo => time,
+        NumberModifier::Add => wave_time.saturating_add(time),
+        NumberModifier::Subtract => wave_time.saturating_sub(time),
+        NumberModifier::Unknown(_) => wave_time,
+    }
+}
+
+impl BriefingTimeline {
+    // player_forces is the 0 based force of each player, as in FORC.
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(
+        briefings: &[MissionBriefing],
+        player_forces: &[u8; 8],
+        player: usize,
+    ) -> BriefingTimeline {
+        let mut timeline = BriefingTimeline {
+            player,
+            ..Default::default()
+        };
+        let mut time = 0u64;
+        // Index into portraits of what each slot shows and into captions of the text message on screen.
+        let mut shown: [Option<usize>; 4] = [None; 4];
+        let mut message = None;
+        let mut objectives = None;
+
+        for (trigger, briefing) in briefings.iter().enumerate() {
+            if !runs_for(briefing, player_forces, player) {
+                continue;
+            }
+
+            for entry in briefing.actions.iter().filter(|x| !x.flags.disabled) {
+                timeline.events.push(BriefingEvent {
+                    time,
+                    trigger,
+                    index: entry.index,
+                    action: entry.action.clone(),
+                });
+
+                match &entry.action {
+                    MissionBriefingAction::Wait { time: wait } => {
+                        time = time.saturating_add(milliseconds(*wait));
+                    }
+                    MissionBriefingAction::PlayWav { wave, wave_time } => {
+                        timeline.sounds.push(BriefingSound {
+                            wave: *wave,
+                            start: time,
+                            end: time.saturating_add(milliseconds(*wave_time)),
+                        });
+                    }
+                    MissionBriefingAction::DisplayTextMessage { text } => {
+                        timeline.close_caption(&mut message, time);
+                        message = Some(timeline.captions.len());
+                        timeline.captions.push(BriefingCaption {
+                            kind: CaptionKind::TextMessage,
+                            text: *text,
+                            slot: None,
+                            start: time,
+                            end: time,
+                        });
+                    }
+                    MissionBriefingAction::SetMissionObjectives { text } => {
+                        timeline.close_caption(&mut objectives, time);
+                        objectives = Some(timeline.captions.len());
+                        timeline.captions.push(BriefingCaption {
+                            kind: CaptionKind::Objectives,
+                            text: *text,
+                            slot: None,
+                            start: time,
+                            end: time,
+                        });
+                    }
+                    MissionBriefingAction::ShowPortrait { unit_type, slot } => {
+                        if let Some(shown) = shown.get_mut(*slot as usize) {
+                            if let Some(previous) = shown.take() {
+                                timeline.portraits[previous].end = time;
+                            }
+                            *shown = Some(timeline.portraits.len());
+                            timeline.portraits.push(PortraitSpan {
+                                slot: *slot,
+                                unit_type: *unit_type,
+                                start: time,
+                                end: time,
+                            });
+                        }
+                    }
+                    MissionBriefingAction::HidePortrait { slot } => {
+                        if let Some(previous) = shown.get_mut(*slot as usize).and_then(Option::take)
+                        {
+                            timeline.portraits[previous].end = time;
+                        }
+                    }
+                    MissionBriefingAction::DisplaySpeakingPortrait { slot, time: speak } => {
+                        timeline.speaking.push(SpeakingSpan {
+                            slot: *slot,
+                            start: time,
+                            end: time.saturating_add(milliseconds(*speak)),
+                        });
+                    }
+                    MissionBriefingAction::DisplayTransmission {
+                        text,
+                        slot,
+                        time: transmission_time,
+                        modifier,
+                        wave,
+                        wave_time,
+                    } => {
+                        let end = time.saturating_add(transmission_duration(
+                            *transmission_time,
+                            modifier,
+                            *wave_time,
+                        ));
+
+                        timeline.close_caption(&mut message, time);
+                        timeline.captions.push(BriefingCaption {
+                            kind: CaptionKind::Transmission,
+                            text: *text,
+                            slot: Some(*slot),
+                            start: time,
+                            end,
+                        });
+                        timeline.speaking.push(SpeakingSpan {
+                            slot: *slot,
+                            start: time,
+                            end,
+                        });
+                        if !wave.is_none() {
+                            timeline.sounds.push(BriefingSound {
+                                wave: *wave,
+                                start: time,
+                                end: time.saturating_add(milliseconds(*wave_time)),
+                            });
+                        }
+
+                        time = end;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        timeline.duration = timeline
+            .sounds
+            .iter()
+            .map(|x| x.end)
+            .chain(timeline.speaking.iter().map(|x| x.end))
+            .fold(time, u64::max);
+
+        // Whatever is still on screen stays until the end.
+        let duration = timeline.duration;
+        timeline.close_caption(&mut message, duration);
+        timeline.close_caption(&mut objectives, duration);
+        for previous in shown.into_iter().flatten() {
+            timeline.portraits[previous].end = duration;
+        }
+
+        timeline
+    }
+
+    // The timelines of players 1 to 8 that have briefing actions.
+    #[instrument(level = "trace", skip_all)]
+    pub fn for_players(
+        briefings: &[MissionBriefing],
+        player_forces: &[u8; 8],
+    ) -> Vec<BriefingTimeline> {
+        (0..8)
+            .map(|player| BriefingTimeline::new(briefings, player_forces, player))
+            .filter(|x| !x.events.is_empty())
+            .collect()
+    }
+
+    fn close_caption(&mut self, caption: &mut Option<usize>, time: u64) {
+        if let Some(previous) = caption.take() {
+            self.captions[previous].end = time;
+        }
+    }
+
+    // Text messages and transmissions with their text and without colors, skipping empty ones.
+    fn cues(&self, strings: &StringTable) -> Vec<(u64, u64, String)> {
+        self.captions
+            .iter()
+            .filter(|x| x.kind != CaptionKind::Objectives && x.end > x.start)
+            .filter_map(|x| {
+                let text = strings.formatted(x.text).ok()?.to_plain_text();
+                // Blank lines would end the cue.
+                let text = text
+                    .lines()
+                    .map(str::trim_end)
+                    .filter(|x| !x.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                (!text.is_empty()).then_some((x.start, x.end, text))
+            })
+            .collect()
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub fn to_srt(&self, strings: &StringTable) -> String {
+        let mut out = String::new();
+
+        for (i, (start, end, text)) in self.cues(strings).into_iter().enumerate() {
+            writeln!(
+                out,
+                "{}\n{} --> {}\n{text}\n",
+                i + 1,
+                timestamp(start, ','),
+                timestamp(end, ',')
+            )
+            .unwrap();
+        }
+
+        out
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub fn to_webvtt(&self, strings: &StringTable) -> String {
+        let mut out = String::from("WEBVTT\n\n");
+
+        for (start, end, text) in self.cues(strings) {
+            writeln!(
+                out,
+                "{} --> {}\n{text}\n",
+                timestamp(start, '.'),
+                timestamp(end, '.')
+            )
+            .unwrap();
+        }
+
+        out
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+// hh:mm:ss followed by the separator and milliseconds, SRT uses a comma and WebVTT a period.
+fn timestamp(time: u64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        time / 3_600_000,
+        time / 60_000 % 60,
+        time / 1000 % 60,
+        time % 1000
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BriefingTimeline, CaptionKind, PortraitSpan};
+    use crate::{
+        chunk_name::ChunkName, ChkWriter, MissionBriefing, MissionBriefingAction,
+        MissionBriefingActionEntry, NumberModifier, ParsedChk, StringId, StringSectionBuilder,
+        StringSectionFormat, StringTable, UnitType,
+    };
+
+    fn make_strings() -> StringTable {
+        let mut builder = StringSectionBuilder::new();
+        builder.push(b"\x03Jim Raynor:\x01 Hello.\r\n\r\nStay sharp.".to_vec());
+        builder.push(b"Welcome".to_vec());
+        builder.push(b"Survive".to_vec());
+
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(
+            ChunkName::STR,
+            builder.to_bytes(StringSectionFormat::Str).unwrap(),
+        );
+        let chk = writer.to_bytes();
+
+        StringTable::new(&ParsedChk::from_bytes(&chk)).unwrap()
+    }
+
+    fn make_briefing(players: &[usize], actions: Vec<MissionBriefingAction>) -> MissionBriefing {
+        let mut activated_for_players = [0; 27];
+        for &player in players {
+            activated_for_players[player] = 1;
+        }
+
+        MissionBriefing {
+            conditions: Vec::new(),
+            actions: actions
+                .into_iter()
+                .enumerate()
+                .map(|(index, action)| MissionBriefingActionEntry::new(index, action))
+                .collect(),
+            execution_flags: 0,
+            activated_for_players,
+            index_of_current_action: 0,
+        }
+    }
+
+    #[test]
+    fn builds_briefing_timeline() {
+        // Players 1 and 2 are in force 1, player 3 in force 2.
+        let player_forces = [0, 0, 1, 2, 2, 2, 2, 2];
+        let briefings = [
+            make_briefing(
+                &[18],
+                vec![
+                    MissionBriefingAction::ShowPortrait {
+                        unit_type: UnitType::JimRaynorMarine,
+                        slot: 0,
+                    },
+                    MissionBriefingAction::SetMissionObjectives { text: StringId(3) },
+                    MissionBriefingAction::DisplayTextMessage { text: StringId(2) },
+                    MissionBriefingAction::Wait { time: 1500 },
+                    MissionBriefingAction::DisplayTransmission {
+                        text: StringId(1),
+                        slot: 0,
+                        time: 500,
+                        modifier: NumberModifier::Add,
+                        wave: StringId(0),
+                        wave_time: 2000,
+                    },
+                    MissionBriefingAction::HidePortrait { slot: 0 },
+                ],
+            ),
+            make_briefing(
+                &[0],
+                vec![MissionBriefingAction::PlayWav {
+                    wave: StringId(2),
+                    wave_time: 3000,
+                }],
+            ),
+            make_briefing(&[19], vec![MissionBriefingAction::Wait { time: 1000 }]),
+        ];
+
+        let timelines = BriefingTimeline::for_players(&briefings, &player_forces);
+        assert_eq!(timelines.len(), 3);
+        assert_eq!(timelines[1].player, 1);
+        assert_eq!(timelines[1].duration, 4000);
+        assert_eq!(timelines[2].player, 2);
+        assert_eq!(timelines[2].events.len(), 1);
+        assert_eq!(timelines[2].duration, 1000);
+
+        let timeline = &timelines[0];
+        assert_eq!(timeline.duration, 7000);
+        assert_eq!(timeline.events.len(), 7);
+        assert_eq!(timeline.events[6].time, 4000);
+        assert_eq!(
+            timeline.portraits,
+            [PortraitSpan {
+                slot: 0,
+                unit_type: UnitType::JimRaynorMarine,
+                start: 0,
+                end: 4000,
+            }]
+        );
+        assert_eq!(
+            timeline
+                .captions
+                .iter()
+                .map(|x| (x.kind, x.start, x.end))
+                .collect::<Vec<_>>(),
+            [
+                (CaptionKind::Objectives, 0, 7000),
+                (CaptionKind::TextMessage, 0, 1500),
+                (CaptionKind::Transmission, 1500, 4000),
+            ]
+        );
+        assert_eq!(timeline.speaking[0].end, 4000);
+
+        let strings = make_strings();
+        assert_eq!(
+            timeline.to_srt(&strings),
+            "1\n00:00:00,000 --> 00:00:01,500\nWelcome\n\n\
+             2\n00:00:01,500 --> 00:00:04,000\nJim Raynor: Hello.\nStay sharp.\n\n"
+        );
+        assert_eq!(
+            timeline.to_webvtt(&strings),
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:01.500\nWelcome\n\n\
+             00:00:01.500 --> 00:00:04.000\nJim Raynor: Hello.\nStay sharp.\n\n"
+        );
+        assert!(timeline
+            .to_json()
+            .unwrap()
+            .starts_with(r#"{"player":0,"duration":7000,"events":[{"time":0,"trigger":0"#));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
 
 // Required for Brood War only and all game types.
@@ -36,17 +36,25 @@ use serde::Serialize;
 // 21 - Grey
 // 22 - Black
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkColr {
     pub player_color: [u8; 8],
 }
 
-pub(crate) fn parse_colr(chunks: &[RiffChunk<'_>]) -> Result<ChkColr, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_colr(chunks: &[RiffChunk<'_>]) -> Result<ChkColr, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::COLR,
+        });
+    }
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkColr {
         player_color: slicer.extract()?,
     })
 }
+
+pub(crate) fn serialize_colr(colr: &ChkColr) -> Vec<u8> {
+    colr.player_color.to_vec()
+}
@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Requirement unknown, but can be in any map type including non-remaster maps.
@@ -13,19 +18,30 @@ use serde::Serialize;
 // 2 - Custom RGB Color (RGB value for the corresponding player defined above)
 // 3 - Use "COLR" selection
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkCrgb {
     pub rgb: [[u8; 3]; 8],
     pub player_color_option: [u8; 8],
 }
 
-pub(crate) fn parse_crgb(chunks: &[RiffChunk<'_>]) -> Result<ChkCrgb, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_crgb(chunks: &[RiffChunk<'_>]) -> Result<ChkCrgb, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::CRGB,
+        });
+    }
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkCrgb {
         rgb: slicer.extract()?,
         player_color_option: slicer.extract()?,
     })
 }
+
+pub(crate) fn serialize_crgb(crgb: &ChkCrgb) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(reinterpret_as_bytes(&crgb.rgb));
+    data.extend_from_slice(&crgb.player_color_option);
+    data
+}
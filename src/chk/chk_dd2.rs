@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Not Required.
@@ -25,17 +30,25 @@ pub struct ChkDd2Individual {
     pub disabled: u8,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkDd2 {
     pub doodads: Vec<ChkDd2Individual>,
 }
 
-pub(crate) fn parse_dd2(chunks: &[RiffChunk]) -> Result<ChkDd2, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_dd2(chunks: &[RiffChunk]) -> Result<ChkDd2, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::DD2,
+        });
+    }
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkDd2 {
         doodads: slicer.extract_rest_as_slice_lax()?.to_vec(),
     })
 }
+
+pub(crate) fn serialize_dd2(dd2: &ChkDd2) -> Vec<u8> {
+    reinterpret_as_bytes(dd2.doodads.as_slice()).to_vec()
+}
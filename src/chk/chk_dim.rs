@@ -1,5 +1,6 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Required for all versions and all game types.
 // Validation: Must be size of 4 bytes.
@@ -13,17 +14,43 @@ use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 pub struct ChkDim<'a> {
-    pub width: &'a u16,
-    pub height: &'a u16,
+    pub width: Cow<'a, u16>,
+    pub height: Cow<'a, u16>,
 }
 
-pub(crate) fn parse_dim<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkDim<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkDimOwned {
+    pub width: u16,
+    pub height: u16,
+}
+
+impl From<&ChkDim<'_>> for ChkDimOwned {
+    fn from(dim: &ChkDim<'_>) -> ChkDimOwned {
+        ChkDimOwned {
+            width: *dim.width,
+            height: *dim.height,
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_dim<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkDim<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::DIM,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkDim {
-        width: slicer.extract_ref()?,
-        height: slicer.extract_ref()?,
+        width: slicer.extract_cow()?,
+        height: slicer.extract_cow()?,
     })
 }
+
+pub(crate) fn serialize_dim(dim: &ChkDimOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&dim.width.to_le_bytes());
+    data.extend_from_slice(&dim.height.to_le_bytes());
+    data
+}
@@ -1,5 +1,6 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Required for all versions and all game types.
 // Validation: Must be size of 2 bytes.
@@ -20,15 +21,36 @@ use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 pub struct ChkEra<'a> {
-    pub tileset: &'a u16,
+    pub tileset: Cow<'a, u16>,
 }
 
-pub(crate) fn parse_era<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkEra<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkEraOwned {
+    pub tileset: u16,
+}
+
+impl From<&ChkEra<'_>> for ChkEraOwned {
+    fn from(era: &ChkEra<'_>) -> ChkEraOwned {
+        ChkEraOwned {
+            tileset: *era.tileset,
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_era<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkEra<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::ERA,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkEra {
-        tileset: slicer.extract_ref()?,
+        tileset: slicer.extract_cow()?,
     })
 }
+
+pub(crate) fn serialize_era(era: &ChkEraOwned) -> Vec<u8> {
+    era.tileset.to_le_bytes().to_vec()
+}
@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for all versions and all game types.
@@ -20,17 +25,21 @@ use serde::Serialize;
 // If this section is less than 20 bytes, the remaining bytes are defaulted to 0.
 // Players can be on a force greater than 4, however they will not appear in the game lobby.
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkForc {
     pub player_forces: [u8; 8],
     pub force_name: [u16; 4],
     pub force_properties: [u8; 4],
 }
 
-pub(crate) fn parse_forc(chunks: &[RiffChunk<'_>]) -> Result<ChkForc, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_forc(chunks: &[RiffChunk<'_>]) -> Result<ChkForc, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::FORC,
+        });
+    }
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkForc {
         player_forces: slicer.extract_lax_zero_pad(),
@@ -38,3 +47,11 @@ pub(crate) fn parse_forc(chunks: &[RiffChunk<'_>]) -> Result<ChkForc, anyhow::Er
         force_properties: slicer.extract_lax_zero_pad(),
     })
 }
+
+pub(crate) fn serialize_forc(forc: &ChkForc) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&forc.player_forces);
+    data.extend_from_slice(reinterpret_as_bytes(&forc.force_name));
+    data.extend_from_slice(&forc.force_properties);
+    data
+}
@@ -1,4 +1,4 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
 
 // Not Required.
@@ -17,17 +17,25 @@ use serde::Serialize;
 // 08 - Closed slot
 // This section is separate from OWNR as a staredit value. Staredit does not display "inactive" as a valid option. Italicized settings denote invalid map options, which may involve a buffer overflow.
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkIown {
     pub player_owner: [u8; 12],
 }
 
-pub(crate) fn parse_iown(chunks: &[RiffChunk<'_>]) -> Result<ChkIown, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_iown(chunks: &[RiffChunk<'_>]) -> Result<ChkIown, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::IOWN,
+        });
+    }
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkIown {
         player_owner: slicer.extract_u8_array_lax(),
     })
 }
+
+pub(crate) fn serialize_iown(iown: &ChkIown) -> Vec<u8> {
+    iown.player_owner.to_vec()
+}
@@ -1,5 +1,6 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Not required.
 
@@ -11,15 +12,36 @@ use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 pub struct ChkIve2<'a> {
-    pub additional_file_format_version: &'a u16,
+    pub additional_file_format_version: Cow<'a, u16>,
 }
 
-pub(crate) fn parse_ive2<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkIve2<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkIve2Owned {
+    pub additional_file_format_version: u16,
+}
+
+impl From<&ChkIve2<'_>> for ChkIve2Owned {
+    fn from(ive2: &ChkIve2<'_>) -> ChkIve2Owned {
+        ChkIve2Owned {
+            additional_file_format_version: *ive2.additional_file_format_version,
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_ive2<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkIve2<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::IVE2,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkIve2 {
-        additional_file_format_version: slicer.extract_ref()?,
+        additional_file_format_version: slicer.extract_cow()?,
     })
 }
+
+pub(crate) fn serialize_ive2(ive2: &ChkIve2Owned) -> Vec<u8> {
+    ive2.additional_file_format_version.to_le_bytes().to_vec()
+}
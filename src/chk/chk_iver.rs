@@ -1,5 +1,6 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Not required.
 
@@ -12,15 +13,39 @@ use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 pub struct ChkIver<'a> {
-    pub additional_file_format_version: Option<&'a u16>,
+    pub additional_file_format_version: Option<Cow<'a, u16>>,
 }
 
-pub(crate) fn parse_iver<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkIver<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkIverOwned {
+    pub additional_file_format_version: Option<u16>,
+}
+
+impl From<&ChkIver<'_>> for ChkIverOwned {
+    fn from(iver: &ChkIver<'_>) -> ChkIverOwned {
+        ChkIverOwned {
+            additional_file_format_version: iver.additional_file_format_version.as_deref().copied(),
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_iver<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkIver<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::IVER,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkIver {
-        additional_file_format_version: slicer.extract_ref_lax()?,
+        additional_file_format_version: slicer.extract_cow_lax()?,
     })
 }
+
+// A truncated IVER is written back as an empty section so that it is still present.
+pub(crate) fn serialize_iver(iver: &ChkIverOwned) -> Vec<u8> {
+    iver.additional_file_format_version
+        .map(|x| x.to_le_bytes().to_vec())
+        .unwrap_or_default()
+}
@@ -0,0 +1,36 @@
+use crate::{error::ChkError, riff::RiffChunk};
+use serde::Serialize;
+
+// Not Required. Written by editors for StarCraft: Remastered and ignored by the game.
+// Validation: Must be at least 8 bytes.
+
+// This section contains the strings OSTR refers to, the editor's own names for things.
+
+// u32: Version, currently 2
+// u32: Number of strings in the section
+// u32[Number of strings]: 1 integer for each string specifying the offset (the spot where the string starts in the section from the start of it).
+// u32[Number of strings]: 1 integer for each string with its properties: the red, green and blue of the color the editor shows it in and a byte of flags.
+// Strings: After the properties, every string in the section one after another. Each one is terminated by a null character.
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkKstr {
+    pub string_data: Vec<u8>,
+}
+
+pub(crate) fn parse_kstr(chunks: &[RiffChunk]) -> Result<ChkKstr, ChkError> {
+    let mut data = Vec::new();
+
+    for chunk in chunks {
+        if chunk.data.len() >= data.len() {
+            data.resize(chunk.data.len(), 0);
+        }
+
+        data.as_mut_slice()[0..chunk.data.len()].copy_from_slice(chunk.data);
+    }
+
+    Ok(ChkKstr { string_data: data })
+}
+
+pub(crate) fn serialize_kstr(kstr: &ChkKstr) -> Vec<u8> {
+    kstr.string_data.clone()
+}
@@ -1,4 +1,4 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
 
 // Required for all versions. Not required for Melee.
@@ -22,12 +22,33 @@ pub struct ChkMask<'a> {
     pub fog: &'a [u8],
 }
 
-pub(crate) fn parse_mask<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkMask<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkMaskOwned {
+    pub fog: Vec<u8>,
+}
+
+impl From<&ChkMask<'_>> for ChkMaskOwned {
+    fn from(mask: &ChkMask<'_>) -> ChkMaskOwned {
+        ChkMaskOwned {
+            fog: mask.fog.to_vec(),
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_mask<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkMask<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::MASK,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkMask {
         fog: slicer.extract_rest_as_slice()?,
     })
 }
+
+pub(crate) fn serialize_mask(mask: &ChkMaskOwned) -> Vec<u8> {
+    mask.fog.clone()
+}
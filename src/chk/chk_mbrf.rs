@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for all versions. Not required for Melee.
@@ -10,7 +15,7 @@ use serde::Serialize;
 
 // This section can be split. Additional MBRF sections will add more briefing triggers.
 
-#[derive(Clone, Copy, Debug, Serialize, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Serialize, Eq, PartialEq, Default)]
 #[repr(C, packed)]
 pub struct ChkMbrfCondition {
     pub location: u32,
@@ -24,7 +29,7 @@ pub struct ChkMbrfCondition {
     pub mask_flag: u16,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Serialize, Eq, PartialEq, Default)]
 #[repr(C, packed)]
 pub struct ChkMbrfAction {
     pub location: u32,
@@ -53,20 +58,28 @@ pub struct ChkMbrfIndividual {
     pub current_action: u8,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkMbrf {
     pub triggers: Vec<ChkMbrfIndividual>,
 }
 
-pub(crate) fn parse_mbrf(chunks: &[RiffChunk]) -> Result<ChkMbrf, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_mbrf(chunks: &[RiffChunk]) -> Result<ChkMbrf, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::MBRF,
+        });
+    }
 
     let mut triggers: Vec<ChkMbrfIndividual> = Vec::new();
 
     for chunk in chunks {
-        let mut slicer = CursorSlicer::new(chunk.data);
+        let mut slicer = CursorSlicer::new(chunk);
         triggers.extend_from_slice(slicer.extract_rest_as_slice_lax()?);
     }
 
     Ok(ChkMbrf { triggers })
 }
+
+pub(crate) fn serialize_mbrf(mbrf: &ChkMbrf) -> Vec<u8> {
+    reinterpret_as_bytes(mbrf.triggers.as_slice()).to_vec()
+}
@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for all versions. Not required for Melee.
@@ -39,12 +44,33 @@ pub struct ChkMrgn<'a> {
     pub locations: &'a [ChkMrgnIndividual],
 }
 
-pub(crate) fn parse_mrgn<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkMrgn<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkMrgnOwned {
+    pub locations: Vec<ChkMrgnIndividual>,
+}
+
+impl From<&ChkMrgn<'_>> for ChkMrgnOwned {
+    fn from(mrgn: &ChkMrgn<'_>) -> ChkMrgnOwned {
+        ChkMrgnOwned {
+            locations: mrgn.locations.to_vec(),
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_mrgn<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkMrgn<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::MRGN,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkMrgn {
         locations: slicer.extract_rest_as_slice_lax()?,
     })
 }
+
+pub(crate) fn serialize_mrgn(mrgn: &ChkMrgnOwned) -> Vec<u8> {
+    reinterpret_as_bytes(mrgn.locations.as_slice()).to_vec()
+}
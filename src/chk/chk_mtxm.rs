@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::reinterpret_slice2};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{parse_u16_slice_zero_pad, reinterpret_as_bytes},
+};
 use serde::Serialize;
 
 // Required for all versions and all game types.
@@ -15,31 +20,22 @@ use serde::Serialize;
 // This section includes doodads as terrain; TILE, which is otherwise identical, doesn't. Out of the terrain sections (TILE, ISOM, and MTXM), SC only reads MTXM for the sake of not having to generate this data on-the-fly: it contains the exact representation of the level's appearance, including doodads. TILE, on the other hand, is directly tied via a tile lookup function to ISOM, and exists for the sake of not having to generate tiles from ISOM on-the-fly in StarEdit.
 // If MTXM section is smaller than (map width*height), then the remaining tiles will be filled with null tiles or tiles specified by previous MTXM sections.
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkMtxm {
     pub data: Vec<u16>, // PROTECTION: some map protectors make MTXM sections that are not a multiple of 2 bytes long. So, need to copy them and pad with 0.
 }
 
-pub(crate) fn parse_mtxm(chunks: &[RiffChunk]) -> Result<ChkMtxm, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_mtxm(chunks: &[RiffChunk]) -> Result<ChkMtxm, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::MTXM,
+        });
+    }
 
     let mut ret = Vec::new();
 
     for chunk in chunks {
-        let sec = chunk.data;
-
-        let data = if sec.len() % 2 == 0 {
-            Vec::from(reinterpret_slice2::<u16>(sec)?)
-        } else {
-            let mut ret = if sec.len() == 1 {
-                Vec::new()
-            } else {
-                Vec::from(reinterpret_slice2::<u16>(&sec[0..sec.len() - 1])?)
-            };
-
-            ret.push(sec[sec.len() - 1] as u16);
-            ret
-        };
+        let data = parse_u16_slice_zero_pad(chunk.data);
 
         if data.len() > ret.len() {
             ret = data;
@@ -50,3 +46,7 @@ pub(crate) fn parse_mtxm(chunks: &[RiffChunk]) -> Result<ChkMtxm, anyhow::Error>
 
     Ok(ChkMtxm { data: ret })
 }
+
+pub(crate) fn serialize_mtxm(mtxm: &ChkMtxm) -> Vec<u8> {
+    reinterpret_as_bytes(mtxm.data.as_slice()).to_vec()
+}
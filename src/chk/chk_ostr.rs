@@ -0,0 +1,77 @@
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
+use serde::Serialize;
+
+// Not Required. Written by editors for StarCraft: Remastered and ignored by the game.
+// Validation: Must be size of 5944 bytes.
+
+// This section overrides the names of the scenario, forces, units, sounds, switches and locations with strings
+// from KSTR. An override of 0 means the name from the usual section is used.
+
+// u32: Version, currently 1
+// u32: KSTR string number of the scenario name
+// u32: KSTR string number of the scenario description
+// u32[4]: KSTR string number of the name of each force
+// u32[228]: KSTR string number of the name of each unit, used with UNIS
+// u32[228]: KSTR string number of the name of each unit, used with UNIx
+// u32[512]: KSTR string number of the path of each sound
+// u32[256]: KSTR string number of the name of each switch
+// u32[255]: KSTR string number of the name of each location
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkOstr {
+    pub version: u32,
+    pub scenario_name: u32,
+    pub scenario_description: u32,
+    pub force_name: [u32; 4],
+    #[serde(skip_serializing)]
+    pub unit_name: [u32; 228],
+    #[serde(skip_serializing)]
+    pub expansion_unit_name: [u32; 228],
+    #[serde(skip_serializing)]
+    pub sound_path: [u32; 512],
+    #[serde(skip_serializing)]
+    pub switch_name: [u32; 256],
+    #[serde(skip_serializing)]
+    pub location_name: [u32; 255],
+}
+
+pub(crate) fn parse_ostr(chunks: &[RiffChunk]) -> Result<ChkOstr, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::OSTR,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
+
+    Ok(ChkOstr {
+        version: slicer.extract()?,
+        scenario_name: slicer.extract()?,
+        scenario_description: slicer.extract()?,
+        force_name: slicer.extract()?,
+        unit_name: slicer.extract()?,
+        expansion_unit_name: slicer.extract()?,
+        sound_path: slicer.extract()?,
+        switch_name: slicer.extract()?,
+        location_name: slicer.extract()?,
+    })
+}
+
+pub(crate) fn serialize_ostr(ostr: &ChkOstr) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&ostr.version.to_le_bytes());
+    data.extend_from_slice(&ostr.scenario_name.to_le_bytes());
+    data.extend_from_slice(&ostr.scenario_description.to_le_bytes());
+    data.extend_from_slice(reinterpret_as_bytes(&ostr.force_name));
+    data.extend_from_slice(reinterpret_as_bytes(&ostr.unit_name));
+    data.extend_from_slice(reinterpret_as_bytes(&ostr.expansion_unit_name));
+    data.extend_from_slice(reinterpret_as_bytes(&ostr.sound_path));
+    data.extend_from_slice(reinterpret_as_bytes(&ostr.switch_name));
+    data.extend_from_slice(reinterpret_as_bytes(&ostr.location_name));
+    data
+}
@@ -1,4 +1,4 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
 
 // Required for all versions and all game types.
@@ -18,17 +18,25 @@ use serde::Serialize;
 // 08 - Closed slot
 // Italicized settings denote invalid map options, which may involve a buffer overflow.
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkOwnr {
     pub player_owner: [u8; 12],
 }
 
-pub(crate) fn parse_ownr(chunks: &[RiffChunk<'_>]) -> Result<ChkOwnr, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_ownr(chunks: &[RiffChunk<'_>]) -> Result<ChkOwnr, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::OWNR,
+        });
+    }
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkOwnr {
         player_owner: slicer.extract_u8_array_lax(),
     })
 }
+
+pub(crate) fn serialize_ownr(ownr: &ChkOwnr) -> Vec<u8> {
+    ownr.player_owner.to_vec()
+}
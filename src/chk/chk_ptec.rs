@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for Vanilla and Hybrid (in Original mode). Not required for Melee.
@@ -33,10 +38,35 @@ pub struct ChkPtec<'a> {
     pub player_uses_default: &'a [[u8; 24]; 12],
 }
 
-pub(crate) fn parse_ptec<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkPtec<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkPtecOwned {
+    pub player_availability: [[u8; 24]; 12],
+    pub already_researched: [[u8; 24]; 12],
+    pub global_availability_defaults: [u8; 24],
+    pub global_already_researched_defaults: [u8; 24],
+    pub player_uses_default: [[u8; 24]; 12],
+}
+
+impl From<&ChkPtec<'_>> for ChkPtecOwned {
+    fn from(ptec: &ChkPtec<'_>) -> ChkPtecOwned {
+        ChkPtecOwned {
+            player_availability: *ptec.player_availability,
+            already_researched: *ptec.already_researched,
+            global_availability_defaults: *ptec.global_availability_defaults,
+            global_already_researched_defaults: *ptec.global_already_researched_defaults,
+            player_uses_default: *ptec.player_uses_default,
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_ptec<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkPtec<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::PTEC,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkPtec {
         player_availability: slicer.extract_ref()?,
@@ -46,3 +76,13 @@ pub(crate) fn parse_ptec<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkPtec<'a>, an
         player_uses_default: slicer.extract_ref()?,
     })
 }
+
+pub(crate) fn serialize_ptec(ptec: &ChkPtecOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(reinterpret_as_bytes(&ptec.player_availability));
+    data.extend_from_slice(reinterpret_as_bytes(&ptec.already_researched));
+    data.extend_from_slice(&ptec.global_availability_defaults);
+    data.extend_from_slice(&ptec.global_already_researched_defaults);
+    data.extend_from_slice(reinterpret_as_bytes(&ptec.player_uses_default));
+    data
+}
@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for Hybrid (in Expansion mode) and Brood War. Not required for Melee.
@@ -21,10 +26,40 @@ pub struct ChkPtex<'a> {
     pub player_uses_default: &'a [[u8; 44]; 12],
 }
 
-pub(crate) fn parse_ptex<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkPtex<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkPtexOwned {
+    #[serde(skip_serializing)]
+    pub player_availability: [[u8; 44]; 12],
+    #[serde(skip_serializing)]
+    pub already_researched: [[u8; 44]; 12],
+    #[serde(skip_serializing)]
+    pub global_availability_defaults: [u8; 44],
+    #[serde(skip_serializing)]
+    pub global_already_researched_defaults: [u8; 44],
+    #[serde(skip_serializing)]
+    pub player_uses_default: [[u8; 44]; 12],
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+impl From<&ChkPtex<'_>> for ChkPtexOwned {
+    fn from(ptex: &ChkPtex<'_>) -> ChkPtexOwned {
+        ChkPtexOwned {
+            player_availability: *ptex.player_availability,
+            already_researched: *ptex.already_researched,
+            global_availability_defaults: *ptex.global_availability_defaults,
+            global_already_researched_defaults: *ptex.global_already_researched_defaults,
+            player_uses_default: *ptex.player_uses_default,
+        }
+    }
+}
+
+pub(crate) fn parse_ptex<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkPtex<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::PTEx,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkPtex {
         player_availability: slicer.extract_ref()?,
@@ -34,3 +69,13 @@ pub(crate) fn parse_ptex<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkPtex<'a>, an
         player_uses_default: slicer.extract_ref()?,
     })
 }
+
+pub(crate) fn serialize_ptex(ptex: &ChkPtexOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(reinterpret_as_bytes(&ptex.player_availability));
+    data.extend_from_slice(reinterpret_as_bytes(&ptex.already_researched));
+    data.extend_from_slice(&ptex.global_availability_defaults);
+    data.extend_from_slice(&ptex.global_already_researched_defaults);
+    data.extend_from_slice(reinterpret_as_bytes(&ptex.player_uses_default));
+    data
+}
@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for all versions. Not required for Melee.
@@ -26,10 +31,34 @@ pub struct ChkPuni<'a> {
     pub unit_player_uses_defaults: &'a [[u8; 228]; 12],
 }
 
-pub(crate) fn parse_puni<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkPuni<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkPuniOwned {
+    #[serde(skip_serializing)]
+    pub unit_player_availability: [[u8; 228]; 12],
+    #[serde(skip_serializing)]
+    pub unit_global_availability: [u8; 228],
+    #[serde(skip_serializing)]
+    pub unit_player_uses_defaults: [[u8; 228]; 12],
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+impl From<&ChkPuni<'_>> for ChkPuniOwned {
+    fn from(puni: &ChkPuni<'_>) -> ChkPuniOwned {
+        ChkPuniOwned {
+            unit_player_availability: *puni.unit_player_availability,
+            unit_global_availability: *puni.unit_global_availability,
+            unit_player_uses_defaults: *puni.unit_player_uses_defaults,
+        }
+    }
+}
+
+pub(crate) fn parse_puni<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkPuni<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::PUNI,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkPuni {
         unit_player_availability: slicer.extract_ref()?,
@@ -37,3 +66,11 @@ pub(crate) fn parse_puni<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkPuni<'a>, an
         unit_player_uses_defaults: slicer.extract_ref()?,
     })
 }
+
+pub(crate) fn serialize_puni(puni: &ChkPuniOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(reinterpret_as_bytes(&puni.unit_player_availability));
+    data.extend_from_slice(&puni.unit_global_availability);
+    data.extend_from_slice(reinterpret_as_bytes(&puni.unit_player_uses_defaults));
+    data
+}
@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for Hybrid (in Expansion mode) and Brood War. Not required for Melee.
@@ -21,10 +26,40 @@ pub struct ChkPupx<'a> {
     pub player_uses_upgrade_defaults: &'a [[u8; 61]; 12],
 }
 
-pub(crate) fn parse_pupx<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkPupx<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkPupxOwned {
+    #[serde(skip_serializing)]
+    pub max_upgrade_level: [[u8; 61]; 12],
+    #[serde(skip_serializing)]
+    pub starting_upgrade_level: [[u8; 61]; 12],
+    #[serde(skip_serializing)]
+    pub global_default_maximum_upgrade_level: [u8; 61],
+    #[serde(skip_serializing)]
+    pub global_default_starting_upgrade_level: [u8; 61],
+    #[serde(skip_serializing)]
+    pub player_uses_upgrade_defaults: [[u8; 61]; 12],
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+impl From<&ChkPupx<'_>> for ChkPupxOwned {
+    fn from(pupx: &ChkPupx<'_>) -> ChkPupxOwned {
+        ChkPupxOwned {
+            max_upgrade_level: *pupx.max_upgrade_level,
+            starting_upgrade_level: *pupx.starting_upgrade_level,
+            global_default_maximum_upgrade_level: *pupx.global_default_maximum_upgrade_level,
+            global_default_starting_upgrade_level: *pupx.global_default_starting_upgrade_level,
+            player_uses_upgrade_defaults: *pupx.player_uses_upgrade_defaults,
+        }
+    }
+}
+
+pub(crate) fn parse_pupx<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkPupx<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::PUPx,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkPupx {
         max_upgrade_level: slicer.extract_ref()?,
@@ -34,3 +69,13 @@ pub(crate) fn parse_pupx<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkPupx<'a>, an
         player_uses_upgrade_defaults: slicer.extract_ref()?,
     })
 }
+
+pub(crate) fn serialize_pupx(pupx: &ChkPupxOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(reinterpret_as_bytes(&pupx.max_upgrade_level));
+    data.extend_from_slice(reinterpret_as_bytes(&pupx.starting_upgrade_level));
+    data.extend_from_slice(&pupx.global_default_maximum_upgrade_level);
+    data.extend_from_slice(&pupx.global_default_starting_upgrade_level);
+    data.extend_from_slice(reinterpret_as_bytes(&pupx.player_uses_upgrade_defaults));
+    data
+}
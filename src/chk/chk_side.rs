@@ -1,4 +1,4 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
 
 // Required for all versions and all game types.
@@ -17,17 +17,25 @@ use serde::Serialize;
 // 07 - Inactive, shown as locked "Select Race" option in SC:R lobby
 // Italicized settings denote invalid map options. Note Players 9-11 are defaultly Inactive and Player 12 is defaultly Neutral.
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkSide {
     pub player_side: [u8; 12],
 }
 
-pub(crate) fn parse_side(chunks: &[RiffChunk<'_>]) -> Result<ChkSide, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_side(chunks: &[RiffChunk<'_>]) -> Result<ChkSide, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::SIDE,
+        });
+    }
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkSide {
         player_side: slicer.extract_u8_array_lax(),
     })
 }
+
+pub(crate) fn serialize_side(side: &ChkSide) -> Vec<u8> {
+    side.player_side.to_vec()
+}
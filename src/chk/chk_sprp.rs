@@ -1,5 +1,6 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Required for all versions and all game types.
 // Validation: Must be size of 4 bytes.
@@ -10,17 +11,43 @@ use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 pub struct ChkSprp<'a> {
-    pub scenario_name_string_number: &'a u16,
-    pub description_string_number: &'a u16,
+    pub scenario_name_string_number: Cow<'a, u16>,
+    pub description_string_number: Cow<'a, u16>,
 }
 
-pub(crate) fn parse_sprp<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkSprp<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkSprpOwned {
+    pub scenario_name_string_number: u16,
+    pub description_string_number: u16,
+}
+
+impl From<&ChkSprp<'_>> for ChkSprpOwned {
+    fn from(sprp: &ChkSprp<'_>) -> ChkSprpOwned {
+        ChkSprpOwned {
+            scenario_name_string_number: *sprp.scenario_name_string_number,
+            description_string_number: *sprp.description_string_number,
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_sprp<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkSprp<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::SPRP,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkSprp {
-        scenario_name_string_number: slicer.extract_ref()?,
-        description_string_number: slicer.extract_ref()?,
+        scenario_name_string_number: slicer.extract_cow()?,
+        description_string_number: slicer.extract_cow()?,
     })
 }
+
+pub(crate) fn serialize_sprp(sprp: &ChkSprpOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&sprp.scenario_name_string_number.to_le_bytes());
+    data.extend_from_slice(&sprp.description_string_number.to_le_bytes());
+    data
+}
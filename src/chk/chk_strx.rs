@@ -1,4 +1,4 @@
-use crate::riff::RiffChunk;
+use crate::{error::ChkError, riff::RiffChunk};
 use serde::Serialize;
 
 // Required for all versions and all game types.
@@ -19,12 +19,12 @@ pub struct ChkStrx<'a> {
     pub strings: &'a [u8],
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkStrx2 {
     pub string_data: Vec<u8>,
 }
 
-pub(crate) fn parse_strx(chunks: &[RiffChunk]) -> Result<ChkStrx2, anyhow::Error> {
+pub(crate) fn parse_strx(chunks: &[RiffChunk]) -> Result<ChkStrx2, ChkError> {
     let mut data = Vec::new();
 
     for chunk in chunks {
@@ -38,6 +38,10 @@ pub(crate) fn parse_strx(chunks: &[RiffChunk]) -> Result<ChkStrx2, anyhow::Error
     Ok(ChkStrx2 { string_data: data })
 }
 
+pub(crate) fn serialize_strx(strx: &ChkStrx2) -> Vec<u8> {
+    strx.string_data.clone()
+}
+
 // let
 // let mut strings = Vec::new();
 
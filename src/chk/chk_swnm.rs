@@ -1,5 +1,11 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Not Required.
 
@@ -10,15 +16,37 @@ use serde::Serialize;
 #[derive(Debug, Serialize)]
 pub struct ChkSwnm<'a> {
     #[serde(skip_serializing)]
-    pub switch_name_string_number: &'a [u32; 256],
+    pub switch_name_string_number: Cow<'a, [u32; 256]>,
 }
 
-pub(crate) fn parse_swnm<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkSwnm<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkSwnmOwned {
+    #[serde(skip_serializing)]
+    pub switch_name_string_number: [u32; 256],
+}
+
+impl From<&ChkSwnm<'_>> for ChkSwnmOwned {
+    fn from(swnm: &ChkSwnm<'_>) -> ChkSwnmOwned {
+        ChkSwnmOwned {
+            switch_name_string_number: *swnm.switch_name_string_number,
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_swnm<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkSwnm<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::SWNM,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkSwnm {
-        switch_name_string_number: slicer.extract_ref()?,
+        switch_name_string_number: slicer.extract_cow()?,
     })
 }
+
+pub(crate) fn serialize_swnm(swnm: &ChkSwnmOwned) -> Vec<u8> {
+    reinterpret_as_bytes(&swnm.switch_name_string_number).to_vec()
+}
@@ -1,5 +1,11 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Required for Vanilla and Hybrid (in Original mode). Not required for Melee.
 // Validation: Must be size of 216 bytes.
@@ -19,22 +25,57 @@ use serde::Serialize;
 #[derive(Debug, Serialize)]
 pub struct ChkTecs<'a> {
     pub technology_uses_default_settings: &'a [u8; 24],
-    pub mineral_cost: &'a [u16; 24],
-    pub gas_cost: &'a [u16; 24],
-    pub time: &'a [u16; 24],
-    pub energy_cost_to_cast: &'a [u16; 24],
+    pub mineral_cost: Cow<'a, [u16; 24]>,
+    pub gas_cost: Cow<'a, [u16; 24]>,
+    pub time: Cow<'a, [u16; 24]>,
+    pub energy_cost_to_cast: Cow<'a, [u16; 24]>,
 }
 
-pub(crate) fn parse_tecs<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkTecs<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkTecsOwned {
+    pub technology_uses_default_settings: [u8; 24],
+    pub mineral_cost: [u16; 24],
+    pub gas_cost: [u16; 24],
+    pub time: [u16; 24],
+    pub energy_cost_to_cast: [u16; 24],
+}
+
+impl From<&ChkTecs<'_>> for ChkTecsOwned {
+    fn from(tecs: &ChkTecs<'_>) -> ChkTecsOwned {
+        ChkTecsOwned {
+            technology_uses_default_settings: *tecs.technology_uses_default_settings,
+            mineral_cost: *tecs.mineral_cost,
+            gas_cost: *tecs.gas_cost,
+            time: *tecs.time,
+            energy_cost_to_cast: *tecs.energy_cost_to_cast,
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_tecs<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkTecs<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::TECS,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkTecs {
         technology_uses_default_settings: slicer.extract_ref()?,
-        mineral_cost: slicer.extract_ref()?,
-        gas_cost: slicer.extract_ref()?,
-        time: slicer.extract_ref()?,
-        energy_cost_to_cast: slicer.extract_ref()?,
+        mineral_cost: slicer.extract_cow()?,
+        gas_cost: slicer.extract_cow()?,
+        time: slicer.extract_cow()?,
+        energy_cost_to_cast: slicer.extract_cow()?,
     })
 }
+
+pub(crate) fn serialize_tecs(tecs: &ChkTecsOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&tecs.technology_uses_default_settings);
+    data.extend_from_slice(reinterpret_as_bytes(&tecs.mineral_cost));
+    data.extend_from_slice(reinterpret_as_bytes(&tecs.gas_cost));
+    data.extend_from_slice(reinterpret_as_bytes(&tecs.time));
+    data.extend_from_slice(reinterpret_as_bytes(&tecs.energy_cost_to_cast));
+    data
+}
@@ -1,5 +1,11 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Required for Hybrid (in Expansion mode) and Brood War. Not required for Melee.
 // Validation: Must be size of 396 bytes.
@@ -12,25 +18,65 @@ pub struct ChkTecx<'a> {
     #[serde(skip_serializing)]
     pub technology_uses_default_settings: &'a [u8; 44],
     #[serde(skip_serializing)]
-    pub mineral_cost: &'a [u16; 44],
+    pub mineral_cost: Cow<'a, [u16; 44]>,
     #[serde(skip_serializing)]
-    pub gas_cost: &'a [u16; 44],
+    pub gas_cost: Cow<'a, [u16; 44]>,
     #[serde(skip_serializing)]
-    pub time: &'a [u16; 44],
+    pub time: Cow<'a, [u16; 44]>,
     #[serde(skip_serializing)]
-    pub energy_cost_to_cast: &'a [u16; 44],
+    pub energy_cost_to_cast: Cow<'a, [u16; 44]>,
 }
 
-pub(crate) fn parse_tecx<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkTecx<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkTecxOwned {
+    #[serde(skip_serializing)]
+    pub technology_uses_default_settings: [u8; 44],
+    #[serde(skip_serializing)]
+    pub mineral_cost: [u16; 44],
+    #[serde(skip_serializing)]
+    pub gas_cost: [u16; 44],
+    #[serde(skip_serializing)]
+    pub time: [u16; 44],
+    #[serde(skip_serializing)]
+    pub energy_cost_to_cast: [u16; 44],
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+impl From<&ChkTecx<'_>> for ChkTecxOwned {
+    fn from(tecx: &ChkTecx<'_>) -> ChkTecxOwned {
+        ChkTecxOwned {
+            technology_uses_default_settings: *tecx.technology_uses_default_settings,
+            mineral_cost: *tecx.mineral_cost,
+            gas_cost: *tecx.gas_cost,
+            time: *tecx.time,
+            energy_cost_to_cast: *tecx.energy_cost_to_cast,
+        }
+    }
+}
+
+pub(crate) fn parse_tecx<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkTecx<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::TECx,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkTecx {
         technology_uses_default_settings: slicer.extract_ref()?,
-        mineral_cost: slicer.extract_ref()?,
-        gas_cost: slicer.extract_ref()?,
-        time: slicer.extract_ref()?,
-        energy_cost_to_cast: slicer.extract_ref()?,
+        mineral_cost: slicer.extract_cow()?,
+        gas_cost: slicer.extract_cow()?,
+        time: slicer.extract_cow()?,
+        energy_cost_to_cast: slicer.extract_cow()?,
     })
 }
+
+pub(crate) fn serialize_tecx(tecx: &ChkTecxOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&tecx.technology_uses_default_settings);
+    data.extend_from_slice(reinterpret_as_bytes(&tecx.mineral_cost));
+    data.extend_from_slice(reinterpret_as_bytes(&tecx.gas_cost));
+    data.extend_from_slice(reinterpret_as_bytes(&tecx.time));
+    data.extend_from_slice(reinterpret_as_bytes(&tecx.energy_cost_to_cast));
+    data
+}
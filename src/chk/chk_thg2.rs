@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for all versions and all game types.
@@ -32,20 +37,28 @@ pub struct ChkThg2Individual {
     pub flags: u16,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkThg2 {
     pub sprites: Vec<ChkThg2Individual>,
 }
 
-pub(crate) fn parse_thg2(chunks: &[RiffChunk]) -> Result<ChkThg2, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_thg2(chunks: &[RiffChunk]) -> Result<ChkThg2, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::THG2,
+        });
+    }
 
     let mut sprites: Vec<ChkThg2Individual> = Vec::new();
 
     for chunk in chunks {
-        let mut slicer = CursorSlicer::new(chunk.data);
+        let mut slicer = CursorSlicer::new(chunk);
         sprites.extend_from_slice(slicer.extract_rest_as_slice_lax()?);
     }
 
     Ok(ChkThg2 { sprites })
 }
+
+pub(crate) fn serialize_thg2(thg2: &ChkThg2) -> Vec<u8> {
+    reinterpret_as_bytes(thg2.sprites.as_slice()).to_vec()
+}
@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for all versions. Not required for Melee.
@@ -60,7 +65,7 @@ use serde::Serialize;
 // u8: Index of the current action, in StarCraft this is incremented after each action is executed, trigger execution ends when this is 64 (Max Actions) or an action is encountered with Action byte as 0
 // This section can be split. Additional TRIG sections will add more triggers.
 
-#[derive(Clone, Copy, Debug, Serialize, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Serialize, Eq, PartialEq, Default)]
 #[repr(C, packed)]
 pub struct ChkTrigCondition {
     pub location: u32,
@@ -74,7 +79,7 @@ pub struct ChkTrigCondition {
     pub mask_flag: u16,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Serialize, Eq, PartialEq, Default)]
 #[repr(C, packed)]
 pub struct ChkTrigAction {
     pub location: u32,
@@ -103,20 +108,28 @@ pub struct ChkTrigIndividual {
     pub current_action: u8,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkTrig {
     pub triggers: Vec<ChkTrigIndividual>,
 }
 
-pub(crate) fn parse_trig(chunks: &[RiffChunk]) -> Result<ChkTrig, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_trig(chunks: &[RiffChunk]) -> Result<ChkTrig, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::TRIG,
+        });
+    }
 
     let mut triggers: Vec<ChkTrigIndividual> = Vec::new();
 
     for chunk in chunks {
-        let mut slicer = CursorSlicer::new(chunk.data);
+        let mut slicer = CursorSlicer::new(chunk);
         triggers.extend_from_slice(slicer.extract_rest_as_slice_lax()?);
     }
 
     Ok(ChkTrig { triggers })
 }
+
+pub(crate) fn serialize_trig(trig: &ChkTrig) -> Vec<u8> {
+    reinterpret_as_bytes(trig.triggers.as_slice()).to_vec()
+}
@@ -1,5 +1,6 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Not required.
 
@@ -11,15 +12,36 @@ use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 pub struct ChkType<'a> {
-    pub scenario_type: &'a u32,
+    pub scenario_type: Cow<'a, u32>,
 }
 
-pub(crate) fn parse_type<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkType<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkTypeOwned {
+    pub scenario_type: u32,
+}
+
+impl From<&ChkType<'_>> for ChkTypeOwned {
+    fn from(type_: &ChkType<'_>) -> ChkTypeOwned {
+        ChkTypeOwned {
+            scenario_type: *type_.scenario_type,
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_type<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkType<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::TYPE,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkType {
-        scenario_type: slicer.extract_ref()?,
+        scenario_type: slicer.extract_cow()?,
     })
 }
+
+pub(crate) fn serialize_type(type_: &ChkTypeOwned) -> Vec<u8> {
+    type_.scenario_type.to_le_bytes().to_vec()
+}
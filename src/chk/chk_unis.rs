@@ -1,5 +1,11 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // u8[228]: 1 byte for each unit, in order of Unit ID
 // 00 - Unit does not use default settings
@@ -19,40 +25,100 @@ pub struct ChkUnis<'a> {
     #[serde(skip_serializing)]
     pub config: &'a [u8; 228],
     #[serde(skip_serializing)]
-    pub hit_points: &'a [u32; 228],
+    pub hit_points: Cow<'a, [u32; 228]>,
     #[serde(skip_serializing)]
-    pub shield_points: &'a [u16; 228],
+    pub shield_points: Cow<'a, [u16; 228]>,
     #[serde(skip_serializing)]
     pub armor_points: &'a [u8; 228],
     #[serde(skip_serializing)]
-    pub build_time: &'a [u16; 228],
+    pub build_time: Cow<'a, [u16; 228]>,
     #[serde(skip_serializing)]
-    pub mineral_cost: &'a [u16; 228],
+    pub mineral_cost: Cow<'a, [u16; 228]>,
     #[serde(skip_serializing)]
-    pub gas_cost: &'a [u16; 228],
+    pub gas_cost: Cow<'a, [u16; 228]>,
     #[serde(skip_serializing)]
-    pub string_number: &'a [u16; 228],
+    pub string_number: Cow<'a, [u16; 228]>,
     #[serde(skip_serializing)]
-    pub base_weapon_damage: &'a [u16; 100],
+    pub base_weapon_damage: Cow<'a, [u16; 100]>,
     #[serde(skip_serializing)]
-    pub upgrade_bonus_weapon_damage: &'a [u16; 100],
+    pub upgrade_bonus_weapon_damage: Cow<'a, [u16; 100]>,
 }
 
-pub(crate) fn parse_unis<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUnis<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkUnisOwned {
+    #[serde(skip_serializing)]
+    pub config: [u8; 228],
+    #[serde(skip_serializing)]
+    pub hit_points: [u32; 228],
+    #[serde(skip_serializing)]
+    pub shield_points: [u16; 228],
+    #[serde(skip_serializing)]
+    pub armor_points: [u8; 228],
+    #[serde(skip_serializing)]
+    pub build_time: [u16; 228],
+    #[serde(skip_serializing)]
+    pub mineral_cost: [u16; 228],
+    #[serde(skip_serializing)]
+    pub gas_cost: [u16; 228],
+    #[serde(skip_serializing)]
+    pub string_number: [u16; 228],
+    #[serde(skip_serializing)]
+    pub base_weapon_damage: [u16; 100],
+    #[serde(skip_serializing)]
+    pub upgrade_bonus_weapon_damage: [u16; 100],
+}
+
+impl From<&ChkUnis<'_>> for ChkUnisOwned {
+    fn from(unis: &ChkUnis<'_>) -> ChkUnisOwned {
+        ChkUnisOwned {
+            config: *unis.config,
+            hit_points: *unis.hit_points,
+            shield_points: *unis.shield_points,
+            armor_points: *unis.armor_points,
+            build_time: *unis.build_time,
+            mineral_cost: *unis.mineral_cost,
+            gas_cost: *unis.gas_cost,
+            string_number: *unis.string_number,
+            base_weapon_damage: *unis.base_weapon_damage,
+            upgrade_bonus_weapon_damage: *unis.upgrade_bonus_weapon_damage,
+        }
+    }
+}
+
+pub(crate) fn parse_unis<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUnis<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::UNIS,
+        });
+    }
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkUnis {
         config: slicer.extract_ref()?,
-        hit_points: slicer.extract_ref()?,
-        shield_points: slicer.extract_ref()?,
+        hit_points: slicer.extract_cow()?,
+        shield_points: slicer.extract_cow()?,
         armor_points: slicer.extract_ref()?,
-        build_time: slicer.extract_ref()?,
-        mineral_cost: slicer.extract_ref()?,
-        gas_cost: slicer.extract_ref()?,
-        string_number: slicer.extract_ref()?,
-        base_weapon_damage: slicer.extract_ref()?,
-        upgrade_bonus_weapon_damage: slicer.extract_ref()?,
+        build_time: slicer.extract_cow()?,
+        mineral_cost: slicer.extract_cow()?,
+        gas_cost: slicer.extract_cow()?,
+        string_number: slicer.extract_cow()?,
+        base_weapon_damage: slicer.extract_cow()?,
+        upgrade_bonus_weapon_damage: slicer.extract_cow()?,
     })
 }
+
+pub(crate) fn serialize_unis(unis: &ChkUnisOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&unis.config);
+    data.extend_from_slice(reinterpret_as_bytes(&unis.hit_points));
+    data.extend_from_slice(reinterpret_as_bytes(&unis.shield_points));
+    data.extend_from_slice(&unis.armor_points);
+    data.extend_from_slice(reinterpret_as_bytes(&unis.build_time));
+    data.extend_from_slice(reinterpret_as_bytes(&unis.mineral_cost));
+    data.extend_from_slice(reinterpret_as_bytes(&unis.gas_cost));
+    data.extend_from_slice(reinterpret_as_bytes(&unis.string_number));
+    data.extend_from_slice(reinterpret_as_bytes(&unis.base_weapon_damage));
+    data.extend_from_slice(reinterpret_as_bytes(&unis.upgrade_bonus_weapon_damage));
+    data
+}
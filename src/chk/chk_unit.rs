@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for all versions and all game types.
@@ -71,20 +76,28 @@ pub struct ChkUnitIndividual {
     pub class_instance_related_to: u32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkUnit {
     pub units: Vec<ChkUnitIndividual>,
 }
 
-pub(crate) fn parse_unit(chunks: &[RiffChunk]) -> Result<ChkUnit, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_unit(chunks: &[RiffChunk]) -> Result<ChkUnit, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::UNIT,
+        });
+    }
 
     let mut units: Vec<ChkUnitIndividual> = Vec::new();
 
     for chunk in chunks {
-        let mut slicer = CursorSlicer::new(chunk.data);
+        let mut slicer = CursorSlicer::new(chunk);
         units.extend_from_slice(slicer.extract_rest_as_slice_lax()?);
     }
 
     Ok(ChkUnit { units })
 }
+
+pub(crate) fn serialize_unit(unit: &ChkUnit) -> Vec<u8> {
+    reinterpret_as_bytes(unit.units.as_slice()).to_vec()
+}
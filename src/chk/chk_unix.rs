@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for Hybrid (in Expansion mode) and Brood War. Not required for Melee.
@@ -7,7 +12,7 @@ use serde::Serialize;
 
 // This section is indentical to UNIS section except it uses the Brood War set of 130 weapons instead of the original 100.
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct ChkUnix {
     #[serde(skip_serializing)]
     pub config: [u8; 228],
@@ -31,10 +36,14 @@ pub struct ChkUnix {
     pub upgrade_bonus_weapon_damage: [u16; 130],
 }
 
-pub(crate) fn parse_unix(chunks: &[RiffChunk]) -> Result<ChkUnix, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+pub(crate) fn parse_unix(chunks: &[RiffChunk]) -> Result<ChkUnix, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::UNIx,
+        });
+    }
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkUnix {
         config: slicer.extract()?,
@@ -49,3 +58,18 @@ pub(crate) fn parse_unix(chunks: &[RiffChunk]) -> Result<ChkUnix, anyhow::Error>
         upgrade_bonus_weapon_damage: slicer.extract()?,
     })
 }
+
+pub(crate) fn serialize_unix(unix: &ChkUnix) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&unix.config);
+    data.extend_from_slice(reinterpret_as_bytes(&unix.hit_points));
+    data.extend_from_slice(reinterpret_as_bytes(&unix.shield_points));
+    data.extend_from_slice(&unix.armor_points);
+    data.extend_from_slice(reinterpret_as_bytes(&unix.build_time));
+    data.extend_from_slice(reinterpret_as_bytes(&unix.mineral_cost));
+    data.extend_from_slice(reinterpret_as_bytes(&unix.gas_cost));
+    data.extend_from_slice(reinterpret_as_bytes(&unix.string_number));
+    data.extend_from_slice(reinterpret_as_bytes(&unix.base_weapon_damage));
+    data.extend_from_slice(reinterpret_as_bytes(&unix.upgrade_bonus_weapon_damage));
+    data
+}
@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for Vanilla and Hybrid (in Original mode). Not required for Melee.
@@ -30,10 +35,40 @@ pub struct ChkUpgr<'a> {
     pub player_uses_upgrade_defaults: &'a [[u8; 46]; 12],
 }
 
-pub(crate) fn parse_upgr<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUpgr<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkUpgrOwned {
+    #[serde(skip_serializing)]
+    pub max_upgrade_level: [[u8; 46]; 12],
+    #[serde(skip_serializing)]
+    pub starting_upgrade_level: [[u8; 46]; 12],
+    #[serde(skip)]
+    pub global_default_maximum_upgrade_level: [u8; 46],
+    #[serde(skip)]
+    pub global_default_starting_upgrade_level: [u8; 46],
+    #[serde(skip_serializing)]
+    pub player_uses_upgrade_defaults: [[u8; 46]; 12],
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+impl From<&ChkUpgr<'_>> for ChkUpgrOwned {
+    fn from(upgr: &ChkUpgr<'_>) -> ChkUpgrOwned {
+        ChkUpgrOwned {
+            max_upgrade_level: *upgr.max_upgrade_level,
+            starting_upgrade_level: *upgr.starting_upgrade_level,
+            global_default_maximum_upgrade_level: *upgr.global_default_maximum_upgrade_level,
+            global_default_starting_upgrade_level: *upgr.global_default_starting_upgrade_level,
+            player_uses_upgrade_defaults: *upgr.player_uses_upgrade_defaults,
+        }
+    }
+}
+
+pub(crate) fn parse_upgr<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUpgr<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::UPGR,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkUpgr {
         max_upgrade_level: slicer.extract_ref()?,
@@ -43,3 +78,13 @@ pub(crate) fn parse_upgr<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUpgr<'a>, an
         player_uses_upgrade_defaults: slicer.extract_ref()?,
     })
 }
+
+pub(crate) fn serialize_upgr(upgr: &ChkUpgrOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(reinterpret_as_bytes(&upgr.max_upgrade_level));
+    data.extend_from_slice(reinterpret_as_bytes(&upgr.starting_upgrade_level));
+    data.extend_from_slice(&upgr.global_default_maximum_upgrade_level);
+    data.extend_from_slice(&upgr.global_default_starting_upgrade_level);
+    data.extend_from_slice(reinterpret_as_bytes(&upgr.player_uses_upgrade_defaults));
+    data
+}
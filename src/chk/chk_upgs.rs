@@ -1,5 +1,11 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Required for Vanilla and Hybrid (in Original mode). Not required for Melee.
 // Validation: Must be size of 598 bytes.
@@ -23,31 +29,79 @@ pub struct ChkUpgs<'a> {
     #[serde(skip_serializing)]
     pub upgrade_uses_default_setings: &'a [u8; 46],
     #[serde(skip_serializing)]
-    pub base_mineral_cost: &'a [u16; 46],
+    pub base_mineral_cost: Cow<'a, [u16; 46]>,
     #[serde(skip_serializing)]
-    pub mineral_cost_factor: &'a [u16; 46],
+    pub mineral_cost_factor: Cow<'a, [u16; 46]>,
     #[serde(skip_serializing)]
-    pub base_gas_cost: &'a [u16; 46],
+    pub base_gas_cost: Cow<'a, [u16; 46]>,
     #[serde(skip_serializing)]
-    pub gas_cost_factor: &'a [u16; 46],
+    pub gas_cost_factor: Cow<'a, [u16; 46]>,
     #[serde(skip_serializing)]
-    pub base_time: &'a [u16; 46],
+    pub base_time: Cow<'a, [u16; 46]>,
     #[serde(skip_serializing)]
-    pub time_factor: &'a [u16; 46],
+    pub time_factor: Cow<'a, [u16; 46]>,
 }
 
-pub(crate) fn parse_upgs<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUpgs<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkUpgsOwned {
+    #[serde(skip_serializing)]
+    pub upgrade_uses_default_setings: [u8; 46],
+    #[serde(skip_serializing)]
+    pub base_mineral_cost: [u16; 46],
+    #[serde(skip_serializing)]
+    pub mineral_cost_factor: [u16; 46],
+    #[serde(skip_serializing)]
+    pub base_gas_cost: [u16; 46],
+    #[serde(skip_serializing)]
+    pub gas_cost_factor: [u16; 46],
+    #[serde(skip_serializing)]
+    pub base_time: [u16; 46],
+    #[serde(skip_serializing)]
+    pub time_factor: [u16; 46],
+}
+
+impl From<&ChkUpgs<'_>> for ChkUpgsOwned {
+    fn from(upgs: &ChkUpgs<'_>) -> ChkUpgsOwned {
+        ChkUpgsOwned {
+            upgrade_uses_default_setings: *upgs.upgrade_uses_default_setings,
+            base_mineral_cost: *upgs.base_mineral_cost,
+            mineral_cost_factor: *upgs.mineral_cost_factor,
+            base_gas_cost: *upgs.base_gas_cost,
+            gas_cost_factor: *upgs.gas_cost_factor,
+            base_time: *upgs.base_time,
+            time_factor: *upgs.time_factor,
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_upgs<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUpgs<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::UPGS,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkUpgs {
         upgrade_uses_default_setings: slicer.extract_ref()?,
-        base_mineral_cost: slicer.extract_ref()?,
-        mineral_cost_factor: slicer.extract_ref()?,
-        base_gas_cost: slicer.extract_ref()?,
-        gas_cost_factor: slicer.extract_ref()?,
-        base_time: slicer.extract_ref()?,
-        time_factor: slicer.extract_ref()?,
+        base_mineral_cost: slicer.extract_cow()?,
+        mineral_cost_factor: slicer.extract_cow()?,
+        base_gas_cost: slicer.extract_cow()?,
+        gas_cost_factor: slicer.extract_cow()?,
+        base_time: slicer.extract_cow()?,
+        time_factor: slicer.extract_cow()?,
     })
 }
+
+pub(crate) fn serialize_upgs(upgs: &ChkUpgsOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&upgs.upgrade_uses_default_setings);
+    data.extend_from_slice(reinterpret_as_bytes(&upgs.base_mineral_cost));
+    data.extend_from_slice(reinterpret_as_bytes(&upgs.mineral_cost_factor));
+    data.extend_from_slice(reinterpret_as_bytes(&upgs.base_gas_cost));
+    data.extend_from_slice(reinterpret_as_bytes(&upgs.gas_cost_factor));
+    data.extend_from_slice(reinterpret_as_bytes(&upgs.base_time));
+    data.extend_from_slice(reinterpret_as_bytes(&upgs.time_factor));
+    data
+}
@@ -1,5 +1,11 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Required for Hybrid (in Expansion mode) and Brood War. Not required for Melee.
 // Validation: Must be size of 794 bytes.
@@ -24,32 +30,83 @@ pub struct ChkUpgx<'a> {
     pub upgrade_uses_default_setings: &'a [u8; 61],
     pub unused: &'a u8,
     #[serde(skip_serializing)]
-    pub base_mineral_cost: &'a [u16; 61],
+    pub base_mineral_cost: Cow<'a, [u16; 61]>,
     #[serde(skip_serializing)]
-    pub mineral_cost_factor: &'a [u16; 61],
+    pub mineral_cost_factor: Cow<'a, [u16; 61]>,
     #[serde(skip_serializing)]
-    pub base_gas_cost: &'a [u16; 61],
+    pub base_gas_cost: Cow<'a, [u16; 61]>,
     #[serde(skip_serializing)]
-    pub gas_cost_factor: &'a [u16; 61],
+    pub gas_cost_factor: Cow<'a, [u16; 61]>,
     #[serde(skip_serializing)]
-    pub base_time: &'a [u16; 61],
+    pub base_time: Cow<'a, [u16; 61]>,
     #[serde(skip_serializing)]
-    pub time_factor: &'a [u16; 61],
+    pub time_factor: Cow<'a, [u16; 61]>,
 }
 
-pub(crate) fn parse_upgx<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUpgx<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkUpgxOwned {
+    #[serde(skip_serializing)]
+    pub upgrade_uses_default_setings: [u8; 61],
+    pub unused: u8,
+    #[serde(skip_serializing)]
+    pub base_mineral_cost: [u16; 61],
+    #[serde(skip_serializing)]
+    pub mineral_cost_factor: [u16; 61],
+    #[serde(skip_serializing)]
+    pub base_gas_cost: [u16; 61],
+    #[serde(skip_serializing)]
+    pub gas_cost_factor: [u16; 61],
+    #[serde(skip_serializing)]
+    pub base_time: [u16; 61],
+    #[serde(skip_serializing)]
+    pub time_factor: [u16; 61],
+}
+
+impl From<&ChkUpgx<'_>> for ChkUpgxOwned {
+    fn from(upgx: &ChkUpgx<'_>) -> ChkUpgxOwned {
+        ChkUpgxOwned {
+            upgrade_uses_default_setings: *upgx.upgrade_uses_default_setings,
+            unused: *upgx.unused,
+            base_mineral_cost: *upgx.base_mineral_cost,
+            mineral_cost_factor: *upgx.mineral_cost_factor,
+            base_gas_cost: *upgx.base_gas_cost,
+            gas_cost_factor: *upgx.gas_cost_factor,
+            base_time: *upgx.base_time,
+            time_factor: *upgx.time_factor,
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_upgx<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUpgx<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::UPGx,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkUpgx {
         upgrade_uses_default_setings: slicer.extract_ref()?,
         unused: slicer.extract_ref()?,
-        base_mineral_cost: slicer.extract_ref()?,
-        mineral_cost_factor: slicer.extract_ref()?,
-        base_gas_cost: slicer.extract_ref()?,
-        gas_cost_factor: slicer.extract_ref()?,
-        base_time: slicer.extract_ref()?,
-        time_factor: slicer.extract_ref()?,
+        base_mineral_cost: slicer.extract_cow()?,
+        mineral_cost_factor: slicer.extract_cow()?,
+        base_gas_cost: slicer.extract_cow()?,
+        gas_cost_factor: slicer.extract_cow()?,
+        base_time: slicer.extract_cow()?,
+        time_factor: slicer.extract_cow()?,
     })
 }
+
+pub(crate) fn serialize_upgx(upgx: &ChkUpgxOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&upgx.upgrade_uses_default_setings);
+    data.push(upgx.unused);
+    data.extend_from_slice(reinterpret_as_bytes(&upgx.base_mineral_cost));
+    data.extend_from_slice(reinterpret_as_bytes(&upgx.mineral_cost_factor));
+    data.extend_from_slice(reinterpret_as_bytes(&upgx.base_gas_cost));
+    data.extend_from_slice(reinterpret_as_bytes(&upgx.gas_cost_factor));
+    data.extend_from_slice(reinterpret_as_bytes(&upgx.base_time));
+    data.extend_from_slice(reinterpret_as_bytes(&upgx.time_factor));
+    data
+}
@@ -1,4 +1,9 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
 
 // Required for all versions. Not required for Melee.
@@ -58,12 +63,33 @@ pub struct ChkUprp<'a> {
     pub cuwp_slots: &'a [ChkUprpIndividual],
 }
 
-pub(crate) fn parse_uprp<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUprp<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkUprpOwned {
+    pub cuwp_slots: Vec<ChkUprpIndividual>,
+}
+
+impl From<&ChkUprp<'_>> for ChkUprpOwned {
+    fn from(uprp: &ChkUprp<'_>) -> ChkUprpOwned {
+        ChkUprpOwned {
+            cuwp_slots: uprp.cuwp_slots.to_vec(),
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_uprp<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUprp<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::UPRP,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkUprp {
         cuwp_slots: slicer.extract_rest_as_slice_lax()?,
     })
 }
+
+pub(crate) fn serialize_uprp(uprp: &ChkUprpOwned) -> Vec<u8> {
+    reinterpret_as_bytes(uprp.cuwp_slots.as_slice()).to_vec()
+}
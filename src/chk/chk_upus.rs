@@ -1,4 +1,4 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
 
 // Not Required.
@@ -14,12 +14,33 @@ pub struct ChkUpus<'a> {
     pub cuwp_slot_is_used: &'a [u8],
 }
 
-pub(crate) fn parse_upus<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUpus<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkUpusOwned {
+    pub cuwp_slot_is_used: Vec<u8>,
+}
+
+impl From<&ChkUpus<'_>> for ChkUpusOwned {
+    fn from(upus: &ChkUpus<'_>) -> ChkUpusOwned {
+        ChkUpusOwned {
+            cuwp_slot_is_used: upus.cuwp_slot_is_used.to_vec(),
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_upus<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkUpus<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::UPUS,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkUpus {
         cuwp_slot_is_used: slicer.extract_rest_as_slice_lax()?,
     })
 }
+
+pub(crate) fn serialize_upus(upus: &ChkUpusOwned) -> Vec<u8> {
+    upus.cuwp_slot_is_used.clone()
+}
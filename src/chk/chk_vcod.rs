@@ -1,5 +1,11 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Required for all versions and all game types.
 // Validation: Must be equal to size of the VCOD data in the EXE resource. Must result in valid checksum.
@@ -23,17 +29,44 @@ use serde::Serialize;
 #[derive(Debug, Serialize)]
 pub struct ChkVcod<'a> {
     #[serde(skip_serializing)]
-    pub seed_values: &'a [u32; 256],
+    pub seed_values: Cow<'a, [u32; 256]>,
     pub hash: &'a [u8; 16],
 }
 
-pub(crate) fn parse_vcod<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkVcod<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkVcodOwned {
+    #[serde(skip_serializing)]
+    pub seed_values: [u32; 256],
+    pub hash: [u8; 16],
+}
+
+impl From<&ChkVcod<'_>> for ChkVcodOwned {
+    fn from(vcod: &ChkVcod<'_>) -> ChkVcodOwned {
+        ChkVcodOwned {
+            seed_values: *vcod.seed_values,
+            hash: *vcod.hash,
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_vcod<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkVcod<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::VCOD,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkVcod {
-        seed_values: slicer.extract_ref()?,
+        seed_values: slicer.extract_cow()?,
         hash: slicer.extract_ref()?,
     })
 }
+
+pub(crate) fn serialize_vcod(vcod: &ChkVcodOwned) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(reinterpret_as_bytes(&vcod.seed_values));
+    data.extend_from_slice(&vcod.hash);
+    data
+}
@@ -1,5 +1,6 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk, util::CursorSlicer};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Required for all versions and all game types.
 // Validation: Must be size of 2 bytes.
@@ -25,15 +26,36 @@ use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 pub struct ChkVer<'a> {
-    pub file_format_version: &'a u16,
+    pub file_format_version: Cow<'a, u16>,
 }
 
-pub(crate) fn parse_ver<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkVer<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkVerOwned {
+    pub file_format_version: u16,
+}
+
+impl From<&ChkVer<'_>> for ChkVerOwned {
+    fn from(ver: &ChkVer<'_>) -> ChkVerOwned {
+        ChkVerOwned {
+            file_format_version: *ver.file_format_version,
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_ver<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkVer<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::VER,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkVer {
-        file_format_version: slicer.extract_ref()?,
+        file_format_version: slicer.extract_cow()?,
     })
 }
+
+pub(crate) fn serialize_ver(ver: &ChkVerOwned) -> Vec<u8> {
+    ver.file_format_version.to_le_bytes().to_vec()
+}
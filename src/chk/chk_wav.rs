@@ -1,5 +1,11 @@
-use crate::{riff::RiffChunk, util::CursorSlicer};
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::RiffChunk,
+    util::{reinterpret_as_bytes, CursorSlicer},
+};
 use serde::Serialize;
+use std::borrow::Cow;
 
 // Not Required.
 
@@ -9,15 +15,36 @@ use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 pub struct ChkWav<'a> {
-    pub wav_string_number: &'a [u32],
+    pub wav_string_number: Cow<'a, [u32]>,
 }
 
-pub(crate) fn parse_wav<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkWav<'a>, anyhow::Error> {
-    anyhow::ensure!(chunks.len() > 0);
+#[derive(Debug, Serialize, Clone)]
+pub struct ChkWavOwned {
+    pub wav_string_number: Vec<u32>,
+}
+
+impl From<&ChkWav<'_>> for ChkWavOwned {
+    fn from(wav: &ChkWav<'_>) -> ChkWavOwned {
+        ChkWavOwned {
+            wav_string_number: wav.wav_string_number.to_vec(),
+        }
+    }
+}
 
-    let mut slicer = CursorSlicer::new(chunks[chunks.len() - 1].data);
+pub(crate) fn parse_wav<'a>(chunks: &[RiffChunk<'a>]) -> Result<ChkWav<'a>, ChkError> {
+    if chunks.is_empty() {
+        return Err(ChkError::MissingSection {
+            chunk_name: ChunkName::WAV,
+        });
+    }
+
+    let mut slicer = CursorSlicer::new(&chunks[chunks.len() - 1]);
 
     Ok(ChkWav {
-        wav_string_number: slicer.extract_rest_as_slice_lax()?,
+        wav_string_number: slicer.extract_rest_as_cow_slice_lax()?,
     })
 }
+
+pub(crate) fn serialize_wav(wav: &ChkWavOwned) -> Vec<u8> {
+    reinterpret_as_bytes(wav.wav_string_number.as_slice()).to_vec()
+}
@@ -8,10 +8,12 @@ pub mod chk_iown;
 pub mod chk_isom;
 pub mod chk_ive2;
 pub mod chk_iver;
+pub mod chk_kstr;
 pub mod chk_mask;
 pub mod chk_mbrf;
 pub mod chk_mrgn;
 pub mod chk_mtxm;
+pub mod chk_ostr;
 pub mod chk_ownr;
 pub mod chk_ptec;
 pub mod chk_ptex;
@@ -39,3 +41,47 @@ pub mod chk_upus;
 pub mod chk_vcod;
 pub mod chk_ver;
 pub mod chk_wav;
+
+pub use chk_colr::ChkColr;
+pub use chk_crgb::ChkCrgb;
+pub use chk_dd2::{ChkDd2, ChkDd2Individual};
+pub use chk_dim::{ChkDim, ChkDimOwned};
+pub use chk_era::{ChkEra, ChkEraOwned};
+pub use chk_forc::ChkForc;
+pub use chk_iown::ChkIown;
+pub use chk_isom::ChkIsom;
+pub use chk_ive2::{ChkIve2, ChkIve2Owned};
+pub use chk_iver::{ChkIver, ChkIverOwned};
+pub use chk_kstr::ChkKstr;
+pub use chk_mask::{ChkMask, ChkMaskOwned};
+pub use chk_mbrf::{ChkMbrf, ChkMbrfAction, ChkMbrfCondition, ChkMbrfIndividual};
+pub use chk_mrgn::{ChkMrgn, ChkMrgnIndividual, ChkMrgnOwned};
+pub use chk_mtxm::ChkMtxm;
+pub use chk_ostr::ChkOstr;
+pub use chk_ownr::ChkOwnr;
+pub use chk_ptec::{ChkPtec, ChkPtecOwned};
+pub use chk_ptex::{ChkPtex, ChkPtexOwned};
+pub use chk_puni::{ChkPuni, ChkPuniOwned};
+pub use chk_pupx::{ChkPupx, ChkPupxOwned};
+pub use chk_side::ChkSide;
+pub use chk_sprp::{ChkSprp, ChkSprpOwned};
+pub use chk_str::{ChkStr, ChkStr2};
+pub use chk_strx::{ChkStrx, ChkStrx2};
+pub use chk_swnm::{ChkSwnm, ChkSwnmOwned};
+pub use chk_tecs::{ChkTecs, ChkTecsOwned};
+pub use chk_tecx::{ChkTecx, ChkTecxOwned};
+pub use chk_thg2::{ChkThg2, ChkThg2Individual};
+pub use chk_tile::ChkTile;
+pub use chk_trig::{ChkTrig, ChkTrigAction, ChkTrigCondition, ChkTrigIndividual};
+pub use chk_type::{ChkType, ChkTypeOwned};
+pub use chk_unis::{ChkUnis, ChkUnisOwned};
+pub use chk_unit::{ChkUnit, ChkUnitIndividual};
+pub use chk_unix::ChkUnix;
+pub use chk_upgr::{ChkUpgr, ChkUpgrOwned};
+pub use chk_upgs::{ChkUpgs, ChkUpgsOwned};
+pub use chk_upgx::{ChkUpgx, ChkUpgxOwned};
+pub use chk_uprp::{ChkUprp, ChkUprpIndividual, ChkUprpOwned};
+pub use chk_upus::{ChkUpus, ChkUpusOwned};
+pub use chk_vcod::{ChkVcod, ChkVcodOwned};
+pub use chk_ver::{ChkVer, ChkVerOwned};
+pub use chk_wav::{ChkWav, ChkWavOwned};
@@ -45,6 +45,8 @@ pub enum ChunkName {
     UNIx,
     UPGx,
     TECx,
+    OSTR,
+    KSTR,
     UNKNOWN(String),
 }
 
@@ -92,6 +94,66 @@ pub(crate) fn parse_chunk_name(chunk_name: &[u8]) -> ChunkName {
         b"UNIx" => ChunkName::UNIx,
         b"UPGx" => ChunkName::UPGx,
         b"TECx" => ChunkName::TECx,
+        b"OSTR" => ChunkName::OSTR,
+        b"KSTR" => ChunkName::KSTR,
         _ => ChunkName::UNKNOWN(encoding_rs::WINDOWS_1252.decode(chunk_name).0.to_string()),
     }
 }
+
+#[instrument(level = "trace", skip_all)]
+pub(crate) fn serialize_chunk_name(chunk_name: &ChunkName) -> [u8; 4] {
+    match chunk_name {
+        ChunkName::TYPE => *b"TYPE",
+        ChunkName::VER => *b"VER ",
+        ChunkName::IVER => *b"IVER",
+        ChunkName::IVE2 => *b"IVE2",
+        ChunkName::VCOD => *b"VCOD",
+        ChunkName::IOWN => *b"IOWN",
+        ChunkName::OWNR => *b"OWNR",
+        ChunkName::ERA => *b"ERA ",
+        ChunkName::DIM => *b"DIM ",
+        ChunkName::SIDE => *b"SIDE",
+        ChunkName::MTXM => *b"MTXM",
+        ChunkName::PUNI => *b"PUNI",
+        ChunkName::UPGR => *b"UPGR",
+        ChunkName::PTEC => *b"PTEC",
+        ChunkName::UNIT => *b"UNIT",
+        ChunkName::ISOM => *b"ISOM",
+        ChunkName::TILE => *b"TILE",
+        ChunkName::DD2 => *b"DD2 ",
+        ChunkName::THG2 => *b"THG2",
+        ChunkName::MASK => *b"MASK",
+        ChunkName::STR => *b"STR ",
+        ChunkName::STRx => *b"STRx",
+        ChunkName::UPRP => *b"UPRP",
+        ChunkName::UPUS => *b"UPUS",
+        ChunkName::MRGN => *b"MRGN",
+        ChunkName::TRIG => *b"TRIG",
+        ChunkName::MBRF => *b"MBRF",
+        ChunkName::SPRP => *b"SPRP",
+        ChunkName::FORC => *b"FORC",
+        ChunkName::WAV => *b"WAV ",
+        ChunkName::UNIS => *b"UNIS",
+        ChunkName::UPGS => *b"UPGS",
+        ChunkName::TECS => *b"TECS",
+        ChunkName::SWNM => *b"SWNM",
+        ChunkName::COLR => *b"COLR",
+        ChunkName::CRGB => *b"CRGB",
+        ChunkName::PUPx => *b"PUPx",
+        ChunkName::PTEx => *b"PTEx",
+        ChunkName::UNIx => *b"UNIx",
+        ChunkName::UPGx => *b"UPGx",
+        ChunkName::TECx => *b"TECx",
+        ChunkName::OSTR => *b"OSTR",
+        ChunkName::KSTR => *b"KSTR",
+        ChunkName::UNKNOWN(name) => {
+            // Unknown names were decoded as WINDOWS_1252 so encode them back the same way.
+            let mut ret = [b' '; 4];
+            let encoded = encoding_rs::WINDOWS_1252.encode(name).0;
+            for (dst, src) in ret.iter_mut().zip(encoded.iter()) {
+                *dst = *src;
+            }
+            ret
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use encoding_rs::Encoding;
+use serde::Serialize;
+use tracing::instrument;
+
+// Color codes and other control characters say nothing about the encoding and throw the detectors off.
+fn strip_control_characters(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().filter(|&&x| x >= 0x20).copied().collect()
+}
+
+fn is_cp949_lead(x: u8) -> bool {
+    (0x81..=0xFE).contains(&x)
+}
+
+fn is_cp949_trail(x: u8) -> bool {
+    matches!(x, 0x41..=0x5A | 0x61..=0x7A | 0x81..=0xFE)
+}
+
+// The 2350 Hangul syllables of KS X 1001, which is where nearly all the Korean text in maps comes from. The
+// rest of CP949 is hanja, symbols and the rarely used syllables of the UHC extension, and those are what
+// Windows-1252 text such as "ÐúSt" or "§tack" turns into when it is decoded as CP949.
+fn is_ks_x_1001_hangul(lead: u8, trail: u8) -> bool {
+    (0xB0..=0xC8).contains(&lead) && (0xA1..=0xFE).contains(&trail)
+}
+
+// A pure Rust guess between the three encodings get_string chooses from: UTF-8, EUC-KR (CP949) and
+// Windows-1252.
+//
+// Strings without any bytes above 0x7F are Windows-1252. Valid UTF-8 with bytes above 0x7F is UTF-8, since
+// text in the other two encodings almost never happens to be valid UTF-8. Otherwise the bytes above 0x7F
+// are split into the ones that form KS X 1001 Hangul syllables when read as CP949 and the rest, and the
+// string is EUC-KR if most of them are Hangul. The guess is reliable when the split is lopsided.
+#[instrument(level = "trace", skip_all)]
+pub(crate) fn detect_encoding(bytes: &[u8]) -> (&'static Encoding, bool) {
+    let bytes = strip_control_characters(bytes);
+
+    if bytes.iter().all(|&x| x < 0x80) {
+        return (encoding_rs::WINDOWS_1252, true);
+    }
+
+    if std::str::from_utf8(bytes.as_slice()).is_ok() {
+        return (encoding_rs::UTF_8, true);
+    }
+
+    let mut hangul_bytes = 0;
+    let mut other_bytes = 0;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let lead = bytes[i];
+
+        if lead < 0x80 {
+            i += 1;
+        } else if i + 1 < bytes.len() && is_cp949_lead(lead) && is_cp949_trail(bytes[i + 1]) {
+            if is_ks_x_1001_hangul(lead, bytes[i + 1]) {
+                hangul_bytes += 2;
+            } else {
+                other_bytes += 2;
+            }
+
+            i += 2;
+        } else {
+            other_bytes += 1;
+            i += 1;
+        }
+    }
+
+    let hangul_ratio = hangul_bytes as f64 / (hangul_bytes + other_bytes) as f64;
+
+    if hangul_ratio >= 0.5 {
+        (encoding_rs::EUC_KR, hangul_ratio >= 0.9)
+    } else {
+        (encoding_rs::WINDOWS_1252, hangul_ratio <= 0.1)
+    }
+}
+
+// The encodings get_string chooses between.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringEncoding {
+    Utf8,
+    EucKr,
+    Windows1252,
+}
+
+impl StringEncoding {
+    pub fn encoding(&self) -> &'static Encoding {
+        match self {
+            StringEncoding::Utf8 => encoding_rs::UTF_8,
+            StringEncoding::EucKr => encoding_rs::EUC_KR,
+            StringEncoding::Windows1252 => encoding_rs::WINDOWS_1252,
+        }
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        self.encoding().decode(bytes).0.into_owned()
+    }
+
+    fn from_encoding(encoding: &'static Encoding) -> StringEncoding {
+        if encoding == encoding_rs::UTF_8 {
+            StringEncoding::Utf8
+        } else if encoding == encoding_rs::EUC_KR {
+            StringEncoding::EucKr
+        } else {
+            StringEncoding::Windows1252
+        }
+    }
+}
+
+// Votes on the encoding of a single string. Returns the encodings the string decodes without errors in,
+// best first, with their scores. Windows-1252 can decode anything so the result is never empty.
+#[instrument(level = "trace", skip_all)]
+pub(crate) fn score_encodings(bytes: &[u8]) -> Vec<(StringEncoding, f64)> {
+    if bytes.is_empty() {
+        return vec![(StringEncoding::Windows1252, 0.0)];
+    }
+
+    let mut euc_kr_failed = false;
+    let mut euc_kr_characters_decoded_successfully: i64 = 0;
+    let euc_kr_characters_len: usize;
+    let mut utf8_failed = false;
+    let mut utf8_characters_decoded_successfully: i64 = 0;
+    let utf8_characters_len: usize;
+
+    let mut win1252_characters_7f_or_above: i64 = 0;
+
+    {
+        let conversion = encoding_rs::EUC_KR.decode(bytes);
+        // println!("EUC_KR: {}", conversion.0);
+        euc_kr_characters_len = conversion.0.chars().count();
+
+        if conversion.2 {
+            euc_kr_failed = true;
+        } else {
+            euc_kr_characters_decoded_successfully += conversion
+                .0
+                .chars()
+                .filter(|&c| ('가'..='힣').contains(&c))
+                .count() as i64;
+        }
+    }
+
+    {
+        let conversion = encoding_rs::UTF_8.decode(bytes);
+        // println!("UTF-8: {}", conversion.0);
+        utf8_characters_len = conversion.0.chars().count();
+
+        if conversion.2 {
+            utf8_failed = true;
+        } else {
+            utf8_characters_decoded_successfully +=
+                conversion.0.chars().filter(|&c| c >= '\u{7f}').count() as i64;
+        }
+    }
+
+    {
+        let conversion = encoding_rs::WINDOWS_1252.decode(bytes);
+        // println!("WIN1252: {}", conversion.0);
+        win1252_characters_7f_or_above +=
+            conversion.0.chars().filter(|&c| c >= '\u{7f}').count() as i64;
+    }
+
+    let mut encoding_map = std::collections::HashMap::new();
+
+    encoding_map.insert(encoding_rs::UTF_8, 0.0);
+    encoding_map.insert(encoding_rs::EUC_KR, 0.0);
+    encoding_map.insert(encoding_rs::WINDOWS_1252, 0.0);
+
+    let (detected_encoding, detected_encoding_is_reliable) = detect_encoding(bytes);
+    *encoding_map.get_mut(detected_encoding).unwrap() += if detected_encoding_is_reliable {
+        0.7
+    } else {
+        0.2
+    };
+
+    #[cfg(feature = "uchardet-bindings")]
+    if let Some(uchardet_guessed_encoding) = uchardet_guess(bytes) {
+        *encoding_map.get_mut(uchardet_guessed_encoding).unwrap() += 0.7;
+    }
+
+    #[cfg(feature = "compact_enc_det-bindings")]
+    if let (Some(compact_enc_det_encoding_guess), compact_enc_det_encoding_guess_is_reliable) =
+        compact_enc_det_guess(bytes)
+    {
+        *encoding_map
+            .get_mut(compact_enc_det_encoding_guess)
+            .unwrap() += if compact_enc_det_encoding_guess_is_reliable {
+            0.7
+        } else {
+            0.2
+        };
+    }
+
+    *encoding_map.get_mut(encoding_rs::EUC_KR).unwrap() +=
+        (euc_kr_characters_decoded_successfully as f64) / (euc_kr_characters_len as f64);
+
+    *encoding_map.get_mut(encoding_rs::WINDOWS_1252).unwrap() -=
+        (win1252_characters_7f_or_above as f64) / (bytes.len() as f64);
+
+    *encoding_map.get_mut(encoding_rs::UTF_8).unwrap() +=
+        (utf8_characters_decoded_successfully as f64) / (utf8_characters_len as f64);
+
+    // println!(
+    //     "\n\
+    //     euc_kr_failed: {euc_kr_failed}, \
+    //     euc_kr_characters_decoded_successfully: {euc_kr_characters_decoded_successfully}, \
+    //     euc_kr_characters_len: {euc_kr_characters_len}, \
+    //     utf8_failed: {utf8_failed}, \
+    //     utf8_characters_decoded_successfully: {utf8_characters_decoded_successfully}, \
+    //     utf8_characters_len: {utf8_characters_len}, \
+    //     win1252_characters_7f_or_above: {win1252_characters_7f_or_above}, \
+    //     win1252_total_characters: {win1252_total_characters}, \
+    //     \n\
+    //     uchardet_guess: {uchardet_guessed_encoding:?}, \
+    //     \n\
+    //     compact_enc_det_encoding_guess: {compact_enc_det_encoding_guess:?}, \
+    //     \n\
+    //     encoding_map: {encoding_map:?}, str: {}\n\
+    //     ------------------------------------------------------------------------------------",
+    //     encoding_rs::WINDOWS_1252.decode(bytes).0
+    // );
+
+    if euc_kr_failed {
+        encoding_map.remove(encoding_rs::EUC_KR);
+    }
+
+    if utf8_failed {
+        encoding_map.remove(encoding_rs::UTF_8);
+    }
+
+    let mut encodings: Vec<_> = encoding_map.drain().collect();
+
+    encodings.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap().reverse());
+
+    encodings
+        .into_iter()
+        .map(|(encoding, score)| (StringEncoding::from_encoding(encoding), score))
+        .collect()
+}
+
+#[cfg(feature = "uchardet-bindings")]
+#[instrument(level = "trace", skip_all)]
+pub(crate) fn uchardet_guess(bytes: &[u8]) -> Option<&'static Encoding> {
+    let vec = strip_control_characters(bytes);
+
+    let charset = unsafe {
+        let handle = uchardet_bindings::uchardet_new();
+        scopeguard::defer! {
+            uchardet_bindings::uchardet_delete(handle);
+        }
+
+        if uchardet_bindings::uchardet_handle_data(handle, vec.as_ptr() as *const i8, vec.len())
+            != 0
+        {
+            panic!();
+        }
+
+        uchardet_bindings::uchardet_data_end(handle);
+
+        std::ffi::CStr::from_ptr(uchardet_bindings::uchardet_get_charset(handle))
+            .to_str()
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    match charset.as_str() {
+        "UTF-8" => Some(encoding_rs::UTF_8),
+        "UHC" => Some(encoding_rs::EUC_KR),
+        "ASCII" | "ISO-8859-7" | "ISO-8859-2" | "WINDOWS-1252" | "WINDOWS-1250"
+        | "MAC-CENTRALEUROPE" | "WINDOWS-1257" | "ISO-8859-10" | "ISO-8859-1" => {
+            Some(encoding_rs::WINDOWS_1252)
+        }
+        "" | "IBM852" | "VISCII" | "ISO-8859-13" | "ISO-8859-9" | "ISO-8859-3" => None,
+        _ => {
+            None
+            //panic!("{charset}")
+        }
+    }
+}
+
+#[cfg(feature = "compact_enc_det-bindings")]
+#[instrument(level = "trace", skip_all)]
+pub(crate) fn compact_enc_det_guess(bytes: &[u8]) -> (Option<&'static Encoding>, bool) {
+    let vec = strip_control_characters(bytes);
+
+    let mut bytes_consumed = 0;
+    let mut is_reliable = false;
+
+    let encoding = unsafe {
+        compact_enc_det_bindings::CompactEncDet_DetectEncoding(
+            vec.as_ptr() as *const i8,
+            vec.len() as i32,
+            std::ptr::null::<i8>(),
+            std::ptr::null::<i8>(),
+            std::ptr::null::<i8>(),
+            compact_enc_det_bindings::Encoding_UNKNOWN_ENCODING as i32,
+            compact_enc_det_bindings::Language_UNKNOWN_LANGUAGE,
+            compact_enc_det_bindings::CompactEncDet_TextCorpusType_WEB_CORPUS,
+            true,
+            &mut bytes_consumed,
+            &mut is_reliable,
+        )
+    };
+
+    (
+        match encoding {
+            compact_enc_det_bindings::Encoding_UTF8 => Some(encoding_rs::UTF_8),
+            compact_enc_det_bindings::Encoding_KOREAN_EUC_KR => Some(encoding_rs::EUC_KR),
+            compact_enc_det_bindings::Encoding_ISO_8859_1
+            | compact_enc_det_bindings::Encoding_MSFT_CP1252
+            | compact_enc_det_bindings::Encoding_ASCII_7BIT => Some(encoding_rs::WINDOWS_1252),
+            compact_enc_det_bindings::Encoding_CHINESE_GB
+            | compact_enc_det_bindings::Encoding_CHINESE_BIG5
+            | compact_enc_det_bindings::Encoding_JAPANESE_EUC_JP => None,
+            _ => {
+                None
+                //panic!("encoding panic'd on: {encoding}")
+            }
+        },
+        is_reliable,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::detect_encoding;
+
+    fn guess(bytes: &[u8]) -> &'static str {
+        detect_encoding(bytes).0.name()
+    }
+
+    #[test]
+    fn detects_korean() {
+        let (bytes, _, _) =
+            encoding_rs::EUC_KR.encode("\u{4}도라에몽\u{4} 의\u{4} \u{4}S\u{5}unken ");
+
+        assert_eq!(detect_encoding(&bytes), (encoding_rs::EUC_KR, true));
+        assert_eq!(guess("마린키우기 Let It Snow".as_bytes()), "UTF-8");
+    }
+
+    #[test]
+    fn detects_windows_1252_that_is_valid_cp949() {
+        // Both decode without errors as CP949, to hanja and to a UHC extension syllable.
+        for s in [
+            "\u{13}\u{2}Ðúst BóüÑÐ\u{12}\u{6}Dust BouND      .",
+            "\u{2}Can \u{1}You \u{2}Stop \u{1}1 \u{4}Unit? \u{3}§tack",
+        ] {
+            let (bytes, _, _) = encoding_rs::WINDOWS_1252.encode(s);
+
+            assert!(!encoding_rs::EUC_KR.decode(&bytes).2);
+            assert_eq!(detect_encoding(&bytes), (encoding_rs::WINDOWS_1252, true));
+        }
+
+        assert_eq!(guess(b"\x04Poo\x06p \x03Boun\x06d"), "windows-1252");
+    }
+}
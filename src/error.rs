@@ -0,0 +1,220 @@
+use crate::chunk_name::ChunkName;
+use serde::Serialize;
+
+// The size rule a section has to satisfy to be accepted by validate_and_group_riff_chunks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum ExpectedSize {
+    Exactly(u32),
+    Either(u32, u32),
+    AtLeast(u32),
+    AtMost(u32),
+    MultipleOf(u32),
+}
+
+impl ExpectedSize {
+    pub fn matches(&self, size: u32) -> bool {
+        match *self {
+            ExpectedSize::Exactly(x) => size == x,
+            ExpectedSize::Either(a, b) => size == a || size == b,
+            ExpectedSize::AtLeast(x) => size >= x,
+            ExpectedSize::AtMost(x) => size <= x,
+            ExpectedSize::MultipleOf(x) => size.is_multiple_of(x),
+        }
+    }
+}
+
+impl std::fmt::Display for ExpectedSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExpectedSize::Exactly(x) => write!(f, "exactly {x} bytes"),
+            ExpectedSize::Either(a, b) => write!(f, "{a} or {b} bytes"),
+            ExpectedSize::AtLeast(x) => write!(f, "at least {x} bytes"),
+            ExpectedSize::AtMost(x) => write!(f, "at most {x} bytes"),
+            ExpectedSize::MultipleOf(x) => write!(f, "a multiple of {x} bytes"),
+        }
+    }
+}
+
+// Offsets are absolute byte offsets into the chk. For InvalidSize it is the offset of the chunk header,
+// for Truncated and Malformed it is where the read that failed started.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum ChkError {
+    MissingSection {
+        chunk_name: ChunkName,
+    },
+    InvalidSize {
+        chunk_name: ChunkName,
+        offset: usize,
+        expected: ExpectedSize,
+        actual: u32,
+    },
+    Truncated {
+        chunk_name: ChunkName,
+        offset: usize,
+        expected: usize,
+        actual: usize,
+    },
+    Malformed {
+        chunk_name: ChunkName,
+        offset: usize,
+        reason: String,
+    },
+    // A string number that is 0 or whose offset or text is past the end of STR or STRx. There is no
+    // offset into the chk as stacked string sections are merged before they are read.
+    InvalidString {
+        chunk_name: ChunkName,
+        string_number: usize,
+        reason: String,
+    },
+}
+
+impl ChkError {
+    pub fn chunk_name(&self) -> &ChunkName {
+        match self {
+            ChkError::MissingSection { chunk_name }
+            | ChkError::InvalidSize { chunk_name, .. }
+            | ChkError::Truncated { chunk_name, .. }
+            | ChkError::Malformed { chunk_name, .. }
+            | ChkError::InvalidString { chunk_name, .. } => chunk_name,
+        }
+    }
+
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            ChkError::MissingSection { .. } | ChkError::InvalidString { .. } => None,
+            ChkError::InvalidSize { offset, .. }
+            | ChkError::Truncated { offset, .. }
+            | ChkError::Malformed { offset, .. } => Some(*offset),
+        }
+    }
+}
+
+impl std::fmt::Display for ChkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChkError::MissingSection { chunk_name } => {
+                write!(f, "section {chunk_name:?} not found")
+            }
+            ChkError::InvalidSize {
+                chunk_name,
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "section {chunk_name:?} at offset {offset} has size {actual}, expected {expected}"
+            ),
+            ChkError::Truncated {
+                chunk_name,
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "section {chunk_name:?} is truncated at offset {offset}, needed {expected} bytes but only {actual} are left"
+            ),
+            ChkError::Malformed {
+                chunk_name,
+                offset,
+                reason,
+            } => write!(
+                f,
+                "section {chunk_name:?} is malformed at offset {offset}: {reason}"
+            ),
+            ChkError::InvalidString {
+                chunk_name,
+                string_number,
+                reason,
+            } => write!(
+                f,
+                "string {string_number} of section {chunk_name:?} is invalid: {reason}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChkError {}
+
+#[cfg(test)]
+mod test {
+    use super::{ChkError, ExpectedSize};
+    use crate::{chunk_name::ChunkName, ChkWriter, ParsedChk};
+
+    #[test]
+    fn distinguishes_missing_invalid_and_truncated_sections() {
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(ChunkName::VER, vec![205, 0, 0]);
+        writer.add_chunk(ChunkName::IVE2, vec![11]);
+
+        let chk = writer.to_bytes();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        assert_eq!(
+            parsed_chk.ver.unwrap_err(),
+            ChkError::InvalidSize {
+                chunk_name: ChunkName::VER,
+                offset: 0,
+                expected: ExpectedSize::Exactly(2),
+                actual: 3,
+            }
+        );
+        assert_eq!(
+            parsed_chk.ive2.unwrap_err(),
+            ChkError::Truncated {
+                chunk_name: ChunkName::IVE2,
+                offset: 8 + 3 + 8,
+                expected: 2,
+                actual: 1,
+            }
+        );
+        assert_eq!(
+            parsed_chk.dim.unwrap_err(),
+            ChkError::MissingSection {
+                chunk_name: ChunkName::DIM
+            }
+        );
+    }
+
+    #[test]
+    fn valid_chunk_wins_over_rejected_chunk() {
+        let mut chk = ChkWriter::new();
+        chk.add_chunk(ChunkName::SIDE, vec![0; 11]);
+        let mut chk = chk.to_bytes();
+
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(ChunkName::SIDE, vec![5; 12]);
+        chk.extend(writer.to_bytes());
+
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        assert_eq!(parsed_chk.side.unwrap().player_side, [5; 12]);
+    }
+
+    #[cfg(feature = "full")]
+    #[test]
+    fn get_string_reports_invalid_strings() {
+        let mut writer = ChkWriter::new();
+        // One string at offset 6 and one past the end.
+        writer.add_chunk(ChunkName::STR, [2, 0, 6, 0, 40, 0, b'H', b'i', 0].to_vec());
+
+        let chk = writer.to_bytes();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        assert_eq!(parsed_chk.get_string(1).unwrap(), "Hi");
+        for string_number in [0, 2, 10] {
+            let err = parsed_chk.get_string(string_number).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    ChkError::InvalidString {
+                        chunk_name: ChunkName::STR,
+                        string_number: x,
+                        ..
+                    } if x == string_number
+                ),
+                "{err}"
+            );
+            assert_eq!(err.offset(), None);
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use crate::{
+    trig::{serialize_group, serialize_unit_type},
+    Action, Condition, Trigger,
+};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use tracing::instrument;
+
+// Death counts are a [[u32; 12]; 228] table indexed by unit and player, Deaths conditions and SetDeaths
+// actions outside of it read and write other memory.
+pub(crate) const DEATH_TABLE: u32 = 0x58A364;
+
+// The address the Deaths entry of a player and unit touches, wrapping around like the game does.
+pub(crate) fn death_table_address(player: u32, unit: u16) -> u32 {
+    (unit as u32)
+        .wrapping_mul(12)
+        .wrapping_add(player)
+        .wrapping_mul(4)
+        .wrapping_add(DEATH_TABLE)
+}
+
+// The player and unit the community tools use for an address, unit 0 and a large player if the address is
+// below the death table.
+pub(crate) fn eud_player_and_unit(address: u32) -> Option<(u32, u16)> {
+    if address & 3 != 0 {
+        return None;
+    }
+
+    let index = address.wrapping_sub(DEATH_TABLE) / 4;
+    if index / 12 <= u16::MAX as u32 {
+        Some((index % 12, (index / 12) as u16))
+    } else {
+        Some((index, 0))
+    }
+}
+
+// A known array in the memory of StarCraft 1.16.1. Remastered emulates these addresses for EUD maps.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct EudSymbol {
+    pub name: &'static str,
+    pub address: u32,
+    // Size of one entry in bytes, e.g. 4 for a u32 per player.
+    pub entry_size: u32,
+    pub entries: u32,
+}
+
+impl EudSymbol {
+    pub fn size(&self) -> u32 {
+        self.entry_size * self.entries
+    }
+
+    pub fn contains(&self, address: u32) -> bool {
+        address.wrapping_sub(self.address) < self.size()
+    }
+}
+
+// Sorted by address.
+pub static EUD_SYMBOLS: [EudSymbol; 20] = [
+    symbol("Local Player", 0x512684, 4, 1),
+    symbol("Player Info", 0x57EEE0, 36, 12),
+    symbol("Ore", 0x57F0F0, 4, 12),
+    symbol("Gas", 0x57F120, 4, 12),
+    symbol("Elapsed Time", 0x57F23C, 4, 1),
+    symbol("Zerg Supply Available", 0x582144, 4, 12),
+    symbol("Zerg Supply Used", 0x582174, 4, 12),
+    symbol("Zerg Supply Max", 0x5821A4, 4, 12),
+    symbol("Terran Supply Available", 0x5821D4, 4, 12),
+    symbol("Terran Supply Used", 0x582204, 4, 12),
+    symbol("Terran Supply Max", 0x582234, 4, 12),
+    symbol("Protoss Supply Available", 0x582264, 4, 12),
+    symbol("Protoss Supply Used", 0x582294, 4, 12),
+    symbol("Protoss Supply Max", 0x5822C4, 4, 12),
+    symbol("Custom Score", 0x5822F4, 4, 12),
+    symbol("Death Table", DEATH_TABLE, 4, 228 * 12),
+    // One bit per switch.
+    symbol("Switches", 0x58DC40, 4, 8),
+    symbol("Locations", 0x58DC60, 20, 255),
+    symbol("Unit Nodes", 0x59CCA8, 336, 1700),
+    symbol("Current Player", 0x6509B0, 4, 1),
+];
+
+const fn symbol(name: &'static str, address: u32, entry_size: u32, entries: u32) -> EudSymbol {
+    EudSymbol {
+        name,
+        address,
+        entry_size,
+        entries,
+    }
+}
+
+// An address inside of a known symbol.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct EudLocation {
+    pub symbol: &'static EudSymbol,
+    pub entry: u32,
+    // Byte offset into the entry.
+    pub offset: u32,
+}
+
+impl std::fmt::Display for EudLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol.name)?;
+        if self.symbol.entries > 1 {
+            write!(f, "[{}]", self.entry)?;
+        }
+        if self.offset != 0 {
+            write!(f, " + 0x{:X}", self.offset)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn symbolize_eud_address(address: u32) -> Option<EudLocation> {
+    let symbol = EUD_SYMBOLS.iter().find(|x| x.contains(address))?;
+    let offset = address - symbol.address;
+
+    Some(EudLocation {
+        symbol,
+        entry: offset / symbol.entry_size,
+        offset: offset % symbol.entry_size,
+    })
+}
+
+// What a map uses EUDs for. Addresses that are not in the symbol table are called "Unknown".
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EudKind {
+    // Deaths with the "SC" mask flag that stay inside the death table, Remastered only supports these with
+    // EUD emulation.
+    MaskedDeaths,
+    Read(&'static str),
+    Write(&'static str),
+}
+
+// A Deaths condition or SetDeaths action that touches memory outside of the death table or uses a mask.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct EudAccess {
+    pub trigger: usize,
+    // Index of the condition or action in the trigger.
+    pub index: usize,
+    pub write: bool,
+    pub address: u32,
+    pub mask: Option<u32>,
+    pub location: Option<EudLocation>,
+}
+
+impl EudAccess {
+    pub fn kind(&self) -> EudKind {
+        let name = self.location.map(|x| x.symbol.name).unwrap_or("Unknown");
+
+        match (name, self.write) {
+            ("Death Table", _) if self.mask.is_some() => EudKind::MaskedDeaths,
+            (name, false) => EudKind::Read(name),
+            (name, true) => EudKind::Write(name),
+        }
+    }
+}
+
+impl std::fmt::Display for EudAccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "trigger #{} {} {}: {} 0x{:08X}",
+            self.trigger,
+            if self.write { "action" } else { "condition" },
+            self.index + 1,
+            if self.write { "writes" } else { "reads" },
+            self.address
+        )?;
+        if let Some(location) = self.location {
+            write!(f, " ({location})")?;
+        }
+        if let Some(mask) = self.mask {
+            write!(f, " masked with 0x{mask:08X}")?;
+        }
+        Ok(())
+    }
+}
+
+// Players up to 26 are groups the game resolves itself, so only players past them, units past 227 and masks
+// make an EUD.
+fn eud_access(
+    trigger: usize,
+    index: usize,
+    write: bool,
+    player: u32,
+    unit: u16,
+    mask: Option<u32>,
+) -> Option<EudAccess> {
+    if unit < 228 && player < 27 && mask.is_none() {
+        return None;
+    }
+
+    let address = death_table_address(player, unit);
+
+    Some(EudAccess {
+        trigger,
+        index,
+        write,
+        address,
+        mask,
+        location: symbolize_eud_address(address),
+    })
+}
+
+// The EUD conditions and actions of a map in trigger order.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct EudReport {
+    pub accesses: Vec<EudAccess>,
+}
+
+impl EudReport {
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(triggers: &[Trigger]) -> EudReport {
+        let mut accesses = Vec::new();
+
+        for (trigger, x) in triggers.iter().enumerate() {
+            for entry in &x.conditions {
+                if let Condition::Deaths {
+                    player,
+                    unit_type,
+                    mask,
+                    ..
+                } = &entry.condition
+                {
+                    accesses.extend(eud_access(
+                        trigger,
+                        entry.index,
+                        false,
+                        serialize_group(player),
+                        serialize_unit_type(unit_type),
+                        *mask,
+                    ));
+                }
+            }
+
+            for entry in &x.actions {
+                if let Action::SetDeaths {
+                    player,
+                    unit_type,
+                    mask,
+                    ..
+                } = &entry.action
+                {
+                    accesses.extend(eud_access(
+                        trigger,
+                        entry.index,
+                        true,
+                        serialize_group(player),
+                        serialize_unit_type(unit_type),
+                        *mask,
+                    ));
+                }
+            }
+        }
+
+        EudReport { accesses }
+    }
+
+    pub fn uses_eud(&self) -> bool {
+        !self.accesses.is_empty()
+    }
+
+    pub fn kinds(&self) -> BTreeSet<EudKind> {
+        self.accesses.iter().map(|x| x.kind()).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{eud_player_and_unit, symbolize_eud_address, EudKind, EudReport, DEATH_TABLE};
+    use crate::{compile_trigedit, ParsedChk, TrigEditNames, Trigger};
+
+    #[test]
+    fn maps_eud_addresses_to_players_and_units() {
+        assert_eq!(eud_player_and_unit(DEATH_TABLE), Some((0, 0)));
+        assert_eq!(eud_player_and_unit(0x00595EF4), Some((4, 1000)));
+        assert_eq!(eud_player_and_unit(DEATH_TABLE - 4), Some((0x3FFFFFFF, 0)));
+        assert_eq!(eud_player_and_unit(DEATH_TABLE + 1), None);
+    }
+
+    #[test]
+    fn symbolizes_addresses() {
+        assert_eq!(
+            symbolize_eud_address(0x57F0F0 + 8).unwrap().to_string(),
+            "Ore[2]"
+        );
+        assert_eq!(
+            symbolize_eud_address(0x59CCA8 + 336 + 0x4C)
+                .unwrap()
+                .to_string(),
+            "Unit Nodes[1] + 0x4C"
+        );
+        assert_eq!(
+            symbolize_eud_address(0x6509B0).unwrap().to_string(),
+            "Current Player"
+        );
+        assert_eq!(symbolize_eud_address(0x6509B4), None);
+    }
+
+    #[test]
+    fn reports_eud_conditions_and_actions() {
+        let parsed_chk = ParsedChk::from_bytes(&[]);
+        let compiled = compile_trigedit(
+            r#"Trigger("Player 1"){
+Conditions:
+	Deaths("Player 1", "Terran Marine", At least, 1);
+	Memory(0x0057F0F8, At least, 500);
+	Masked Deaths("Player 2", "Terran Marine", Exactly, 0, 0xFF);
+
+Actions:
+	Set Deaths("Current Player", "Terran Marine", Add, 1);
+	Set Memory(0x00400000, Set To, 0);
+}
+"#,
+            &TrigEditNames::new(&parsed_chk),
+        )
+        .unwrap();
+        let triggers: Vec<Trigger> = compiled
+            .triggers
+            .iter()
+            .map(|x| Trigger::from_raw(x, &[]))
+            .collect();
+
+        let report = EudReport::new(&triggers);
+
+        assert!(report.uses_eud());
+        assert_eq!(report.accesses.len(), 3);
+        assert_eq!(
+            report.accesses[0].to_string(),
+            "trigger #0 condition 2: reads 0x0057F0F8 (Ore[2])"
+        );
+        assert_eq!(
+            report.accesses[1].to_string(),
+            "trigger #0 condition 3: reads 0x0058A368 (Death Table[1]) masked with 0x000000FF"
+        );
+        assert_eq!(
+            report.accesses[2].to_string(),
+            "trigger #0 action 2: writes 0x00400000"
+        );
+        assert_eq!(
+            report.kinds().into_iter().collect::<Vec<_>>(),
+            [
+                EudKind::MaskedDeaths,
+                EudKind::Read("Ore"),
+                EudKind::Write("Unknown")
+            ]
+        );
+    }
+}
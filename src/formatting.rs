@@ -0,0 +1,253 @@
+use serde::Serialize;
+use tracing::instrument;
+
+// A text color control byte. See http://www.staredit.net/wiki/index.php/Text_Formatting for the codes.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextColor(pub u8);
+
+impl TextColor {
+    pub const DEFAULT: TextColor = TextColor(0x01);
+
+    pub fn is_color_code(code: u8) -> bool {
+        matches!(code, 0x01..=0x08 | 0x0B | 0x0E..=0x11 | 0x14..=0x1F)
+    }
+
+    // 0x0B and 0x14 hide the text that follows them.
+    pub fn is_invisible(&self) -> bool {
+        self.0 == 0x0B || self.0 == 0x14
+    }
+
+    // The colors from tfontgam.pcx, the player colors match the minimap colors of players 1 to 12.
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match self.0 {
+            0x03 => (0xDC, 0xDC, 0x3C),
+            0x04 => (0xFF, 0xFF, 0xFF),
+            0x05 => (0x84, 0x84, 0x84),
+            0x06 => (0xC8, 0x18, 0x18),
+            0x07 => (0x10, 0xFC, 0x18),
+            0x08 => (0xF4, 0x04, 0x04),
+            0x0E => (0x0C, 0x48, 0xCC),
+            0x0F => (0x2C, 0xB4, 0x94),
+            0x10 => (0x88, 0x40, 0x9C),
+            0x11 => (0xF8, 0x8C, 0x14),
+            0x15 => (0x70, 0x30, 0x14),
+            0x16 => (0xCC, 0xE0, 0xD0),
+            0x17 => (0xFC, 0xFC, 0x38),
+            0x18 => (0x08, 0x80, 0x08),
+            0x19 => (0xFC, 0xFC, 0x7C),
+            0x1B => (0xEC, 0xC4, 0xB0),
+            0x1C => (0x40, 0x68, 0xD4),
+            0x1D => (0x74, 0xA4, 0x7C),
+            0x1E => (0x72, 0x90, 0xB8),
+            0x1F => (0x00, 0xE4, 0xFC),
+            // 0x01 default, 0x02 and 0x1A cyan.
+            _ => (0xB8, 0xB8, 0xE8),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+    #[default]
+    Left,
+    // 0x12
+    Right,
+    // 0x13
+    Center,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct TextSpan {
+    pub text: String,
+    pub color: TextColor,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct TextLine {
+    pub alignment: Alignment,
+    pub spans: Vec<TextSpan>,
+}
+
+// A decoded string split into lines of colored spans, the way StarCraft lays it out.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct FormattedText {
+    pub lines: Vec<TextLine>,
+}
+
+fn escape_html(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => ret.push_str("&amp;"),
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '"' => ret.push_str("&quot;"),
+            '\'' => ret.push_str("&#39;"),
+            _ => ret.push(c),
+        }
+    }
+
+    ret
+}
+
+impl TextLine {
+    fn push(&mut self, color: TextColor, c: char) {
+        match self.spans.last_mut() {
+            Some(span) if span.color == color => span.text.push(c),
+            _ => self.spans.push(TextSpan {
+                text: c.to_string(),
+                color,
+            }),
+        }
+    }
+}
+
+impl FormattedText {
+    // Every line starts out left aligned in the default color. 0x0A starts a new line, 0x0D is dropped
+    // because strings use \r\n, 0x09 is kept as a tab and 0x0C ends the string. Alignment codes apply to
+    // the whole line they are on. Other control bytes that are not color codes are dropped.
+    #[instrument(level = "trace", skip_all)]
+    pub fn parse(s: &str) -> FormattedText {
+        let mut lines = Vec::new();
+        let mut line = TextLine::default();
+        let mut color = TextColor::DEFAULT;
+
+        for c in s.chars() {
+            match c as u32 {
+                0x0A => {
+                    lines.push(std::mem::take(&mut line));
+                    color = TextColor::DEFAULT;
+                }
+                0x0C => break,
+                0x12 => line.alignment = Alignment::Right,
+                0x13 => line.alignment = Alignment::Center,
+                0x09 => line.push(color, '\t'),
+                x if x < 0x20 && TextColor::is_color_code(x as u8) => color = TextColor(x as u8),
+                x if x < 0x20 => {}
+                _ => line.push(color, c),
+            }
+        }
+
+        lines.push(line);
+
+        FormattedText { lines }
+    }
+
+    fn visible_spans(line: &TextLine) -> impl Iterator<Item = &TextSpan> {
+        line.spans.iter().filter(|x| !x.color.is_invisible())
+    }
+
+    pub fn to_plain_text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| {
+                FormattedText::visible_spans(line)
+                    .map(|x| x.text.as_str())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // 24 bit color escape codes, alignment is ignored as the width of the terminal is unknown.
+    pub fn to_ansi(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| {
+                let mut ret = String::new();
+
+                for span in FormattedText::visible_spans(line) {
+                    let (r, g, b) = span.color.rgb();
+                    ret.push_str(&format!("\x1b[38;2;{r};{g};{b}m{}", span.text));
+                }
+
+                if !ret.is_empty() {
+                    ret.push_str("\x1b[0m");
+                }
+
+                ret
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // One div per line with a span per color.
+    pub fn to_html(&self) -> String {
+        let mut ret = String::new();
+
+        for line in &self.lines {
+            match line.alignment {
+                Alignment::Left => ret.push_str("<div>"),
+                Alignment::Right => ret.push_str("<div style=\"text-align: right\">"),
+                Alignment::Center => ret.push_str("<div style=\"text-align: center\">"),
+            }
+
+            let mut empty = true;
+
+            for span in FormattedText::visible_spans(line) {
+                let (r, g, b) = span.color.rgb();
+                ret.push_str(&format!(
+                    "<span style=\"color: #{r:02x}{g:02x}{b:02x}\">{}</span>",
+                    escape_html(&span.text)
+                ));
+                empty = false;
+            }
+
+            if empty {
+                ret.push_str("<br>");
+            }
+
+            ret.push_str("</div>");
+        }
+
+        ret
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Alignment, FormattedText, TextColor, TextSpan};
+
+    #[test]
+    fn parses_colors_alignment_and_lines() {
+        let text = FormattedText::parse(
+            "\u{3}Marine \u{6}Special\r\n\u{13}Forces\u{b} hidden\u{c}dropped",
+        );
+
+        assert_eq!(text.lines.len(), 2);
+        assert_eq!(text.lines[0].alignment, Alignment::Left);
+        assert_eq!(
+            text.lines[0].spans,
+            vec![
+                TextSpan {
+                    text: "Marine ".to_owned(),
+                    color: TextColor(0x03)
+                },
+                TextSpan {
+                    text: "Special".to_owned(),
+                    color: TextColor(0x06)
+                },
+            ]
+        );
+        assert_eq!(text.lines[1].alignment, Alignment::Center);
+        assert_eq!(text.lines[1].spans[0].color, TextColor::DEFAULT);
+        assert_eq!(text.to_plain_text(), "Marine Special\nForces");
+    }
+
+    #[test]
+    fn renders_ansi_and_html() {
+        let text = FormattedText::parse("\u{8}<Red>\n\n\u{12}\u{e}Blue & \"more\"");
+
+        assert_eq!(
+            text.to_ansi(),
+            "\x1b[38;2;244;4;4m<Red>\x1b[0m\n\n\x1b[38;2;12;72;204mBlue & \"more\"\x1b[0m"
+        );
+        assert_eq!(
+            text.to_html(),
+            "<div><span style=\"color: #f40404\">&lt;Red&gt;</span></div>\
+             <div><br></div>\
+             <div style=\"text-align: right\"><span style=\"color: #0c48cc\">Blue &amp; &quot;more&quot;</span></div>"
+        );
+    }
+}
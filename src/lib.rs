@@ -1,17 +1,92 @@
-mod chk;
+#[cfg(feature = "full")]
+mod briefing_timeline;
+pub mod chk;
 mod chunk_name;
+#[cfg(feature = "full")]
+mod encoding;
+mod error;
+#[cfg(feature = "full")]
+mod eud;
+mod formatting;
 mod parsed_chk;
-mod riff;
+mod parsed_chk_owned;
+mod report;
+pub mod riff;
+#[cfg(feature = "full")]
+mod simulator;
+mod sound;
+#[cfg(feature = "full")]
+mod string_references;
+#[cfg(feature = "full")]
+mod string_section;
+#[cfg(feature = "full")]
+mod string_table;
 mod util;
+mod validate;
+mod writer;
 
 #[cfg(feature = "full")]
 mod trig;
+#[cfg(feature = "full")]
+mod trigedit;
+#[cfg(feature = "full")]
+mod trigger_graph;
+
+#[cfg(feature = "mpq")]
+pub mod mpq;
 
 #[cfg(test)]
 mod test;
 
+pub use error::{ChkError, ExpectedSize};
+pub use formatting::{Alignment, FormattedText, TextColor, TextLine, TextSpan};
 pub use parsed_chk::ParsedChk;
-pub use riff::parse_riff;
+pub use parsed_chk_owned::ParsedChkOwned;
+pub use report::{ChunkReport, ChunkStatus, ParseReport};
+pub use riff::{parse_riff, ParseMode};
+pub use sound::{SoundFormat, SoundReference, SoundSource};
+pub use validate::{GameType, Version, Violation};
+pub use writer::ChkWriter;
 
+#[cfg(feature = "full")]
+pub use briefing_timeline::{
+    BriefingCaption, BriefingEvent, BriefingSound, BriefingTimeline, CaptionKind, PortraitSpan,
+    SpeakingSpan,
+};
+#[cfg(feature = "full")]
+pub use encoding::StringEncoding;
+#[cfg(feature = "full")]
+pub use eud::{
+    symbolize_eud_address, EudAccess, EudKind, EudLocation, EudReport, EudSymbol, EUD_SYMBOLS,
+};
+#[cfg(feature = "full")]
+pub use simulator::{
+    CycleReport, FiredAction, PlayerStatus, SimulatedUnit, SimulationState, TriggerSimulator,
+    TRIGGER_CYCLE_MILLISECONDS,
+};
+#[cfg(feature = "full")]
+pub use string_references::{
+    DanglingReference, StringReference, StringReferenceIndex, StringUsage,
+};
+#[cfg(feature = "full")]
+pub use string_section::{
+    RebuildOptions, StringSectionBuilder, StringSectionError, StringSectionFormat,
+};
+#[cfg(feature = "full")]
+pub use string_table::{EncodingMode, StringError, StringId, StringTable, StringTableEntry};
 #[cfg(feature = "full")]
 pub use trig::*;
+#[cfg(feature = "full")]
+pub use trigedit::{
+    compile_trigedit, escape_trigedit_string, mission_briefing_to_trigedit, triggers_to_trigedit,
+    unescape_trigedit_string, CompiledTriggers, TrigEditError, TrigEditNames,
+};
+#[cfg(feature = "full")]
+pub use trigger_graph::{
+    DeadReason, GraphEdge, StateVariable, TriggerGraph, TriggerNode, VariableAccess,
+};
+
+#[cfg(feature = "mpq")]
+pub use mpq::open_map;
+#[cfg(feature = "mpq")]
+pub use sound::{Sound, SoundReport, SoundStatus};
@@ -0,0 +1,139 @@
+// Decompressor for the IMA ADPCM variant Storm uses for WAV files.
+
+use super::MpqError;
+
+const INITIAL_STEP_INDEX: i32 = 0x2C;
+
+const NEXT_STEP_TABLE: [i32; 32] = [
+    -1, 0, -1, 4, -1, 2, -1, 6, -1, 1, -1, 5, -1, 3, -1, 7, -1, 1, -1, 5, -1, 3, -1, 7, -1, 2, -1,
+    4, -1, 6, -1, 8,
+];
+
+const STEP_SIZE_TABLE: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66,
+    73, 80, 88, 97, 107, 118, 130, 143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408, 449,
+    494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066, 2272,
+    2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845, 8630, 9493,
+    10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794, 32767,
+];
+
+fn decompress_sample(
+    bit_shift: u8,
+    encoded_sample: u8,
+    step_size: i32,
+    predicted_sample: i32,
+) -> i32 {
+    let mut difference = step_size >> bit_shift;
+
+    for bit in 0..6 {
+        if encoded_sample & (1 << bit) != 0 {
+            difference += step_size >> bit;
+        }
+    }
+
+    if encoded_sample & 0x40 != 0 {
+        (predicted_sample - difference).max(i16::MIN as i32)
+    } else {
+        (predicted_sample + difference).min(i16::MAX as i32)
+    }
+}
+
+// Returns 16 bit little endian PCM samples, interleaved when there are two channels.
+pub(crate) fn decompress_adpcm(data: &[u8], channel_count: usize) -> Result<Vec<u8>, MpqError> {
+    let truncated = || MpqError::Decompression {
+        reason: "ADPCM stream ended early".to_owned(),
+    };
+
+    // The first byte is unused, the second is the bit shift of every sample.
+    let bit_shift = *data.get(1).ok_or_else(truncated)?;
+    let mut offset = 2;
+
+    let mut step_indexes = [INITIAL_STEP_INDEX; 2];
+    let mut predicted_samples = [0i32; 2];
+    let mut ret = Vec::new();
+
+    for predicted_sample in predicted_samples.iter_mut().take(channel_count) {
+        let sample = data.get(offset..offset + 2).ok_or_else(truncated)?;
+        offset += 2;
+
+        *predicted_sample = i16::from_le_bytes([sample[0], sample[1]]) as i32;
+        ret.extend_from_slice(sample);
+    }
+
+    let mut channel = channel_count - 1;
+
+    for &encoded_sample in &data[offset..] {
+        channel = (channel + 1) % channel_count;
+
+        if encoded_sample & 0x80 != 0 {
+            match encoded_sample & 0x7F {
+                // Lower the step index and repeat the previous sample.
+                0 => {
+                    if step_indexes[channel] != 0 {
+                        step_indexes[channel] -= 1;
+                    }
+                    ret.extend_from_slice(&(predicted_samples[channel] as i16).to_le_bytes());
+                }
+                // Raise the step index, the next sample is for the same channel.
+                1 => {
+                    step_indexes[channel] = (step_indexes[channel] + 8).min(0x58);
+                    channel = (channel + 1) % channel_count;
+                }
+                2 => {
+                    channel = (channel + 1) % channel_count;
+                }
+                _ => {
+                    step_indexes[channel] = (step_indexes[channel] - 8).max(0);
+                    channel = (channel + 1) % channel_count;
+                }
+            }
+        } else {
+            let step_size = STEP_SIZE_TABLE[step_indexes[channel] as usize];
+            predicted_samples[channel] = decompress_sample(
+                bit_shift,
+                encoded_sample,
+                step_size,
+                predicted_samples[channel],
+            );
+            ret.extend_from_slice(&(predicted_samples[channel] as i16).to_le_bytes());
+
+            step_indexes[channel] = (step_indexes[channel]
+                + NEXT_STEP_TABLE[(encoded_sample & 0x1F) as usize])
+                .clamp(0, 88);
+        }
+    }
+
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod test {
+    use super::decompress_adpcm;
+
+    fn samples(data: &[u8]) -> Vec<i16> {
+        data.chunks(2)
+            .map(|x| i16::from_le_bytes([x[0], x[1]]))
+            .collect()
+    }
+
+    #[test]
+    fn decompresses_mono() {
+        // Initial sample 16, then +524 (494 >> 4 + 494), then the previous sample repeated.
+        let data = [0, 4, 16, 0, 0x01, 0x80];
+
+        assert_eq!(
+            samples(&decompress_adpcm(&data, 1).unwrap()),
+            vec![16, 540, 540]
+        );
+    }
+
+    #[test]
+    fn decompresses_stereo() {
+        let data = [0, 4, 1, 0, 2, 0, 0x80, 0x80];
+
+        assert_eq!(
+            samples(&decompress_adpcm(&data, 2).unwrap()),
+            vec![1, 2, 1, 2]
+        );
+    }
+}
@@ -0,0 +1,550 @@
+use super::{
+    compression::decompress,
+    crypt::{
+        decrypt, file_key, hash_string, HASH_FILE_KEY, HASH_NAME_A, HASH_NAME_B, HASH_TABLE_OFFSET,
+    },
+    pkware::explode,
+    MpqError,
+};
+use serde::Serialize;
+use tracing::instrument;
+
+pub(crate) const MPQ_FILE_IMPLODE: u32 = 0x00000100;
+pub(crate) const MPQ_FILE_COMPRESS: u32 = 0x00000200;
+pub(crate) const MPQ_FILE_ENCRYPTED: u32 = 0x00010000;
+pub(crate) const MPQ_FILE_FIX_KEY: u32 = 0x00020000;
+pub(crate) const MPQ_FILE_SINGLE_UNIT: u32 = 0x01000000;
+pub(crate) const MPQ_FILE_SECTOR_CRC: u32 = 0x04000000;
+pub(crate) const MPQ_FILE_EXISTS: u32 = 0x80000000;
+
+pub(crate) const HASH_ENTRY_EMPTY: u32 = 0xFFFFFFFF;
+pub(crate) const HASH_ENTRY_DELETED: u32 = 0xFFFFFFFE;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct MpqHashEntry {
+    pub name_a: u32,
+    pub name_b: u32,
+    pub locale: u16,
+    pub platform: u16,
+    pub block_index: u32,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct MpqBlockEntry {
+    pub file_position: u32,
+    pub compressed_size: u32,
+    pub file_size: u32,
+    pub flags: u32,
+}
+
+// A read only view of an MPQ v1 archive, the format used by .scm/.scx files.
+//
+// Only the parts of the header StarCraft actually reads are used, so the tricks map protectors play on
+// the rest of it (bogus header size, archive size and format version) are ignored.
+#[derive(Debug)]
+pub struct Mpq<'a> {
+    data: &'a [u8],
+    archive_offset: usize,
+    sector_size: usize,
+    hash_table_entries: u32,
+    hash_table: Vec<MpqHashEntry>,
+    block_table: Vec<MpqBlockEntry>,
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+impl<'a> Mpq<'a> {
+    #[instrument(level = "trace", skip_all)]
+    pub fn from_bytes(data: &'a [u8]) -> Result<Mpq<'a>, MpqError> {
+        let archive_offset = Mpq::find_header(data).ok_or(MpqError::HeaderNotFound)?;
+        let header = &data[archive_offset..];
+
+        let sector_size_shift = read_u16(header, 14).ok_or(MpqError::HeaderNotFound)?;
+        let hash_table_position = read_u32(header, 16).ok_or(MpqError::HeaderNotFound)?;
+        let block_table_position = read_u32(header, 20).ok_or(MpqError::HeaderNotFound)?;
+        let hash_table_entries = read_u32(header, 24).ok_or(MpqError::HeaderNotFound)?;
+        let block_table_entries = read_u32(header, 28).ok_or(MpqError::HeaderNotFound)?;
+
+        let hash_table = Mpq::read_table(
+            data,
+            archive_offset,
+            hash_table_position,
+            hash_table_entries,
+            hash_string(b"(hash table)", HASH_FILE_KEY),
+        )
+        .as_chunks::<16>()
+        .0
+        .iter()
+        .map(|x| MpqHashEntry {
+            name_a: u32::from_le_bytes([x[0], x[1], x[2], x[3]]),
+            name_b: u32::from_le_bytes([x[4], x[5], x[6], x[7]]),
+            locale: u16::from_le_bytes([x[8], x[9]]),
+            platform: u16::from_le_bytes([x[10], x[11]]),
+            block_index: u32::from_le_bytes([x[12], x[13], x[14], x[15]]),
+        })
+        .collect();
+
+        let block_table = Mpq::read_table(
+            data,
+            archive_offset,
+            block_table_position,
+            block_table_entries,
+            hash_string(b"(block table)", HASH_FILE_KEY),
+        )
+        .as_chunks::<16>()
+        .0
+        .iter()
+        .map(|x| MpqBlockEntry {
+            file_position: u32::from_le_bytes([x[0], x[1], x[2], x[3]]),
+            compressed_size: u32::from_le_bytes([x[4], x[5], x[6], x[7]]),
+            file_size: u32::from_le_bytes([x[8], x[9], x[10], x[11]]),
+            flags: u32::from_le_bytes([x[12], x[13], x[14], x[15]]),
+        })
+        .collect();
+
+        Ok(Mpq {
+            data,
+            archive_offset,
+            // PROTECTION: Storm computes the sector size in 32 bits, so a large shift wraps around.
+            sector_size: 512u32.wrapping_shl(sector_size_shift as u32) as usize,
+            hash_table_entries,
+            hash_table,
+            block_table,
+        })
+    }
+
+    // The header is at the start of the file or at a later 512 byte boundary. An MPQ\x1B user data header
+    // points at the real header.
+    fn find_header(data: &[u8]) -> Option<usize> {
+        let mut offset = 0;
+
+        while offset + 32 <= data.len() {
+            match &data[offset..offset + 4] {
+                b"MPQ\x1A" => return Some(offset),
+                b"MPQ\x1B" => {
+                    let header_offset = offset + read_u32(data, offset + 8)? as usize;
+
+                    if data.get(header_offset..header_offset + 4) == Some(b"MPQ\x1A") {
+                        return Some(header_offset);
+                    }
+                }
+                _ => {}
+            }
+
+            offset += 512;
+        }
+
+        None
+    }
+
+    // PROTECTION: table positions are 32 bit and wrap around, and tables that run past the end of the file
+    // are cut short instead of rejected.
+    fn read_table(
+        data: &[u8],
+        archive_offset: usize,
+        position: u32,
+        entries: u32,
+        key: u32,
+    ) -> Vec<u8> {
+        let start = (archive_offset as u32).wrapping_add(position) as usize;
+        let len = (entries as usize * 16).min(data.len().saturating_sub(start)) / 16 * 16;
+
+        let mut ret = data[start.min(data.len())..][..len].to_vec();
+        decrypt(ret.as_mut_slice(), key);
+
+        ret
+    }
+
+    pub fn sector_size(&self) -> usize {
+        self.sector_size
+    }
+
+    pub fn hash_table(&self) -> &[MpqHashEntry] {
+        self.hash_table.as_slice()
+    }
+
+    pub fn block_table(&self) -> &[MpqBlockEntry] {
+        self.block_table.as_slice()
+    }
+
+    // Walks the hash table the way Storm does, starting at the slot the name hashes to and stopping at the
+    // first empty slot.
+    //
+    // PROTECTION: protectors add colliding entries for the same name that point at nonexistent or fake
+    // blocks. Entries whose block index is out of range are skipped, and the language neutral entry is
+    // preferred over localized ones.
+    #[instrument(level = "trace", skip_all)]
+    pub fn find_file(&self, filename: impl AsRef<[u8]>) -> Option<&MpqBlockEntry> {
+        let filename = filename.as_ref();
+
+        if self.hash_table_entries == 0 {
+            return None;
+        }
+
+        let name_a = hash_string(filename, HASH_NAME_A);
+        let name_b = hash_string(filename, HASH_NAME_B);
+        let start = hash_string(filename, HASH_TABLE_OFFSET) & (self.hash_table_entries - 1);
+
+        let mut ret = None;
+
+        for i in 0..self.hash_table_entries {
+            let index = (start as u64 + i as u64) % self.hash_table_entries as u64;
+
+            // PROTECTION: slots past the end of the file are treated as empty.
+            let Some(entry) = self.hash_table.get(index as usize) else {
+                break;
+            };
+
+            if entry.block_index == HASH_ENTRY_EMPTY {
+                break;
+            }
+
+            if entry.block_index == HASH_ENTRY_DELETED
+                || entry.name_a != name_a
+                || entry.name_b != name_b
+            {
+                continue;
+            }
+
+            // PROTECTION: deleted files and fake entries added by protectors lack the exists flag.
+            if let Some(block) = self
+                .block_table
+                .get(entry.block_index as usize)
+                .filter(|x| x.flags & MPQ_FILE_EXISTS != 0)
+            {
+                if entry.locale == 0 {
+                    return Some(block);
+                }
+
+                ret = ret.or(Some(block));
+            }
+        }
+
+        ret
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub fn read_file(&self, filename: impl AsRef<[u8]>) -> Result<Vec<u8>, MpqError> {
+        let filename = filename.as_ref();
+        let block = self
+            .find_file(filename)
+            .ok_or_else(|| MpqError::FileNotFound {
+                filename: String::from_utf8_lossy(filename).into_owned(),
+            })?;
+
+        self.read_block(block, filename)
+    }
+
+    // Reads bytes relative to the start of a file, failing if they are not all in the archive.
+    fn slice(&self, file_start: usize, start: usize, len: usize) -> Result<&'a [u8], MpqError> {
+        let offset = file_start + start;
+
+        self.data
+            .get(offset..offset + len)
+            .ok_or(MpqError::Truncated {
+                offset,
+                expected: len,
+                actual: self.data.len().saturating_sub(offset),
+            })
+    }
+
+    fn read_block(&self, block: &MpqBlockEntry, filename: &[u8]) -> Result<Vec<u8>, MpqError> {
+        let file_start = (self.archive_offset as u32).wrapping_add(block.file_position) as usize;
+        let file_size = block.file_size as usize;
+
+        let key = if block.flags & MPQ_FILE_ENCRYPTED != 0 {
+            Some(file_key(
+                filename,
+                block.file_position,
+                block.file_size,
+                block.flags & MPQ_FILE_FIX_KEY != 0,
+            ))
+        } else {
+            None
+        };
+
+        let decompress_sector = |mut sector: Vec<u8>, sector_index: usize, expected: usize| {
+            if let Some(key) = key {
+                decrypt(sector.as_mut_slice(), key.wrapping_add(sector_index as u32));
+            }
+
+            // A sector that did not get smaller when compressed is stored as is.
+            if sector.len() >= expected {
+                sector.truncate(expected);
+                Ok(sector)
+            } else if block.flags & MPQ_FILE_COMPRESS != 0 {
+                decompress(sector.as_slice(), expected)
+            } else if block.flags & MPQ_FILE_IMPLODE != 0 {
+                explode(sector.as_slice(), expected)
+            } else {
+                Ok(sector)
+            }
+        };
+
+        if block.flags & MPQ_FILE_SINGLE_UNIT != 0 {
+            let sector = self.slice(file_start, 0, block.compressed_size as usize)?;
+
+            return decompress_sector(sector.to_vec(), 0, file_size);
+        }
+
+        if self.sector_size == 0 {
+            return Err(MpqError::Decompression {
+                reason: "sector size is zero".to_owned(),
+            });
+        }
+
+        let sector_count = file_size.div_ceil(self.sector_size);
+        let mut ret = Vec::new();
+
+        if block.flags & (MPQ_FILE_COMPRESS | MPQ_FILE_IMPLODE) == 0 {
+            // PROTECTION: the compressed size of uncompressed files is not trusted, only the file size is.
+            for i in 0..sector_count {
+                let expected = self.sector_size.min(file_size - i * self.sector_size);
+                let sector = self.slice(file_start, i * self.sector_size, expected)?;
+
+                ret.extend(decompress_sector(sector.to_vec(), i, expected)?);
+            }
+
+            return Ok(ret);
+        }
+
+        // The sector offset table has one more entry than there are sectors, plus one for the CRCs.
+        let table_entries = sector_count
+            + 1
+            + if block.flags & MPQ_FILE_SECTOR_CRC != 0 {
+                1
+            } else {
+                0
+            };
+
+        let mut offsets = self.slice(file_start, 0, table_entries * 4)?.to_vec();
+
+        if let Some(key) = key {
+            decrypt(offsets.as_mut_slice(), key.wrapping_sub(1));
+        }
+
+        let offsets: Vec<usize> = offsets
+            .as_chunks::<4>()
+            .0
+            .iter()
+            .map(|&x| u32::from_le_bytes(x) as usize)
+            .collect();
+
+        for i in 0..sector_count {
+            let expected = self.sector_size.min(file_size - i * self.sector_size);
+
+            if offsets[i + 1] < offsets[i] {
+                return Err(MpqError::Decompression {
+                    reason: format!(
+                        "sector {i} of {:?} has a negative size",
+                        String::from_utf8_lossy(filename)
+                    ),
+                });
+            }
+
+            let sector = self.slice(file_start, offsets[i], offsets[i + 1] - offsets[i])?;
+
+            ret.extend(decompress_sector(sector.to_vec(), i, expected)?);
+        }
+
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Mpq, MPQ_FILE_COMPRESS, MPQ_FILE_ENCRYPTED, MPQ_FILE_EXISTS, MPQ_FILE_FIX_KEY};
+    use crate::mpq::{
+        compression::COMPRESSION_ZLIB,
+        crypt::{encrypt, file_key, hash_string, HASH_FILE_KEY},
+        read_chk, MpqError, SCENARIO_CHK,
+    };
+
+    // Builds a 16 slot archive with one file stored in 512 byte sectors. Block 0 is the file and block 1
+    // is the same file without the exists flag. hash_entries are the (locale, block index) of the entries
+    // for the file, in the order they are probed.
+    fn make_archive(
+        contents: &[u8],
+        flags: u32,
+        hash_entries: &[(u16, u32)],
+        prefix: usize,
+    ) -> Vec<u8> {
+        let sector_size = 512;
+        let file_position = 32u32;
+        let key = file_key(
+            SCENARIO_CHK.as_bytes(),
+            file_position,
+            contents.len() as u32,
+            flags & MPQ_FILE_FIX_KEY != 0,
+        );
+
+        let mut sectors: Vec<Vec<u8>> = contents
+            .chunks(sector_size)
+            .map(|x| {
+                let compressed = [
+                    &[COMPRESSION_ZLIB][..],
+                    &miniz_oxide::deflate::compress_to_vec_zlib(x, 9),
+                ]
+                .concat();
+
+                // Sectors that do not get smaller are stored as is.
+                if flags & MPQ_FILE_COMPRESS != 0 && compressed.len() < x.len() {
+                    compressed
+                } else {
+                    x.to_vec()
+                }
+            })
+            .collect();
+
+        if flags & MPQ_FILE_ENCRYPTED != 0 {
+            for (i, sector) in sectors.iter_mut().enumerate() {
+                encrypt(sector.as_mut_slice(), key.wrapping_add(i as u32));
+            }
+        }
+
+        let mut file = Vec::new();
+        if flags & MPQ_FILE_COMPRESS != 0 {
+            let mut offset = (sectors.len() as u32 + 1) * 4;
+            let mut table = offset.to_le_bytes().to_vec();
+            for sector in &sectors {
+                offset += sector.len() as u32;
+                table.extend(offset.to_le_bytes());
+            }
+            if flags & MPQ_FILE_ENCRYPTED != 0 {
+                encrypt(table.as_mut_slice(), key.wrapping_sub(1));
+            }
+            file.extend(table);
+        }
+        file.extend(sectors.concat());
+
+        let hash_table_position = file_position + file.len() as u32;
+        let block_table_position = hash_table_position + 16 * 16;
+
+        let mut hash_table = vec![0xFF; 16 * 16];
+        let start = (hash_string(SCENARIO_CHK.as_bytes(), 0) & 15) as usize;
+        for (i, (locale, block_index)) in hash_entries.iter().enumerate() {
+            let slot = (start + i) % 16;
+            hash_table[slot * 16..slot * 16 + 16].copy_from_slice(
+                &[
+                    hash_string(SCENARIO_CHK.as_bytes(), 1).to_le_bytes(),
+                    hash_string(SCENARIO_CHK.as_bytes(), 2).to_le_bytes(),
+                    [locale.to_le_bytes(), [0, 0]].concat().try_into().unwrap(),
+                    block_index.to_le_bytes(),
+                ]
+                .concat(),
+            );
+        }
+        encrypt(
+            hash_table.as_mut_slice(),
+            hash_string(b"(hash table)", HASH_FILE_KEY),
+        );
+
+        let mut block_table = [flags | MPQ_FILE_EXISTS, flags]
+            .iter()
+            .flat_map(|x| {
+                [
+                    file_position.to_le_bytes(),
+                    (file.len() as u32).to_le_bytes(),
+                    (contents.len() as u32).to_le_bytes(),
+                    x.to_le_bytes(),
+                ]
+                .concat()
+            })
+            .collect::<Vec<u8>>();
+        encrypt(
+            block_table.as_mut_slice(),
+            hash_string(b"(block table)", HASH_FILE_KEY),
+        );
+
+        let header = [
+            &b"MPQ\x1A"[..],
+            // PROTECTION: bogus header size, archive size and format version.
+            &0xDEADu32.to_le_bytes(),
+            &0xFFFFFFFFu32.to_le_bytes(),
+            &7u16.to_le_bytes(),
+            &0u16.to_le_bytes(),
+            &hash_table_position.to_le_bytes(),
+            &block_table_position.to_le_bytes(),
+            &16u32.to_le_bytes(),
+            &2u32.to_le_bytes(),
+        ]
+        .concat();
+
+        [vec![0; prefix], header, file, hash_table, block_table].concat()
+    }
+
+    fn make_chk() -> Vec<u8> {
+        (0..1300).map(|x| (x % 251) as u8).collect()
+    }
+
+    #[test]
+    fn reads_plain_file() {
+        let archive = make_archive(&make_chk(), 0, &[(0, 0)], 0);
+
+        assert!(read_chk(archive.as_slice()).unwrap() == make_chk());
+    }
+
+    #[test]
+    fn reads_compressed_encrypted_file() {
+        let flags = MPQ_FILE_COMPRESS | MPQ_FILE_ENCRYPTED | MPQ_FILE_FIX_KEY;
+        let archive = make_archive(&make_chk(), flags, &[(0, 0)], 0);
+
+        assert!(read_chk(archive.as_slice()).unwrap() == make_chk());
+    }
+
+    #[test]
+    fn finds_header_after_prefix() {
+        let archive = make_archive(&make_chk(), MPQ_FILE_COMPRESS, &[(0, 0)], 1024);
+
+        assert!(read_chk(archive.as_slice()).unwrap() == make_chk());
+        assert!(matches!(
+            read_chk(&archive[1..]),
+            Err(MpqError::HeaderNotFound)
+        ));
+    }
+
+    #[test]
+    fn skips_colliding_entries_with_bogus_blocks() {
+        // The neutral entry points past the block table, so the localized one is used.
+        let archive = make_archive(&make_chk(), 0, &[(0, 1000), (0x412, 0)], 0);
+
+        assert!(read_chk(archive.as_slice()).unwrap() == make_chk());
+    }
+
+    #[test]
+    fn skips_blocks_that_do_not_exist() {
+        // The neutral entry is for a deleted block, so the localized one is used.
+        let archive = make_archive(&make_chk(), 0, &[(0, 1), (0x412, 0)], 0);
+
+        assert!(read_chk(archive.as_slice()).unwrap() == make_chk());
+
+        let archive = make_archive(&make_chk(), 0, &[(0, 1)], 0);
+        let mpq = Mpq::from_bytes(archive.as_slice()).unwrap();
+
+        assert!(matches!(
+            mpq.read_file(SCENARIO_CHK),
+            Err(MpqError::FileNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn missing_file() {
+        let archive = make_archive(&make_chk(), 0, &[(0, 0)], 0);
+        let mpq = Mpq::from_bytes(archive.as_slice()).unwrap();
+
+        assert!(matches!(
+            mpq.read_file("staredit\\wav\\missing.wav"),
+            Err(MpqError::FileNotFound { .. })
+        ));
+    }
+}
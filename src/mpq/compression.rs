@@ -0,0 +1,226 @@
+use super::{adpcm::decompress_adpcm, huffman::decompress_huffman, pkware::explode, MpqError};
+use miniz_oxide::inflate::TINFLStatus;
+use std::io::Read;
+
+pub(crate) const COMPRESSION_HUFFMAN: u8 = 0x01;
+pub(crate) const COMPRESSION_ZLIB: u8 = 0x02;
+pub(crate) const COMPRESSION_PKWARE: u8 = 0x08;
+pub(crate) const COMPRESSION_BZIP2: u8 = 0x10;
+pub(crate) const COMPRESSION_ADPCM_MONO: u8 = 0x40;
+pub(crate) const COMPRESSION_ADPCM_STEREO: u8 = 0x80;
+
+// Decompresses a sector of a file with the COMPRESS flag. The first byte is a mask of the compressions
+// that were applied, they are undone in the reverse order Storm applies them.
+pub(crate) fn decompress(data: &[u8], expected_size: usize) -> Result<Vec<u8>, MpqError> {
+    let (&mask, data) = data.split_first().ok_or(MpqError::Decompression {
+        reason: "empty compressed sector".to_owned(),
+    })?;
+
+    let known = COMPRESSION_HUFFMAN
+        | COMPRESSION_ZLIB
+        | COMPRESSION_PKWARE
+        | COMPRESSION_BZIP2
+        | COMPRESSION_ADPCM_MONO
+        | COMPRESSION_ADPCM_STEREO;
+
+    if mask & !known != 0 {
+        return Err(MpqError::UnsupportedCompression { mask });
+    }
+
+    let mut ret = data.to_vec();
+
+    if mask & COMPRESSION_BZIP2 != 0 {
+        let mut decompressed = Vec::with_capacity(expected_size);
+        bzip2_rs::DecoderReader::new(ret.as_slice())
+            .take(expected_size as u64)
+            .read_to_end(&mut decompressed)
+            .map_err(|x| MpqError::Decompression {
+                reason: format!("bzip2: {x}"),
+            })?;
+        ret = decompressed;
+    }
+
+    if mask & COMPRESSION_PKWARE != 0 {
+        ret = explode(ret.as_slice(), expected_size)?;
+    }
+
+    if mask & COMPRESSION_ZLIB != 0 {
+        ret =
+            miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(ret.as_slice(), expected_size)
+                .map_err(|x| MpqError::Decompression {
+                    reason: match x.status {
+                        TINFLStatus::HasMoreOutput => {
+                            format!("zlib: output is larger than {expected_size} bytes")
+                        }
+                        _ => format!("zlib: {x:?}"),
+                    },
+                })?;
+    }
+
+    // The Huffman stream ends with its own marker, the expected size only bounds it because ADPCM
+    // still expands it afterwards.
+    if mask & COMPRESSION_HUFFMAN != 0 {
+        ret = decompress_huffman(ret.as_slice(), expected_size)?;
+    }
+
+    if mask & COMPRESSION_ADPCM_STEREO != 0 {
+        ret = decompress_adpcm(ret.as_slice(), 2)?;
+    }
+
+    if mask & COMPRESSION_ADPCM_MONO != 0 {
+        ret = decompress_adpcm(ret.as_slice(), 1)?;
+    }
+
+    ret.truncate(expected_size);
+
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        decompress, COMPRESSION_ADPCM_MONO, COMPRESSION_ADPCM_STEREO, COMPRESSION_BZIP2,
+        COMPRESSION_HUFFMAN, COMPRESSION_PKWARE, COMPRESSION_ZLIB,
+    };
+    use crate::mpq::{huffman::compress_huffman, MpqError};
+
+    #[test]
+    fn decompresses_zlib() {
+        let data: Vec<u8> = (0..1000).map(|x| (x % 7) as u8).collect();
+        let compressed = [
+            &[COMPRESSION_ZLIB][..],
+            &miniz_oxide::deflate::compress_to_vec_zlib(data.as_slice(), 6),
+        ]
+        .concat();
+
+        assert_eq!(decompress(&compressed, data.len()).unwrap(), data);
+    }
+
+    #[test]
+    fn limits_zlib_output() {
+        let data = vec![0; 100000];
+        let compressed = [
+            &[COMPRESSION_ZLIB][..],
+            &miniz_oxide::deflate::compress_to_vec_zlib(data.as_slice(), 6),
+        ]
+        .concat();
+
+        assert!(matches!(
+            decompress(&compressed, 4096),
+            Err(MpqError::Decompression { .. })
+        ));
+    }
+
+    #[test]
+    fn decompresses_bzip2() {
+        let compressed = [
+            COMPRESSION_BZIP2,
+            0x42,
+            0x5a,
+            0x68,
+            0x39,
+            0x31,
+            0x41,
+            0x59,
+            0x26,
+            0x53,
+            0x59,
+            0x12,
+            0x61,
+            0x1e,
+            0x29,
+            0x00,
+            0x00,
+            0x33,
+            0x91,
+            0x80,
+            0x40,
+            0x01,
+            0x2a,
+            0x69,
+            0x98,
+            0x00,
+            0x20,
+            0x00,
+            0x31,
+            0x03,
+            0x40,
+            0xd0,
+            0x0a,
+            0xa8,
+            0x00,
+            0x7a,
+            0x9b,
+            0x1d,
+            0x1d,
+            0x10,
+            0x64,
+            0x6c,
+            0x20,
+            0xf0,
+            0xf0,
+            0x82,
+            0x08,
+            0x3e,
+            0x2e,
+            0xe4,
+            0x8a,
+            0x70,
+            0xa1,
+            0x20,
+            0x24,
+            0xc2,
+            0x3c,
+            0x52,
+        ];
+
+        assert_eq!(
+            decompress(&compressed, 13 * 8).unwrap(),
+            b"scenario.chk ".repeat(8)
+        );
+    }
+
+    #[test]
+    fn decompresses_pkware() {
+        let compressed = [
+            COMPRESSION_PKWARE,
+            0x00,
+            0x04,
+            0x82,
+            0x24,
+            0x25,
+            0x8f,
+            0x80,
+            0x7f,
+        ];
+
+        assert_eq!(decompress(&compressed, 13).unwrap(), b"AIAIAIAIAIAIA");
+    }
+
+    #[test]
+    fn decompresses_huffman_adpcm() {
+        // The ADPCM streams of the tests in adpcm.rs.
+        let adpcm = [0, 4, 16, 0, 0x01, 0x80];
+        let compressed = compress_huffman(&adpcm, 6);
+
+        let mono = [
+            &[COMPRESSION_HUFFMAN | COMPRESSION_ADPCM_MONO][..],
+            &compressed,
+        ]
+        .concat();
+        assert_eq!(
+            decompress(&mono, 6).unwrap(),
+            [16, 0, 0x1c, 0x02, 0x1c, 0x02]
+        );
+
+        let adpcm = [0, 4, 1, 0, 2, 0, 0x80, 0x80];
+        let compressed = compress_huffman(&adpcm, 6);
+
+        let stereo = [
+            &[COMPRESSION_HUFFMAN | COMPRESSION_ADPCM_STEREO][..],
+            &compressed,
+        ]
+        .concat();
+        assert_eq!(decompress(&stereo, 8).unwrap(), [1, 0, 2, 0, 1, 0, 2, 0]);
+    }
+}
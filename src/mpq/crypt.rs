@@ -0,0 +1,147 @@
+use std::sync::OnceLock;
+
+// The hash types used with hash_string.
+pub(crate) const HASH_TABLE_OFFSET: u32 = 0;
+pub(crate) const HASH_NAME_A: u32 = 1;
+pub(crate) const HASH_NAME_B: u32 = 2;
+pub(crate) const HASH_FILE_KEY: u32 = 3;
+
+fn crypt_table() -> &'static [u32; 0x500] {
+    static CRYPT_TABLE: OnceLock<[u32; 0x500]> = OnceLock::new();
+
+    CRYPT_TABLE.get_or_init(|| {
+        let mut table = [0u32; 0x500];
+        let mut seed: u32 = 0x00100001;
+
+        for index1 in 0..0x100 {
+            let mut index2 = index1;
+
+            for _ in 0..5 {
+                seed = (seed * 125 + 3) % 0x2AAAAB;
+                let temp1 = (seed & 0xFFFF) << 0x10;
+                seed = (seed * 125 + 3) % 0x2AAAAB;
+                let temp2 = seed & 0xFFFF;
+
+                table[index2] = temp1 | temp2;
+                index2 += 0x100;
+            }
+        }
+
+        table
+    })
+}
+
+// Hashes a file name the way Storm does: case insensitive and with '/' treated as '\'. File names are
+// hashed as raw bytes as sound file names are often in the map's code page rather than UTF-8.
+pub(crate) fn hash_string(s: &[u8], hash_type: u32) -> u32 {
+    let table = crypt_table();
+    let mut seed1: u32 = 0x7FED7FED;
+    let mut seed2: u32 = 0xEEEEEEEE;
+
+    for &ch in s {
+        let ch = match ch {
+            b'/' => b'\\',
+            _ => ch.to_ascii_uppercase(),
+        } as u32;
+
+        seed1 = table[(hash_type * 0x100 + ch) as usize] ^ seed1.wrapping_add(seed2);
+        seed2 = ch
+            .wrapping_add(seed1)
+            .wrapping_add(seed2)
+            .wrapping_add(seed2 << 5)
+            .wrapping_add(3);
+    }
+
+    seed1
+}
+
+// Whether two names refer to the same file, ignoring case and treating '/' as '\' like Storm does.
+pub(crate) fn same_filename(a: &[u8], b: &[u8]) -> bool {
+    hash_string(a, HASH_NAME_A) == hash_string(b, HASH_NAME_A)
+        && hash_string(a, HASH_NAME_B) == hash_string(b, HASH_NAME_B)
+}
+
+// The key of a file is derived from its name without the path. FIX_KEY files also mix in their position and size.
+pub(crate) fn file_key(filename: &[u8], file_position: u32, file_size: u32, fix_key: bool) -> u32 {
+    let name = filename
+        .rsplit(|&x| x == b'\\' || x == b'/')
+        .next()
+        .unwrap_or(filename);
+    let key = hash_string(name, HASH_FILE_KEY);
+
+    if fix_key {
+        key.wrapping_add(file_position) ^ file_size
+    } else {
+        key
+    }
+}
+
+// Decrypts whole u32s in place, a trailing partial u32 is left alone.
+pub(crate) fn decrypt(data: &mut [u8], mut key: u32) {
+    let table = crypt_table();
+    let mut seed: u32 = 0xEEEEEEEE;
+
+    for x in data.as_chunks_mut::<4>().0 {
+        seed = seed.wrapping_add(table[0x400 + (key & 0xFF) as usize]);
+
+        let ch = u32::from_le_bytes(*x) ^ key.wrapping_add(seed);
+        *x = ch.to_le_bytes();
+
+        key = ((!key << 0x15).wrapping_add(0x11111111)) | (key >> 0x0B);
+        seed = ch
+            .wrapping_add(seed)
+            .wrapping_add(seed << 5)
+            .wrapping_add(3);
+    }
+}
+
+pub(crate) fn encrypt(data: &mut [u8], mut key: u32) {
+    let table = crypt_table();
+    let mut seed: u32 = 0xEEEEEEEE;
+
+    for x in data.as_chunks_mut::<4>().0 {
+        seed = seed.wrapping_add(table[0x400 + (key & 0xFF) as usize]);
+
+        let ch = u32::from_le_bytes(*x);
+        *x = (ch ^ key.wrapping_add(seed)).to_le_bytes();
+
+        key = ((!key << 0x15).wrapping_add(0x11111111)) | (key >> 0x0B);
+        seed = ch
+            .wrapping_add(seed)
+            .wrapping_add(seed << 5)
+            .wrapping_add(3);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decrypt, encrypt, hash_string, HASH_FILE_KEY};
+
+    #[test]
+    fn table_keys_match_storm() {
+        assert_eq!(hash_string(b"(hash table)", HASH_FILE_KEY), 0xC3AF3770);
+        assert_eq!(hash_string(b"(block table)", HASH_FILE_KEY), 0xEC83B3A3);
+    }
+
+    #[test]
+    fn hash_is_case_and_separator_insensitive() {
+        assert_eq!(
+            hash_string(b"staredit\\scenario.chk", 1),
+            hash_string(b"STAREDIT/Scenario.CHK", 1)
+        );
+    }
+
+    #[test]
+    fn decrypt_reverses_encrypt() {
+        let original: Vec<u8> = (0..38).collect();
+        let mut data = original.clone();
+
+        encrypt(&mut data, 0x12345678);
+        assert_ne!(data, original);
+        // The trailing 2 bytes are not part of a u32.
+        assert_eq!(data[36..], original[36..]);
+
+        decrypt(&mut data, 0x12345678);
+        assert_eq!(data, original);
+    }
+}
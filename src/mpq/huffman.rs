@@ -0,0 +1,404 @@
+// Decompressor for the adaptive Huffman coding Storm applies to WAV files after ADPCM.
+//
+// Every node of the tree is also kept in a list sorted by weight in descending order. The two
+// children of a node are always next to each other in that list, the lower weight one second, so a
+// node only has to store its lower weight child.
+
+use super::MpqError;
+
+const END_OF_STREAM: u16 = 0x100;
+const NEW_BYTE: u16 = 0x101;
+
+// 0x102 leaves and the 0x101 nodes above them, the list head is stored in front.
+const MAX_NODE_COUNT: usize = 0x204;
+
+// Index of the list head, its next node is the root of the tree.
+const HEAD: usize = 0;
+
+// The weights every byte starts with for each compression type. ADPCM picks type 6, 7 or 8 from its
+// compression level, type 0 starts from nothing and keeps adapting to every byte it sees.
+fn weights(compression_type: u8) -> Option<[u8; 256]> {
+    let rows: &[(usize, &[u8])] = match compression_type {
+        0 => return Some([0x0A; 256]),
+        6 => &[
+            (0x00, &[0xC3, 0xCB, 0xF5, 0x41, 0xFF, 0x7B, 0xF7, 0x21]),
+            (0x40, &[0xBF, 0xCC, 0xF2, 0x40, 0xFD, 0x7C, 0xF7, 0x22]),
+            (0x80, &[0x7A, 0x46]),
+        ],
+        7 => &[
+            (
+                0x00,
+                &[
+                    0xC3, 0xD9, 0xEF, 0x3D, 0xF9, 0x7C, 0xE9, 0x1E, 0xFD, 0xAB, 0xF1, 0x2C, 0xFC,
+                    0x5B, 0xFE, 0x17,
+                ],
+            ),
+            (
+                0x40,
+                &[
+                    0xBD, 0xD9, 0xEC, 0x3D, 0xF5, 0x7D, 0xE8, 0x1D, 0xFB, 0xAE, 0xF0, 0x2C, 0xFB,
+                    0x5C, 0xFF, 0x18,
+                ],
+            ),
+            (0x80, &[0x70, 0x6C]),
+        ],
+        8 => &[
+            (
+                0x00,
+                &[
+                    0xBA, 0xC5, 0xDA, 0x33, 0xE3, 0x6D, 0xD8, 0x18, 0xE5, 0x94, 0xDA, 0x23, 0xDF,
+                    0x4A, 0xD1, 0x10, 0xEE, 0xAF, 0xE4, 0x2C, 0xEA, 0x5A, 0xDE, 0x15, 0xF4, 0x87,
+                    0xE9, 0x21, 0xF6, 0x43, 0xFC, 0x12,
+                ],
+            ),
+            (
+                0x40,
+                &[
+                    0xB0, 0xC7, 0xD8, 0x33, 0xE3, 0x6B, 0xD6, 0x1C, 0xE4, 0x92, 0xD9, 0x22, 0xDE,
+                    0x4C, 0xD1, 0x11, 0xEF, 0xB0, 0xE3, 0x2B, 0xEA, 0x5C, 0xDE, 0x16, 0xF3, 0x86,
+                    0xEA, 0x21, 0xF4, 0x45, 0xFB, 0x13,
+                ],
+            ),
+            (0x80, &[0x5E, 0x55]),
+        ],
+        _ => return None,
+    };
+
+    let mut ret = [0; 256];
+
+    for &(offset, row) in rows {
+        ret[offset..offset + row.len()].copy_from_slice(row);
+    }
+
+    Some(ret)
+}
+
+#[derive(Clone, Copy, Default)]
+struct Node {
+    value: u16,
+    weight: u32,
+    parent: Option<usize>,
+    child_lo: Option<usize>,
+    prev: usize,
+    next: usize,
+}
+
+struct Tree {
+    nodes: Vec<Node>,
+    by_value: [Option<usize>; 0x102],
+    // Type 0 raises the weight of every byte it decodes, the others only of new bytes.
+    adaptive: bool,
+}
+
+impl Tree {
+    fn new(compression_type: u8) -> Result<Tree, MpqError> {
+        let weights = weights(compression_type).ok_or_else(|| MpqError::Decompression {
+            reason: format!("unknown Huffman compression type {compression_type}"),
+        })?;
+
+        let mut tree = Tree {
+            nodes: vec![Node::default()],
+            by_value: [None; 0x102],
+            adaptive: compression_type == 0,
+        };
+
+        for (value, &weight) in weights.iter().enumerate() {
+            if weight != 0 {
+                let node = tree.push(value as u16, weight as u32);
+                tree.insert_after(node, tree.find_higher_or_equal(tree.last(), weight as u32));
+                tree.by_value[value] = Some(node);
+            }
+        }
+
+        for value in [END_OF_STREAM, NEW_BYTE] {
+            let node = tree.push(value, 1);
+            tree.insert_before(node, HEAD);
+            tree.by_value[value as usize] = Some(node);
+        }
+
+        // Pair up the two lowest weight nodes under a new parent until only the root is left.
+        let mut child_lo = tree.last();
+
+        while child_lo != HEAD {
+            let child_hi = tree.nodes[child_lo].prev;
+            if child_hi == HEAD {
+                break;
+            }
+
+            let weight = tree.nodes[child_lo].weight + tree.nodes[child_hi].weight;
+            let parent = tree.push(0, weight);
+            tree.insert_after(parent, tree.find_higher_or_equal(tree.last(), weight));
+
+            tree.nodes[child_lo].parent = Some(parent);
+            tree.nodes[child_hi].parent = Some(parent);
+            tree.nodes[parent].child_lo = Some(child_lo);
+
+            child_lo = tree.nodes[child_hi].prev;
+        }
+
+        Ok(tree)
+    }
+
+    fn push(&mut self, value: u16, weight: u32) -> usize {
+        self.nodes.push(Node {
+            value,
+            weight,
+            ..Node::default()
+        });
+        self.nodes.len() - 1
+    }
+
+    fn root(&self) -> usize {
+        self.nodes[HEAD].next
+    }
+
+    fn last(&self) -> usize {
+        self.nodes[HEAD].prev
+    }
+
+    fn unlink(&mut self, node: usize) {
+        let Node { prev, next, .. } = self.nodes[node];
+        self.nodes[prev].next = next;
+        self.nodes[next].prev = prev;
+    }
+
+    // `node` must not be in the list.
+    fn insert_after(&mut self, node: usize, at: usize) {
+        let next = self.nodes[at].next;
+        self.nodes[node].prev = at;
+        self.nodes[node].next = next;
+        self.nodes[next].prev = node;
+        self.nodes[at].next = node;
+    }
+
+    fn insert_before(&mut self, node: usize, at: usize) {
+        self.insert_after(node, self.nodes[at].prev);
+    }
+
+    // Walks towards the root from `node` and returns the first node with at least `weight`, or the
+    // list head if there is none.
+    fn find_higher_or_equal(&self, mut node: usize, weight: u32) -> usize {
+        while node != HEAD {
+            if self.nodes[node].weight >= weight {
+                return node;
+            }
+            node = self.nodes[node].prev;
+        }
+
+        HEAD
+    }
+
+    fn node(&self, value: u8) -> Option<usize> {
+        self.by_value[value as usize]
+    }
+
+    // Called after a byte has been coded with its own node, or added with the new byte marker.
+    fn update(&mut self, value: u8) -> Result<(), MpqError> {
+        match self.node(value) {
+            Some(node) if self.adaptive => self.increment(node),
+            Some(_) => {}
+            None => {
+                // The lowest weight leaf becomes the parent of itself and the new byte.
+                let last = self.last();
+                if self.nodes.len() + 2 > MAX_NODE_COUNT {
+                    return Err(MpqError::Decompression {
+                        reason: "too many bytes added to the Huffman tree".to_owned(),
+                    });
+                }
+
+                let child_hi = self.push(self.nodes[last].value, self.nodes[last].weight);
+                self.insert_before(child_hi, HEAD);
+                self.nodes[child_hi].parent = Some(last);
+                self.by_value[self.nodes[last].value as usize] = Some(child_hi);
+
+                let child_lo = self.push(value as u16, 0);
+                self.insert_before(child_lo, HEAD);
+                self.nodes[child_lo].parent = Some(last);
+                self.nodes[last].child_lo = Some(child_lo);
+                self.by_value[value as usize] = Some(child_lo);
+
+                self.increment(child_lo);
+                self.increment(child_lo);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Raises the weight of `node` and its ancestors, swapping each with the first node of its old
+    // weight so the list stays sorted.
+    fn increment(&mut self, mut node: usize) {
+        loop {
+            self.nodes[node].weight += 1;
+            let weight = self.nodes[node].weight;
+
+            let higher = self.find_higher_or_equal(self.nodes[node].prev, weight);
+            let other = self.nodes[higher].next;
+
+            if other != node {
+                if let (Some(parent), Some(other_parent)) =
+                    (self.nodes[node].parent, self.nodes[other].parent)
+                {
+                    self.unlink(other);
+                    self.insert_before(other, node);
+                    self.unlink(node);
+                    self.insert_after(node, higher);
+
+                    let other_parent_child_lo = self.nodes[other_parent].child_lo;
+                    if self.nodes[parent].child_lo == Some(node) {
+                        self.nodes[parent].child_lo = Some(other);
+                    }
+                    if other_parent_child_lo == Some(other) {
+                        self.nodes[other_parent].child_lo = Some(node);
+                    }
+
+                    self.nodes[node].parent = Some(other_parent);
+                    self.nodes[other].parent = Some(parent);
+                }
+            }
+
+            match self.nodes[node].parent {
+                Some(parent) => node = parent,
+                None => break,
+            }
+        }
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl BitReader<'_> {
+    fn bit(&mut self) -> Result<bool, MpqError> {
+        let byte = self
+            .data
+            .get(self.offset / 8)
+            .ok_or_else(|| MpqError::Decompression {
+                reason: "Huffman stream ended early".to_owned(),
+            })?;
+        let ret = byte >> (self.offset % 8) & 1 != 0;
+        self.offset += 1;
+        Ok(ret)
+    }
+
+    fn byte(&mut self) -> Result<u8, MpqError> {
+        let mut ret = 0;
+        for bit in 0..8 {
+            ret |= (self.bit()? as u8) << bit;
+        }
+        Ok(ret)
+    }
+}
+
+pub(crate) fn decompress_huffman(data: &[u8], expected_size: usize) -> Result<Vec<u8>, MpqError> {
+    let mut reader = BitReader { data, offset: 0 };
+    let mut tree = Tree::new(reader.byte()?)?;
+    let mut ret = Vec::with_capacity(expected_size);
+
+    while ret.len() < expected_size {
+        // 1 picks the higher weight child, which is right before the lower weight one.
+        let mut node = tree.root();
+        while let Some(child_lo) = tree.nodes[node].child_lo {
+            node = if reader.bit()? {
+                tree.nodes[child_lo].prev
+            } else {
+                child_lo
+            };
+        }
+        let value = tree.nodes[node].value;
+
+        let value = match value {
+            END_OF_STREAM => break,
+            NEW_BYTE => reader.byte()?,
+            value => value as u8,
+        };
+
+        tree.update(value)?;
+        ret.push(value);
+    }
+
+    Ok(ret)
+}
+
+// Mirror of the decoder, used to build test archives.
+#[cfg(test)]
+pub(crate) fn compress_huffman(data: &[u8], compression_type: u8) -> Vec<u8> {
+    let mut tree = Tree::new(compression_type).unwrap();
+    let mut bits = Vec::new();
+
+    // Codes are the path from the root, 1 picks the higher weight child.
+    fn put_code(tree: &Tree, bits: &mut Vec<bool>, mut node: usize) {
+        let mut code = Vec::new();
+        while let Some(parent) = tree.nodes[node].parent {
+            code.push(tree.nodes[parent].child_lo != Some(node));
+            node = parent;
+        }
+        bits.extend(code.iter().rev());
+    }
+
+    bits.extend((0..8).map(|bit| compression_type >> bit & 1 != 0));
+
+    for &byte in data {
+        match tree.node(byte) {
+            Some(node) => put_code(&tree, &mut bits, node),
+            None => {
+                put_code(&tree, &mut bits, tree.by_value[NEW_BYTE as usize].unwrap());
+                bits.extend((0..8).map(|bit| byte >> bit & 1 != 0));
+            }
+        }
+        tree.update(byte).unwrap();
+    }
+
+    put_code(
+        &tree,
+        &mut bits,
+        tree.by_value[END_OF_STREAM as usize].unwrap(),
+    );
+
+    bits.chunks(8)
+        .map(|x| {
+            x.iter()
+                .enumerate()
+                .fold(0, |acc, (bit, &set)| acc | (set as u8) << bit)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compress_huffman, decompress_huffman};
+
+    #[test]
+    fn round_trips_every_compression_type() {
+        let data: Vec<u8> = (0..4000u32)
+            .map(|x| match x % 5 {
+                0 => 0x80,
+                1 => (x % 13) as u8,
+                2 => 0x40 | (x % 11) as u8,
+                _ => (x * 7 % 256) as u8,
+            })
+            .collect();
+
+        for compression_type in [0, 6, 7, 8] {
+            let compressed = compress_huffman(&data, compression_type);
+            assert_eq!(
+                decompress_huffman(&compressed, data.len()).unwrap(),
+                data,
+                "compression type {compression_type}"
+            );
+        }
+    }
+
+    #[test]
+    fn stops_at_end_of_stream() {
+        let compressed = compress_huffman(b"abc", 0);
+        assert_eq!(decompress_huffman(&compressed, 100).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn rejects_unknown_compression_type() {
+        assert!(decompress_huffman(&[9, 0, 0], 4).is_err());
+    }
+}
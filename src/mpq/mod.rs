@@ -0,0 +1,88 @@
+mod adpcm;
+mod archive;
+mod compression;
+mod crypt;
+mod huffman;
+mod pkware;
+mod writer;
+
+pub use archive::{Mpq, MpqBlockEntry, MpqHashEntry};
+pub use writer::MpqWriter;
+
+pub(crate) use crypt::same_filename;
+#[cfg(test)]
+pub(crate) use huffman::compress_huffman;
+
+use crate::ParsedChkOwned;
+use std::path::Path;
+use tracing::instrument;
+
+pub const SCENARIO_CHK: &str = "staredit\\scenario.chk";
+
+#[derive(Debug)]
+pub enum MpqError {
+    Io(std::io::Error),
+    // No MPQ header was found at any 512 byte boundary.
+    HeaderNotFound,
+    FileNotFound {
+        filename: String,
+    },
+    // Offsets are absolute byte offsets into the archive file.
+    Truncated {
+        offset: usize,
+        expected: usize,
+        actual: usize,
+    },
+    UnsupportedCompression {
+        mask: u8,
+    },
+    Decompression {
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for MpqError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MpqError::Io(err) => write!(f, "{err}"),
+            MpqError::HeaderNotFound => write!(f, "MPQ header not found"),
+            MpqError::FileNotFound { filename } => write!(f, "file {filename:?} not found"),
+            MpqError::Truncated {
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "archive is truncated at offset {offset}, needed {expected} bytes but only {actual} are left"
+            ),
+            MpqError::UnsupportedCompression { mask } => {
+                write!(f, "unsupported compression mask 0x{mask:02x}")
+            }
+            MpqError::Decompression { reason } => write!(f, "decompression failed: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for MpqError {}
+
+impl From<std::io::Error> for MpqError {
+    fn from(err: std::io::Error) -> MpqError {
+        MpqError::Io(err)
+    }
+}
+
+// Extracts staredit\scenario.chk from the bytes of a .scm/.scx.
+#[instrument(level = "trace", skip_all)]
+pub fn read_chk(archive: &[u8]) -> Result<Vec<u8>, MpqError> {
+    Mpq::from_bytes(archive)?.read_file(SCENARIO_CHK)
+}
+
+// Opens a .scm/.scx and parses its scenario.chk. ParsedChk borrows from the chk bytes so the owned model is
+// returned here, use read_chk and ParsedChk::from_bytes to get the zero-copy one.
+#[instrument(level = "trace", skip_all)]
+pub fn open_map(path: impl AsRef<Path>) -> Result<ParsedChkOwned, MpqError> {
+    let archive = std::fs::read(path)?;
+    let chk = read_chk(archive.as_slice())?;
+
+    Ok(ParsedChkOwned::from_bytes(chk.as_slice()))
+}
@@ -0,0 +1,350 @@
+// The PKWARE Data Compression Library "implode" format. The decompressor follows Mark Adler's blast.c.
+
+use super::MpqError;
+
+const MAX_BITS: usize = 13;
+
+// Code lengths of the literal, length and distance codes, run length encoded as
+// (number of repeats - 1) << 4 | length.
+const LITERAL_LENGTHS: [u8; 98] = [
+    11, 124, 8, 7, 28, 7, 188, 13, 76, 4, 10, 8, 12, 10, 12, 10, 8, 23, 8, 9, 7, 6, 7, 8, 7, 6, 55,
+    8, 23, 24, 12, 11, 7, 9, 11, 12, 6, 7, 22, 5, 7, 24, 6, 11, 9, 6, 7, 22, 7, 11, 38, 7, 9, 8,
+    25, 11, 8, 11, 9, 12, 8, 12, 5, 38, 5, 38, 5, 11, 7, 5, 6, 21, 6, 10, 53, 8, 7, 24, 10, 27, 44,
+    253, 253, 253, 252, 252, 252, 13, 12, 45, 12, 45, 12, 61, 12, 45, 44, 173,
+];
+const LENGTH_LENGTHS: [u8; 6] = [2, 35, 36, 53, 38, 23];
+const DISTANCE_LENGTHS: [u8; 7] = [2, 20, 53, 230, 247, 151, 248];
+
+const LENGTH_BASE: [u16; 16] = [3, 2, 4, 5, 6, 7, 8, 9, 10, 12, 16, 24, 40, 72, 136, 264];
+const LENGTH_EXTRA: [u8; 16] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8];
+
+struct Huffman {
+    count: [u16; MAX_BITS + 1],
+    symbol: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(compact: &[u8]) -> Huffman {
+        let lengths: Vec<usize> = compact
+            .iter()
+            .flat_map(|&x| std::iter::repeat_n((x & 15) as usize, (x >> 4) as usize + 1))
+            .collect();
+
+        let mut count = [0u16; MAX_BITS + 1];
+        for &length in &lengths {
+            count[length] += 1;
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for length in 1..MAX_BITS {
+            offsets[length + 1] = offsets[length] + count[length];
+        }
+
+        let mut symbol = vec![0u16; lengths.len()];
+        for (i, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbol[offsets[length] as usize] = i as u16;
+                offsets[length] += 1;
+            }
+        }
+
+        Huffman { count, symbol }
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+    bit_buffer: u32,
+    bit_count: u32,
+}
+
+impl BitReader<'_> {
+    fn bits(&mut self, need: u32) -> Result<u32, MpqError> {
+        let mut value = self.bit_buffer;
+
+        while self.bit_count < need {
+            let byte = *self.data.get(self.offset).ok_or(MpqError::Decompression {
+                reason: "implode stream ended early".to_owned(),
+            })?;
+            self.offset += 1;
+
+            value |= (byte as u32) << self.bit_count;
+            self.bit_count += 8;
+        }
+
+        self.bit_buffer = value >> need;
+        self.bit_count -= need;
+
+        Ok(value & ((1 << need) - 1))
+    }
+
+    // The codes are stored bit reversed and inverted compared to a canonical Huffman code.
+    fn decode(&mut self, huffman: &Huffman) -> Result<u16, MpqError> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+
+        for length in 1..=MAX_BITS {
+            code |= (self.bits(1)? ^ 1) as i32;
+            let count = huffman.count[length] as i32;
+
+            if code - count < first {
+                return Ok(huffman.symbol[(index + (code - first)) as usize]);
+            }
+
+            index += count;
+            first += count;
+            first <<= 1;
+            code <<= 1;
+        }
+
+        Err(MpqError::Decompression {
+            reason: "invalid implode code".to_owned(),
+        })
+    }
+}
+
+pub(crate) fn explode(data: &[u8], expected_size: usize) -> Result<Vec<u8>, MpqError> {
+    let literals = Huffman::new(&LITERAL_LENGTHS);
+    let lengths = Huffman::new(&LENGTH_LENGTHS);
+    let distances = Huffman::new(&DISTANCE_LENGTHS);
+
+    let mut reader = BitReader {
+        data,
+        offset: 0,
+        bit_buffer: 0,
+        bit_count: 0,
+    };
+
+    let coded_literals = reader.bits(8)?;
+    let dictionary_bits = reader.bits(8)?;
+
+    if coded_literals > 1 || !(4..=6).contains(&dictionary_bits) {
+        return Err(MpqError::Decompression {
+            reason: format!("invalid implode header {coded_literals} {dictionary_bits}"),
+        });
+    }
+
+    let mut ret = Vec::with_capacity(expected_size);
+
+    while ret.len() < expected_size {
+        if reader.bits(1)? == 1 {
+            let symbol = reader.decode(&lengths)? as usize;
+            let length =
+                LENGTH_BASE[symbol] as usize + reader.bits(LENGTH_EXTRA[symbol] as u32)? as usize;
+
+            // End of stream.
+            if length == 519 {
+                break;
+            }
+
+            let shift = if length == 2 { 2 } else { dictionary_bits };
+            let distance =
+                ((reader.decode(&distances)? as usize) << shift) + reader.bits(shift)? as usize + 1;
+
+            if distance > ret.len() {
+                return Err(MpqError::Decompression {
+                    reason: "implode distance is too far back".to_owned(),
+                });
+            }
+
+            for _ in 0..length {
+                ret.push(ret[ret.len() - distance]);
+            }
+        } else {
+            let symbol = if coded_literals == 1 {
+                reader.decode(&literals)? as u8
+            } else {
+                reader.bits(8)? as u8
+            };
+
+            ret.push(symbol);
+        }
+    }
+
+    ret.truncate(expected_size);
+
+    Ok(ret)
+}
+
+// Canonical code and code length of every symbol, matching the codes BitReader::decode reads.
+fn canonical_codes(compact: &[u8]) -> Vec<(u16, u32)> {
+    let huffman = Huffman::new(compact);
+
+    let mut ret = vec![(0, 0); huffman.symbol.len()];
+    let mut code = 0;
+    let mut first_symbol = 0;
+
+    for (length, &count) in huffman.count.iter().enumerate().skip(1) {
+        let count = count as usize;
+
+        for (i, &symbol) in huffman.symbol[first_symbol..first_symbol + count]
+            .iter()
+            .enumerate()
+        {
+            ret[symbol as usize] = (code + i as u16, length as u32);
+        }
+
+        code = (code + count as u16) << 1;
+        first_symbol += count;
+    }
+
+    ret
+}
+
+#[derive(Default)]
+struct BitWriter {
+    data: Vec<u8>,
+    bit_buffer: u32,
+    bit_count: u32,
+}
+
+impl BitWriter {
+    fn bits(&mut self, value: u32, count: u32) {
+        self.bit_buffer |= value << self.bit_count;
+        self.bit_count += count;
+
+        while self.bit_count >= 8 {
+            self.data.push(self.bit_buffer as u8);
+            self.bit_buffer >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    fn code(&mut self, (code, length): (u16, u32)) {
+        for i in (0..length).rev() {
+            self.bits(((code as u32 >> i) & 1) ^ 1, 1);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bit_count > 0 {
+            self.data.push(self.bit_buffer as u8);
+        }
+
+        self.data
+    }
+}
+
+// Compresses with uncoded literals and a 4096 byte dictionary using greedy matching.
+pub(crate) fn implode(data: &[u8]) -> Vec<u8> {
+    const DICTIONARY_BITS: u32 = 6;
+    const WINDOW_SIZE: usize = 4096;
+    const MIN_LENGTH: usize = 3;
+    const MAX_LENGTH: usize = 518;
+    const MAX_CHAIN: usize = 64;
+    const NONE: usize = usize::MAX;
+
+    let length_codes = canonical_codes(&LENGTH_LENGTHS);
+    let distance_codes = canonical_codes(&DISTANCE_LENGTHS);
+
+    let mut writer = BitWriter::default();
+    writer.bits(0, 8);
+    writer.bits(DICTIONARY_BITS, 8);
+
+    let hash = |i: usize| {
+        ((data[i] as usize) << 8 ^ (data[i + 1] as usize) << 4 ^ data[i + 2] as usize) & 0xFFFF
+    };
+    let mut head = vec![NONE; 0x10000];
+    let mut prev = vec![NONE; data.len()];
+
+    let mut i = 0;
+    while i < data.len() {
+        let mut best_length = 0;
+        let mut best_distance = 0;
+
+        if i + MIN_LENGTH <= data.len() {
+            let mut candidate = head[hash(i)];
+            let mut chain = 0;
+
+            while candidate != NONE && i - candidate <= WINDOW_SIZE && chain < MAX_CHAIN {
+                let length = data[candidate..]
+                    .iter()
+                    .zip(&data[i..])
+                    .take(MAX_LENGTH)
+                    .take_while(|(a, b)| a == b)
+                    .count();
+
+                if length > best_length {
+                    best_length = length;
+                    best_distance = i - candidate;
+                }
+
+                candidate = prev[candidate];
+                chain += 1;
+            }
+        }
+
+        let advance = if best_length >= MIN_LENGTH {
+            let symbol = (0..16)
+                .find(|&x| {
+                    let base = LENGTH_BASE[x] as usize;
+                    best_length >= base && best_length < base + (1 << LENGTH_EXTRA[x])
+                })
+                .unwrap_or(15);
+            let distance = best_distance - 1;
+
+            writer.bits(1, 1);
+            writer.code(length_codes[symbol]);
+            writer.bits(
+                (best_length - LENGTH_BASE[symbol] as usize) as u32,
+                LENGTH_EXTRA[symbol] as u32,
+            );
+            writer.code(distance_codes[distance >> DICTIONARY_BITS]);
+            writer.bits(
+                (distance & ((1 << DICTIONARY_BITS) - 1)) as u32,
+                DICTIONARY_BITS,
+            );
+
+            best_length
+        } else {
+            writer.bits(0, 1);
+            writer.bits(data[i] as u32, 8);
+
+            1
+        };
+
+        for (j, link) in prev.iter_mut().enumerate().skip(i).take(advance) {
+            if j + MIN_LENGTH <= data.len() {
+                let h = hash(j);
+                *link = head[h];
+                head[h] = j;
+            }
+        }
+
+        i += advance;
+    }
+
+    // The end of stream marker is a length of 519.
+    writer.bits(1, 1);
+    writer.code(length_codes[15]);
+    writer.bits(519 - LENGTH_BASE[15] as u32, LENGTH_EXTRA[15] as u32);
+
+    writer.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{explode, implode};
+
+    #[test]
+    fn explodes_blast_example() {
+        let data = [0x00, 0x04, 0x82, 0x24, 0x25, 0x8f, 0x80, 0x7f];
+
+        assert_eq!(explode(&data, 100).unwrap(), b"AIAIAIAIAIAIA");
+    }
+
+    #[test]
+    fn implode_round_trip() {
+        let data: Vec<u8> = (0..20000u32)
+            .map(|x| (x % 600 * 7 % 251) as u8)
+            .chain(b"scenario.chk".repeat(100))
+            .collect();
+        let compressed = implode(data.as_slice());
+
+        assert!(compressed.len() < data.len() / 4);
+        assert!(explode(compressed.as_slice(), data.len()).unwrap() == data);
+        assert!(explode(implode(&[]).as_slice(), 0).unwrap().is_empty());
+    }
+}
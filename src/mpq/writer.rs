@@ -0,0 +1,399 @@
+use super::{
+    archive::{MpqBlockEntry, MpqHashEntry, HASH_ENTRY_EMPTY, MPQ_FILE_COMPRESS, MPQ_FILE_EXISTS},
+    compression::COMPRESSION_PKWARE,
+    crypt::{
+        encrypt, hash_string, same_filename, HASH_FILE_KEY, HASH_NAME_A, HASH_NAME_B,
+        HASH_TABLE_OFFSET,
+    },
+    pkware::implode,
+    Mpq, MpqError, SCENARIO_CHK,
+};
+use crate::ParsedChk;
+use tracing::instrument;
+
+const LISTFILE: &str = "(listfile)";
+
+// 512 << 3 = 4096 byte sectors, the size StarEdit uses.
+const SECTOR_SIZE_SHIFT: u16 = 3;
+const SECTOR_SIZE: usize = 512 << SECTOR_SIZE_SHIFT;
+
+// Collects files and emits them as an MPQ v1 archive, i.e. a .scm/.scx.
+// A (listfile) naming every file is always added. Files are never encrypted. When compression is enabled
+// every sector is imploded with PKWARE DCL, which is the only compression the original StarCraft can read,
+// and sectors that do not get smaller are stored as is.
+#[derive(Debug, Default, Clone)]
+pub struct MpqWriter {
+    files: Vec<(Vec<u8>, Vec<u8>)>,
+    compress: bool,
+}
+
+impl MpqWriter {
+    pub fn new() -> MpqWriter {
+        MpqWriter::default()
+    }
+
+    // Builds an archive holding the chk and every sound it references that exists in source, which is
+    // usually the archive the chk was read from. Sounds are referenced by the WAV section and by
+    // Transmission and PlayWav actions, see ParsedChk::get_sound_references. Referenced files that are
+    // missing from source are left out.
+    #[instrument(level = "trace", skip_all)]
+    pub fn from_map(chk: &[u8], source: &Mpq) -> Result<MpqWriter, MpqError> {
+        let mut ret = MpqWriter::new();
+        ret.add_file(SCENARIO_CHK, chk.to_vec());
+
+        let parsed_chk = ParsedChk::from_bytes(chk);
+        let mut filenames: Vec<Vec<u8>> = Vec::new();
+
+        for reference in parsed_chk.get_sound_references() {
+            if filenames
+                .iter()
+                .any(|x| same_filename(x, &reference.filename))
+            {
+                continue;
+            }
+
+            match source.read_file(&reference.filename) {
+                Ok(data) => ret.add_file(&reference.filename, data),
+                Err(MpqError::FileNotFound { .. }) => {}
+                Err(err) => return Err(err),
+            }
+
+            filenames.push(reference.filename);
+        }
+
+        Ok(ret)
+    }
+
+    pub fn set_compression(&mut self, compress: bool) {
+        self.compress = compress;
+    }
+
+    // Adds a file. If a file with the same name was already added it is replaced in place. Names are
+    // compared the way Storm compares them, ignoring case and treating '/' as '\'.
+    pub fn add_file(&mut self, filename: impl AsRef<[u8]>, data: Vec<u8>) {
+        let filename = filename.as_ref();
+
+        if let Some(existing) = self
+            .files
+            .iter_mut()
+            .find(|(name, _)| same_filename(name, filename))
+        {
+            existing.1 = data;
+        } else {
+            self.files.push((filename.to_vec(), data));
+        }
+    }
+
+    pub fn remove_file(&mut self, filename: impl AsRef<[u8]>) {
+        self.files
+            .retain(|(name, _)| !same_filename(name, filename.as_ref()));
+    }
+
+    pub fn files(&self) -> &[(Vec<u8>, Vec<u8>)] {
+        self.files.as_slice()
+    }
+
+    // Splits a file into sectors preceded by the sector offset table.
+    fn compress_file(
+        name: &[u8],
+        data: &[u8],
+        compress_sector: &impl Fn(&[u8], usize, &[u8]) -> Vec<u8>,
+    ) -> Vec<u8> {
+        let sectors: Vec<Vec<u8>> = data
+            .chunks(SECTOR_SIZE)
+            .enumerate()
+            .map(|(sector_index, x)| {
+                let compressed = compress_sector(name, sector_index, x);
+
+                if compressed.len() < x.len() {
+                    compressed
+                } else {
+                    x.to_vec()
+                }
+            })
+            .collect();
+
+        let mut offset = (sectors.len() as u32 + 1) * 4;
+        let mut ret = offset.to_le_bytes().to_vec();
+
+        for sector in &sectors {
+            offset += sector.len() as u32;
+            ret.extend(offset.to_le_bytes());
+        }
+
+        ret.extend(sectors.concat());
+        ret
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.build(|_, _, x| [&[COMPRESSION_PKWARE][..], &implode(x)].concat())
+    }
+
+    // compress_sector is given the filename, the sector index and the sector, and returns the sector
+    // with its compression mask in front. It lets tests store sectors the way StarEdit does.
+    pub(crate) fn build(
+        &self,
+        compress_sector: impl Fn(&[u8], usize, &[u8]) -> Vec<u8>,
+    ) -> Vec<u8> {
+        let listfile = self
+            .files
+            .iter()
+            .map(|(name, _)| name.as_slice())
+            .collect::<Vec<_>>()
+            .join(&b"\r\n"[..]);
+
+        let files: Vec<(&[u8], &[u8])> = self
+            .files
+            .iter()
+            .filter(|(name, _)| !same_filename(name, LISTFILE.as_bytes()))
+            .map(|(name, data)| (name.as_slice(), data.as_slice()))
+            .chain([(LISTFILE.as_bytes(), listfile.as_slice())])
+            .collect();
+
+        // Keep the hash table at most half full so lookups stay short.
+        let hash_table_entries = (files.len() * 2).max(16).next_power_of_two();
+
+        let mut hash_table = vec![
+            MpqHashEntry {
+                name_a: 0xFFFFFFFF,
+                name_b: 0xFFFFFFFF,
+                locale: 0xFFFF,
+                platform: 0xFFFF,
+                block_index: HASH_ENTRY_EMPTY,
+            };
+            hash_table_entries
+        ];
+        let mut block_table = Vec::with_capacity(files.len());
+        let mut body = Vec::new();
+
+        for (block_index, (name, data)) in files.into_iter().enumerate() {
+            let (stored, flags) = if self.compress {
+                (
+                    MpqWriter::compress_file(name, data, &compress_sector),
+                    MPQ_FILE_COMPRESS | MPQ_FILE_EXISTS,
+                )
+            } else {
+                (data.to_vec(), MPQ_FILE_EXISTS)
+            };
+
+            block_table.push(MpqBlockEntry {
+                file_position: 32 + body.len() as u32,
+                compressed_size: stored.len() as u32,
+                file_size: data.len() as u32,
+                flags,
+            });
+            body.extend(stored);
+
+            let mut slot = hash_string(name, HASH_TABLE_OFFSET) as usize & (hash_table_entries - 1);
+            while hash_table[slot].block_index != HASH_ENTRY_EMPTY {
+                slot = (slot + 1) % hash_table_entries;
+            }
+
+            hash_table[slot] = MpqHashEntry {
+                name_a: hash_string(name, HASH_NAME_A),
+                name_b: hash_string(name, HASH_NAME_B),
+                locale: 0,
+                platform: 0,
+                block_index: block_index as u32,
+            };
+        }
+
+        let mut hash_table_bytes: Vec<u8> = hash_table
+            .iter()
+            .flat_map(|x| {
+                [
+                    x.name_a.to_le_bytes(),
+                    x.name_b.to_le_bytes(),
+                    [x.locale.to_le_bytes(), x.platform.to_le_bytes()]
+                        .concat()
+                        .try_into()
+                        .unwrap(),
+                    x.block_index.to_le_bytes(),
+                ]
+                .concat()
+            })
+            .collect();
+        encrypt(
+            hash_table_bytes.as_mut_slice(),
+            hash_string(b"(hash table)", HASH_FILE_KEY),
+        );
+
+        let mut block_table_bytes: Vec<u8> = block_table
+            .iter()
+            .flat_map(|x| {
+                [
+                    x.file_position.to_le_bytes(),
+                    x.compressed_size.to_le_bytes(),
+                    x.file_size.to_le_bytes(),
+                    x.flags.to_le_bytes(),
+                ]
+                .concat()
+            })
+            .collect();
+        encrypt(
+            block_table_bytes.as_mut_slice(),
+            hash_string(b"(block table)", HASH_FILE_KEY),
+        );
+
+        let hash_table_position = 32 + body.len() as u32;
+        let block_table_position = hash_table_position + hash_table_bytes.len() as u32;
+        let archive_size = block_table_position + block_table_bytes.len() as u32;
+
+        let header = [
+            &b"MPQ\x1A"[..],
+            &32u32.to_le_bytes(),
+            &archive_size.to_le_bytes(),
+            &0u16.to_le_bytes(),
+            &SECTOR_SIZE_SHIFT.to_le_bytes(),
+            &hash_table_position.to_le_bytes(),
+            &block_table_position.to_le_bytes(),
+            &(hash_table_entries as u32).to_le_bytes(),
+            &(block_table.len() as u32).to_le_bytes(),
+        ]
+        .concat();
+
+        [header, body, hash_table_bytes, block_table_bytes].concat()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MpqWriter;
+    use crate::{
+        chk::chk_trig::ChkTrigAction,
+        chunk_name::ChunkName,
+        mpq::{read_chk, Mpq, MpqError, SCENARIO_CHK},
+        test::{make_str, make_trig},
+        ChkWriter,
+    };
+
+    const WAV_FILENAME: &str = "staredit\\wav\\hello.wav";
+
+    fn make_chk() -> Vec<u8> {
+        let mut wav = vec![0u8; 512 * 4];
+        wav[0] = 1;
+
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(ChunkName::STR, make_str(&[WAV_FILENAME]));
+        writer.add_chunk(ChunkName::WAV, wav);
+        writer.add_chunk(ChunkName::MTXM, (0..20000).map(|x| (x % 7) as u8).collect());
+        writer.to_bytes()
+    }
+
+    fn make_wav() -> Vec<u8> {
+        (0..10000u32).map(|x| (x * 31 % 256) as u8).collect()
+    }
+
+    #[test]
+    fn round_trips_through_reader() {
+        for compress in [false, true] {
+            let mut writer = MpqWriter::new();
+            writer.set_compression(compress);
+            writer.add_file(SCENARIO_CHK, vec![1, 2, 3]);
+            writer.add_file("STAREDIT/SCENARIO.CHK", make_chk());
+            writer.add_file(WAV_FILENAME, make_wav());
+            writer.add_file("staredit\\wav\\unused.wav", vec![5; 100]);
+
+            let archive = writer.to_bytes();
+            let mpq = Mpq::from_bytes(archive.as_slice()).unwrap();
+
+            assert!(read_chk(archive.as_slice()).unwrap() == make_chk());
+            assert!(mpq.read_file(WAV_FILENAME).unwrap() == make_wav());
+            assert_eq!(
+                mpq.read_file("(listfile)").unwrap(),
+                b"staredit\\scenario.chk\r\nstaredit\\wav\\hello.wav\r\nstaredit\\wav\\unused.wav"
+            );
+            assert_eq!(mpq.block_table().len(), 4);
+
+            if compress {
+                assert!(archive.len() < make_chk().len());
+            }
+        }
+    }
+
+    #[test]
+    fn from_map_keeps_sounds_only_triggers_use() {
+        const TRIGGER_WAV_FILENAME: &str = "staredit\\wav\\trigger.wav";
+
+        // The WAV section lists string 1. A PlayWav plays string 2, which no other section lists,
+        // and a Transmission plays string 3, which is string 1 in a different case.
+        let mut wav = vec![0u8; 512 * 4];
+        wav[0] = 1;
+
+        let trig = make_trig(
+            &[],
+            &[
+                ChkTrigAction {
+                    wav_string_number: 2,
+                    action: 8,
+                    ..Default::default()
+                },
+                ChkTrigAction {
+                    wav_string_number: 3,
+                    action: 7,
+                    ..Default::default()
+                },
+            ],
+        );
+
+        let mut chk = ChkWriter::new();
+        chk.add_chunk(
+            ChunkName::STR,
+            make_str(&[
+                WAV_FILENAME,
+                TRIGGER_WAV_FILENAME,
+                "STAREDIT\\WAV\\HELLO.WAV",
+            ]),
+        );
+        chk.add_chunk(ChunkName::WAV, wav);
+        chk.add_chunk(ChunkName::TRIG, trig);
+        let chk = chk.to_bytes();
+
+        let mut source = MpqWriter::new();
+        source.add_file(SCENARIO_CHK, chk.clone());
+        source.add_file(WAV_FILENAME, make_wav());
+        source.add_file(TRIGGER_WAV_FILENAME, vec![7; 100]);
+        let source = source.to_bytes();
+        let source = Mpq::from_bytes(source.as_slice()).unwrap();
+
+        let writer = MpqWriter::from_map(&chk, &source).unwrap();
+
+        assert_eq!(
+            writer
+                .files()
+                .iter()
+                .map(|(name, _)| name.as_slice())
+                .collect::<Vec<_>>(),
+            vec![
+                SCENARIO_CHK.as_bytes(),
+                WAV_FILENAME.as_bytes(),
+                TRIGGER_WAV_FILENAME.as_bytes()
+            ]
+        );
+        assert_eq!(writer.files()[2].1, vec![7; 100]);
+    }
+
+    #[test]
+    fn from_map_keeps_referenced_wavs() {
+        let mut source = MpqWriter::new();
+        source.add_file(SCENARIO_CHK, make_chk());
+        source.add_file(WAV_FILENAME, make_wav());
+        source.add_file("staredit\\wav\\unused.wav", vec![5; 100]);
+        let source = source.to_bytes();
+        let source = Mpq::from_bytes(source.as_slice()).unwrap();
+
+        let mut writer = MpqWriter::from_map(&make_chk(), &source).unwrap();
+        writer.set_compression(true);
+
+        let archive = writer.to_bytes();
+        let mpq = Mpq::from_bytes(archive.as_slice()).unwrap();
+
+        assert!(read_chk(archive.as_slice()).unwrap() == make_chk());
+        assert!(mpq.read_file(WAV_FILENAME).unwrap() == make_wav());
+        assert!(matches!(
+            mpq.read_file("staredit\\wav\\unused.wav"),
+            Err(MpqError::FileNotFound { .. })
+        ));
+    }
+}
@@ -10,10 +10,12 @@ use crate::{
         chk_isom::{parse_isom, ChkIsom},
         chk_ive2::{parse_ive2, ChkIve2},
         chk_iver::{parse_iver, ChkIver},
+        chk_kstr::{parse_kstr, ChkKstr},
         chk_mask::{parse_mask, ChkMask},
         chk_mbrf::{parse_mbrf, ChkMbrf},
         chk_mrgn::{parse_mrgn, ChkMrgn},
         chk_mtxm::{parse_mtxm, ChkMtxm},
+        chk_ostr::{parse_ostr, ChkOstr},
         chk_ownr::{parse_ownr, ChkOwnr},
         chk_ptec::{parse_ptec, ChkPtec},
         chk_ptex::{parse_ptex, ChkPtex},
@@ -43,56 +45,66 @@ use crate::{
         chk_wav::{parse_wav, ChkWav},
     },
     chunk_name::ChunkName,
-    riff::{parse_riff, validate_and_group_riff_chunks},
+    error::ChkError,
+    report::ParseReport,
+    riff::{
+        parse_riff_with_mode, validate_and_group_riff_chunks, validate_riff_chunk, ParseMode,
+        RiffChunk,
+    },
+    writer::ChkWriter,
 };
-use anyhow::Result;
 use serde::ser::SerializeMap;
 use serde::{Serialize, Serializer};
 use tracing::instrument;
 
 #[derive(Debug)]
 pub struct ParsedChk<'a> {
-    pub colr: Result<ChkColr>,
-    pub crgb: Result<ChkCrgb>,
-    pub dd2: Result<ChkDd2>,
-    pub dim: Result<ChkDim<'a>>,
-    pub era: Result<ChkEra<'a>>,
-    pub forc: Result<ChkForc>,
-    pub iown: Result<ChkIown>,
-    pub isom: Result<ChkIsom>,
-    pub ive2: Result<ChkIve2<'a>>,
-    pub iver: Result<ChkIver<'a>>,
-    pub mask: Result<ChkMask<'a>>,
-    pub mbrf: Result<ChkMbrf>,
-    pub mrgn: Result<ChkMrgn<'a>>,
-    pub mtxm: Result<ChkMtxm>,
-    pub ownr: Result<ChkOwnr>,
-    pub ptec: Result<ChkPtec<'a>>,
-    pub ptex: Result<ChkPtex<'a>>,
-    pub puni: Result<ChkPuni<'a>>,
-    pub pupx: Result<ChkPupx<'a>>,
-    pub side: Result<ChkSide>,
-    pub sprp: Result<ChkSprp<'a>>,
-    pub str: Result<ChkStr2>,
-    pub strx: Result<ChkStrx2>,
-    pub swnm: Result<ChkSwnm<'a>>,
-    pub tecs: Result<ChkTecs<'a>>,
-    pub tecx: Result<ChkTecx<'a>>,
-    pub thg2: Result<ChkThg2>,
-    pub tile: Result<ChkTile>,
-    pub trig: Result<ChkTrig>,
-    pub type_: Result<ChkType<'a>>,
-    pub unis: Result<ChkUnis<'a>>,
-    pub unit: Result<ChkUnit>,
-    pub unix: Result<ChkUnix>,
-    pub upgr: Result<ChkUpgr<'a>>,
-    pub upgs: Result<ChkUpgs<'a>>,
-    pub upgx: Result<ChkUpgx<'a>>,
-    pub uprp: Result<ChkUprp<'a>>,
-    pub upus: Result<ChkUpus<'a>>,
-    pub vcod: Result<ChkVcod<'a>>,
-    pub ver: Result<ChkVer<'a>>,
-    pub wav: Result<ChkWav<'a>>,
+    pub colr: Result<ChkColr, ChkError>,
+    pub crgb: Result<ChkCrgb, ChkError>,
+    pub dd2: Result<ChkDd2, ChkError>,
+    pub dim: Result<ChkDim<'a>, ChkError>,
+    pub era: Result<ChkEra<'a>, ChkError>,
+    pub forc: Result<ChkForc, ChkError>,
+    pub iown: Result<ChkIown, ChkError>,
+    pub isom: Result<ChkIsom, ChkError>,
+    pub ive2: Result<ChkIve2<'a>, ChkError>,
+    pub iver: Result<ChkIver<'a>, ChkError>,
+    pub kstr: Result<ChkKstr, ChkError>,
+    pub mask: Result<ChkMask<'a>, ChkError>,
+    pub mbrf: Result<ChkMbrf, ChkError>,
+    pub mrgn: Result<ChkMrgn<'a>, ChkError>,
+    pub mtxm: Result<ChkMtxm, ChkError>,
+    pub ostr: Result<ChkOstr, ChkError>,
+    pub ownr: Result<ChkOwnr, ChkError>,
+    pub ptec: Result<ChkPtec<'a>, ChkError>,
+    pub ptex: Result<ChkPtex<'a>, ChkError>,
+    pub puni: Result<ChkPuni<'a>, ChkError>,
+    pub pupx: Result<ChkPupx<'a>, ChkError>,
+    pub side: Result<ChkSide, ChkError>,
+    pub sprp: Result<ChkSprp<'a>, ChkError>,
+    pub str: Result<ChkStr2, ChkError>,
+    pub strx: Result<ChkStrx2, ChkError>,
+    pub swnm: Result<ChkSwnm<'a>, ChkError>,
+    pub tecs: Result<ChkTecs<'a>, ChkError>,
+    pub tecx: Result<ChkTecx<'a>, ChkError>,
+    pub thg2: Result<ChkThg2, ChkError>,
+    pub tile: Result<ChkTile, ChkError>,
+    pub trig: Result<ChkTrig, ChkError>,
+    pub type_: Result<ChkType<'a>, ChkError>,
+    pub unis: Result<ChkUnis<'a>, ChkError>,
+    pub unit: Result<ChkUnit, ChkError>,
+    pub unix: Result<ChkUnix, ChkError>,
+    pub upgr: Result<ChkUpgr<'a>, ChkError>,
+    pub upgs: Result<ChkUpgs<'a>, ChkError>,
+    pub upgx: Result<ChkUpgx<'a>, ChkError>,
+    pub uprp: Result<ChkUprp<'a>, ChkError>,
+    pub upus: Result<ChkUpus<'a>, ChkError>,
+    pub vcod: Result<ChkVcod<'a>, ChkError>,
+    pub ver: Result<ChkVer<'a>, ChkError>,
+    pub wav: Result<ChkWav<'a>, ChkError>,
+    // Chunks that did not end up in a section, either because the section is unknown or because it failed
+    // to parse, in the order they appear in the chk. They are written back as they are.
+    pub unparsed: Vec<RiffChunk<'a>>,
 }
 
 impl Serialize for ParsedChk<'_> {
@@ -113,10 +125,12 @@ impl Serialize for ParsedChk<'_> {
         // let _ = self.isom.as_ref().map(|x| map.serialize_entry("ISOM", &x));
         let _ = self.ive2.as_ref().map(|x| map.serialize_entry("IVE2", &x));
         let _ = self.iver.as_ref().map(|x| map.serialize_entry("IVER", &x));
+        let _ = self.kstr.as_ref().map(|x| map.serialize_entry("KSTR", &x));
         let _ = self.mask.as_ref().map(|x| map.serialize_entry("MASK", &x));
         let _ = self.mbrf.as_ref().map(|x| map.serialize_entry("MBRF", &x));
         let _ = self.mrgn.as_ref().map(|x| map.serialize_entry("MRGN", &x));
         // let _ = self.mtxm.as_ref().map(|x| map.serialize_entry("MTXM", &x));
+        let _ = self.ostr.as_ref().map(|x| map.serialize_entry("OSTR", &x));
         let _ = self.ownr.as_ref().map(|x| map.serialize_entry("OWNR", &x));
         let _ = self.ptec.as_ref().map(|x| map.serialize_entry("PTEC", &x));
         let _ = self.ptex.as_ref().map(|x| map.serialize_entry("PTEx", &x));
@@ -152,326 +166,256 @@ impl Serialize for ParsedChk<'_> {
 impl<'a> ParsedChk<'a> {
     #[instrument(level = "trace", skip_all)]
     pub fn from_bytes(chk: &'a [u8]) -> ParsedChk<'a> {
-        let riff_chunks = parse_riff(chk);
-        let riff_chunks = validate_and_group_riff_chunks(riff_chunks.as_slice());
+        ParsedChk::from_bytes_with_mode(chk, ParseMode::Lenient)
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub fn from_bytes_with_mode(chk: &'a [u8], mode: ParseMode) -> ParsedChk<'a> {
+        let (all_riff_chunks, _) = parse_riff_with_mode(chk, mode);
+
+        ParsedChk::from_riff_chunks(&all_riff_chunks, mode)
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub(crate) fn from_riff_chunks(
+        all_riff_chunks: &[RiffChunk<'a>],
+        mode: ParseMode,
+    ) -> ParsedChk<'a> {
+        let riff_chunks = validate_and_group_riff_chunks(all_riff_chunks);
+
+        // A section with no valid chunks reports why the last chunk of that name was rejected, if there was one.
+        let not_found = |chunk_name: ChunkName| {
+            all_riff_chunks
+                .iter()
+                .rev()
+                .filter(|x| x.chunk_name == chunk_name)
+                .find_map(|x| validate_riff_chunk(x).err())
+                .unwrap_or(ChkError::MissingSection { chunk_name })
+        };
 
         #[rustfmt::skip]
-        let ret = ParsedChk {
-            colr: riff_chunks.get(&ChunkName::COLR).map(|x| parse_colr(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            crgb: riff_chunks.get(&ChunkName::CRGB).map(|x| parse_crgb(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            dd2:  riff_chunks.get(&ChunkName::DD2 ).map(|x| parse_dd2 (x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            dim:  riff_chunks.get(&ChunkName::DIM ).map(|x| parse_dim (x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            era:  riff_chunks.get(&ChunkName::ERA ).map(|x| parse_era (x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            forc: riff_chunks.get(&ChunkName::FORC).map(|x| parse_forc(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            iown: riff_chunks.get(&ChunkName::IOWN).map(|x| parse_iown(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            isom: riff_chunks.get(&ChunkName::ISOM).map(|x| parse_isom(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            ive2: riff_chunks.get(&ChunkName::IVE2).map(|x| parse_ive2(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            iver: riff_chunks.get(&ChunkName::IVER).map(|x| parse_iver(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            mask: riff_chunks.get(&ChunkName::MASK).map(|x| parse_mask(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            mbrf: riff_chunks.get(&ChunkName::MBRF).map(|x| parse_mbrf(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            mrgn: riff_chunks.get(&ChunkName::MRGN).map(|x| parse_mrgn(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            mtxm: riff_chunks.get(&ChunkName::MTXM).map(|x| parse_mtxm(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            ownr: riff_chunks.get(&ChunkName::OWNR).map(|x| parse_ownr(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            ptec: riff_chunks.get(&ChunkName::PTEC).map(|x| parse_ptec(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            ptex: riff_chunks.get(&ChunkName::PTEx).map(|x| parse_ptex(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            puni: riff_chunks.get(&ChunkName::PUNI).map(|x| parse_puni(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            pupx: riff_chunks.get(&ChunkName::PUPx).map(|x| parse_pupx(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            side: riff_chunks.get(&ChunkName::SIDE).map(|x| parse_side(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            sprp: riff_chunks.get(&ChunkName::SPRP).map(|x| parse_sprp(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            str:  riff_chunks.get(&ChunkName::STR ).map(|x| parse_str (x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            strx: riff_chunks.get(&ChunkName::STRx).map(|x| parse_strx(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            swnm: riff_chunks.get(&ChunkName::SWNM).map(|x| parse_swnm(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            tecs: riff_chunks.get(&ChunkName::TECS).map(|x| parse_tecs(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            tecx: riff_chunks.get(&ChunkName::TECx).map(|x| parse_tecx(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            thg2: riff_chunks.get(&ChunkName::THG2).map(|x| parse_thg2(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            tile: riff_chunks.get(&ChunkName::TILE).map(|x| parse_tile(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            trig: riff_chunks.get(&ChunkName::TRIG).map(|x| parse_trig(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            type_:riff_chunks.get(&ChunkName::TYPE).map(|x| parse_type(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            unis: riff_chunks.get(&ChunkName::UNIS).map(|x| parse_unis(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            unit: riff_chunks.get(&ChunkName::UNIT).map(|x| parse_unit(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            unix: riff_chunks.get(&ChunkName::UNIx).map(|x| parse_unix(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            upgr: riff_chunks.get(&ChunkName::UPGR).map(|x| parse_upgr(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            upgs: riff_chunks.get(&ChunkName::UPGS).map(|x| parse_upgs(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            upgx: riff_chunks.get(&ChunkName::UPGx).map(|x| parse_upgx(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            uprp: riff_chunks.get(&ChunkName::UPRP).map(|x| parse_uprp(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            upus: riff_chunks.get(&ChunkName::UPUS).map(|x| parse_upus(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            vcod: riff_chunks.get(&ChunkName::VCOD).map(|x| parse_vcod(x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            ver:  riff_chunks.get(&ChunkName::VER ).map(|x| parse_ver (x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
-            wav:  riff_chunks.get(&ChunkName::WAV ).map(|x| parse_wav (x.as_slice())).unwrap_or(Err(anyhow::anyhow!("Not Found"))),
+        let mut ret = ParsedChk {
+            colr: riff_chunks.get(&ChunkName::COLR).map(|x| parse_colr(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::COLR))),
+            crgb: riff_chunks.get(&ChunkName::CRGB).map(|x| parse_crgb(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::CRGB))),
+            dd2:  riff_chunks.get(&ChunkName::DD2 ).map(|x| parse_dd2 (x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::DD2))),
+            dim:  riff_chunks.get(&ChunkName::DIM ).map(|x| parse_dim (x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::DIM))),
+            era:  riff_chunks.get(&ChunkName::ERA ).map(|x| parse_era (x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::ERA))),
+            forc: riff_chunks.get(&ChunkName::FORC).map(|x| parse_forc(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::FORC))),
+            iown: riff_chunks.get(&ChunkName::IOWN).map(|x| parse_iown(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::IOWN))),
+            isom: riff_chunks.get(&ChunkName::ISOM).map(|x| parse_isom(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::ISOM))),
+            ive2: riff_chunks.get(&ChunkName::IVE2).map(|x| parse_ive2(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::IVE2))),
+            iver: riff_chunks.get(&ChunkName::IVER).map(|x| parse_iver(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::IVER))),
+            kstr: riff_chunks.get(&ChunkName::KSTR).map(|x| parse_kstr(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::KSTR))),
+            mask: riff_chunks.get(&ChunkName::MASK).map(|x| parse_mask(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::MASK))),
+            mbrf: riff_chunks.get(&ChunkName::MBRF).map(|x| parse_mbrf(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::MBRF))),
+            mrgn: riff_chunks.get(&ChunkName::MRGN).map(|x| parse_mrgn(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::MRGN))),
+            mtxm: riff_chunks.get(&ChunkName::MTXM).map(|x| parse_mtxm(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::MTXM))),
+            ostr: riff_chunks.get(&ChunkName::OSTR).map(|x| parse_ostr(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::OSTR))),
+            ownr: riff_chunks.get(&ChunkName::OWNR).map(|x| parse_ownr(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::OWNR))),
+            ptec: riff_chunks.get(&ChunkName::PTEC).map(|x| parse_ptec(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::PTEC))),
+            ptex: riff_chunks.get(&ChunkName::PTEx).map(|x| parse_ptex(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::PTEx))),
+            puni: riff_chunks.get(&ChunkName::PUNI).map(|x| parse_puni(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::PUNI))),
+            pupx: riff_chunks.get(&ChunkName::PUPx).map(|x| parse_pupx(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::PUPx))),
+            side: riff_chunks.get(&ChunkName::SIDE).map(|x| parse_side(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::SIDE))),
+            sprp: riff_chunks.get(&ChunkName::SPRP).map(|x| parse_sprp(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::SPRP))),
+            str:  riff_chunks.get(&ChunkName::STR ).map(|x| parse_str (x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::STR))),
+            strx: riff_chunks.get(&ChunkName::STRx).map(|x| parse_strx(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::STRx))),
+            swnm: riff_chunks.get(&ChunkName::SWNM).map(|x| parse_swnm(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::SWNM))),
+            tecs: riff_chunks.get(&ChunkName::TECS).map(|x| parse_tecs(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::TECS))),
+            tecx: riff_chunks.get(&ChunkName::TECx).map(|x| parse_tecx(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::TECx))),
+            thg2: riff_chunks.get(&ChunkName::THG2).map(|x| parse_thg2(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::THG2))),
+            tile: riff_chunks.get(&ChunkName::TILE).map(|x| parse_tile(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::TILE))),
+            trig: riff_chunks.get(&ChunkName::TRIG).map(|x| parse_trig(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::TRIG))),
+            type_:riff_chunks.get(&ChunkName::TYPE).map(|x| parse_type(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::TYPE))),
+            unis: riff_chunks.get(&ChunkName::UNIS).map(|x| parse_unis(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::UNIS))),
+            unit: riff_chunks.get(&ChunkName::UNIT).map(|x| parse_unit(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::UNIT))),
+            unix: riff_chunks.get(&ChunkName::UNIx).map(|x| parse_unix(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::UNIx))),
+            upgr: riff_chunks.get(&ChunkName::UPGR).map(|x| parse_upgr(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::UPGR))),
+            upgs: riff_chunks.get(&ChunkName::UPGS).map(|x| parse_upgs(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::UPGS))),
+            upgx: riff_chunks.get(&ChunkName::UPGx).map(|x| parse_upgx(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::UPGx))),
+            uprp: riff_chunks.get(&ChunkName::UPRP).map(|x| parse_uprp(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::UPRP))),
+            upus: riff_chunks.get(&ChunkName::UPUS).map(|x| parse_upus(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::UPUS))),
+            vcod: riff_chunks.get(&ChunkName::VCOD).map(|x| parse_vcod(x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::VCOD))),
+            ver:  riff_chunks.get(&ChunkName::VER ).map(|x| parse_ver (x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::VER))),
+            wav:  riff_chunks.get(&ChunkName::WAV ).map(|x| parse_wav (x.as_slice())).unwrap_or_else(|| Err(not_found(ChunkName::WAV))),
+            unparsed: Vec::new(),
         };
 
+        ret.unparsed = all_riff_chunks
+            .iter()
+            .filter(|x| !ret.is_parsed(&x.chunk_name))
+            .cloned()
+            .collect();
+
+        if mode == ParseMode::GameAccurate {
+            // StarCraft copies MTXM into a buffer of exactly width * height tiles, anything past that is
+            // ignored and anything missing is a null tile.
+            if let (Ok(mtxm), Ok(dim)) = (&mut ret.mtxm, &ret.dim) {
+                mtxm.data
+                    .resize(*dim.width as usize * *dim.height as usize, 0);
+            }
+        }
+
         ret
     }
 
+    fn is_parsed(&self, chunk_name: &ChunkName) -> bool {
+        !matches!(chunk_name, ChunkName::UNKNOWN(_)) && self.section_error(chunk_name).is_none()
+    }
+
+    // Why the section of the given name could not be parsed, None if it was or the name is unknown.
+    pub(crate) fn section_error(&self, chunk_name: &ChunkName) -> Option<&ChkError> {
+        match chunk_name {
+            ChunkName::COLR => self.colr.as_ref().err(),
+            ChunkName::CRGB => self.crgb.as_ref().err(),
+            ChunkName::DD2 => self.dd2.as_ref().err(),
+            ChunkName::DIM => self.dim.as_ref().err(),
+            ChunkName::ERA => self.era.as_ref().err(),
+            ChunkName::FORC => self.forc.as_ref().err(),
+            ChunkName::IOWN => self.iown.as_ref().err(),
+            ChunkName::ISOM => self.isom.as_ref().err(),
+            ChunkName::IVE2 => self.ive2.as_ref().err(),
+            ChunkName::IVER => self.iver.as_ref().err(),
+            ChunkName::KSTR => self.kstr.as_ref().err(),
+            ChunkName::MASK => self.mask.as_ref().err(),
+            ChunkName::MBRF => self.mbrf.as_ref().err(),
+            ChunkName::MRGN => self.mrgn.as_ref().err(),
+            ChunkName::MTXM => self.mtxm.as_ref().err(),
+            ChunkName::OSTR => self.ostr.as_ref().err(),
+            ChunkName::OWNR => self.ownr.as_ref().err(),
+            ChunkName::PTEC => self.ptec.as_ref().err(),
+            ChunkName::PTEx => self.ptex.as_ref().err(),
+            ChunkName::PUNI => self.puni.as_ref().err(),
+            ChunkName::PUPx => self.pupx.as_ref().err(),
+            ChunkName::SIDE => self.side.as_ref().err(),
+            ChunkName::SPRP => self.sprp.as_ref().err(),
+            ChunkName::STR => self.str.as_ref().err(),
+            ChunkName::STRx => self.strx.as_ref().err(),
+            ChunkName::SWNM => self.swnm.as_ref().err(),
+            ChunkName::TECS => self.tecs.as_ref().err(),
+            ChunkName::TECx => self.tecx.as_ref().err(),
+            ChunkName::THG2 => self.thg2.as_ref().err(),
+            ChunkName::TILE => self.tile.as_ref().err(),
+            ChunkName::TRIG => self.trig.as_ref().err(),
+            ChunkName::TYPE => self.type_.as_ref().err(),
+            ChunkName::UNIS => self.unis.as_ref().err(),
+            ChunkName::UNIT => self.unit.as_ref().err(),
+            ChunkName::UNIx => self.unix.as_ref().err(),
+            ChunkName::UPGR => self.upgr.as_ref().err(),
+            ChunkName::UPGS => self.upgs.as_ref().err(),
+            ChunkName::UPGx => self.upgx.as_ref().err(),
+            ChunkName::UPRP => self.uprp.as_ref().err(),
+            ChunkName::UPUS => self.upus.as_ref().err(),
+            ChunkName::VCOD => self.vcod.as_ref().err(),
+            ChunkName::VER => self.ver.as_ref().err(),
+            ChunkName::WAV => self.wav.as_ref().err(),
+            ChunkName::UNKNOWN(_) => None,
+        }
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub fn from_bytes_with_report(chk: &'a [u8]) -> (ParsedChk<'a>, ParseReport) {
+        // Both are built from the same chunks so the report describes exactly what was parsed.
+        let (riff_chunks, skipped) = parse_riff_with_mode(chk, ParseMode::Lenient);
+
+        let parsed_chk = ParsedChk::from_riff_chunks(&riff_chunks, ParseMode::Lenient);
+        let report = ParseReport::from_riff_chunks(chk.len(), &riff_chunks, skipped, &parsed_chk);
+
+        (parsed_chk, report)
+    }
+
     #[cfg(feature = "full")]
     #[instrument(level = "trace", skip(self))]
     pub fn get_string(
         &self,
         // encoding_order: &Vec<&'static encoding_rs::Encoding>,
         index: usize,
-    ) -> Result<String> {
-        use crate::util::parse_null_terminated_bytestring_unsigned;
+    ) -> Result<String, ChkError> {
+        use crate::{encoding::score_encodings, util::parse_null_terminated_bytestring_unsigned};
+
+        let chunk_name = if self.strx.is_ok() {
+            ChunkName::STRx
+        } else {
+            ChunkName::STR
+        };
+        let invalid = |reason: String| ChkError::InvalidString {
+            chunk_name: chunk_name.clone(),
+            string_number: index,
+            reason,
+        };
 
         if index == 0 {
-            return Ok("Zero index provided to 'get_string'".to_owned());
+            return Err(invalid("string number 0 means no string".to_owned()));
         }
 
-        let index = index - 1;
-
         let bytes = if let Ok(x) = &self.strx {
-            let mut offset = 4;
-            offset += index * 4;
+            let offset = 4 + (index - 1) * 4;
 
             if offset + 4 >= x.string_data.len() {
-                return Ok(format!(
-                    "Out of bounds access STRx offset table. index: {index}, offset+4: {}, string_data.len(): {}",
-                    offset + 4,
+                return Err(invalid(format!(
+                    "offset table entry at {offset} is past the end of the string data, which is {} bytes",
                     x.string_data.len()
-                ));
+                )));
             }
 
-            let str_offset: usize =
-                u32::from_le_bytes(x.string_data[offset..offset + 4].try_into()?).try_into()?;
+            let str_offset = u32::from_le_bytes([
+                x.string_data[offset],
+                x.string_data[offset + 1],
+                x.string_data[offset + 2],
+                x.string_data[offset + 3],
+            ]) as usize;
 
             if str_offset >= x.string_data.len() {
-                return Ok(format!(
-                    "Out of bounds access STRx. index: {index}, offset: {offset}, string_data.len(): {}, str_offset: {str_offset}",
-                    x.string_data.len(),
-                ));
+                return Err(invalid(format!(
+                    "string offset {str_offset} is past the end of the string data, which is {} bytes",
+                    x.string_data.len()
+                )));
             }
 
             parse_null_terminated_bytestring_unsigned(&x.string_data[str_offset..])
         } else if let Ok(x) = &self.str {
-            let mut offset = 2;
-            offset += index * 2;
+            let offset = 2 + (index - 1) * 2;
 
             if offset + 2 >= x.string_data.len() {
-                return Ok(format!(
-                    "Out of bounds access STR offset table. index: {index}, offset+4: {}, string_data.len(): {}",
-                    offset + 2,
+                return Err(invalid(format!(
+                    "offset table entry at {offset} is past the end of the string data, which is {} bytes",
                     x.string_data.len()
-                ));
+                )));
             }
 
-            let str_offset: usize =
-                u16::from_le_bytes(x.string_data[offset..offset + 2].try_into()?).try_into()?;
+            let str_offset =
+                u16::from_le_bytes([x.string_data[offset], x.string_data[offset + 1]]) as usize;
 
             if str_offset >= x.string_data.len() {
-                return Ok(format!(
-                    "Out of bounds access STR. index: {index}, offset: {offset}, string_data.len(): {}, str_offset: {str_offset}",
-                    x.string_data.len(),
-                ));
+                return Err(invalid(format!(
+                    "string offset {str_offset} is past the end of the string data, which is {} bytes",
+                    x.string_data.len()
+                )));
             }
 
             parse_null_terminated_bytestring_unsigned(&x.string_data[str_offset..])
         } else {
-            anyhow::bail!("No STR or STRx section")
+            return Err(ChkError::MissingSection {
+                chunk_name: ChunkName::STR,
+            });
         };
 
         if bytes.is_empty() {
             return Ok("".to_owned());
         }
 
-        let mut euc_kr_failed = false;
-        let mut euc_kr_characters_decoded_successfully: i64 = 0;
-        let euc_kr_characters_len: usize;
-        let mut utf8_failed = false;
-        let mut utf8_characters_decoded_successfully: i64 = 0;
-        let utf8_characters_len: usize;
-
-        let mut win1252_characters_7f_or_above: i64 = 0;
-
-        {
-            let conversion = encoding_rs::EUC_KR.decode(bytes);
-            // println!("EUC_KR: {}", conversion.0);
-            euc_kr_characters_len = conversion.0.chars().count();
-
-            if conversion.2 {
-                euc_kr_failed = true;
-            } else {
-                euc_kr_characters_decoded_successfully += conversion
-                    .0
-                    .chars()
-                    .filter(|&c| ('가'..='힣').contains(&c))
-                    .count() as i64;
-            }
-        }
-
-        {
-            let conversion = encoding_rs::UTF_8.decode(bytes);
-            // println!("UTF-8: {}", conversion.0);
-            utf8_characters_len = conversion.0.chars().count();
-
-            if conversion.2 {
-                utf8_failed = true;
-            } else {
-                utf8_characters_decoded_successfully +=
-                    conversion.0.chars().filter(|&c| c >= '\u{7f}').count() as i64;
-            }
-        }
-
-        {
-            let conversion = encoding_rs::WINDOWS_1252.decode(bytes);
-            // println!("WIN1252: {}", conversion.0);
-            win1252_characters_7f_or_above +=
-                conversion.0.chars().filter(|&c| c >= '\u{7f}').count() as i64;
-        }
-
-        let uchardet_guessed_encoding = unsafe {
-            let handle = uchardet_bindings::uchardet_new();
-            scopeguard::defer! {
-                uchardet_bindings::uchardet_delete(handle);
-            }
-
-            // filter out color codes because it might be breaking uchardet.
-            let vec: Vec<_> = bytes.iter().filter(|&&x| x >= 0x20).copied().collect();
-
-            if uchardet_bindings::uchardet_handle_data(handle, vec.as_ptr() as *const i8, vec.len())
-                != 0
-            {
-                panic!();
-            }
-
-            uchardet_bindings::uchardet_data_end(handle);
-
-            let charset = std::ffi::CStr::from_ptr(uchardet_bindings::uchardet_get_charset(handle))
-                .to_str()?
-                .to_string();
-
-            match charset.as_str() {
-                "UTF-8" => anyhow::Ok(Some(encoding_rs::UTF_8)),
-                "UHC" => anyhow::Ok(Some(encoding_rs::EUC_KR)),
-                "ASCII" | "ISO-8859-7" | "ISO-8859-2" | "WINDOWS-1252" | "WINDOWS-1250"
-                | "MAC-CENTRALEUROPE" | "WINDOWS-1257" | "ISO-8859-10" | "ISO-8859-1" => {
-                    anyhow::Ok(Some(encoding_rs::WINDOWS_1252))
-                }
-                "" | "IBM852" | "VISCII" | "ISO-8859-13" | "ISO-8859-9" | "ISO-8859-3" => {
-                    anyhow::Ok(None)
-                }
-                _ => {
-                    anyhow::Ok(None)
-                    //panic!("{charset}")
-                }
-            }
-        }?;
-
-        let (compact_enc_det_encoding_guess, compact_enc_det_encoding_guess_is_reliable) = unsafe {
-            // filter out color codes because it might be breaking uchardet.
-            let vec: Vec<_> = bytes.iter().filter(|&&x| x >= 0x20).copied().collect();
-
-            let mut bytes_consumed = 0;
-            let mut is_reliable = false;
-
-            let encoding = compact_enc_det_bindings::CompactEncDet_DetectEncoding(
-                vec.as_ptr() as *const i8,
-                vec.len() as i32,
-                std::ptr::null::<i8>(),
-                std::ptr::null::<i8>(),
-                std::ptr::null::<i8>(),
-                compact_enc_det_bindings::Encoding_UNKNOWN_ENCODING as i32,
-                compact_enc_det_bindings::Language_UNKNOWN_LANGUAGE,
-                compact_enc_det_bindings::CompactEncDet_TextCorpusType_WEB_CORPUS,
-                true,
-                &mut bytes_consumed,
-                &mut is_reliable,
-            );
-
-            // println!("is_reliable: {is_reliable}, bytes_consumed: {bytes_consumed}");
-
-            (
-                match encoding {
-                    compact_enc_det_bindings::Encoding_UTF8 => Some(encoding_rs::UTF_8),
-                    compact_enc_det_bindings::Encoding_KOREAN_EUC_KR => Some(encoding_rs::EUC_KR),
-                    compact_enc_det_bindings::Encoding_ISO_8859_1
-                    | compact_enc_det_bindings::Encoding_MSFT_CP1252
-                    | compact_enc_det_bindings::Encoding_ASCII_7BIT => {
-                        Some(encoding_rs::WINDOWS_1252)
-                    }
-                    compact_enc_det_bindings::Encoding_CHINESE_GB
-                    | compact_enc_det_bindings::Encoding_CHINESE_BIG5
-                    | compact_enc_det_bindings::Encoding_JAPANESE_EUC_JP => None,
-                    _ => {
-                        None
-                        //panic!("encoding panic'd on: {encoding}")
-                    }
-                },
-                is_reliable,
-            )
-        };
-
-        let mut encoding_map = std::collections::HashMap::new();
-
-        encoding_map.insert(encoding_rs::UTF_8, 0.0);
-        encoding_map.insert(encoding_rs::EUC_KR, 0.0);
-        encoding_map.insert(encoding_rs::WINDOWS_1252, 0.0);
-
-        if let Some(uchardet_guessed_encoding) = uchardet_guessed_encoding {
-            *encoding_map.get_mut(uchardet_guessed_encoding).unwrap() += 0.7;
-        }
-
-        if let Some(compact_enc_det_encoding_guess) = compact_enc_det_encoding_guess {
-            *encoding_map
-                .get_mut(compact_enc_det_encoding_guess)
-                .unwrap() += if compact_enc_det_encoding_guess_is_reliable {
-                0.7
-            } else {
-                0.2
-            };
-        }
-
-        *encoding_map.get_mut(encoding_rs::EUC_KR).unwrap() +=
-            (euc_kr_characters_decoded_successfully as f64) / (euc_kr_characters_len as f64);
-
-        *encoding_map.get_mut(encoding_rs::WINDOWS_1252).unwrap() -=
-            (win1252_characters_7f_or_above as f64) / (bytes.len() as f64);
-
-        *encoding_map.get_mut(encoding_rs::UTF_8).unwrap() +=
-            (utf8_characters_decoded_successfully as f64) / (utf8_characters_len as f64);
-
-        // println!(
-        //     "\n\
-        //     euc_kr_failed: {euc_kr_failed}, \
-        //     euc_kr_characters_decoded_successfully: {euc_kr_characters_decoded_successfully}, \
-        //     euc_kr_characters_len: {euc_kr_characters_len}, \
-        //     utf8_failed: {utf8_failed}, \
-        //     utf8_characters_decoded_successfully: {utf8_characters_decoded_successfully}, \
-        //     utf8_characters_len: {utf8_characters_len}, \
-        //     win1252_characters_7f_or_above: {win1252_characters_7f_or_above}, \
-        //     win1252_total_characters: {win1252_total_characters}, \
-        //     \n\
-        //     uchardet_guess: {uchardet_guessed_encoding:?}, \
-        //     \n\
-        //     compact_enc_det_encoding_guess: {compact_enc_det_encoding_guess:?}, \
-        //     \n\
-        //     encoding_map: {encoding_map:?}, str: {}\n\
-        //     ------------------------------------------------------------------------------------",
-        //     encoding_rs::WINDOWS_1252.decode(bytes).0
-        // );
-
-        if euc_kr_failed {
-            encoding_map.remove(encoding_rs::EUC_KR);
-        }
-
-        if utf8_failed {
-            encoding_map.remove(encoding_rs::UTF_8);
-        }
-
-        let mut encodings: Vec<_> = encoding_map.drain().collect();
-
-        encodings.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap().reverse());
-
-        Ok(encodings[0].0.decode(bytes).0.to_string())
+        Ok(score_encodings(bytes)[0].0.decode(bytes))
 
         // TODO: Implement voting idea.
         // decoding failure = complete veto.
         // udetchar can vote with some weight.
         // number of chars successfully decoded specifically in that range can also vote as some weight.
-        // Other strings in the map can also vote with some weight but not sure how to implement that exactly.
+        // Other strings in the map can also vote, see StringTable.
         // Table of exceptions can also make a vote.
     }
 
     #[instrument(level = "trace", skip(self))]
-    pub fn get_all_string_references(&self) -> Result<Vec<u32>, anyhow::Error> {
+    pub fn get_all_string_references(&self) -> Result<Vec<u32>, ChkError> {
         let mut ret = Vec::new();
 
         if let Ok(x) = &self.sprp {
@@ -500,13 +444,13 @@ impl<'a> ParsedChk<'a> {
         }
 
         if let Ok(x) = &self.wav {
-            for string_number in x.wav_string_number {
+            for string_number in x.wav_string_number.iter() {
                 ret.push(*string_number);
             }
         }
 
         if let Ok(x) = &self.swnm {
-            for switch_name_string_number in x.switch_name_string_number {
+            for switch_name_string_number in x.switch_name_string_number.iter() {
                 ret.push(*switch_name_string_number);
             }
         }
@@ -536,32 +480,225 @@ impl<'a> ParsedChk<'a> {
         Ok(ret.into_iter().filter(|&x| x != 0).collect())
     }
 
+    // The undecoded bytes of a string, up to its null terminator. Uses STRx if present, like get_string.
+    #[instrument(level = "trace", skip(self))]
+    pub fn get_string_bytes(&self, string_number: usize) -> Option<&[u8]> {
+        use crate::util::parse_null_terminated_bytestring_unsigned;
+
+        let index = string_number.checked_sub(1)?;
+
+        let (string_data, str_offset) = if let Ok(x) = &self.strx {
+            let offset = 4 + index * 4;
+            let str_offset =
+                u32::from_le_bytes(x.string_data.get(offset..offset + 4)?.try_into().ok()?);
+
+            (&x.string_data, str_offset as usize)
+        } else if let Ok(x) = &self.str {
+            let offset = 2 + index * 2;
+            let str_offset =
+                u16::from_le_bytes(x.string_data.get(offset..offset + 2)?.try_into().ok()?);
+
+            (&x.string_data, str_offset as usize)
+        } else {
+            return None;
+        };
+
+        Some(parse_null_terminated_bytestring_unsigned(
+            string_data.get(str_offset..)?,
+        ))
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        ChkWriter::from(self).to_bytes()
+    }
+
     #[cfg(feature = "full")]
     #[instrument(level = "trace", skip(self))]
-    pub fn get_location_name(&self, index: usize) -> Result<String> {
+    pub fn get_location_name(&self, index: usize) -> Result<String, ChkError> {
+        // Locations are numbered from 1, there is no string for a location that does not exist.
+        let invalid = |reason: String| ChkError::InvalidString {
+            chunk_name: ChunkName::MRGN,
+            string_number: 0,
+            reason,
+        };
+
         if index == 0 {
-            return Ok("No Location".to_owned());
+            return Err(invalid("location 0 means no location".to_owned()));
         }
 
-        if let Ok(mrgn) = &self.mrgn {
-            if mrgn.locations.len() <= index {
-                return Ok("Location index out of bounds".to_owned());
-            }
+        // The editor's name from OSTR/KSTR takes precedence, like in the editors that write them.
+        if let Some(name) = self
+            .ostr
+            .as_ref()
+            .ok()
+            .and_then(|x| x.location_name.get(index - 1))
+            .and_then(|&x| self.get_editor_string(x as usize))
+        {
+            return Ok(name);
+        }
 
-            if mrgn.locations[index - 1].name_string_number == 0 {
-                Ok(format!("Location {} has a string name index of 0", index))
-            } else {
-                self.get_string(mrgn.locations[index - 1].name_string_number as usize)
-            }
-        } else {
-            Ok("No Location".to_owned())
+        let mrgn = self.mrgn.as_ref().map_err(|x| x.clone())?;
+
+        if index > mrgn.locations.len() {
+            return Err(invalid(format!(
+                "location {index} does not exist, there are {} locations",
+                mrgn.locations.len()
+            )));
+        }
+
+        match mrgn.locations[index - 1].name_string_number {
+            0 => Err(invalid(format!("location {index} has no name"))),
+            string_number => self.get_string(string_number as usize),
+        }
+    }
+
+    // The undecoded bytes of a string in KSTR, up to its null terminator. Unlike STR and STRx, offsets in
+    // KSTR come after a version.
+    #[instrument(level = "trace", skip(self))]
+    pub fn get_editor_string_bytes(&self, string_number: usize) -> Option<&[u8]> {
+        use crate::util::parse_null_terminated_bytestring_unsigned;
+
+        let string_data = &self.kstr.as_ref().ok()?.string_data;
+        let number_of_strings = u32::from_le_bytes(string_data.get(4..8)?.try_into().ok()?);
+
+        if string_number == 0 || string_number > number_of_strings as usize {
+            return None;
+        }
+
+        let offset = 8 + (string_number - 1) * 4;
+        let str_offset = u32::from_le_bytes(string_data.get(offset..offset + 4)?.try_into().ok()?);
+
+        Some(parse_null_terminated_bytestring_unsigned(
+            string_data.get(str_offset as usize..)?,
+        ))
+    }
+
+    // A string from KSTR. None if there is no KSTR or the string does not exist.
+    #[cfg(feature = "full")]
+    #[instrument(level = "trace", skip(self))]
+    pub fn get_editor_string(&self, string_number: usize) -> Option<String> {
+        use crate::encoding::score_encodings;
+
+        let bytes = self.get_editor_string_bytes(string_number)?;
+        Some(score_encodings(bytes)[0].0.decode(bytes))
+    }
+
+    // The OSTR override if it is set and exists in KSTR, otherwise the string from STR/STRx. None if neither
+    // is set, in which case the game uses a default name.
+    #[cfg(feature = "full")]
+    fn get_name(
+        &self,
+        editor_string_number: Option<u32>,
+        string_number: u32,
+    ) -> Result<Option<String>, ChkError> {
+        if let Some(name) = editor_string_number.and_then(|x| self.get_editor_string(x as usize)) {
+            return Ok(Some(name));
         }
+
+        if string_number == 0 {
+            return Ok(None);
+        }
+
+        self.get_string(string_number as usize).map(Some)
+    }
+
+    #[cfg(feature = "full")]
+    #[instrument(level = "trace", skip_all)]
+    pub fn get_scenario_name(&self) -> Result<Option<String>, ChkError> {
+        let sprp = self.sprp.as_ref().map_err(|x| x.clone())?;
+
+        self.get_name(
+            self.ostr.as_ref().ok().map(|x| x.scenario_name),
+            *sprp.scenario_name_string_number as u32,
+        )
+    }
+
+    #[cfg(feature = "full")]
+    #[instrument(level = "trace", skip_all)]
+    pub fn get_scenario_description(&self) -> Result<Option<String>, ChkError> {
+        let sprp = self.sprp.as_ref().map_err(|x| x.clone())?;
+
+        self.get_name(
+            self.ostr.as_ref().ok().map(|x| x.scenario_description),
+            *sprp.description_string_number as u32,
+        )
+    }
+
+    // force is 0 based.
+    #[cfg(feature = "full")]
+    #[instrument(level = "trace", skip(self))]
+    pub fn get_force_name(&self, force: usize) -> Result<Option<String>, ChkError> {
+        let forc = self.forc.as_ref().map_err(|x| x.clone())?;
+
+        self.get_name(
+            self.ostr
+                .as_ref()
+                .ok()
+                .and_then(|x| x.force_name.get(force).copied()),
+            forc.force_name.get(force).copied().unwrap_or(0) as u32,
+        )
+    }
+
+    // Uses UNIx and the expansion names in OSTR if UNIx is present, otherwise UNIS. The name in UNIx/UNIS is
+    // only used if the unit does not use default settings, the OSTR override is always used.
+    #[cfg(feature = "full")]
+    #[instrument(level = "trace", skip(self))]
+    pub fn get_unit_name(&self, unit_id: usize) -> Result<Option<String>, ChkError> {
+        let (config, string_number, editor_string_number) = if let Ok(x) = &self.unix {
+            (
+                &x.config,
+                &x.string_number,
+                self.ostr.as_ref().ok().map(|x| &x.expansion_unit_name),
+            )
+        } else {
+            let unis = self.unis.as_ref().map_err(|x| x.clone())?;
+            (
+                unis.config,
+                &*unis.string_number,
+                self.ostr.as_ref().ok().map(|x| &x.unit_name),
+            )
+        };
+
+        let string_number = match config.get(unit_id) {
+            Some(0) => string_number[unit_id] as u32,
+            _ => 0,
+        };
+
+        self.get_name(
+            editor_string_number.and_then(|x| x.get(unit_id).copied()),
+            string_number,
+        )
+    }
+
+    // switch is 0 based.
+    #[cfg(feature = "full")]
+    #[instrument(level = "trace", skip(self))]
+    pub fn get_switch_name(&self, switch: usize) -> Result<Option<String>, ChkError> {
+        let string_number = match &self.swnm {
+            Ok(x) => x
+                .switch_name_string_number
+                .get(switch)
+                .copied()
+                .unwrap_or(0),
+            Err(_) => 0,
+        };
+
+        self.get_name(
+            self.ostr
+                .as_ref()
+                .ok()
+                .and_then(|x| x.switch_name.get(switch).copied()),
+            string_number,
+        )
     }
 }
 
 #[cfg(test)]
 mod test {
 
+    #[cfg(feature = "full")]
+    use crate::{chunk_name::ChunkName, test::make_string_section, ChkError, ChkWriter};
     use crate::{
         test::{get_all_test_chks, get_chk},
         ParsedChk,
@@ -611,4 +748,76 @@ mod test {
 
         assert!(parsed_chk.forc.is_ok());
     }
+
+    #[cfg(feature = "full")]
+    #[test]
+    fn editor_names_override_strings() {
+        let mut ostr = vec![0u8; 5944];
+        ostr[0..4].copy_from_slice(&1u32.to_le_bytes());
+        // Scenario name, switch 2 and location 1.
+        ostr[4..8].copy_from_slice(&1u32.to_le_bytes());
+        ostr[28 + 912 * 2 + 2048 + 4..][..4].copy_from_slice(&2u32.to_le_bytes());
+        ostr[28 + 912 * 2 + 2048 + 1024..][..4].copy_from_slice(&3u32.to_le_bytes());
+
+        let mut mrgn = vec![0u8; 1280];
+        mrgn[16..18].copy_from_slice(&2u16.to_le_bytes());
+
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(ChunkName::OSTR, ostr);
+        writer.add_chunk(ChunkName::MRGN, mrgn);
+        writer.add_chunk(
+            ChunkName::SPRP,
+            [1u16, 2].iter().flat_map(|x| x.to_le_bytes()).collect(),
+        );
+        writer.add_chunk(
+            ChunkName::STR,
+            make_string_section(&2u16.to_le_bytes(), &["Name", "Description"], false),
+        );
+        writer.add_chunk(
+            ChunkName::KSTR,
+            make_string_section(
+                &[2u32.to_le_bytes(), 3u32.to_le_bytes()].concat(),
+                &["Editor name", "Switch", "Location"],
+                true,
+            ),
+        );
+
+        let chk = writer.to_bytes();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        assert_eq!(
+            parsed_chk.get_scenario_name().unwrap().unwrap(),
+            "Editor name"
+        );
+        assert_eq!(
+            parsed_chk.get_scenario_description().unwrap().unwrap(),
+            "Description"
+        );
+        assert_eq!(parsed_chk.get_switch_name(0).unwrap(), None);
+        assert_eq!(parsed_chk.get_switch_name(1).unwrap().unwrap(), "Switch");
+        assert_eq!(parsed_chk.get_location_name(1).unwrap(), "Location");
+        assert!(matches!(
+            parsed_chk.get_location_name(2),
+            Err(ChkError::InvalidString { .. })
+        ));
+        assert!(parsed_chk.get_location_name(0).is_err());
+        // MRGN holds 64 locations, the last one exists but has no name.
+        assert!(parsed_chk
+            .get_location_name(64)
+            .unwrap_err()
+            .to_string()
+            .contains("has no name"));
+        assert!(parsed_chk
+            .get_location_name(65)
+            .unwrap_err()
+            .to_string()
+            .contains("does not exist"));
+        assert_eq!(parsed_chk.get_editor_string_bytes(4), None);
+
+        // Both sections are written back as they were read.
+        let written = ChkWriter::from(&parsed_chk);
+        for chunk in [&writer.chunks()[1], &writer.chunks()[4]] {
+            assert!(written.chunks().contains(chunk));
+        }
+    }
 }
@@ -0,0 +1,417 @@
+use crate::{
+    chk::{
+        chk_colr::ChkColr, chk_crgb::ChkCrgb, chk_dd2::ChkDd2, chk_dim::ChkDimOwned,
+        chk_era::ChkEraOwned, chk_forc::ChkForc, chk_iown::ChkIown, chk_isom::ChkIsom,
+        chk_ive2::ChkIve2Owned, chk_iver::ChkIverOwned, chk_kstr::ChkKstr, chk_mask::ChkMaskOwned,
+        chk_mbrf::ChkMbrf, chk_mrgn::ChkMrgnOwned, chk_mtxm::ChkMtxm, chk_ostr::ChkOstr,
+        chk_ownr::ChkOwnr, chk_ptec::ChkPtecOwned, chk_ptex::ChkPtexOwned, chk_puni::ChkPuniOwned,
+        chk_pupx::ChkPupxOwned, chk_side::ChkSide, chk_sprp::ChkSprpOwned, chk_str::ChkStr2,
+        chk_strx::ChkStrx2, chk_swnm::ChkSwnmOwned, chk_tecs::ChkTecsOwned, chk_tecx::ChkTecxOwned,
+        chk_thg2::ChkThg2, chk_tile::ChkTile, chk_trig::ChkTrig, chk_type::ChkTypeOwned,
+        chk_unis::ChkUnisOwned, chk_unit::ChkUnit, chk_unix::ChkUnix, chk_upgr::ChkUpgrOwned,
+        chk_upgs::ChkUpgsOwned, chk_upgx::ChkUpgxOwned, chk_uprp::ChkUprpOwned,
+        chk_upus::ChkUpusOwned, chk_vcod::ChkVcodOwned, chk_ver::ChkVerOwned, chk_wav::ChkWavOwned,
+    },
+    chunk_name::ChunkName,
+    writer::ChkWriter,
+    ParsedChk,
+};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use tracing::instrument;
+
+// An owned copy of a ParsedChk that does not borrow from the input buffer, so it can be kept around,
+// sent to other threads, edited and written back out with ChkWriter.
+// Sections that were missing or failed to parse are None, the chunks of the ones that failed are kept in
+// unparsed.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedChkOwned {
+    pub colr: Option<ChkColr>,
+    pub crgb: Option<ChkCrgb>,
+    pub dd2: Option<ChkDd2>,
+    pub dim: Option<ChkDimOwned>,
+    pub era: Option<ChkEraOwned>,
+    pub forc: Option<ChkForc>,
+    pub iown: Option<ChkIown>,
+    pub isom: Option<ChkIsom>,
+    pub ive2: Option<ChkIve2Owned>,
+    pub iver: Option<ChkIverOwned>,
+    pub kstr: Option<ChkKstr>,
+    pub mask: Option<ChkMaskOwned>,
+    pub mbrf: Option<ChkMbrf>,
+    pub mrgn: Option<ChkMrgnOwned>,
+    pub mtxm: Option<ChkMtxm>,
+    pub ostr: Option<ChkOstr>,
+    pub ownr: Option<ChkOwnr>,
+    pub ptec: Option<ChkPtecOwned>,
+    pub ptex: Option<ChkPtexOwned>,
+    pub puni: Option<ChkPuniOwned>,
+    pub pupx: Option<ChkPupxOwned>,
+    pub side: Option<ChkSide>,
+    pub sprp: Option<ChkSprpOwned>,
+    pub str: Option<ChkStr2>,
+    pub strx: Option<ChkStrx2>,
+    pub swnm: Option<ChkSwnmOwned>,
+    pub tecs: Option<ChkTecsOwned>,
+    pub tecx: Option<ChkTecxOwned>,
+    pub thg2: Option<ChkThg2>,
+    pub tile: Option<ChkTile>,
+    pub trig: Option<ChkTrig>,
+    pub type_: Option<ChkTypeOwned>,
+    pub unis: Option<ChkUnisOwned>,
+    pub unit: Option<ChkUnit>,
+    pub unix: Option<ChkUnix>,
+    pub upgr: Option<ChkUpgrOwned>,
+    pub upgs: Option<ChkUpgsOwned>,
+    pub upgx: Option<ChkUpgxOwned>,
+    pub uprp: Option<ChkUprpOwned>,
+    pub upus: Option<ChkUpusOwned>,
+    pub vcod: Option<ChkVcodOwned>,
+    pub ver: Option<ChkVerOwned>,
+    pub wav: Option<ChkWavOwned>,
+    // See ParsedChk::unparsed. ChkWriter leaves out the chunks of sections that have been filled in since.
+    pub unparsed: Vec<(ChunkName, Vec<u8>)>,
+}
+
+impl Serialize for ParsedChkOwned {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+
+        if let Some(x) = &self.colr {
+            map.serialize_entry("COLR", x)?;
+        }
+        if let Some(x) = &self.crgb {
+            map.serialize_entry("CRGB", x)?;
+        }
+        if let Some(x) = &self.dd2 {
+            map.serialize_entry("DD2", x)?;
+        }
+        if let Some(x) = &self.dim {
+            map.serialize_entry("DIM", x)?;
+        }
+        if let Some(x) = &self.era {
+            map.serialize_entry("ERA", x)?;
+        }
+        if let Some(x) = &self.forc {
+            map.serialize_entry("FORC", x)?;
+        }
+        if let Some(x) = &self.iown {
+            map.serialize_entry("IOWN", x)?;
+        }
+        if let Some(x) = &self.ive2 {
+            map.serialize_entry("IVE2", x)?;
+        }
+        if let Some(x) = &self.iver {
+            map.serialize_entry("IVER", x)?;
+        }
+        if let Some(x) = &self.kstr {
+            map.serialize_entry("KSTR", x)?;
+        }
+        if let Some(x) = &self.mask {
+            map.serialize_entry("MASK", x)?;
+        }
+        if let Some(x) = &self.mbrf {
+            map.serialize_entry("MBRF", x)?;
+        }
+        if let Some(x) = &self.mrgn {
+            map.serialize_entry("MRGN", x)?;
+        }
+        if let Some(x) = &self.ostr {
+            map.serialize_entry("OSTR", x)?;
+        }
+        if let Some(x) = &self.ownr {
+            map.serialize_entry("OWNR", x)?;
+        }
+        if let Some(x) = &self.ptec {
+            map.serialize_entry("PTEC", x)?;
+        }
+        if let Some(x) = &self.ptex {
+            map.serialize_entry("PTEx", x)?;
+        }
+        if let Some(x) = &self.puni {
+            map.serialize_entry("PUNI", x)?;
+        }
+        if let Some(x) = &self.pupx {
+            map.serialize_entry("PUPx", x)?;
+        }
+        if let Some(x) = &self.side {
+            map.serialize_entry("SIDE", x)?;
+        }
+        if let Some(x) = &self.sprp {
+            map.serialize_entry("SPRP", x)?;
+        }
+        if let Some(x) = &self.str {
+            map.serialize_entry("STR", x)?;
+        }
+        if let Some(x) = &self.strx {
+            map.serialize_entry("STRx", x)?;
+        }
+        if let Some(x) = &self.swnm {
+            map.serialize_entry("SWNM", x)?;
+        }
+        if let Some(x) = &self.tecs {
+            map.serialize_entry("TECS", x)?;
+        }
+        if let Some(x) = &self.tecx {
+            map.serialize_entry("TECx", x)?;
+        }
+        if let Some(x) = &self.thg2 {
+            map.serialize_entry("THG2", x)?;
+        }
+        if let Some(x) = &self.type_ {
+            map.serialize_entry("TYPE", x)?;
+        }
+        if let Some(x) = &self.unis {
+            map.serialize_entry("UNIS", x)?;
+        }
+        if let Some(x) = &self.unit {
+            map.serialize_entry("UNIT", x)?;
+        }
+        if let Some(x) = &self.unix {
+            map.serialize_entry("UNIx", x)?;
+        }
+        if let Some(x) = &self.upgr {
+            map.serialize_entry("UPGR", x)?;
+        }
+        if let Some(x) = &self.upgs {
+            map.serialize_entry("UPGS", x)?;
+        }
+        if let Some(x) = &self.upgx {
+            map.serialize_entry("UPGx", x)?;
+        }
+        if let Some(x) = &self.uprp {
+            map.serialize_entry("UPRP", x)?;
+        }
+        if let Some(x) = &self.upus {
+            map.serialize_entry("UPUS", x)?;
+        }
+        if let Some(x) = &self.vcod {
+            map.serialize_entry("VCOD", x)?;
+        }
+        if let Some(x) = &self.ver {
+            map.serialize_entry("VER", x)?;
+        }
+        if let Some(x) = &self.wav {
+            map.serialize_entry("WAV", x)?;
+        }
+
+        map.end()
+    }
+}
+
+impl From<&ParsedChk<'_>> for ParsedChkOwned {
+    #[instrument(level = "trace", skip_all)]
+    fn from(parsed_chk: &ParsedChk<'_>) -> ParsedChkOwned {
+        ParsedChkOwned {
+            colr: parsed_chk.colr.as_ref().ok().cloned(),
+            crgb: parsed_chk.crgb.as_ref().ok().cloned(),
+            dd2: parsed_chk.dd2.as_ref().ok().cloned(),
+            dim: parsed_chk.dim.as_ref().ok().map(Into::into),
+            era: parsed_chk.era.as_ref().ok().map(Into::into),
+            forc: parsed_chk.forc.as_ref().ok().cloned(),
+            iown: parsed_chk.iown.as_ref().ok().cloned(),
+            isom: parsed_chk.isom.as_ref().ok().cloned(),
+            ive2: parsed_chk.ive2.as_ref().ok().map(Into::into),
+            iver: parsed_chk.iver.as_ref().ok().map(Into::into),
+            kstr: parsed_chk.kstr.as_ref().ok().cloned(),
+            mask: parsed_chk.mask.as_ref().ok().map(Into::into),
+            mbrf: parsed_chk.mbrf.as_ref().ok().cloned(),
+            mrgn: parsed_chk.mrgn.as_ref().ok().map(Into::into),
+            mtxm: parsed_chk.mtxm.as_ref().ok().cloned(),
+            ostr: parsed_chk.ostr.as_ref().ok().cloned(),
+            ownr: parsed_chk.ownr.as_ref().ok().cloned(),
+            ptec: parsed_chk.ptec.as_ref().ok().map(Into::into),
+            ptex: parsed_chk.ptex.as_ref().ok().map(Into::into),
+            puni: parsed_chk.puni.as_ref().ok().map(Into::into),
+            pupx: parsed_chk.pupx.as_ref().ok().map(Into::into),
+            side: parsed_chk.side.as_ref().ok().cloned(),
+            sprp: parsed_chk.sprp.as_ref().ok().map(Into::into),
+            str: parsed_chk.str.as_ref().ok().cloned(),
+            strx: parsed_chk.strx.as_ref().ok().cloned(),
+            swnm: parsed_chk.swnm.as_ref().ok().map(Into::into),
+            tecs: parsed_chk.tecs.as_ref().ok().map(Into::into),
+            tecx: parsed_chk.tecx.as_ref().ok().map(Into::into),
+            thg2: parsed_chk.thg2.as_ref().ok().cloned(),
+            tile: parsed_chk.tile.as_ref().ok().cloned(),
+            trig: parsed_chk.trig.as_ref().ok().cloned(),
+            type_: parsed_chk.type_.as_ref().ok().map(Into::into),
+            unis: parsed_chk.unis.as_ref().ok().map(Into::into),
+            unit: parsed_chk.unit.as_ref().ok().cloned(),
+            unix: parsed_chk.unix.as_ref().ok().cloned(),
+            upgr: parsed_chk.upgr.as_ref().ok().map(Into::into),
+            upgs: parsed_chk.upgs.as_ref().ok().map(Into::into),
+            upgx: parsed_chk.upgx.as_ref().ok().map(Into::into),
+            uprp: parsed_chk.uprp.as_ref().ok().map(Into::into),
+            upus: parsed_chk.upus.as_ref().ok().map(Into::into),
+            vcod: parsed_chk.vcod.as_ref().ok().map(Into::into),
+            ver: parsed_chk.ver.as_ref().ok().map(Into::into),
+            wav: parsed_chk.wav.as_ref().ok().map(Into::into),
+            unparsed: parsed_chk
+                .unparsed
+                .iter()
+                .map(|x| (x.chunk_name.clone(), x.data.to_vec()))
+                .collect(),
+        }
+    }
+}
+
+impl ParsedChkOwned {
+    #[instrument(level = "trace", skip_all)]
+    pub fn from_bytes(chk: &[u8]) -> ParsedChkOwned {
+        ParsedChkOwned::from(&ParsedChk::from_bytes(chk))
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        ChkWriter::from(self).to_bytes()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ParsedChkOwned;
+    use crate::{
+        chk::chk_ive2::ChkIve2Owned,
+        chunk_name::{parse_chunk_name, ChunkName},
+        riff::parse_riff,
+        ChkWriter, ParsedChk,
+    };
+
+    fn make_test_chk() -> Vec<u8> {
+        let mut writer = ChkWriter::new();
+
+        let mut mrgn = vec![0; 1280];
+        mrgn[0..4].copy_from_slice(&32u32.to_le_bytes());
+        mrgn[16..18].copy_from_slice(&1u16.to_le_bytes());
+
+        writer.add_chunk(ChunkName::VER, 59u16.to_le_bytes().to_vec());
+        writer.add_chunk(
+            ChunkName::DIM,
+            [128u16.to_le_bytes(), 128u16.to_le_bytes()].concat(),
+        );
+        writer.add_chunk(ChunkName::SIDE, vec![0, 1, 2, 0, 1, 2, 0, 1, 7, 7, 7, 4]);
+        writer.add_chunk(ChunkName::MRGN, mrgn);
+        writer.add_chunk(ChunkName::SPRP, vec![1, 0, 2, 0]);
+
+        writer.to_bytes()
+    }
+
+    #[test]
+    fn owned_chk_outlives_input() {
+        let owned = {
+            let chk = make_test_chk();
+            ParsedChkOwned::from_bytes(chk.as_slice())
+        };
+
+        let owned = std::thread::spawn(move || owned).join().unwrap();
+
+        assert_eq!(owned.ver.as_ref().unwrap().file_format_version, 59);
+        assert_eq!(owned.dim.as_ref().unwrap().width, 128);
+        assert_eq!(owned.mrgn.as_ref().unwrap().locations.len(), 64);
+        assert_eq!({ owned.mrgn.as_ref().unwrap().locations[0].left }, 32);
+        assert!(owned.unit.is_none());
+    }
+
+    #[test]
+    fn edit_and_write_owned_chk() {
+        let chk = make_test_chk();
+        let mut owned = ParsedChkOwned::from(&ParsedChk::from_bytes(chk.as_slice()));
+
+        owned.side.as_mut().unwrap().player_side.swap(0, 1);
+        owned.dim.as_mut().unwrap().height = 64;
+        owned.mrgn.as_mut().unwrap().locations[0].name_string_number = 2;
+        owned.sprp = None;
+
+        let written = owned.to_bytes();
+        let reparsed_chk = ParsedChk::from_bytes(written.as_slice());
+
+        assert_eq!(
+            reparsed_chk.side.as_ref().unwrap().player_side,
+            [1, 0, 2, 0, 1, 2, 0, 1, 7, 7, 7, 4]
+        );
+        assert_eq!(*reparsed_chk.dim.as_ref().unwrap().height, 64);
+        assert_eq!(
+            { reparsed_chk.mrgn.as_ref().unwrap().locations[0].name_string_number },
+            2
+        );
+        assert!(reparsed_chk.sprp.is_err());
+    }
+
+    #[test]
+    fn filled_in_section_replaces_unparsed_chunks() {
+        // Too short for IVE2, followed by two chunks of the same unknown section.
+        let mut chk = Vec::new();
+        for (chunk_name, data) in [("IVE2", 11), ("ZZZZ", 1), ("ZZZZ", 2)] {
+            let mut writer = ChkWriter::new();
+            writer.add_chunk(parse_chunk_name(chunk_name.as_bytes()), vec![data]);
+            chk.extend(writer.to_bytes());
+        }
+
+        let mut owned = ParsedChkOwned::from_bytes(chk.as_slice());
+
+        assert!(owned.ive2.is_none());
+        assert_eq!(
+            owned.unparsed,
+            vec![
+                (ChunkName::IVE2, vec![11]),
+                (ChunkName::UNKNOWN("ZZZZ".to_owned()), vec![1]),
+                (ChunkName::UNKNOWN("ZZZZ".to_owned()), vec![2]),
+            ]
+        );
+
+        owned.ive2 = Some(ChkIve2Owned {
+            additional_file_format_version: 11,
+        });
+
+        let written = owned.to_bytes();
+        let chunks = parse_riff(written.as_slice());
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].chunk_name, ChunkName::IVE2);
+        assert_eq!(chunks[0].data, &[11, 0]);
+        assert_eq!(chunks[1].data, &[1]);
+        assert_eq!(chunks[2].data, &[2]);
+    }
+
+    #[test]
+    fn misaligned_sections() {
+        let mut vcod = vec![0; 1040];
+        vcod[4..8].copy_from_slice(&0xDEADBEEFu32.to_le_bytes());
+
+        let mut unis = vec![0; 4048];
+        unis[228..232].copy_from_slice(&0x12345678u32.to_le_bytes());
+        unis[3192 + 10..3192 + 12].copy_from_slice(&7u16.to_le_bytes());
+
+        let mut mrgn = vec![0; 1280];
+        mrgn[0..4].copy_from_slice(&32u32.to_le_bytes());
+
+        // The 2 bytes of VER leave the data of every section after it 2 bytes off of a 4 byte boundary.
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(ChunkName::VER, 59u16.to_le_bytes().to_vec());
+        writer.add_chunk(ChunkName::VCOD, vcod);
+        writer.add_chunk(ChunkName::UNIS, unis);
+        writer.add_chunk(ChunkName::MRGN, mrgn);
+        let chk = writer.to_bytes();
+
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        assert_eq!(parsed_chk.vcod.as_ref().unwrap().seed_values[1], 0xDEADBEEF);
+        assert_eq!(parsed_chk.unis.as_ref().unwrap().hit_points[0], 0x12345678);
+        assert_eq!(parsed_chk.unis.as_ref().unwrap().string_number[5], 7);
+        assert_eq!({ parsed_chk.mrgn.as_ref().unwrap().locations[0].left }, 32);
+
+        let owned = ParsedChkOwned::from_bytes(chk.as_slice());
+
+        assert_eq!(owned.vcod.as_ref().unwrap().seed_values[1], 0xDEADBEEF);
+        assert_eq!(owned.unis.as_ref().unwrap().hit_points[0], 0x12345678);
+        assert_eq!(owned.unis.as_ref().unwrap().string_number[5], 7);
+        assert_eq!({ owned.mrgn.as_ref().unwrap().locations[0].left }, 32);
+
+        let reparsed_chk = ParsedChkOwned::from_bytes(owned.to_bytes().as_slice());
+
+        assert_eq!(reparsed_chk.unis.unwrap().hit_points[0], 0x12345678);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    chunk_name::ChunkName,
+    error::ChkError,
+    riff::{
+        merge_strategy, parse_riff_with_mode, validate_riff_chunk, MergeStrategy, ParseMode,
+        RiffChunk, SkipReason, SkippedChunk,
+    },
+    ParsedChk,
+};
+use serde::Serialize;
+use tracing::instrument;
+
+// Describes how every chunk in a chk was interpreted: which chunks were skipped by parse_riff,
+// which were rejected by validation or parsing, which were merged together and which were overridden by
+// a later chunk.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct ParseReport {
+    pub chunks: Vec<ChunkReport>,
+    // Bytes at the end of the chk that are too short to hold a chunk header.
+    pub trailing_bytes: usize,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct ChunkReport {
+    pub chunk_name: ChunkName,
+    pub offset: usize,
+    pub size: u32,
+    pub status: ChunkStatus,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub enum ChunkStatus {
+    // The chunk is the one the section is read from.
+    Used,
+    // The chunk is combined with the other chunks of the same section.
+    Merged(MergeStrategy),
+    // A later chunk at the given offset replaces all of the data in this one.
+    OverriddenBy { offset: usize },
+    // The chunk header was read but the chunk could not be sliced out of the chk.
+    Skipped(SkipReason),
+    // The chunk was rejected by validate_riff_chunk, or the section it would be read into failed to parse.
+    Rejected(ChkError),
+}
+
+impl ParseReport {
+    #[instrument(level = "trace", skip_all)]
+    pub fn from_bytes(chk: &[u8]) -> ParseReport {
+        ParseReport::from_bytes_with_mode(chk, ParseMode::Lenient)
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub fn from_bytes_with_mode(chk: &[u8], mode: ParseMode) -> ParseReport {
+        let (riff_chunks, skipped) = parse_riff_with_mode(chk, mode);
+        let parsed_chk = ParsedChk::from_riff_chunks(&riff_chunks, mode);
+
+        ParseReport::from_riff_chunks(chk.len(), &riff_chunks, skipped, &parsed_chk)
+    }
+
+    // The report for the chunks parse_riff_with_mode split a chk of the given length into, and that
+    // parsed_chk was built from.
+    #[instrument(level = "trace", skip_all)]
+    pub(crate) fn from_riff_chunks(
+        chk_len: usize,
+        riff_chunks: &[RiffChunk],
+        skipped: Vec<SkippedChunk>,
+        parsed_chk: &ParsedChk,
+    ) -> ParseReport {
+        let mut chunks: Vec<ChunkReport> = Vec::new();
+
+        for (i, chunk) in riff_chunks.iter().enumerate() {
+            let status = if let Err(err) = validate_riff_chunk(chunk) {
+                ChunkStatus::Rejected(err)
+            } else {
+                // Only later chunks that were accepted can affect this one.
+                let mut later = riff_chunks[i + 1..]
+                    .iter()
+                    .filter(|x| x.chunk_name == chunk.chunk_name)
+                    .filter(|x| validate_riff_chunk(x).is_ok());
+
+                let status = match merge_strategy(&chunk.chunk_name) {
+                    MergeStrategy::LastWins => match later.next_back() {
+                        Some(x) => ChunkStatus::OverriddenBy { offset: x.offset },
+                        None => ChunkStatus::Used,
+                    },
+                    MergeStrategy::Overlay => match later.find(|x| x.size >= chunk.size) {
+                        Some(x) => ChunkStatus::OverriddenBy { offset: x.offset },
+                        None => ChunkStatus::Merged(MergeStrategy::Overlay),
+                    },
+                    MergeStrategy::Append => ChunkStatus::Merged(MergeStrategy::Append),
+                };
+
+                // A chunk the section is read from was not used if the section then failed to parse.
+                match (status, parsed_chk.section_error(&chunk.chunk_name)) {
+                    (ChunkStatus::OverriddenBy { offset }, _) => {
+                        ChunkStatus::OverriddenBy { offset }
+                    }
+                    (_, Some(err)) => ChunkStatus::Rejected(err.clone()),
+                    (status, None) => status,
+                }
+            };
+
+            chunks.push(ChunkReport {
+                chunk_name: chunk.chunk_name.clone(),
+                offset: chunk.offset,
+                size: chunk.size,
+                status,
+            });
+        }
+
+        for chunk in skipped {
+            chunks.push(ChunkReport {
+                chunk_name: chunk.chunk_name,
+                offset: chunk.offset,
+                size: chunk.size,
+                status: ChunkStatus::Skipped(chunk.reason),
+            });
+        }
+
+        chunks.sort_by_key(|x| x.offset);
+
+        let end_of_last_header = chunks
+            .iter()
+            .map(|x| match x.status {
+                ChunkStatus::Skipped(_) => x.offset + 8,
+                _ => x.offset + 8 + x.size as usize,
+            })
+            .max()
+            .unwrap_or(0);
+
+        ParseReport {
+            chunks,
+            trailing_bytes: chk_len.saturating_sub(end_of_last_header),
+        }
+    }
+
+    pub fn rejected(&self) -> impl Iterator<Item = &ChunkReport> {
+        self.chunks
+            .iter()
+            .filter(|x| matches!(x.status, ChunkStatus::Rejected(_) | ChunkStatus::Skipped(_)))
+    }
+}
+
+impl std::fmt::Display for ParseReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for chunk in &self.chunks {
+            write!(
+                f,
+                "{:>10} {:<12} size: {:<10} ",
+                chunk.offset,
+                format!("{:?}", chunk.chunk_name),
+                chunk.size
+            )?;
+
+            match &chunk.status {
+                ChunkStatus::Used => writeln!(f, "used")?,
+                ChunkStatus::Merged(strategy) => writeln!(f, "merged ({strategy:?})")?,
+                ChunkStatus::OverriddenBy { offset } => {
+                    writeln!(f, "overridden by chunk at {offset}")?
+                }
+                ChunkStatus::Skipped(reason) => writeln!(f, "skipped ({reason:?})")?,
+                ChunkStatus::Rejected(err) => writeln!(f, "rejected ({err})")?,
+            }
+        }
+
+        if self.trailing_bytes > 0 {
+            writeln!(f, "{} trailing bytes", self.trailing_bytes)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ChunkStatus, ParseReport};
+    use crate::{
+        chunk_name::ChunkName,
+        error::{ChkError, ExpectedSize},
+        riff::{MergeStrategy, SkipReason},
+        ChkWriter, ParsedChk,
+    };
+
+    fn chunk(chunk_name: ChunkName, data: Vec<u8>) -> Vec<u8> {
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(chunk_name, data);
+        writer.to_bytes()
+    }
+
+    #[test]
+    fn reports_every_chunk() {
+        let chk = [
+            chunk(ChunkName::VER, vec![59, 0]),
+            chunk(ChunkName::VER, vec![205, 0]),
+            chunk(ChunkName::SIDE, vec![0; 11]),
+            chunk(ChunkName::STR, vec![1; 10]),
+            chunk(ChunkName::STR, vec![2; 4]),
+            chunk(ChunkName::UNIT, vec![0; 36]),
+            chunk(ChunkName::UNIT, vec![0; 36]),
+            // Claims to be larger than the rest of the file.
+            [b"MASK".as_slice(), &100u32.to_le_bytes(), &[0; 4]].concat(),
+        ]
+        .concat();
+
+        let (parsed_chk, report) = ParsedChk::from_bytes_with_report(chk.as_slice());
+
+        assert_eq!(*parsed_chk.ver.unwrap().file_format_version, 205);
+
+        let statuses: Vec<_> = report.chunks.iter().map(|x| x.status.clone()).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ChunkStatus::OverriddenBy { offset: 10 },
+                ChunkStatus::Used,
+                ChunkStatus::Rejected(ChkError::InvalidSize {
+                    chunk_name: ChunkName::SIDE,
+                    offset: 20,
+                    expected: ExpectedSize::Exactly(12),
+                    actual: 11,
+                }),
+                ChunkStatus::Merged(MergeStrategy::Overlay),
+                ChunkStatus::Merged(MergeStrategy::Overlay),
+                ChunkStatus::Merged(MergeStrategy::Append),
+                ChunkStatus::Merged(MergeStrategy::Append),
+                ChunkStatus::Skipped(SkipReason::OutOfBounds),
+            ]
+        );
+        assert_eq!(report.rejected().count(), 2);
+        assert_eq!(report.trailing_bytes, 4);
+    }
+
+    #[test]
+    fn chunk_of_section_that_failed_to_parse_is_rejected() {
+        let chk = [
+            chunk(ChunkName::VER, vec![205, 0]),
+            // Passes validation, but IVE2 is 2 bytes.
+            chunk(ChunkName::IVE2, vec![11]),
+        ]
+        .concat();
+
+        let (parsed_chk, report) = ParsedChk::from_bytes_with_report(chk.as_slice());
+        let err = parsed_chk.ive2.unwrap_err();
+
+        assert_eq!(report.chunks[0].status, ChunkStatus::Used);
+        assert_eq!(report.chunks[1].status, ChunkStatus::Rejected(err));
+        assert_eq!(ParseReport::from_bytes(chk.as_slice()), report);
+        assert_eq!(report.rejected().count(), 1);
+    }
+
+    #[test]
+    fn overlay_fully_covered_by_later_chunk_is_overridden() {
+        let chk = [
+            chunk(ChunkName::MTXM, vec![1; 4]),
+            chunk(ChunkName::MTXM, vec![2; 8]),
+        ]
+        .concat();
+
+        let report = ParseReport::from_bytes(chk.as_slice());
+
+        assert_eq!(
+            report.chunks[0].status,
+            ChunkStatus::OverriddenBy { offset: 12 }
+        );
+        assert_eq!(
+            report.chunks[1].status,
+            ChunkStatus::Merged(MergeStrategy::Overlay)
+        );
+    }
+}
@@ -1,26 +1,104 @@
-use crate::{chunk_name::parse_chunk_name, chunk_name::ChunkName, util::parse_slice};
+use crate::{
+    chunk_name::parse_chunk_name,
+    error::{ChkError, ExpectedSize},
+    util::parse_slice,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tracing::instrument;
 
+pub use crate::chunk_name::ChunkName;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RiffChunk<'a> {
     pub chunk_name: ChunkName,
     pub size: u32,
-    pub offset: usize,
+    pub offset: usize, // offset of the chunk header, not the chunk data.
     #[serde(skip)]
     pub data: &'a [u8],
 }
 
+// A chunk header that parse_riff read but did not turn into a RiffChunk.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct SkippedChunk {
+    pub chunk_name: ChunkName,
+    pub size: u32,
+    pub offset: usize,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    NegativeSize,
+    OutOfBounds,
+    // The chunk header was already read once, following it again would never terminate.
+    Loop,
+}
+
+// Selects how the chunk headers of a chk are walked.
+//
+// Both modes walk the chk the same way StarCraft does: read an 8 byte header, then move forward by the
+// chunk size, which is a u32 that wraps around, so a "negative" size jumps backwards. Both modes stop
+// when they reach a header they already read, StarCraft would loop forever on such a map.
+//
+// Lenient ignores chunks that do not fit in the chk and requires at least one byte after a header.
+//
+// GameAccurate reproduces the rest of the loader:
+// * A chunk that runs past the end of the chk is truncated to the bytes that are left.
+// * A header that ends exactly at the end of the chk is read as an empty chunk.
+// * MTXM is cut or padded with null tiles to width * height of the map, as that is the buffer
+//   StarCraft copies it into.
+//
+// How the chunks of one section are combined (overwrite, partial-copy or append) is the same in both
+// modes, see merge_strategy.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    #[default]
+    Lenient,
+    GameAccurate,
+}
+
 #[instrument(level = "trace", skip_all)]
 pub fn parse_riff(chk: &[u8]) -> Vec<RiffChunk> {
+    parse_riff_with_skipped(chk).0
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn parse_riff_with_skipped(chk: &[u8]) -> (Vec<RiffChunk<'_>>, Vec<SkippedChunk>) {
+    parse_riff_with_mode(chk, ParseMode::Lenient)
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn parse_riff_with_mode(
+    chk: &[u8],
+    mode: ParseMode,
+) -> (Vec<RiffChunk<'_>>, Vec<SkippedChunk>) {
     let mut offset = 0;
     let mut ret = Vec::new();
+    let mut skipped = Vec::new();
+    let mut visited = HashSet::new();
 
-    while offset + 8 < chk.len() {
+    let has_header = |offset: usize| match mode {
+        ParseMode::Lenient => offset + 8 < chk.len(),
+        ParseMode::GameAccurate => offset + 8 <= chk.len(),
+    };
+
+    while has_header(offset) {
         let chunk_name: ChunkName = parse_chunk_name(&chk[offset..offset + 4]);
         let size: u32 = u32::from_le_bytes(parse_slice(&chk[offset + 4..offset + 8]));
 
+        let chunk_header_offset = offset;
+
+        if !visited.insert(chunk_header_offset) {
+            skipped.push(SkippedChunk {
+                chunk_name,
+                size,
+                offset: chunk_header_offset,
+                reason: SkipReason::Loop,
+            });
+            break;
+        }
+
         let chunk_data_start_offset = offset + 8;
         let chunk_data_end_offset =
             ((chunk_data_start_offset as u64 + size as u64) % (u32::MAX as u64 + 1)) as usize;
@@ -28,62 +106,130 @@ pub fn parse_riff(chk: &[u8]) -> Vec<RiffChunk> {
 
         if chunk_data_end_offset < chunk_data_start_offset {
             // negative sized chunks are ignored.
+            skipped.push(SkippedChunk {
+                chunk_name,
+                size,
+                offset: chunk_header_offset,
+                reason: SkipReason::NegativeSize,
+            });
             continue;
         }
 
-        if chunk_data_end_offset <= chk.len() {
-            let data = &chk[chunk_data_start_offset..chunk_data_end_offset];
+        if chunk_data_end_offset <= chk.len() || mode == ParseMode::GameAccurate {
+            // PROTECTION: in GameAccurate mode a chunk claiming to be larger than the file gets whatever is left.
+            let data = &chk[chunk_data_start_offset..chunk_data_end_offset.min(chk.len())];
             ret.push(RiffChunk {
                 chunk_name,
                 size,
-                offset,
+                offset: chunk_header_offset,
                 data,
             });
+        } else {
+            skipped.push(SkippedChunk {
+                chunk_name,
+                size,
+                offset: chunk_header_offset,
+                reason: SkipReason::OutOfBounds,
+            });
         }
     }
 
-    ret
+    (ret, skipped)
+}
+
+// How the chunks of a section are combined when a section appears more than once.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    // Only the last chunk is used.
+    LastWins,
+    // Each chunk is copied over the previous ones starting from the beginning of the section.
+    Overlay,
+    // The contents of every chunk are concatenated.
+    Append,
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn merge_strategy(chunk_name: &ChunkName) -> MergeStrategy {
+    match chunk_name {
+        ChunkName::MTXM | ChunkName::STR | ChunkName::STRx | ChunkName::KSTR => {
+            MergeStrategy::Overlay
+        }
+        ChunkName::UNIT | ChunkName::THG2 | ChunkName::TRIG | ChunkName::MBRF => {
+            MergeStrategy::Append
+        }
+        _ => MergeStrategy::LastWins,
+    }
+}
+
+// Size rules for the sections that are validated. Sections without a rule are always accepted.
+#[instrument(level = "trace", skip_all)]
+pub fn expected_size(chunk_name: &ChunkName) -> Option<ExpectedSize> {
+    match chunk_name {
+        ChunkName::VER => Some(ExpectedSize::Exactly(2)),
+        ChunkName::VCOD => None, // Not sure how to validate this exactly but vcod isn't really read anyway.
+        ChunkName::OWNR => Some(ExpectedSize::Exactly(12)),
+        ChunkName::ERA => Some(ExpectedSize::Exactly(2)),
+        ChunkName::DIM => Some(ExpectedSize::Exactly(4)),
+        ChunkName::SIDE => Some(ExpectedSize::Exactly(12)),
+        ChunkName::MTXM => Some(ExpectedSize::AtMost(256 * 256 * 2)),
+        ChunkName::PUNI => Some(ExpectedSize::Exactly(5700)),
+        ChunkName::UPGR => Some(ExpectedSize::Exactly(1748)),
+        ChunkName::PTEC => Some(ExpectedSize::Exactly(912)),
+        ChunkName::UNIT => Some(ExpectedSize::MultipleOf(36)),
+        ChunkName::THG2 => Some(ExpectedSize::MultipleOf(10)),
+        ChunkName::MASK => None, // this section will always validate?
+        ChunkName::STR => Some(ExpectedSize::AtLeast(1)),
+        ChunkName::STRx => Some(ExpectedSize::AtLeast(1)), // assumed
+        ChunkName::UPRP => Some(ExpectedSize::AtLeast(1280)),
+        ChunkName::MRGN => Some(ExpectedSize::Either(1280, 5100)),
+        ChunkName::TRIG => Some(ExpectedSize::MultipleOf(2400)),
+        ChunkName::MBRF => Some(ExpectedSize::MultipleOf(2400)),
+        ChunkName::SPRP => Some(ExpectedSize::Exactly(4)),
+        ChunkName::FORC => Some(ExpectedSize::AtMost(20)),
+        ChunkName::UNIS => Some(ExpectedSize::Exactly(4048)),
+        ChunkName::UPGS => Some(ExpectedSize::Exactly(598)),
+        ChunkName::TECS => Some(ExpectedSize::Exactly(216)),
+        ChunkName::COLR => Some(ExpectedSize::Exactly(8)),
+        ChunkName::CRGB => Some(ExpectedSize::Exactly(32)),
+        ChunkName::PUPx => Some(ExpectedSize::Exactly(2318)),
+        ChunkName::PTEx => Some(ExpectedSize::Exactly(1672)),
+        ChunkName::UNIx => Some(ExpectedSize::Exactly(4168)),
+        ChunkName::UPGx => Some(ExpectedSize::Exactly(794)),
+        ChunkName::TECx => Some(ExpectedSize::Exactly(396)),
+        ChunkName::OSTR => Some(ExpectedSize::Exactly(5944)),
+        ChunkName::KSTR => Some(ExpectedSize::AtLeast(8)),
+        _ => None,
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn validate_riff_chunk(chunk: &RiffChunk) -> Result<(), ChkError> {
+    if let ChunkName::UNKNOWN(_) = chunk.chunk_name {
+        return Err(ChkError::Malformed {
+            chunk_name: chunk.chunk_name.clone(),
+            offset: chunk.offset,
+            reason: "unknown section name".to_owned(),
+        });
+    }
+
+    match expected_size(&chunk.chunk_name) {
+        Some(expected) if !expected.matches(chunk.size) => Err(ChkError::InvalidSize {
+            chunk_name: chunk.chunk_name.clone(),
+            offset: chunk.offset,
+            expected,
+            actual: chunk.size,
+        }),
+        _ => Ok(()),
+    }
 }
 
 #[instrument(level = "trace", skip_all)]
 pub fn validate_and_group_riff_chunks<'a>(
     chk: &[RiffChunk<'a>],
 ) -> HashMap<ChunkName, Vec<RiffChunk<'a>>> {
-    let validating_iterator = chk.iter().filter(|chunk| match chunk.chunk_name {
-        ChunkName::VER => chunk.size == 2,
-        ChunkName::VCOD => true, // Not sure how to validate this exactly but vcod isn't really read anyway.
-        ChunkName::OWNR => chunk.size == 12,
-        ChunkName::ERA => chunk.size == 2,
-        ChunkName::DIM => chunk.size == 4,
-        ChunkName::SIDE => chunk.size == 12,
-        ChunkName::MTXM => chunk.size <= (256 * 256 * 2),
-        ChunkName::PUNI => chunk.size == 5700,
-        ChunkName::UPGR => chunk.size == 1748,
-        ChunkName::PTEC => chunk.size == 912,
-        ChunkName::UNIT => chunk.size % 36 == 0,
-        ChunkName::THG2 => chunk.size % 10 == 0,
-        ChunkName::MASK => true, // this section will always validate?
-        ChunkName::STR => chunk.size >= 1,
-        ChunkName::STRx => chunk.size >= 1, // assumed
-        ChunkName::UPRP => chunk.size >= 1280,
-        ChunkName::MRGN => chunk.size == 1280 || chunk.size == 5100,
-        ChunkName::TRIG => chunk.size % 2400 == 0,
-        ChunkName::MBRF => chunk.size % 2400 == 0,
-        ChunkName::SPRP => chunk.size == 4,
-        ChunkName::FORC => chunk.size <= 20,
-        ChunkName::UNIS => chunk.size == 4048,
-        ChunkName::UPGS => chunk.size == 598,
-        ChunkName::TECS => chunk.size == 216,
-        ChunkName::COLR => chunk.size == 8,
-        ChunkName::CRGB => chunk.size == 32,
-        ChunkName::PUPx => chunk.size == 2318,
-        ChunkName::PTEx => chunk.size == 1672,
-        ChunkName::UNIx => chunk.size == 4168,
-        ChunkName::UPGx => chunk.size == 794,
-        ChunkName::TECx => chunk.size == 396,
-        ChunkName::UNKNOWN(_) => false,
-        _ => true,
-    });
+    let validating_iterator = chk
+        .iter()
+        .filter(|chunk| validate_riff_chunk(chunk).is_ok());
 
     let mut ret = HashMap::new();
 
@@ -98,9 +244,121 @@ pub fn validate_and_group_riff_chunks<'a>(
 
 #[cfg(test)]
 mod test {
-    use crate::{chunk_name::ChunkName, riff::parse_riff, test::get_all_test_chks};
+    use crate::{
+        chunk_name::ChunkName,
+        riff::{parse_riff, parse_riff_with_mode, ParseMode, SkipReason},
+        test::get_all_test_chks,
+        ParsedChk,
+    };
     use futures::{pin_mut, TryStreamExt};
 
+    fn header(chunk_name: &[u8; 4], size: i32) -> Vec<u8> {
+        [chunk_name.as_slice(), &size.to_le_bytes()].concat()
+    }
+
+    #[test]
+    fn negative_size_jumps_backwards() {
+        let chk = [
+            header(b"XXXX", 20),
+            // Hidden inside XXXX and only reachable by jumping back.
+            header(b"VER ", 2),
+            vec![205, 0],
+            header(b"ZZZZ", 10),
+            vec![0, 0],
+            header(b"JUMP", -28),
+            header(b"VER ", 2),
+            vec![59, 0],
+        ]
+        .concat();
+
+        for mode in [ParseMode::Lenient, ParseMode::GameAccurate] {
+            let (chunks, skipped) = parse_riff_with_mode(chk.as_slice(), mode);
+
+            let offsets: Vec<_> = chunks.iter().map(|x| x.offset).collect();
+            assert_eq!(offsets, vec![0, 8, 18, 36]);
+            assert_eq!(skipped.len(), 1);
+            assert_eq!(skipped[0].offset, 28);
+            assert_eq!(skipped[0].reason, SkipReason::NegativeSize);
+
+            let parsed_chk = ParsedChk::from_bytes_with_mode(chk.as_slice(), mode);
+            assert_eq!(*parsed_chk.ver.unwrap().file_format_version, 59);
+        }
+    }
+
+    #[test]
+    fn jump_to_previous_header_terminates() {
+        // The trailing byte keeps Lenient from stopping before the JUMP header.
+        let chk = [
+            header(b"VER ", 2),
+            vec![205, 0],
+            header(b"JUMP", -18),
+            vec![0],
+        ]
+        .concat();
+
+        for mode in [ParseMode::Lenient, ParseMode::GameAccurate] {
+            let (chunks, skipped) = parse_riff_with_mode(chk.as_slice(), mode);
+
+            assert_eq!(chunks.len(), 1);
+            assert_eq!(
+                skipped.iter().map(|x| x.reason).collect::<Vec<_>>(),
+                vec![SkipReason::NegativeSize, SkipReason::Loop]
+            );
+        }
+
+        // A size of -8 points a chunk at its own header.
+        let chk = header(b"JUMP", -8);
+        assert_eq!(
+            parse_riff_with_mode(chk.as_slice(), ParseMode::GameAccurate)
+                .1
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn game_accurate_truncates_chunks_past_end_of_file() {
+        let chk = [
+            header(b"VER ", 2),
+            vec![205, 0],
+            header(b"STR ", 100),
+            vec![1, 0, 2, 0],
+        ]
+        .concat();
+
+        let lenient = ParsedChk::from_bytes_with_mode(chk.as_slice(), ParseMode::Lenient);
+        assert!(lenient.str.is_err());
+
+        let game = ParsedChk::from_bytes_with_mode(chk.as_slice(), ParseMode::GameAccurate);
+        assert_eq!(game.str.unwrap().string_data, vec![1, 0, 2, 0]);
+    }
+
+    #[test]
+    fn game_accurate_reads_empty_chunk_at_end_of_file() {
+        let chk = [header(b"VER ", 2), vec![205, 0], header(b"UNIT", 0)].concat();
+
+        assert_eq!(parse_riff(chk.as_slice()).len(), 1);
+
+        let game = ParsedChk::from_bytes_with_mode(chk.as_slice(), ParseMode::GameAccurate);
+        assert_eq!(game.unit.unwrap().units.len(), 0);
+    }
+
+    #[test]
+    fn game_accurate_fits_mtxm_to_map_size() {
+        let dim = [header(b"DIM ", 4), vec![2, 0, 2, 0]].concat();
+        let short = [dim.clone(), header(b"MTXM", 6), vec![1, 0, 2, 0, 3, 0]].concat();
+        let long = [dim, header(b"MTXM", 10), vec![1, 0, 2, 0, 3, 0, 4, 0, 5, 0]].concat();
+
+        let lenient = ParsedChk::from_bytes_with_mode(short.as_slice(), ParseMode::Lenient);
+        assert_eq!(lenient.mtxm.unwrap().data, vec![1, 2, 3]);
+
+        let game = ParsedChk::from_bytes_with_mode(short.as_slice(), ParseMode::GameAccurate);
+        assert_eq!(game.mtxm.unwrap().data, vec![1, 2, 3, 0]);
+
+        let game = ParsedChk::from_bytes_with_mode(long.as_slice(), ParseMode::GameAccurate);
+        assert_eq!(game.mtxm.unwrap().data, vec![1, 2, 3, 4]);
+    }
+
     #[tokio::test]
     async fn test_parse_riff() {
         let stream = get_all_test_chks();
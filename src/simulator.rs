@@ -0,0 +1,1239 @@
+use crate::{
+    chk::chk_mrgn::ChkMrgnIndividual,
+    eud::DEATH_TABLE,
+    trig::{serialize_group, serialize_unit_type},
+    Action, ActionState, AllianceStatus, Condition, Group, LocationId, NumberModifier,
+    NumericComparison, ParsedChk, ResourceType, ScoreType, SwitchState, Trigger, UnitType,
+};
+use serde::Serialize;
+use tracing::instrument;
+
+// Game time between two trigger cycles, the game checks triggers every 2 seconds.
+pub const TRIGGER_CYCLE_MILLISECONDS: u32 = 2000;
+
+// A unit the simulator knows about, positions are in pixels.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct SimulatedUnit {
+    // 0 based.
+    pub player: usize,
+    pub unit_id: u16,
+    pub x: u32,
+    pub y: u32,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerStatus {
+    Playing,
+    Victory,
+    Defeat,
+    Draw,
+}
+
+// Everything conditions read and actions write. Scripts can change it between cycles, e.g. to move units or
+// add kills the simulator does not model. Player arrays are indexed by the 0 based player.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct SimulationState {
+    // Milliseconds of game time since the start.
+    pub time: u32,
+    // Seconds, None until a trigger sets it.
+    pub countdown_timer: Option<u32>,
+    pub countdown_timer_paused: bool,
+    pub switches: Vec<bool>,
+    // Indexed by unit id.
+    pub deaths: Vec<[u32; 12]>,
+    pub kills: Vec<[u32; 12]>,
+    pub ore: [u32; 12],
+    pub gas: [u32; 12],
+    pub unit_score: [u32; 12],
+    pub building_score: [u32; 12],
+    pub kill_score: [u32; 12],
+    pub razing_score: [u32; 12],
+    pub custom_score: [u32; 12],
+    // alliances[a][b] is true if a is allied to b.
+    pub alliances: [[bool; 12]; 12],
+    pub status: [PlayerStatus; 12],
+    pub units: Vec<SimulatedUnit>,
+    pub locations: Vec<ChkMrgnIndividual>,
+    pub seed: u32,
+}
+
+// An action that ran. simulated is false if the action changes something the simulator does not model, like
+// unit orders or EUD memory.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct FiredAction {
+    pub trigger: usize,
+    pub player: usize,
+    // Index of the action in the trigger.
+    pub index: usize,
+    pub action: Action,
+    pub simulated: bool,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct CycleReport {
+    // Game time in milliseconds when the cycle ran.
+    pub time: u32,
+    pub fired: Vec<FiredAction>,
+}
+
+// Where a player continues after a Wait, checked at the start of each cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Resume {
+    trigger: usize,
+    action: usize,
+    until: u32,
+    preserved: bool,
+}
+
+enum Flow {
+    Continue,
+    Wait(u32),
+}
+
+// Runs the triggers of a map without the game. Players run their triggers in order, a trigger that fires
+// without a Preserve Trigger action does not run for that player again and a Wait holds up all triggers of the
+// player until it is over.
+//
+// Waits are only checked once per trigger cycle, so a Wait always lasts a multiple of 2 seconds. The game
+// checks them every frame, which is what hyper triggers rely on to run triggers more often than once a cycle,
+// so maps that use them fire their triggers far less often here than in the game.
+#[derive(Debug, Clone)]
+pub struct TriggerSimulator {
+    pub state: SimulationState,
+    triggers: Vec<Trigger>,
+    // Players that run triggers, human and computer slots of player 1 to 8.
+    players: Vec<usize>,
+    // 0 based force of players 1 to 8.
+    forces: [usize; 8],
+    neutral_players: Vec<usize>,
+    // finished[trigger][player]
+    finished: Vec<[bool; 8]>,
+    resume: [Option<Resume>; 8],
+}
+
+impl TriggerSimulator {
+    #[instrument(skip_all)]
+    pub fn new(parsed_chk: &ParsedChk) -> TriggerSimulator {
+        TriggerSimulator::with_triggers(parsed_chk, crate::parse_triggers(parsed_chk))
+    }
+
+    // Units are the placed units of the map except start locations, every force with allied set starts out
+    // allied.
+    #[instrument(skip_all)]
+    pub fn with_triggers(parsed_chk: &ParsedChk, triggers: Vec<Trigger>) -> TriggerSimulator {
+        let owners = parsed_chk
+            .ownr
+            .as_ref()
+            .map(|x| x.player_owner)
+            .unwrap_or([6; 12]);
+
+        // 5 is a computer and 6 a human slot.
+        let players = (0..8)
+            .filter(|&x| owners[x] == 5 || owners[x] == 6)
+            .collect::<Vec<_>>();
+        let neutral_players = (0..12).filter(|&x| owners[x] == 7).collect::<Vec<_>>();
+
+        let mut forces = [0; 8];
+        let mut alliances = [[false; 12]; 12];
+        for (player, row) in alliances.iter_mut().enumerate() {
+            row[player] = true;
+        }
+
+        if let Ok(forc) = &parsed_chk.forc {
+            for (player, &force) in forc.player_forces.iter().enumerate() {
+                forces[player] = force as usize;
+            }
+
+            for a in 0..8 {
+                for b in 0..8 {
+                    let allied = forc
+                        .force_properties
+                        .get(forces[a])
+                        .is_some_and(|x| x & 0x02 != 0);
+                    if forces[a] == forces[b] && allied {
+                        alliances[a][b] = true;
+                    }
+                }
+            }
+        }
+
+        let units = parsed_chk
+            .unit
+            .as_ref()
+            .map(|x| {
+                x.units
+                    .iter()
+                    .filter(|x| x.owner < 12 && x.unit_id != 214)
+                    .map(|x| SimulatedUnit {
+                        player: x.owner as usize,
+                        unit_id: x.unit_id,
+                        x: x.x as u32,
+                        y: x.y as u32,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let locations = parsed_chk
+            .mrgn
+            .as_ref()
+            .map(|x| x.locations.to_vec())
+            .unwrap_or_default();
+
+        TriggerSimulator {
+            state: SimulationState {
+                time: 0,
+                countdown_timer: None,
+                countdown_timer_paused: false,
+                switches: vec![false; 256],
+                deaths: vec![[0; 12]; 228],
+                kills: vec![[0; 12]; 228],
+                ore: [0; 12],
+                gas: [0; 12],
+                unit_score: [0; 12],
+                building_score: [0; 12],
+                kill_score: [0; 12],
+                razing_score: [0; 12],
+                custom_score: [0; 12],
+                alliances,
+                status: [PlayerStatus::Playing; 12],
+                units,
+                locations,
+                seed: 0,
+            },
+            finished: vec![[false; 8]; triggers.len()],
+            triggers,
+            players,
+            forces,
+            neutral_players,
+            resume: [None; 8],
+        }
+    }
+
+    pub fn triggers(&self) -> &[Trigger] {
+        &self.triggers
+    }
+
+    // Whether the trigger still runs for the player, i.e. it has not fired without being preserved.
+    pub fn is_active(&self, trigger: usize, player: usize) -> bool {
+        self.finished
+            .get(trigger)
+            .and_then(|x| x.get(player))
+            .is_some_and(|x| !x)
+    }
+
+    // Runs one trigger cycle for every player still playing, then advances the clocks.
+    #[instrument(level = "trace", skip_all)]
+    pub fn step(&mut self) -> CycleReport {
+        let mut report = CycleReport {
+            time: self.state.time,
+            fired: Vec::new(),
+        };
+
+        for player in self.players.clone() {
+            if self.state.status[player] == PlayerStatus::Playing {
+                self.run_player(player, &mut report);
+            }
+        }
+
+        self.state.time = self.state.time.saturating_add(TRIGGER_CYCLE_MILLISECONDS);
+        if !self.state.countdown_timer_paused {
+            self.state.countdown_timer = self
+                .state
+                .countdown_timer
+                .map(|x| x.saturating_sub(TRIGGER_CYCLE_MILLISECONDS / 1000));
+        }
+
+        report
+    }
+
+    pub fn run(&mut self, cycles: usize) -> Vec<CycleReport> {
+        (0..cycles).map(|_| self.step()).collect()
+    }
+
+    fn runs_for(&self, trigger: usize, player: usize) -> bool {
+        let executed_for_player = &self.triggers[trigger].activated_for_players;
+        let force = self.forces[player];
+
+        executed_for_player[player] != 0
+            || executed_for_player[17] != 0
+            || (force < 4 && executed_for_player[18 + force] != 0)
+    }
+
+    fn run_player(&mut self, player: usize, report: &mut CycleReport) {
+        let mut start = 0;
+
+        if let Some(resume) = self.resume[player] {
+            if self.state.time < resume.until {
+                return;
+            }
+
+            self.resume[player] = None;
+            if !self.run_actions(
+                player,
+                resume.trigger,
+                resume.action,
+                resume.preserved,
+                report,
+            ) {
+                return;
+            }
+            start = resume.trigger + 1;
+        }
+
+        for trigger in start..self.triggers.len() {
+            if !self.runs_for(trigger, player) || self.finished[trigger][player] {
+                continue;
+            }
+
+            let conditions_hold = self.triggers[trigger]
+                .conditions
+                .iter()
+                .filter(|x| !x.flags.disabled)
+                .all(|x| self.condition(player, &x.condition));
+
+            if conditions_hold && !self.run_actions(player, trigger, 0, false, report) {
+                return;
+            }
+
+            if self.state.status[player] != PlayerStatus::Playing {
+                return;
+            }
+        }
+    }
+
+    // Returns false if the player waits.
+    fn run_actions(
+        &mut self,
+        player: usize,
+        trigger: usize,
+        start: usize,
+        mut preserved: bool,
+        report: &mut CycleReport,
+    ) -> bool {
+        let actions = self.triggers[trigger]
+            .actions
+            .iter()
+            .filter(|x| x.index >= start && !x.flags.disabled)
+            .map(|x| (x.index, x.action.clone()))
+            .collect::<Vec<_>>();
+
+        for (index, action) in actions {
+            preserved |= action == Action::PreserveTrigger;
+            let (flow, simulated) = self.action(player, &action);
+
+            report.fired.push(FiredAction {
+                trigger,
+                player,
+                index,
+                action,
+                simulated,
+            });
+
+            if let Flow::Wait(time) = flow {
+                self.resume[player] = Some(Resume {
+                    trigger,
+                    action: index + 1,
+                    until: self.state.time.saturating_add(time),
+                    preserved,
+                });
+                return false;
+            }
+        }
+
+        if !preserved {
+            self.finished[trigger][player] = true;
+        }
+
+        true
+    }
+
+    // The 0 based players of a group from the point of view of the current player.
+    fn players_of(&self, current: usize, group: &Group) -> Vec<usize> {
+        let players = self.players.iter().copied();
+
+        match group {
+            Group::CurrentPlayer => vec![current],
+            Group::Foes => players
+                .filter(|&x| x != current && !self.state.alliances[current][x])
+                .collect(),
+            Group::Allies => players
+                .filter(|&x| x != current && self.state.alliances[current][x])
+                .collect(),
+            Group::NeutralPlayers => self.neutral_players.clone(),
+            Group::AllPlayers => players.collect(),
+            Group::Force1 | Group::Force2 | Group::Force3 | Group::Force4 => {
+                let force = serialize_group(group) as usize - 18;
+                players.filter(|&x| self.forces[x] == force).collect()
+            }
+            _ => match serialize_group(group) {
+                x if x < 12 => vec![x as usize],
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    fn in_location(&self, unit: &SimulatedUnit, location: LocationId) -> bool {
+        let Some(x) = (location.0 as usize)
+            .checked_sub(1)
+            .and_then(|x| self.state.locations.get(x))
+        else {
+            return false;
+        };
+
+        // Locations can be flipped, the edges are inclusive.
+        (x.left.min(x.right)..=x.left.max(x.right)).contains(&unit.x)
+            && (x.top.min(x.bottom)..=x.top.max(x.bottom)).contains(&unit.y)
+    }
+
+    fn location_center(&self, location: LocationId) -> Option<(u32, u32)> {
+        let x = self
+            .state
+            .locations
+            .get((location.0 as usize).checked_sub(1)?)?;
+
+        // MRGN coordinates are untrusted, so average without adding them up first.
+        let middle = |a: u32, b: u32| a / 2 + b / 2 + (a & b & 1);
+
+        Some((middle(x.left, x.right), middle(x.top, x.bottom)))
+    }
+
+    fn count_units(
+        &self,
+        players: &[usize],
+        unit_type: &UnitType,
+        location: Option<LocationId>,
+    ) -> u32 {
+        self.state
+            .units
+            .iter()
+            .filter(|x| players.contains(&x.player) && is_unit_of_type(x.unit_id, unit_type))
+            .filter(|x| location.is_none_or(|location| self.in_location(x, location)))
+            .count() as u32
+    }
+
+    fn score(&self, player: usize, score_type: &ScoreType) -> Option<u32> {
+        let state = &self.state;
+
+        Some(match score_type {
+            ScoreType::Total => state.unit_score[player]
+                .wrapping_add(state.building_score[player])
+                .wrapping_add(state.kill_score[player])
+                .wrapping_add(state.razing_score[player]),
+            ScoreType::Units => state.unit_score[player],
+            ScoreType::Buildings => state.building_score[player],
+            ScoreType::UnitsAndBuildings => {
+                state.unit_score[player].wrapping_add(state.building_score[player])
+            }
+            ScoreType::Kills => state.kill_score[player],
+            ScoreType::Razings => state.razing_score[player],
+            ScoreType::KillsAndRazings => {
+                state.kill_score[player].wrapping_add(state.razing_score[player])
+            }
+            ScoreType::Custom => state.custom_score[player],
+            ScoreType::Unknown(_) => return None,
+        })
+    }
+
+    fn resources(&self, player: usize, resource_type: &ResourceType) -> Option<u32> {
+        Some(match resource_type {
+            ResourceType::Ore => self.state.ore[player],
+            ResourceType::Gas => self.state.gas[player],
+            ResourceType::OreAndGas => self.state.ore[player].wrapping_add(self.state.gas[player]),
+            ResourceType::Unknown(_) => return None,
+        })
+    }
+
+    // Whether the current player has the most, or the least, of something among the players still playing.
+    // Ties count for every tied player.
+    fn extreme(&self, current: usize, most: bool, value: impl Fn(usize) -> Option<u32>) -> bool {
+        let Some(own) = value(current) else {
+            return false;
+        };
+
+        self.players
+            .iter()
+            .filter(|&&x| self.state.status[x] == PlayerStatus::Playing)
+            .filter_map(|&x| value(x))
+            .all(|x| if most { own >= x } else { own <= x })
+    }
+
+    fn death_counter(&self, player: usize, unit_type: &UnitType) -> Option<u32> {
+        self.state
+            .deaths
+            .get(serialize_unit_type(unit_type) as usize)
+            .map(|x| x[player])
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    fn condition(&self, current: usize, condition: &Condition) -> bool {
+        match condition {
+            Condition::NoCondition => true,
+            Condition::CountdownTimer { comparison, number } => self
+                .state
+                .countdown_timer
+                .is_some_and(|x| compare(x, comparison, *number)),
+            Condition::Command {
+                player,
+                comparison,
+                unit_type,
+                number,
+            } => {
+                let players = self.players_of(current, player);
+                compare(
+                    self.count_units(&players, unit_type, None),
+                    comparison,
+                    *number,
+                )
+            }
+            Condition::Bring {
+                player,
+                comparison,
+                unit_type,
+                location,
+                number,
+            } => {
+                let players = self.players_of(current, player);
+                compare(
+                    self.count_units(&players, unit_type, Some(*location)),
+                    comparison,
+                    *number,
+                )
+            }
+            Condition::Accumulate {
+                player,
+                comparison,
+                number,
+                resource_type,
+                ..
+            } => self
+                .players_of(current, player)
+                .iter()
+                .map(|&x| self.resources(x, resource_type))
+                .sum::<Option<u32>>()
+                .is_some_and(|x| compare(x, comparison, *number)),
+            Condition::Kill {
+                player,
+                comparison,
+                unit_type,
+                number,
+            } => {
+                let unit = serialize_unit_type(unit_type) as usize;
+                let kills = self
+                    .players_of(current, player)
+                    .iter()
+                    .filter_map(|&x| self.state.kills.get(unit).map(|kills| kills[x]))
+                    .fold(0u32, |a, b| a.wrapping_add(b));
+                compare(kills, comparison, *number)
+            }
+            Condition::CommandsTheMost { unit_type } => self.extreme(current, true, |x| {
+                Some(self.count_units(&[x], unit_type, None))
+            }),
+            Condition::CommandsTheMostAt {
+                unit_type,
+                location,
+            } => self.extreme(current, true, |x| {
+                Some(self.count_units(&[x], unit_type, Some(*location)))
+            }),
+            Condition::MostKills { unit_type } => self.extreme(current, true, |x| {
+                self.state
+                    .kills
+                    .get(serialize_unit_type(unit_type) as usize)
+                    .map(|kills| kills[x])
+            }),
+            Condition::HighestScore { score_type } => {
+                self.extreme(current, true, |x| self.score(x, score_type))
+            }
+            Condition::MostResources { resource_type } => {
+                self.extreme(current, true, |x| self.resources(x, resource_type))
+            }
+            Condition::Switch {
+                switch,
+                switch_state,
+            } => {
+                let value = self.state.switches.get(*switch as usize).copied();
+                match switch_state {
+                    SwitchState::Set => value == Some(true),
+                    SwitchState::Cleared => value == Some(false),
+                    SwitchState::Unknown(_) => false,
+                }
+            }
+            Condition::ElapsedTime { comparison, number } => {
+                compare(self.state.time / 1000, comparison, *number)
+            }
+            Condition::DataIsAMissionBriefing => false,
+            Condition::Opponents {
+                player,
+                comparison,
+                number,
+            } => self.players_of(current, player).iter().all(|&player| {
+                let opponents = self
+                    .players
+                    .iter()
+                    .filter(|&&x| {
+                        x != player
+                            && !self.state.alliances[player][x]
+                            && self.state.status[x] == PlayerStatus::Playing
+                    })
+                    .count() as u32;
+                compare(opponents, comparison, *number)
+            }),
+            // EUD conditions outside the death table are never true.
+            Condition::Deaths {
+                player,
+                comparison,
+                unit_type,
+                number,
+                mask,
+                eud_offset,
+            } => {
+                let deaths = match player {
+                    Group::Unknown(_) => {
+                        let index = eud_offset.wrapping_sub(DEATH_TABLE) / 4;
+                        self.state
+                            .deaths
+                            .get(index as usize / 12)
+                            .map(|x| x[index as usize % 12])
+                    }
+                    _ => self
+                        .players_of(current, player)
+                        .iter()
+                        .map(|&x| self.death_counter(x, unit_type))
+                        .try_fold(0u32, |a, b| Some(a.wrapping_add(b?))),
+                };
+
+                deaths.is_some_and(|x| compare(x & mask.unwrap_or(u32::MAX), comparison, *number))
+            }
+            Condition::CommandsTheLeast { unit_type } => self.extreme(current, false, |x| {
+                Some(self.count_units(&[x], unit_type, None))
+            }),
+            Condition::CommandsTheLeastAt {
+                unit_type,
+                location,
+            } => self.extreme(current, false, |x| {
+                Some(self.count_units(&[x], unit_type, Some(*location)))
+            }),
+            Condition::LeastKills { unit_type } => self.extreme(current, false, |x| {
+                self.state
+                    .kills
+                    .get(serialize_unit_type(unit_type) as usize)
+                    .map(|kills| kills[x])
+            }),
+            Condition::LowestScore { score_type } => {
+                self.extreme(current, false, |x| self.score(x, score_type))
+            }
+            Condition::LeastResources { resource_type } => {
+                self.extreme(current, false, |x| self.resources(x, resource_type))
+            }
+            Condition::Score {
+                player,
+                comparison,
+                score_type,
+                number,
+            } => self
+                .players_of(current, player)
+                .iter()
+                .map(|&x| self.score(x, score_type))
+                .try_fold(0u32, |a, b| Some(a.wrapping_add(b?)))
+                .is_some_and(|x| compare(x, comparison, *number)),
+            Condition::Always => true,
+            Condition::Never => false,
+            Condition::Unknown { .. } => false,
+        }
+    }
+
+    // The game's linear congruential generator.
+    fn random(&mut self) -> u32 {
+        self.state.seed = self.state.seed.wrapping_mul(0x015A4E35).wrapping_add(1);
+        (self.state.seed >> 16) & 0x7FFF
+    }
+
+    fn remove_units(
+        &mut self,
+        players: &[usize],
+        unit_type: &UnitType,
+        number: i64,
+        location: Option<LocationId>,
+    ) -> Vec<SimulatedUnit> {
+        let mut removed = Vec::new();
+        let mut kept = Vec::new();
+
+        for x in std::mem::take(&mut self.state.units) {
+            let matches = players.contains(&x.player)
+                && is_unit_of_type(x.unit_id, unit_type)
+                && location.is_none_or(|location| self.in_location(&x, location));
+
+            // 0 means all units.
+            if matches && (number == 0 || (removed.len() as i64) < number) {
+                removed.push(x);
+            } else {
+                kept.push(x);
+            }
+        }
+
+        self.state.units = kept;
+        removed
+    }
+
+    fn kill_units(
+        &mut self,
+        players: &[usize],
+        unit_type: &UnitType,
+        number: i64,
+        location: Option<LocationId>,
+    ) {
+        for x in self.remove_units(players, unit_type, number, location) {
+            if let Some(deaths) = self.state.deaths.get_mut(x.unit_id as usize) {
+                deaths[x.player] = deaths[x.player].wrapping_add(1);
+            }
+        }
+    }
+
+    // Returns whether to wait and whether the effect is modelled.
+    #[instrument(level = "trace", skip_all)]
+    fn action(&mut self, current: usize, action: &Action) -> (Flow, bool) {
+        let mut simulated = true;
+
+        match action {
+            Action::Victory => self.state.status[current] = PlayerStatus::Victory,
+            Action::Defeat => self.state.status[current] = PlayerStatus::Defeat,
+            Action::Draw => {
+                for &x in &self.players {
+                    self.state.status[x] = PlayerStatus::Draw;
+                }
+            }
+            Action::Wait { time } => return (Flow::Wait(*time as u32), true),
+            Action::CreateUnit {
+                player,
+                unit_type,
+                number,
+                location,
+            }
+            | Action::CreateUnitWithProperties {
+                player,
+                unit_type,
+                number,
+                location,
+                ..
+            } => {
+                let unit_id = serialize_unit_type(unit_type);
+                match self.location_center(*location) {
+                    Some((x, y)) if unit_id < 228 => {
+                        for player in self.players_of(current, player) {
+                            for _ in 0..*number {
+                                self.state.units.push(SimulatedUnit {
+                                    player,
+                                    unit_id,
+                                    x,
+                                    y,
+                                });
+                            }
+                        }
+                    }
+                    _ => simulated = false,
+                }
+            }
+            Action::SetSwitch {
+                switch,
+                switch_action,
+            } => {
+                let random = self.random() & 1 == 1;
+                match self.state.switches.get_mut(*switch as usize) {
+                    Some(x) => match switch_action {
+                        ActionState::EnabledOrSet => *x = true,
+                        ActionState::DisabledOrClear => *x = false,
+                        ActionState::ToggleOrToggle => *x = !*x,
+                        ActionState::RandomizeSwitch => *x = random,
+                        ActionState::Unknown(_) => simulated = false,
+                    },
+                    None => simulated = false,
+                }
+            }
+            Action::SetCountdownTimer { time, modifier } => {
+                let timer = self.state.countdown_timer.unwrap_or(0);
+                match modify(timer, modifier, *time, false) {
+                    Some(x) => self.state.countdown_timer = Some(x),
+                    None => simulated = false,
+                }
+            }
+            Action::KillAllUnits { player, unit_type } => {
+                let players = self.players_of(current, player);
+                self.kill_units(&players, unit_type, 0, None);
+            }
+            Action::KillUnitAtLocation {
+                player,
+                unit_type,
+                number,
+                location,
+            } => {
+                let players = self.players_of(current, player);
+                self.kill_units(&players, unit_type, *number, Some(*location));
+            }
+            Action::RemoveAllUnits { player, unit_type } => {
+                let players = self.players_of(current, player);
+                self.remove_units(&players, unit_type, 0, None);
+            }
+            Action::RemoveUnitAtLocation {
+                player,
+                unit_type,
+                number,
+                location,
+            } => {
+                let players = self.players_of(current, player);
+                self.remove_units(&players, unit_type, *number, Some(*location));
+            }
+            Action::SetResources {
+                player,
+                number,
+                modifier,
+                resource_type,
+            } => {
+                let resources: &[usize] = match resource_type {
+                    ResourceType::Ore => &[0],
+                    ResourceType::Gas => &[1],
+                    ResourceType::OreAndGas => &[0, 1],
+                    ResourceType::Unknown(_) => {
+                        return (Flow::Continue, false);
+                    }
+                };
+
+                for player in self.players_of(current, player) {
+                    for &x in resources {
+                        let target = if x == 0 {
+                            &mut self.state.ore[player]
+                        } else {
+                            &mut self.state.gas[player]
+                        };
+
+                        match modify(*target, modifier, *number, false) {
+                            Some(x) => *target = x,
+                            None => simulated = false,
+                        }
+                    }
+                }
+            }
+            Action::SetScore {
+                player,
+                number,
+                modifier,
+                score_type,
+            } => {
+                for player in self.players_of(current, player) {
+                    let state = &mut self.state;
+                    let target = match score_type {
+                        ScoreType::Units => &mut state.unit_score[player],
+                        ScoreType::Buildings => &mut state.building_score[player],
+                        ScoreType::Kills => &mut state.kill_score[player],
+                        ScoreType::Razings => &mut state.razing_score[player],
+                        ScoreType::Custom => &mut state.custom_score[player],
+                        // Sums of other scores.
+                        _ => {
+                            simulated = false;
+                            continue;
+                        }
+                    };
+
+                    match modify(*target, modifier, *number, false) {
+                        Some(x) => *target = x,
+                        None => simulated = false,
+                    }
+                }
+            }
+            Action::MoveLocation {
+                player,
+                unit_type,
+                source_location,
+                destination_location,
+            } => {
+                let players = self.players_of(current, player);
+                let unit = self.state.units.iter().find(|x| {
+                    players.contains(&x.player)
+                        && is_unit_of_type(x.unit_id, unit_type)
+                        && self.in_location(x, *source_location)
+                });
+
+                if let (Some(&unit), Some(location)) = (
+                    unit,
+                    (destination_location.0 as usize)
+                        .checked_sub(1)
+                        .and_then(|x| self.state.locations.get_mut(x)),
+                ) {
+                    let half_width = location.right.abs_diff(location.left) / 2;
+                    let half_height = location.bottom.abs_diff(location.top) / 2;
+                    location.left = unit.x.saturating_sub(half_width);
+                    location.right = unit.x + half_width;
+                    location.top = unit.y.saturating_sub(half_height);
+                    location.bottom = unit.y + half_height;
+                }
+            }
+            Action::MoveUnit {
+                player,
+                unit_type,
+                number,
+                source_location,
+                destination_location,
+            } => {
+                let players = self.players_of(current, player);
+                let moved = self.remove_units(&players, unit_type, *number, Some(*source_location));
+                let center = self.location_center(*destination_location);
+
+                for mut x in moved {
+                    if let Some((center_x, center_y)) = center {
+                        (x.x, x.y) = (center_x, center_y);
+                    }
+                    self.state.units.push(x);
+                }
+            }
+            Action::SetDeaths {
+                player,
+                unit_type,
+                number,
+                modifier,
+                mask,
+                ..
+            } => {
+                let unit = serialize_unit_type(unit_type) as usize;
+                let players = self.players_of(current, player);
+
+                if unit >= self.state.deaths.len() || players.is_empty() {
+                    simulated = false;
+                }
+
+                for player in players {
+                    let Some(deaths) = self.state.deaths.get_mut(unit) else {
+                        break;
+                    };
+
+                    let mask = mask.unwrap_or(u32::MAX);
+                    match modify(deaths[player] & mask, modifier, *number, true) {
+                        Some(x) => deaths[player] = deaths[player] & !mask | x & mask,
+                        None => simulated = false,
+                    }
+                }
+            }
+            Action::GiveUnitsToPlayer {
+                source_player,
+                destination_player,
+                unit_type,
+                number,
+                location,
+            } => {
+                let players = self.players_of(current, source_player);
+                match self.players_of(current, destination_player)[..] {
+                    [destination] => {
+                        for mut x in
+                            self.remove_units(&players, unit_type, *number, Some(*location))
+                        {
+                            x.player = destination;
+                            self.state.units.push(x);
+                        }
+                    }
+                    _ => simulated = false,
+                }
+            }
+            Action::PauseTimer => self.state.countdown_timer_paused = true,
+            Action::UnpauseTimer => self.state.countdown_timer_paused = false,
+            Action::SetAllianceStatus {
+                player,
+                alliance_status,
+            } => {
+                let allied = match alliance_status {
+                    AllianceStatus::Enemy => false,
+                    AllianceStatus::Allied | AllianceStatus::AlliedVictory => true,
+                    AllianceStatus::Unknown(_) => {
+                        return (Flow::Continue, false);
+                    }
+                };
+                for x in self.players_of(current, player) {
+                    if x != current {
+                        self.state.alliances[current][x] = allied;
+                    }
+                }
+            }
+            // Only change what the players see and hear.
+            Action::PreserveTrigger
+            | Action::PauseGame
+            | Action::UnpauseGame
+            | Action::Transmission { .. }
+            | Action::PlayWav { .. }
+            | Action::DisplayTextMessage { .. }
+            | Action::CenterView { .. }
+            | Action::SetMissionObjectives { .. }
+            | Action::LeaderBoardControl { .. }
+            | Action::LeaderBoardControlAtLocation { .. }
+            | Action::LeaderBoardResources { .. }
+            | Action::LeaderBoardKills { .. }
+            | Action::LeaderBoardPoints { .. }
+            | Action::MinimapPing { .. }
+            | Action::TalkingPortrait { .. }
+            | Action::MuteUnitSpeech
+            | Action::UnmuteUnitSpeech
+            | Action::LeaderBoardComputerPlayers { .. }
+            | Action::LeaderBoardGoalControl { .. }
+            | Action::LeaderBoardGoalControlAtLocation { .. }
+            | Action::LeaderBoardGoalResources { .. }
+            | Action::LeaderBoardGoalKills { .. }
+            | Action::LeaderBoardGoalPoints { .. }
+            | Action::LeaderboardGreed { .. }
+            | Action::SetNextScenario { .. }
+            | Action::Comment { .. }
+            | Action::DisableDebugMode
+            | Action::EnableDebugMode => {}
+            Action::NoAction
+            | Action::Unknown { .. }
+            | Action::RunAIScript { .. }
+            | Action::RunAIScriptAtLocation { .. }
+            | Action::SetDoodadState { .. }
+            | Action::SetInvincibility { .. }
+            | Action::Order { .. }
+            | Action::ModifyUnitHitPoints { .. }
+            | Action::ModifyUnitEnergy { .. }
+            | Action::ModifyUnitShieldPoints { .. }
+            | Action::ModifyUnitResource { .. }
+            | Action::ModifyUnitHangarCount { .. } => simulated = false,
+        }
+
+        (Flow::Continue, simulated)
+    }
+}
+
+// Buildings are unit 106 to 201, everything before them is a man.
+fn is_unit_of_type(unit_id: u16, unit_type: &UnitType) -> bool {
+    match unit_type {
+        UnitType::AnyUnit => true,
+        UnitType::Men => unit_id < 106,
+        UnitType::Buildings => (106..=201).contains(&unit_id),
+        // Barracks, Factory, Starport, Hatchery, Lair, Hive, Robotics Facility, Gateway and Stargate.
+        UnitType::Factories => [111, 113, 114, 131, 132, 133, 155, 160, 167].contains(&unit_id),
+        _ => serialize_unit_type(unit_type) == unit_id,
+    }
+}
+
+fn compare(value: u32, comparison: &NumericComparison, number: i64) -> bool {
+    let number = number as u32;
+
+    match comparison {
+        NumericComparison::AtLeast => value >= number,
+        NumericComparison::AtMost => value <= number,
+        NumericComparison::Exactly => value == number,
+        NumericComparison::Unknown(_) => false,
+    }
+}
+
+// Death counts wrap around, everything else stops at 0 and u32::MAX.
+fn modify(value: u32, modifier: &NumberModifier, number: i64, wrap: bool) -> Option<u32> {
+    let number = number as u32;
+
+    Some(match (modifier, wrap) {
+        (NumberModifier::SetTo, _) => number,
+        (NumberModifier::Add, true) => value.wrapping_add(number),
+        (NumberModifier::Add, false) => value.saturating_add(number),
+        (NumberModifier::Subtract, true) => value.wrapping_sub(number),
+        (NumberModifier::Subtract, false) => value.saturating_sub(number),
+        (NumberModifier::Unknown(_), _) => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{PlayerStatus, SimulatedUnit, TriggerSimulator};
+    use crate::{
+        chk::chk_mrgn::ChkMrgnIndividual, chunk_name::ChunkName, compile_trigedit, test::make_mrgn,
+        ChkWriter, LocationId, ParsedChk, TrigEditNames, Trigger,
+    };
+
+    fn make_chk() -> Vec<u8> {
+        // Location 1 covers the top left 256x256 pixels.
+        let mrgn = make_mrgn(&[ChkMrgnIndividual {
+            left: 0,
+            top: 0,
+            right: 256,
+            bottom: 256,
+            name_string_number: 0,
+            elevation_flags: 0,
+        }]);
+
+        // Two marines of player 1 in location 1.
+        let mut unit = vec![0u8; 2 * 36];
+        for x in unit.chunks_mut(36) {
+            x[4..6].copy_from_slice(&100u16.to_le_bytes());
+            x[6..8].copy_from_slice(&100u16.to_le_bytes());
+        }
+
+        let mut ownr = vec![0u8; 12];
+        ownr[0] = 6;
+        ownr[1] = 5;
+
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(ChunkName::MRGN, mrgn);
+        writer.add_chunk(ChunkName::UNIT, unit);
+        writer.add_chunk(ChunkName::OWNR, ownr);
+        writer.to_bytes()
+    }
+
+    #[test]
+    fn runs_trigger_cycles() {
+        let chk = make_chk();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+        let compiled = compile_trigedit(
+            r#"Trigger("Player 1"){
+Conditions:
+	Bring("Player 1", "Terran Marine", "Location 1", At least, 2);
+
+Actions:
+	Set Switch("Switch1", set);
+	Set Deaths("Current Player", "Terran Marine", Add, 1);
+	Preserve Trigger();
+}
+
+Trigger("All players"){
+Conditions:
+	Switch("Switch1", set);
+	Elapsed Time(At least, 2);
+
+Actions:
+	Wait(3000);
+	Set Resources("Current Player", Add, 50, ore);
+}
+
+Trigger("Player 1"){
+Conditions:
+	Deaths("Player 1", "Terran Marine", At least, 3);
+
+Actions:
+	Victory();
+}
+"#,
+            &TrigEditNames::new(&parsed_chk),
+        )
+        .unwrap();
+        let triggers = compiled
+            .triggers
+            .iter()
+            .map(|x| Trigger::from_raw(x, &[]))
+            .collect();
+
+        let mut simulator = TriggerSimulator::with_triggers(&parsed_chk, triggers);
+        let fired = |simulator: &mut TriggerSimulator| {
+            simulator
+                .step()
+                .fired
+                .iter()
+                .map(|x| (x.trigger, x.player, x.index))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(fired(&mut simulator), [(0, 0, 0), (0, 0, 1), (0, 0, 2)]);
+        assert!(simulator.state.switches[0]);
+
+        // Both players wait, so player 1 does not get to trigger 2.
+        assert_eq!(
+            fired(&mut simulator),
+            [(0, 0, 0), (0, 0, 1), (0, 0, 2), (1, 0, 0), (1, 1, 0)]
+        );
+        assert_eq!(fired(&mut simulator), []);
+        assert_eq!(fired(&mut simulator), [(1, 0, 1), (1, 1, 1)]);
+        assert_eq!(simulator.state.ore[..2], [50, 50]);
+        assert_eq!(simulator.state.deaths[0][0], 2);
+        assert!(!simulator.is_active(1, 0));
+
+        assert_eq!(
+            fired(&mut simulator),
+            [(0, 0, 0), (0, 0, 1), (0, 0, 2), (2, 0, 0)]
+        );
+        assert_eq!(simulator.state.status[0], PlayerStatus::Victory);
+        assert_eq!(fired(&mut simulator), []);
+    }
+
+    #[test]
+    fn units_can_be_scripted() {
+        let chk = make_chk();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+        let compiled = compile_trigedit(
+            r#"Trigger("Player 2"){
+Conditions:
+	Command("Player 2", "Zerg Zergling", Exactly, 1);
+	Bring("Foes", "Men", "Location 1", At most, 1);
+
+Actions:
+	Kill Unit At Location("Foes", "Terran Marine", 1, "Location 1");
+	Preserve Trigger();
+}
+"#,
+            &TrigEditNames::new(&parsed_chk),
+        )
+        .unwrap();
+        let triggers = compiled
+            .triggers
+            .iter()
+            .map(|x| Trigger::from_raw(x, &[]))
+            .collect();
+
+        let mut simulator = TriggerSimulator::with_triggers(&parsed_chk, triggers);
+        assert!(simulator.step().fired.is_empty());
+
+        simulator.state.units.pop();
+        simulator.state.units.push(SimulatedUnit {
+            player: 1,
+            unit_id: 37,
+            x: 500,
+            y: 500,
+        });
+
+        let report = simulator.step();
+        assert_eq!(report.time, 2000);
+        assert_eq!(report.fired.len(), 2);
+        assert!(report.fired.iter().all(|x| x.simulated));
+        assert_eq!(simulator.state.units.len(), 1);
+        assert_eq!(simulator.state.deaths[0][0], 1);
+    }
+
+    #[test]
+    fn players_in_invalid_forces_do_not_run_force_triggers() {
+        let chk = make_chk();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+        let compiled = compile_trigedit(
+            r#"Trigger("Force 4"){
+Conditions:
+	Always();
+
+Actions:
+	Victory();
+}
+"#,
+            &TrigEditNames::new(&parsed_chk),
+        )
+        .unwrap();
+        let triggers = compiled
+            .triggers
+            .iter()
+            .map(|x| Trigger::from_raw(x, &[]))
+            .collect();
+
+        let mut simulator = TriggerSimulator::with_triggers(&parsed_chk, triggers);
+        simulator.forces = [4, 4, 4, 4, 4, 4, 4, 4];
+        assert!(simulator.step().fired.is_empty());
+
+        simulator.forces[0] = 3;
+        assert_eq!(simulator.step().fired.len(), 1);
+        assert_eq!(simulator.state.status[0], PlayerStatus::Victory);
+    }
+
+    #[test]
+    fn location_center_does_not_overflow() {
+        let chk = make_chk();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+        let mut simulator = TriggerSimulator::with_triggers(&parsed_chk, Vec::new());
+
+        let location = &mut simulator.state.locations[0];
+        location.left = u32::MAX;
+        location.right = u32::MAX;
+        location.top = u32::MAX - 1;
+        location.bottom = 1;
+
+        assert_eq!(
+            simulator.location_center(LocationId(1)),
+            Some((u32::MAX, u32::MAX / 2))
+        );
+    }
+}
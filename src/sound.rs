@@ -0,0 +1,476 @@
+use crate::ParsedChk;
+use serde::Serialize;
+use tracing::instrument;
+
+// Where a sound is referenced from. Indices are 0 based.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub enum SoundSource {
+    WavSection { index: usize },
+    // A Transmission or PlayWav action.
+    Trigger { trigger: usize, action: usize },
+    // A DisplayTransmission or PlayWav mission briefing action.
+    MissionBriefing { trigger: usize, action: usize },
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct SoundReference {
+    pub string_number: u32,
+    // The path of the sound in the archive, undecoded because that is how Storm looks it up.
+    pub filename: Vec<u8>,
+    pub source: SoundSource,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum SoundFormat {
+    Wav,
+    Ogg,
+}
+
+impl SoundFormat {
+    pub fn detect(data: &[u8]) -> Option<SoundFormat> {
+        if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WAVE" {
+            Some(SoundFormat::Wav)
+        } else if data.starts_with(b"OggS") {
+            Some(SoundFormat::Ogg)
+        } else {
+            None
+        }
+    }
+
+    // The length of the sound. None if the headers needed to work it out are missing.
+    pub fn duration_ms(&self, data: &[u8]) -> Option<u32> {
+        match self {
+            SoundFormat::Wav => wav_duration_ms(data),
+            SoundFormat::Ogg => ogg_duration_ms(data),
+        }
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+// The length of the data chunk divided by the byte rate from the fmt chunk.
+fn wav_duration_ms(data: &[u8]) -> Option<u32> {
+    let mut byte_rate = None;
+    let mut offset = 12;
+
+    while offset + 8 <= data.len() {
+        let size = read_u32(data, offset + 4)? as usize;
+
+        match &data[offset..offset + 4] {
+            b"fmt " => byte_rate = read_u32(data, offset + 16),
+            b"data" => {
+                // Truncated files are common, only count the bytes that are actually there.
+                let size = size.min(data.len() - offset - 8) as u64;
+                let byte_rate = byte_rate.filter(|&x| x != 0)? as u64;
+
+                return u32::try_from(size * 1000 / byte_rate).ok();
+            }
+            _ => {}
+        }
+
+        // Chunks are padded to an even size.
+        offset = offset.checked_add(8 + size + (size & 1))?;
+    }
+
+    None
+}
+
+// The granule position of the last page divided by the sample rate from the Vorbis identification header.
+fn ogg_duration_ms(data: &[u8]) -> Option<u32> {
+    let segments = *data.get(26)? as usize;
+    let identification = data.get(27 + segments..)?;
+
+    if !identification.starts_with(b"\x01vorbis") {
+        return None;
+    }
+
+    let sample_rate = read_u32(identification, 12).filter(|&x| x != 0)? as u64;
+
+    let last_page = data.windows(4).rposition(|x| x == b"OggS")?;
+    let granule_position =
+        u64::from_le_bytes(data.get(last_page + 6..last_page + 14)?.try_into().ok()?);
+
+    u32::try_from(granule_position.checked_mul(1000)? / sample_rate).ok()
+}
+
+impl ParsedChk<'_> {
+    // Every reference to a sound in the WAV section, TRIG and MBRF, in that order. References whose string
+    // does not exist are left out.
+    #[instrument(level = "trace", skip_all)]
+    pub fn get_sound_references(&self) -> Vec<SoundReference> {
+        let mut references = Vec::new();
+
+        if let Ok(x) = &self.wav {
+            for (index, &string_number) in x.wav_string_number.iter().enumerate() {
+                references.push((string_number, SoundSource::WavSection { index }));
+            }
+        }
+
+        if let Ok(x) = &self.trig {
+            for (trigger, x) in x.triggers.iter().enumerate() {
+                for (action, x) in x.actions.iter().enumerate() {
+                    // Transmission, PlayWav.
+                    if x.action == 7 || x.action == 8 {
+                        references.push((
+                            x.wav_string_number,
+                            SoundSource::Trigger { trigger, action },
+                        ));
+                    }
+                }
+            }
+        }
+
+        if let Ok(x) = &self.mbrf {
+            for (trigger, x) in x.triggers.iter().enumerate() {
+                for (action, x) in x.actions.iter().enumerate() {
+                    // PlayWav, DisplayTransmission.
+                    if x.action == 2 || x.action == 8 {
+                        references.push((
+                            x.wav_string_number,
+                            SoundSource::MissionBriefing { trigger, action },
+                        ));
+                    }
+                }
+            }
+        }
+
+        references
+            .into_iter()
+            .filter_map(|(string_number, source)| {
+                Some(SoundReference {
+                    string_number,
+                    filename: self.get_string_bytes(string_number as usize)?.to_vec(),
+                    source,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "mpq")]
+mod archive {
+    use super::{SoundFormat, SoundSource};
+    use crate::{
+        mpq::{same_filename, Mpq, MpqError, SCENARIO_CHK},
+        ParsedChk,
+    };
+    use serde::Serialize;
+    use tracing::instrument;
+
+    #[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+    pub enum SoundStatus {
+        Found,
+        Missing,
+        // The file exists but could not be read, e.g. it uses an unknown compression.
+        Unreadable { reason: String },
+    }
+
+    #[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+    pub struct Sound {
+        pub filename: Vec<u8>,
+        pub references: Vec<SoundSource>,
+        pub status: SoundStatus,
+        pub format: Option<SoundFormat>,
+        pub duration_ms: Option<u32>,
+        #[serde(skip)]
+        pub data: Option<Vec<u8>>,
+    }
+
+    // The sounds a map references resolved against its archive.
+    #[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+    pub struct SoundReport {
+        // One entry per file, in the order they are first referenced.
+        pub sounds: Vec<Sound>,
+        // Files named in the (listfile) that nothing references. Archives without a (listfile) have no names
+        // to list, so this is empty for them.
+        pub unreferenced: Vec<Vec<u8>>,
+    }
+
+    impl SoundReport {
+        #[instrument(level = "trace", skip_all)]
+        pub fn from_archive(parsed_chk: &ParsedChk, archive: &Mpq) -> SoundReport {
+            let mut sounds: Vec<Sound> = Vec::new();
+
+            for reference in parsed_chk.get_sound_references() {
+                if let Some(sound) = sounds
+                    .iter_mut()
+                    .find(|x| same_filename(&x.filename, &reference.filename))
+                {
+                    sound.references.push(reference.source);
+                    continue;
+                }
+
+                let (status, data) = match archive.read_file(&reference.filename) {
+                    Ok(data) => (SoundStatus::Found, Some(data)),
+                    Err(MpqError::FileNotFound { .. }) => (SoundStatus::Missing, None),
+                    Err(err) => (
+                        SoundStatus::Unreadable {
+                            reason: err.to_string(),
+                        },
+                        None,
+                    ),
+                };
+
+                let format = data.as_deref().and_then(SoundFormat::detect);
+                let duration_ms = format.and_then(|x| x.duration_ms(data.as_deref()?));
+
+                sounds.push(Sound {
+                    filename: reference.filename,
+                    references: vec![reference.source],
+                    status,
+                    format,
+                    duration_ms,
+                    data,
+                });
+            }
+
+            let listfile = archive.read_file("(listfile)").unwrap_or_default();
+
+            let unreferenced = listfile
+                .split(|&x| x == b'\r' || x == b'\n' || x == b';')
+                .filter(|x| !x.is_empty())
+                .filter(|x| {
+                    ![
+                        SCENARIO_CHK.as_bytes(),
+                        b"(listfile)",
+                        b"(attributes)",
+                        b"(signature)",
+                    ]
+                    .iter()
+                    .any(|name| same_filename(x, name))
+                })
+                .filter(|x| !sounds.iter().any(|sound| same_filename(&sound.filename, x)))
+                .filter(|x| archive.find_file(x).is_some())
+                .map(|x| x.to_vec())
+                .collect();
+
+            SoundReport {
+                sounds,
+                unreferenced,
+            }
+        }
+
+        pub fn missing(&self) -> impl Iterator<Item = &Sound> {
+            self.sounds
+                .iter()
+                .filter(|x| x.status != SoundStatus::Found)
+        }
+    }
+}
+
+#[cfg(feature = "mpq")]
+pub use archive::{Sound, SoundReport, SoundStatus};
+
+#[cfg(test)]
+mod test {
+    use super::{SoundFormat, SoundSource};
+    use crate::{
+        chk::chk_trig::ChkTrigAction,
+        chunk_name::ChunkName,
+        test::{make_str, make_trig},
+        ChkWriter, ParsedChk,
+    };
+
+    fn make_wav(seconds: u32) -> Vec<u8> {
+        let samples = vec![0u8; 22050 * 2 * seconds as usize];
+        let fmt = [
+            &1u16.to_le_bytes()[..],
+            &1u16.to_le_bytes(),
+            &22050u32.to_le_bytes(),
+            &(22050u32 * 2).to_le_bytes(),
+            &2u16.to_le_bytes(),
+            &16u16.to_le_bytes(),
+        ]
+        .concat();
+
+        [
+            &b"RIFF"[..],
+            &(4 + 8 + fmt.len() as u32 + 8 + samples.len() as u32).to_le_bytes(),
+            b"WAVE",
+            b"fmt ",
+            &(fmt.len() as u32).to_le_bytes(),
+            &fmt,
+            b"data",
+            &(samples.len() as u32).to_le_bytes(),
+            &samples,
+        ]
+        .concat()
+    }
+
+    fn make_chk(strings: &[&str], wav: &[u32], trigger_wav: u32) -> Vec<u8> {
+        let mut wav_section = vec![0u32; 512];
+        wav_section[..wav.len()].copy_from_slice(wav);
+
+        // A trigger whose first action is PlayWav.
+        let trig = make_trig(
+            &[],
+            &[ChkTrigAction {
+                wav_string_number: trigger_wav,
+                action: 8,
+                ..Default::default()
+            }],
+        );
+
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(ChunkName::STR, make_str(strings));
+        writer.add_chunk(
+            ChunkName::WAV,
+            wav_section.iter().flat_map(|x| x.to_le_bytes()).collect(),
+        );
+        writer.add_chunk(ChunkName::TRIG, trig);
+        writer.to_bytes()
+    }
+
+    #[test]
+    fn finds_references_in_wav_section_and_triggers() {
+        let chk = make_chk(
+            &["staredit\\wav\\a.wav", "staredit\\wav\\b.ogg"],
+            &[1, 0, 9],
+            2,
+        );
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        let references: Vec<_> = parsed_chk
+            .get_sound_references()
+            .into_iter()
+            .map(|x| (x.string_number, x.filename, x.source))
+            .collect();
+
+        assert_eq!(
+            references,
+            vec![
+                (
+                    1,
+                    b"staredit\\wav\\a.wav".to_vec(),
+                    SoundSource::WavSection { index: 0 }
+                ),
+                (
+                    2,
+                    b"staredit\\wav\\b.ogg".to_vec(),
+                    SoundSource::Trigger {
+                        trigger: 0,
+                        action: 0
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn wav_duration() {
+        let wav = make_wav(3);
+
+        assert_eq!(SoundFormat::detect(&wav), Some(SoundFormat::Wav));
+        assert_eq!(SoundFormat::Wav.duration_ms(&wav), Some(3000));
+        // Truncated.
+        assert_eq!(
+            SoundFormat::Wav.duration_ms(&wav[..wav.len() - 22050]),
+            Some(2500)
+        );
+    }
+
+    #[test]
+    fn ogg_duration() {
+        let identification = [
+            &b"\x01vorbis"[..],
+            &0u32.to_le_bytes(),
+            &[2],
+            &44100u32.to_le_bytes(),
+            &[0; 13],
+        ]
+        .concat();
+
+        let page = |granule_position: u64, packet: &[u8]| {
+            [
+                &b"OggS"[..],
+                &[0, 0],
+                &granule_position.to_le_bytes(),
+                &[0; 12],
+                &[1, packet.len() as u8],
+                packet,
+            ]
+            .concat()
+        };
+
+        let ogg = [page(0, &identification), page(88200, &[0; 10])].concat();
+
+        assert_eq!(SoundFormat::detect(&ogg), Some(SoundFormat::Ogg));
+        assert_eq!(SoundFormat::Ogg.duration_ms(&ogg), Some(2000));
+    }
+
+    #[cfg(feature = "mpq")]
+    #[test]
+    fn resolves_sounds_against_archive() {
+        use super::{SoundReport, SoundStatus};
+        use crate::mpq::{Mpq, MpqWriter, SCENARIO_CHK};
+
+        let chk = make_chk(
+            &["staredit\\wav\\a.wav", "staredit\\wav\\missing.wav"],
+            &[1, 2],
+            1,
+        );
+
+        let mut writer = MpqWriter::new();
+        writer.add_file(SCENARIO_CHK, chk.clone());
+        writer.add_file("STAREDIT\\WAV\\A.WAV", make_wav(1));
+        writer.add_file("staredit\\wav\\unused.wav", make_wav(1));
+        let archive = writer.to_bytes();
+        let archive = Mpq::from_bytes(archive.as_slice()).unwrap();
+
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+        let report = SoundReport::from_archive(&parsed_chk, &archive);
+
+        assert_eq!(report.sounds.len(), 2);
+        assert_eq!(report.sounds[0].status, SoundStatus::Found);
+        assert_eq!(report.sounds[0].references.len(), 2);
+        assert_eq!(report.sounds[0].duration_ms, Some(1000));
+        assert!(report.sounds[0].data.as_deref() == Some(make_wav(1).as_slice()));
+        assert_eq!(report.sounds[1].status, SoundStatus::Missing);
+        assert_eq!(report.missing().count(), 1);
+        assert_eq!(
+            report.unreferenced,
+            vec![b"staredit\\wav\\unused.wav".to_vec()]
+        );
+    }
+
+    #[cfg(feature = "mpq")]
+    #[test]
+    fn resolves_adpcm_compressed_sound() {
+        use super::{SoundReport, SoundStatus};
+        use crate::mpq::{compress_huffman, Mpq, MpqWriter, SCENARIO_CHK};
+
+        let chk = make_chk(&["staredit\\wav\\a.wav"], &[1], 1);
+        let wav = make_wav(1);
+
+        let mut writer = MpqWriter::new();
+        writer.set_compression(true);
+        writer.add_file(SCENARIO_CHK, chk.clone());
+        writer.add_file("staredit\\wav\\a.wav", wav.clone());
+
+        // Like StarEdit, every sector after the one holding the header is ADPCM then Huffman coded.
+        // The samples are silence, so each sector is its first sample followed by repeats of it.
+        let archive = writer.build(|name, sector_index, sector| {
+            if name != b"staredit\\wav\\a.wav" || sector_index == 0 {
+                return sector.to_vec();
+            }
+
+            assert!(sector.iter().all(|&x| x == 0));
+            let adpcm = [&[0, 4, 0, 0][..], &vec![0x80; sector.len() / 2 - 1]].concat();
+            [&[0x41][..], &compress_huffman(&adpcm, 7)].concat()
+        });
+        let archive = Mpq::from_bytes(archive.as_slice()).unwrap();
+
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+        let report = SoundReport::from_archive(&parsed_chk, &archive);
+
+        assert_eq!(report.sounds.len(), 1);
+        assert_eq!(report.sounds[0].status, SoundStatus::Found);
+        assert_eq!(report.sounds[0].format, Some(SoundFormat::Wav));
+        assert_eq!(report.sounds[0].duration_ms, Some(1000));
+        assert_eq!(report.sounds[0].data.as_deref(), Some(wav.as_slice()));
+    }
+}
@@ -0,0 +1,413 @@
+use crate::{
+    string_table::StringId,
+    trig::{parse_action, parse_mission_briefing_action},
+    ParsedChk, StringError, StringTable,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+// What a string is used for. Indices are 0 based.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub enum StringUsage {
+    ScenarioName,
+    ScenarioDescription,
+    ForceName {
+        force: usize,
+    },
+    // From UNIx if present, otherwise UNIS. The game only uses the name if the unit does not use default
+    // settings, so names of units that do are stale.
+    UnitName {
+        unit_id: usize,
+        uses_default_settings: bool,
+    },
+    WavSection {
+        index: usize,
+    },
+    SwitchName {
+        switch: usize,
+    },
+    LocationName {
+        location: usize,
+    },
+    TriggerText {
+        trigger: usize,
+        action: usize,
+    },
+    TriggerWav {
+        trigger: usize,
+        action: usize,
+    },
+    MissionBriefingText {
+        trigger: usize,
+        action: usize,
+    },
+    MissionBriefingWav {
+        trigger: usize,
+        action: usize,
+    },
+}
+
+impl StringUsage {
+    // Whether the game actually shows or plays the string.
+    pub fn is_effective(&self) -> bool {
+        !matches!(
+            self,
+            StringUsage::UnitName {
+                uses_default_settings: true,
+                ..
+            }
+        )
+    }
+}
+
+impl std::fmt::Display for StringUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringUsage::ScenarioName => write!(f, "SPRP scenario name"),
+            StringUsage::ScenarioDescription => write!(f, "SPRP scenario description"),
+            StringUsage::ForceName { force } => write!(f, "FORC force {}", force + 1),
+            StringUsage::UnitName {
+                unit_id,
+                uses_default_settings,
+            } => {
+                write!(f, "unit {unit_id} name")?;
+                if *uses_default_settings {
+                    write!(f, " (unused, the unit uses default settings)")?;
+                }
+                Ok(())
+            }
+            StringUsage::WavSection { index } => write!(f, "WAV slot {index}"),
+            StringUsage::SwitchName { switch } => write!(f, "SWNM switch {}", switch + 1),
+            StringUsage::LocationName { location } => {
+                write!(f, "MRGN location {}", location + 1)
+            }
+            StringUsage::TriggerText { trigger, action } => {
+                write!(f, "TRIG #{trigger} action {} text", action + 1)
+            }
+            StringUsage::TriggerWav { trigger, action } => {
+                write!(f, "TRIG #{trigger} action {} wav", action + 1)
+            }
+            StringUsage::MissionBriefingText { trigger, action } => {
+                write!(f, "MBRF #{trigger} action {} text", action + 1)
+            }
+            StringUsage::MissionBriefingWav { trigger, action } => {
+                write!(f, "MBRF #{trigger} action {} wav", action + 1)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct StringReference {
+    pub string_id: StringId,
+    pub usage: StringUsage,
+}
+
+// A reference to a string that does not exist.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct DanglingReference {
+    pub reference: StringReference,
+    pub error: StringError,
+}
+
+// Maps every string to the places that use it.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct StringReferenceIndex {
+    usages: BTreeMap<StringId, Vec<StringUsage>>,
+}
+
+impl ParsedChk<'_> {
+    // Every non zero string reference in the map with what it is used for, in section order.
+    #[instrument(level = "trace", skip_all)]
+    pub fn get_string_references(&self) -> Vec<StringReference> {
+        let mut references = Vec::new();
+
+        if let Ok(x) = &self.sprp {
+            references.push((
+                *x.scenario_name_string_number as u32,
+                StringUsage::ScenarioName,
+            ));
+            references.push((
+                *x.description_string_number as u32,
+                StringUsage::ScenarioDescription,
+            ));
+        }
+
+        if let Ok(x) = &self.forc {
+            for (force, &string_number) in x.force_name.iter().enumerate() {
+                references.push((string_number as u32, StringUsage::ForceName { force }));
+            }
+        }
+
+        let units = if let Ok(x) = &self.unix {
+            Some((&x.config, &x.string_number))
+        } else if let Ok(x) = &self.unis {
+            Some((x.config, &*x.string_number))
+        } else {
+            None
+        };
+
+        if let Some((config, string_number)) = units {
+            for (unit_id, (&config, &string_number)) in config.iter().zip(string_number).enumerate()
+            {
+                references.push((
+                    string_number as u32,
+                    StringUsage::UnitName {
+                        unit_id,
+                        uses_default_settings: config != 0,
+                    },
+                ));
+            }
+        }
+
+        if let Ok(x) = &self.wav {
+            for (index, &string_number) in x.wav_string_number.iter().enumerate() {
+                references.push((string_number, StringUsage::WavSection { index }));
+            }
+        }
+
+        if let Ok(x) = &self.swnm {
+            for (switch, &string_number) in x.switch_name_string_number.iter().enumerate() {
+                references.push((string_number, StringUsage::SwitchName { switch }));
+            }
+        }
+
+        if let Ok(x) = &self.mrgn {
+            for (location, x) in x.locations.iter().enumerate() {
+                references.push((
+                    x.name_string_number as u32,
+                    StringUsage::LocationName { location },
+                ));
+            }
+        }
+
+        // Only the fields the action uses, unused fields are often left with garbage in them.
+        if let Ok(x) = &self.trig {
+            for (trigger, x) in x.triggers.iter().enumerate() {
+                for (action, &x) in x.actions.iter().enumerate() {
+                    let x = parse_action(x, &[]);
+                    if let Some(text) = x.text() {
+                        references.push((text.0, StringUsage::TriggerText { trigger, action }));
+                    }
+                    if let Some(wave) = x.wave() {
+                        references.push((wave.0, StringUsage::TriggerWav { trigger, action }));
+                    }
+                }
+            }
+        }
+
+        if let Ok(x) = &self.mbrf {
+            for (trigger, x) in x.triggers.iter().enumerate() {
+                for (action, &x) in x.actions.iter().enumerate() {
+                    let x = parse_mission_briefing_action(x);
+                    if let Some(text) = x.text() {
+                        references
+                            .push((text.0, StringUsage::MissionBriefingText { trigger, action }));
+                    }
+                    if let Some(wave) = x.wave() {
+                        references
+                            .push((wave.0, StringUsage::MissionBriefingWav { trigger, action }));
+                    }
+                }
+            }
+        }
+
+        references
+            .into_iter()
+            .filter(|&(string_number, _)| string_number != 0)
+            .map(|(string_number, usage)| StringReference {
+                string_id: StringId(string_number),
+                usage,
+            })
+            .collect()
+    }
+}
+
+impl StringReferenceIndex {
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(parsed_chk: &ParsedChk) -> StringReferenceIndex {
+        let mut usages: BTreeMap<StringId, Vec<StringUsage>> = BTreeMap::new();
+
+        for reference in parsed_chk.get_string_references() {
+            usages
+                .entry(reference.string_id)
+                .or_default()
+                .push(reference.usage);
+        }
+
+        StringReferenceIndex { usages }
+    }
+
+    // Everything that uses the string, effective or not.
+    pub fn usages(&self, string_id: StringId) -> &[StringUsage] {
+        self.usages
+            .get(&string_id)
+            .map(|x| x.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn is_used(&self, string_id: StringId) -> bool {
+        self.usages(string_id).iter().any(|x| x.is_effective())
+    }
+
+    // Every referenced string in order with its usages.
+    pub fn iter(&self) -> impl Iterator<Item = (StringId, &[StringUsage])> + '_ {
+        self.usages.iter().map(|(&k, v)| (k, v.as_slice()))
+    }
+
+    // Strings in the table that nothing effectively uses.
+    pub fn orphaned(&self, string_table: &StringTable) -> Vec<StringId> {
+        string_table
+            .iter()
+            .map(|(string_id, _)| string_id)
+            .filter(|&string_id| !self.is_used(string_id))
+            .collect()
+    }
+
+    // References to strings that are out of range or cannot be read.
+    pub fn dangling(&self, string_table: &StringTable) -> Vec<DanglingReference> {
+        let mut ret = Vec::new();
+
+        for (string_id, usages) in self.iter() {
+            if let Err(error) = string_table.get(string_id) {
+                for usage in usages {
+                    ret.push(DanglingReference {
+                        reference: StringReference {
+                            string_id,
+                            usage: usage.clone(),
+                        },
+                        error: error.clone(),
+                    });
+                }
+            }
+        }
+
+        ret
+    }
+
+    // Strings effectively used in more than one place, editing one of them changes the others.
+    pub fn shared(&self) -> Vec<(StringId, Vec<&StringUsage>)> {
+        self.iter()
+            .map(|(string_id, usages)| {
+                (
+                    string_id,
+                    usages
+                        .iter()
+                        .filter(|x| x.is_effective())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .filter(|(_, usages)| usages.len() > 1)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{StringReferenceIndex, StringUsage};
+    use crate::{
+        chk::chk_trig::ChkTrigAction,
+        chunk_name::ChunkName,
+        test::{make_sprp, make_str, make_trig},
+        ChkWriter, ParsedChk, StringError, StringId, StringTable,
+    };
+
+    fn make_chk() -> Vec<u8> {
+        let mut unis = vec![1u8; 228];
+        unis[0] = 0;
+        unis.resize(4048, 0);
+        let string_number = 228 * 14;
+        // Marine has a custom name, Ghost uses default settings so its name is stale.
+        unis[string_number..string_number + 2].copy_from_slice(&3u16.to_le_bytes());
+        unis[string_number + 2..string_number + 4].copy_from_slice(&4u16.to_le_bytes());
+
+        let mut swnm = vec![0u8; 256 * 4];
+        swnm[11 * 4..12 * 4].copy_from_slice(&1u32.to_le_bytes());
+
+        // A transmission, then a Play WAV and a Victory with garbage in their unused string fields.
+        let trig = make_trig(
+            &[],
+            &[
+                ChkTrigAction::default(),
+                ChkTrigAction::default(),
+                ChkTrigAction {
+                    string_number: 9,
+                    wav_string_number: 5,
+                    action: 7,
+                    ..Default::default()
+                },
+                ChkTrigAction {
+                    string_number: 6,
+                    action: 8,
+                    ..Default::default()
+                },
+                ChkTrigAction {
+                    string_number: 4,
+                    wav_string_number: 10,
+                    action: 1,
+                    ..Default::default()
+                },
+            ],
+        );
+
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(ChunkName::STR, make_str(&[""; 6]));
+        writer.add_chunk(ChunkName::SPRP, make_sprp(1, 2));
+        writer.add_chunk(ChunkName::UNIS, unis);
+        writer.add_chunk(ChunkName::SWNM, swnm);
+        writer.add_chunk(ChunkName::TRIG, trig);
+        writer.to_bytes()
+    }
+
+    #[test]
+    fn indexes_typed_usages() {
+        let chk = make_chk();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+        let string_table = StringTable::new(&parsed_chk).unwrap();
+        let index = StringReferenceIndex::new(&parsed_chk);
+
+        assert_eq!(
+            index.usages(StringId(1)),
+            [
+                StringUsage::ScenarioName,
+                StringUsage::SwitchName { switch: 11 }
+            ]
+        );
+        assert_eq!(
+            index.usages(StringId(3)),
+            [StringUsage::UnitName {
+                unit_id: 0,
+                uses_default_settings: false
+            }]
+        );
+        assert_eq!(
+            index.usages(StringId(5))[0].to_string(),
+            "TRIG #0 action 3 wav"
+        );
+
+        assert_eq!(
+            index.orphaned(&string_table),
+            vec![StringId(4), StringId(6)]
+        );
+        assert_eq!(index.shared().len(), 1);
+        assert_eq!(index.shared()[0].0, StringId(1));
+
+        let dangling = index.dangling(&string_table);
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(
+            dangling[0].reference.usage,
+            StringUsage::TriggerText {
+                trigger: 0,
+                action: 2
+            }
+        );
+        assert!(matches!(
+            dangling[0].error,
+            StringError::OutOfRange {
+                number_of_strings: 6,
+                ..
+            }
+        ));
+    }
+}
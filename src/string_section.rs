@@ -0,0 +1,441 @@
+use crate::{
+    chk::{chk_str::ChkStr2, chk_strx::ChkStrx2},
+    string_table::StringId,
+    trig::{parse_action, parse_mission_briefing_action},
+    ParsedChkOwned, StringError, StringTable,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tracing::instrument;
+
+// The 16 bit STR layout every version of StarCraft reads, or the 32 bit STRx layout that only StarCraft:
+// Remastered reads.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum StringSectionFormat {
+    Str,
+    Strx,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub enum StringSectionError {
+    // Neither STR nor STRx is present.
+    NoStringSection,
+    // STR can hold at most 65535 strings.
+    TooManyStrings { number_of_strings: usize },
+    // A string would start past the largest offset STR can hold.
+    TooLarge { offset: usize },
+}
+
+impl std::fmt::Display for StringSectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringSectionError::NoStringSection => write!(f, "the map has no STR or STRx section"),
+            StringSectionError::TooManyStrings { number_of_strings } => write!(
+                f,
+                "{number_of_strings} strings do not fit in STR, which holds at most 65535"
+            ),
+            StringSectionError::TooLarge { offset } => write!(
+                f,
+                "a string would start at offset {offset}, past the 65535 STR can address"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StringSectionError {}
+
+// Builds a STR or STRx section from a list of strings. String i is string number i + 1. Identical strings
+// share their bytes in the output.
+#[derive(Debug, Default, Clone)]
+pub struct StringSectionBuilder {
+    strings: Vec<Vec<u8>>,
+}
+
+impl StringSectionBuilder {
+    pub fn new() -> StringSectionBuilder {
+        StringSectionBuilder::default()
+    }
+
+    // Strings that cannot be read become empty, strings past the end of the offset table are left out.
+    fn from_string_table(string_table: &StringTable) -> StringSectionBuilder {
+        let mut strings = Vec::new();
+
+        for string_number in 1..=string_table.number_of_strings() {
+            match string_table.bytes(StringId(string_number)) {
+                Ok(bytes) => strings.push(bytes.to_vec()),
+                Err(StringError::OffsetOutOfBounds { .. }) => strings.push(Vec::new()),
+                Err(_) => break,
+            }
+        }
+
+        StringSectionBuilder { strings }
+    }
+
+    pub fn from_str_section(str: &ChkStr2) -> StringSectionBuilder {
+        StringSectionBuilder::from_string_table(&StringTable::from_str_section(str))
+    }
+
+    pub fn from_strx(strx: &ChkStrx2) -> StringSectionBuilder {
+        StringSectionBuilder::from_string_table(&StringTable::from_strx(strx))
+    }
+
+    pub fn push(&mut self, bytes: Vec<u8>) -> StringId {
+        self.strings.push(bytes);
+        StringId(self.strings.len() as u32)
+    }
+
+    pub fn strings(&self) -> &[Vec<u8>] {
+        self.strings.as_slice()
+    }
+
+    // The string count, the offset table and then the strings, each followed by a null terminator.
+    #[instrument(level = "trace", skip_all)]
+    pub fn to_bytes(&self, format: StringSectionFormat) -> Result<Vec<u8>, StringSectionError> {
+        let offset_size = match format {
+            StringSectionFormat::Str => 2,
+            StringSectionFormat::Strx => 4,
+        };
+
+        if format == StringSectionFormat::Str && self.strings.len() > u16::MAX as usize {
+            return Err(StringSectionError::TooManyStrings {
+                number_of_strings: self.strings.len(),
+            });
+        }
+
+        let mut offsets = Vec::with_capacity(self.strings.len());
+        let mut data = Vec::new();
+        let mut seen: HashMap<&[u8], usize> = HashMap::new();
+        let start = offset_size * (self.strings.len() + 1);
+
+        for string in &self.strings {
+            let offset = *seen.entry(string.as_slice()).or_insert_with(|| {
+                let offset = start + data.len();
+                data.extend(string);
+                data.push(0);
+                offset
+            });
+
+            if format == StringSectionFormat::Str && offset > u16::MAX as usize {
+                return Err(StringSectionError::TooLarge { offset });
+            }
+
+            offsets.push(offset);
+        }
+
+        let mut ret = Vec::with_capacity(start + data.len());
+
+        for x in [self.strings.len()].iter().chain(&offsets) {
+            ret.extend(&(*x as u32).to_le_bytes()[..offset_size]);
+        }
+
+        ret.extend(data);
+        Ok(ret)
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct RebuildOptions {
+    // Identical strings are merged into the first of them.
+    pub deduplicate: bool,
+    // Strings nothing refers to are dropped. Unit names are kept even if the unit uses default settings.
+    pub drop_unreferenced: bool,
+    pub format: StringSectionFormat,
+}
+
+impl ParsedChkOwned {
+    // Calls f with every non zero string number in every section and stores what it returns. UNIS and UNIx
+    // are both visited. Trigger and briefing action fields are only visited if the action uses them.
+    pub fn map_string_numbers(&mut self, mut f: impl FnMut(u32) -> u32) {
+        let mut f = |x: u32| if x == 0 { 0 } else { f(x) };
+
+        // SPRP, FORC, UNIS/UNIx and MRGN only hold 16 bit string numbers, larger results are truncated.
+        let mut f16 = |x: &mut u16| *x = f(*x as u32) as u16;
+
+        if let Some(x) = &mut self.sprp {
+            f16(&mut x.scenario_name_string_number);
+            f16(&mut x.description_string_number);
+        }
+
+        if let Some(x) = &mut self.forc {
+            x.force_name.iter_mut().for_each(&mut f16);
+        }
+
+        if let Some(x) = &mut self.unix {
+            x.string_number.iter_mut().for_each(&mut f16);
+        }
+
+        if let Some(x) = &mut self.unis {
+            x.string_number.iter_mut().for_each(&mut f16);
+        }
+
+        if let Some(x) = &mut self.mrgn {
+            for location in &mut x.locations {
+                let mut string_number = location.name_string_number;
+                f16(&mut string_number);
+                location.name_string_number = string_number;
+            }
+        }
+
+        let mut f = |x: &mut u32| *x = f(*x);
+
+        if let Some(x) = &mut self.wav {
+            x.wav_string_number.iter_mut().for_each(&mut f);
+        }
+
+        if let Some(x) = &mut self.swnm {
+            x.switch_name_string_number.iter_mut().for_each(&mut f);
+        }
+
+        // Only the fields an action uses are string numbers, the rest may hold anything.
+        if let Some(x) = &mut self.trig {
+            for trigger in &mut x.triggers {
+                for action in &mut trigger.actions {
+                    let parsed = parse_action(*action, &[]);
+
+                    if parsed.text().is_some() {
+                        let mut string_number = action.string_number;
+                        f(&mut string_number);
+                        action.string_number = string_number;
+                    }
+
+                    if parsed.wave().is_some() {
+                        let mut wav_string_number = action.wav_string_number;
+                        f(&mut wav_string_number);
+                        action.wav_string_number = wav_string_number;
+                    }
+                }
+            }
+        }
+
+        if let Some(x) = &mut self.mbrf {
+            for trigger in &mut x.triggers {
+                for action in &mut trigger.actions {
+                    let parsed = parse_mission_briefing_action(*action);
+
+                    if parsed.text().is_some() {
+                        let mut string_number = action.string_number;
+                        f(&mut string_number);
+                        action.string_number = string_number;
+                    }
+
+                    if parsed.wave().is_some() {
+                        let mut wav_string_number = action.wav_string_number;
+                        f(&mut wav_string_number);
+                        action.wav_string_number = wav_string_number;
+                    }
+                }
+            }
+        }
+    }
+
+    // Rebuilds the string section from STRx if present, otherwise STR, and renumbers every reference to
+    // match. References to strings that do not exist are set to 0 so they do not end up pointing at a
+    // different string. The other string section is removed. Returns the new number of every string that
+    // was kept.
+    #[instrument(level = "trace", skip_all)]
+    pub fn rebuild_strings(
+        &mut self,
+        options: RebuildOptions,
+    ) -> Result<BTreeMap<StringId, StringId>, StringSectionError> {
+        let source = match (&self.strx, &self.str) {
+            (Some(strx), _) => StringSectionBuilder::from_strx(strx),
+            (_, Some(str)) => StringSectionBuilder::from_str_section(str),
+            _ => return Err(StringSectionError::NoStringSection),
+        };
+
+        let mut referenced = BTreeSet::new();
+        self.map_string_numbers(|x| {
+            referenced.insert(x);
+            x
+        });
+
+        let mut builder = StringSectionBuilder::new();
+        let mut renumbered = BTreeMap::new();
+        let mut seen: HashMap<&[u8], StringId> = HashMap::new();
+
+        for (i, string) in source.strings().iter().enumerate() {
+            let string_id = StringId(i as u32 + 1);
+
+            if options.drop_unreferenced && !referenced.contains(&string_id.0) {
+                continue;
+            }
+
+            let new_string_id = match seen.get(string.as_slice()) {
+                Some(&x) if options.deduplicate => x,
+                _ => builder.push(string.clone()),
+            };
+
+            seen.entry(string.as_slice()).or_insert(new_string_id);
+            renumbered.insert(string_id, new_string_id);
+        }
+
+        let string_data = builder.to_bytes(options.format)?;
+
+        self.map_string_numbers(|x| renumbered.get(&StringId(x)).map_or(0, |x| x.0));
+
+        match options.format {
+            StringSectionFormat::Str => {
+                self.str = Some(ChkStr2 { string_data });
+                self.strx = None;
+            }
+            StringSectionFormat::Strx => {
+                self.strx = Some(ChkStrx2 { string_data });
+                self.str = None;
+            }
+        }
+
+        Ok(renumbered)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RebuildOptions, StringSectionBuilder, StringSectionError, StringSectionFormat};
+    use crate::{
+        chk::{chk_str::ChkStr2, chk_trig::ChkTrigAction},
+        chunk_name::ChunkName,
+        test::{make_sprp, make_trig},
+        ChkWriter, ParsedChk, ParsedChkOwned, StringId, StringTable,
+    };
+
+    // Strings 1 and 3 are the scenario name and description, switch 1 is named by string 2. The first
+    // action of the first trigger displays string 9, which does not exist, and the second is a NoAction
+    // whose unused string field also holds 9.
+    fn make_chk() -> Vec<u8> {
+        let mut builder = StringSectionBuilder::new();
+        for string in [&b"abc"[..], b"switch", b"abc", b"unused"] {
+            builder.push(string.to_vec());
+        }
+
+        let mut swnm = vec![0u8; 256 * 4];
+        swnm[0..4].copy_from_slice(&2u32.to_le_bytes());
+
+        let trig = make_trig(
+            &[],
+            &[
+                ChkTrigAction {
+                    string_number: 9,
+                    action: 9,
+                    ..Default::default()
+                },
+                ChkTrigAction {
+                    string_number: 9,
+                    ..Default::default()
+                },
+            ],
+        );
+
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(
+            ChunkName::STR,
+            builder.to_bytes(StringSectionFormat::Str).unwrap(),
+        );
+        writer.add_chunk(ChunkName::SPRP, make_sprp(1, 3));
+        writer.add_chunk(ChunkName::SWNM, swnm);
+        writer.add_chunk(ChunkName::TRIG, trig);
+        writer.to_bytes()
+    }
+
+    #[test]
+    fn shares_identical_strings() {
+        let mut builder = StringSectionBuilder::new();
+        builder.push(b"ab".to_vec());
+        builder.push(b"ab".to_vec());
+        builder.push(Vec::new());
+
+        assert_eq!(
+            builder.to_bytes(StringSectionFormat::Str).unwrap(),
+            [&[3, 0, 8, 0, 8, 0, 11, 0][..], b"ab\0\0"].concat()
+        );
+
+        let strx = builder.to_bytes(StringSectionFormat::Strx).unwrap();
+        assert_eq!(&strx[0..8], &[3, 0, 0, 0, 16, 0, 0, 0]);
+        assert_eq!(&strx[16..], b"ab\0\0");
+
+        let str = ChkStr2 {
+            string_data: builder.to_bytes(StringSectionFormat::Str).unwrap(),
+        };
+        assert_eq!(
+            StringSectionBuilder::from_str_section(&str).strings(),
+            builder.strings()
+        );
+    }
+
+    #[test]
+    fn str_limits() {
+        let mut builder = StringSectionBuilder::new();
+        builder.push(vec![b'a'; 70000]);
+        builder.push(b"b".to_vec());
+
+        assert_eq!(
+            builder.to_bytes(StringSectionFormat::Str),
+            Err(StringSectionError::TooLarge { offset: 70007 })
+        );
+        assert!(builder.to_bytes(StringSectionFormat::Strx).is_ok());
+    }
+
+    #[test]
+    fn compacts_and_converts_to_strx_and_back() {
+        let chk = make_chk();
+        let mut owned = ParsedChkOwned::from(&ParsedChk::from_bytes(chk.as_slice()));
+
+        let renumbered = owned
+            .rebuild_strings(RebuildOptions {
+                deduplicate: true,
+                drop_unreferenced: true,
+                format: StringSectionFormat::Strx,
+            })
+            .unwrap();
+
+        assert_eq!(renumbered.get(&StringId(3)), Some(&StringId(1)));
+        assert_eq!(renumbered.get(&StringId(4)), None);
+        assert!(owned.str.is_none());
+
+        let written = owned.to_bytes();
+        let parsed_chk = ParsedChk::from_bytes(written.as_slice());
+        let string_table = StringTable::new(&parsed_chk).unwrap();
+
+        assert!(parsed_chk.str.is_err());
+        assert_eq!(string_table.number_of_strings(), 2);
+        assert_eq!(
+            *parsed_chk
+                .sprp
+                .as_ref()
+                .unwrap()
+                .scenario_name_string_number,
+            1
+        );
+        assert_eq!(
+            *parsed_chk.sprp.as_ref().unwrap().description_string_number,
+            1
+        );
+        assert_eq!(
+            parsed_chk.swnm.as_ref().unwrap().switch_name_string_number[0],
+            2
+        );
+        assert_eq!(string_table.text(StringId(2)).unwrap(), "switch");
+        assert_eq!(
+            { parsed_chk.trig.as_ref().unwrap().triggers[0].actions[0].string_number },
+            0
+        );
+        assert_eq!(
+            { parsed_chk.trig.as_ref().unwrap().triggers[0].actions[1].string_number },
+            9
+        );
+
+        let mut owned = ParsedChkOwned::from(&parsed_chk);
+        owned
+            .rebuild_strings(RebuildOptions {
+                deduplicate: false,
+                drop_unreferenced: false,
+                format: StringSectionFormat::Str,
+            })
+            .unwrap();
+
+        assert!(owned.strx.is_none());
+        assert_eq!(
+            owned.str.as_ref().unwrap().string_data,
+            [&[2, 0, 6, 0, 10, 0][..], b"abc\0switch\0"].concat()
+        );
+    }
+}
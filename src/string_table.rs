@@ -0,0 +1,661 @@
+use crate::{
+    chk::{chk_str::ChkStr2, chk_strx::ChkStrx2},
+    chunk_name::ChunkName,
+    encoding::{score_encodings, StringEncoding},
+    error::ChkError,
+    formatting::FormattedText,
+    util::parse_null_terminated_bytestring_unsigned,
+    ParsedChk,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+// A 1 based string number as stored in STR/STRx and referenced by the other sections. 0 means no string.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StringId(pub u32);
+
+impl StringId {
+    pub fn is_none(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl From<u32> for StringId {
+    fn from(string_number: u32) -> StringId {
+        StringId(string_number)
+    }
+}
+
+impl std::fmt::Display for StringId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub enum StringError {
+    // String number 0, which means no string rather than an empty one.
+    NoString,
+    // The string number is larger than the number of strings in the section.
+    OutOfRange {
+        string_id: StringId,
+        number_of_strings: u32,
+    },
+    // The offset of the string would be read from past the end of the section.
+    OffsetTableTruncated {
+        string_id: StringId,
+    },
+    // The offset of the string is past the end of the section.
+    OffsetOutOfBounds {
+        string_id: StringId,
+        offset: usize,
+        section_len: usize,
+    },
+}
+
+impl std::fmt::Display for StringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringError::NoString => write!(f, "string number 0 means no string"),
+            StringError::OutOfRange {
+                string_id,
+                number_of_strings,
+            } => write!(
+                f,
+                "string {string_id} is out of range, there are {number_of_strings} strings"
+            ),
+            StringError::OffsetTableTruncated { string_id } => {
+                write!(f, "the offset of string {string_id} is past the end of the section")
+            }
+            StringError::OffsetOutOfBounds {
+                string_id,
+                offset,
+                section_len,
+            } => write!(
+                f,
+                "string {string_id} starts at offset {offset} but the section is {section_len} bytes"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StringError {}
+
+// How a StringTable picks the encoding of each string.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+pub enum EncodingMode {
+    // Every string uses the dominant encoding of the map, unless it cannot be decoded in it.
+    MapWide,
+    // Every string uses the encoding with the best score after adding the map's score for it times
+    // prior_weight. A weight of 0 gives the same results as get_string.
+    PerString { prior_weight: f64 },
+}
+
+// Strings that start at the same offset are the same bytes, so they share one entry.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct StringTableEntry {
+    // Offset of the string from the start of the section.
+    pub offset: usize,
+    // Every string that starts at the offset, in order.
+    pub string_ids: Vec<StringId>,
+    // The string up to but not including its null terminator.
+    pub bytes: Vec<u8>,
+    pub text: String,
+    pub encoding: StringEncoding,
+    // The encodings the string decodes without errors in, best first, as voted on by the string alone.
+    pub scores: Vec<(StringEncoding, f64)>,
+    // Whether any section refers to one of the strings in a way the game uses, see
+    // StringUsage::is_effective. Only referenced strings take part in the map's vote, once per offset.
+    pub referenced: bool,
+}
+
+// Every string in STR or STRx, parsed once. The encoding of the strings is decided by all the referenced
+// strings together instead of guessing each string on its own like get_string does. Short strings are easy
+// to mistake for another encoding, e.g. "Àú" in CP1252 is the Hangul syllable "저" in CP949, so letting the
+// rest of the map vote keeps a map from mixing encodings.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct StringTable {
+    // The index into strings of every string number.
+    entries: Vec<Result<usize, StringError>>,
+    // One per offset, sorted by offset.
+    strings: Vec<StringTableEntry>,
+    number_of_strings: u32,
+    map_scores: Vec<(StringEncoding, f64)>,
+    overrides: BTreeMap<StringId, StringEncoding>,
+    mode: EncodingMode,
+}
+
+impl StringTable {
+    // Uses STRx if present, like get_string.
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(parsed_chk: &ParsedChk) -> Result<StringTable, ChkError> {
+        let mut ret = match (&parsed_chk.strx, &parsed_chk.str) {
+            (Ok(strx), _) => StringTable::from_strx(strx),
+            (_, Ok(str)) => StringTable::from_str_section(str),
+            _ => {
+                return Err(ChkError::MissingSection {
+                    chunk_name: ChunkName::STR,
+                })
+            }
+        };
+
+        for entry in &mut ret.strings {
+            entry.referenced = false;
+        }
+
+        for reference in parsed_chk.get_string_references() {
+            if !reference.usage.is_effective() {
+                continue;
+            }
+
+            if let Ok(index) = ret.index(reference.string_id) {
+                ret.strings[index].referenced = true;
+            }
+        }
+
+        ret.vote();
+        Ok(ret)
+    }
+
+    // Every string is treated as referenced.
+    pub fn from_str_section(str: &ChkStr2) -> StringTable {
+        StringTable::from_string_data(str.string_data.as_slice(), 2)
+    }
+
+    // Every string is treated as referenced.
+    pub fn from_strx(strx: &ChkStrx2) -> StringTable {
+        StringTable::from_string_data(strx.string_data.as_slice(), 4)
+    }
+
+    // STR and STRx only differ in the size of the string count and of the offsets.
+    fn from_string_data(string_data: &[u8], offset_size: usize) -> StringTable {
+        let read = |offset: usize| {
+            let bytes = string_data.get(offset..offset + offset_size)?;
+            Some(
+                bytes
+                    .iter()
+                    .rev()
+                    .fold(0usize, |acc, &x| (acc << 8) | x as usize),
+            )
+        };
+
+        let number_of_strings = read(0).unwrap_or(0);
+
+        // PROTECTION: the string count can be far larger than the section. Offsets past the end of the section
+        // are not materialized, get reports them as OffsetTableTruncated.
+        let readable_strings = number_of_strings.min(string_data.len() / offset_size);
+
+        let offsets: Vec<Result<usize, StringError>> = (1..=readable_strings)
+            .map(|string_number| {
+                let string_id = StringId(string_number as u32);
+                let offset = read(string_number * offset_size)
+                    .ok_or(StringError::OffsetTableTruncated { string_id })?;
+
+                if offset >= string_data.len() {
+                    return Err(StringError::OffsetOutOfBounds {
+                        string_id,
+                        offset,
+                        section_len: string_data.len(),
+                    });
+                }
+
+                Ok(offset)
+            })
+            .collect();
+
+        // PROTECTION: protected maps point thousands of strings at the same offset, so every offset is only
+        // parsed once.
+        let mut string_ids: BTreeMap<usize, Vec<StringId>> = BTreeMap::new();
+        for (i, offset) in offsets.iter().enumerate() {
+            if let Ok(offset) = offset {
+                string_ids
+                    .entry(*offset)
+                    .or_default()
+                    .push(StringId(i as u32 + 1));
+            }
+        }
+
+        let strings: Vec<StringTableEntry> = string_ids
+            .into_iter()
+            .map(|(offset, string_ids)| {
+                let bytes = parse_null_terminated_bytestring_unsigned(&string_data[offset..]);
+
+                StringTableEntry {
+                    offset,
+                    string_ids,
+                    bytes: bytes.to_vec(),
+                    text: String::new(),
+                    encoding: StringEncoding::Windows1252,
+                    scores: score_encodings(bytes),
+                    referenced: true,
+                }
+            })
+            .collect();
+
+        let entries = offsets
+            .into_iter()
+            .map(|x| x.map(|offset| strings.partition_point(|x| x.offset < offset)))
+            .collect();
+
+        let mut ret = StringTable {
+            entries,
+            strings,
+            number_of_strings: number_of_strings as u32,
+            map_scores: Vec::new(),
+            overrides: BTreeMap::new(),
+            mode: EncodingMode::MapWide,
+        };
+
+        ret.vote();
+        ret
+    }
+
+    // Every referenced string votes with its own scores weighted by how many bytes above 0x7F it has, as those
+    // are the only bytes the encodings disagree on. An encoding a string cannot be decoded in gets -1 from it.
+    fn vote(&mut self) {
+        // Ties go to the encoding that comes first, and a map without any bytes above 0x7F reads the same in
+        // all of them.
+        let mut map_scores = vec![
+            (StringEncoding::Windows1252, 0.0),
+            (StringEncoding::EucKr, 0.0),
+            (StringEncoding::Utf8, 0.0),
+        ];
+        let mut total_weight = 0.0;
+
+        for entry in self.strings.iter().filter(|x| x.referenced) {
+            let weight = entry.bytes.iter().filter(|&&x| x >= 0x80).count() as f64;
+            total_weight += weight;
+
+            for (encoding, map_score) in &mut map_scores {
+                *map_score += weight
+                    * entry
+                        .scores
+                        .iter()
+                        .find(|(x, _)| x == encoding)
+                        .map_or(-1.0, |(_, score)| *score);
+            }
+        }
+
+        if total_weight > 0.0 {
+            for (_, map_score) in &mut map_scores {
+                *map_score /= total_weight;
+            }
+        }
+
+        map_scores.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        self.map_scores = map_scores;
+        self.decode();
+    }
+
+    fn decode(&mut self) {
+        for i in 0..self.strings.len() {
+            let encoding = self.pick_encoding(&self.strings[i]);
+
+            let entry = &mut self.strings[i];
+            entry.encoding = encoding;
+            entry.text = encoding.decode(entry.bytes.as_slice());
+        }
+    }
+
+    // An override of any of the strings at the offset applies to all of them, the lowest string number wins.
+    fn pick_encoding(&self, entry: &StringTableEntry) -> StringEncoding {
+        if let Some(&encoding) = entry.string_ids.iter().find_map(|x| self.overrides.get(x)) {
+            return encoding;
+        }
+
+        match self.mode {
+            EncodingMode::MapWide => {
+                let dominant_encoding = self.dominant_encoding();
+
+                if entry.scores.iter().any(|(x, _)| *x == dominant_encoding) {
+                    dominant_encoding
+                } else {
+                    entry.scores[0].0
+                }
+            }
+            EncodingMode::PerString { prior_weight } => {
+                entry
+                    .scores
+                    .iter()
+                    .map(|&(encoding, score)| {
+                        (encoding, score + prior_weight * self.map_score(encoding))
+                    })
+                    // max_by returns the last of equal elements, so compare in reverse to keep the first.
+                    .min_by(|(_, a), (_, b)| b.total_cmp(a))
+                    .unwrap()
+                    .0
+            }
+        }
+    }
+
+    pub fn mode(&self) -> EncodingMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: EncodingMode) {
+        self.mode = mode;
+        self.decode();
+    }
+
+    // The score of every encoding for the map as a whole, best first.
+    pub fn map_scores(&self) -> &[(StringEncoding, f64)] {
+        self.map_scores.as_slice()
+    }
+
+    pub fn dominant_encoding(&self) -> StringEncoding {
+        self.map_scores[0].0
+    }
+
+    // How far ahead of the runner up the dominant encoding is. 0 means the vote was a tie.
+    pub fn confidence(&self) -> f64 {
+        self.map_scores[0].1 - self.map_scores[1].1
+    }
+
+    fn map_score(&self, encoding: StringEncoding) -> f64 {
+        self.map_scores
+            .iter()
+            .find(|(x, _)| *x == encoding)
+            .map_or(0.0, |(_, score)| *score)
+    }
+
+    // Forces a string to be decoded with an encoding, regardless of the mode and the votes.
+    pub fn set_override(&mut self, string_id: StringId, encoding: StringEncoding) {
+        self.overrides.insert(string_id, encoding);
+        self.decode();
+    }
+
+    pub fn clear_override(&mut self, string_id: StringId) {
+        self.overrides.remove(&string_id);
+        self.decode();
+    }
+
+    // The number of strings the section says it has, including the ones that could not be read.
+    pub fn number_of_strings(&self) -> u32 {
+        self.number_of_strings
+    }
+
+    pub fn get(&self, string_id: StringId) -> Result<&StringTableEntry, StringError> {
+        Ok(&self.strings[self.index(string_id)?])
+    }
+
+    fn index(&self, string_id: StringId) -> Result<usize, StringError> {
+        if string_id.is_none() {
+            return Err(StringError::NoString);
+        }
+
+        match self.entries.get(string_id.0 as usize - 1) {
+            Some(x) => x.clone(),
+            None if string_id.0 <= self.number_of_strings => {
+                Err(StringError::OffsetTableTruncated { string_id })
+            }
+            None => Err(StringError::OutOfRange {
+                string_id,
+                number_of_strings: self.number_of_strings,
+            }),
+        }
+    }
+
+    pub fn text(&self, string_id: StringId) -> Result<&str, StringError> {
+        Ok(self.get(string_id)?.text.as_str())
+    }
+
+    pub fn bytes(&self, string_id: StringId) -> Result<&[u8], StringError> {
+        Ok(self.get(string_id)?.bytes.as_slice())
+    }
+
+    // The decoded string split into colored spans, ready to be rendered.
+    pub fn formatted(&self, string_id: StringId) -> Result<FormattedText, StringError> {
+        Ok(FormattedText::parse(self.text(string_id)?))
+    }
+
+    // Other strings that start at the same offset.
+    pub fn shared_with(&self, string_id: StringId) -> Vec<StringId> {
+        self.get(string_id)
+            .map(|x| {
+                x.string_ids
+                    .iter()
+                    .copied()
+                    .filter(|&x| x != string_id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // Other strings that start at a different offset but use some of the same bytes, usually because one is a
+    // suffix of the other. Strings are null terminated, so that is the case exactly when they end at the same
+    // null, and those strings are next to each other when sorted by offset.
+    pub fn overlaps_with(&self, string_id: StringId) -> Vec<StringId> {
+        let Ok(index) = self.index(string_id) else {
+            return Vec::new();
+        };
+
+        let end = |x: &StringTableEntry| x.offset + x.bytes.len();
+        let this = end(&self.strings[index]);
+
+        let before = self.strings[..index]
+            .iter()
+            .rev()
+            .take_while(|&x| end(x) == this);
+        let after = self.strings[index + 1..]
+            .iter()
+            .take_while(|&x| end(x) == this);
+
+        let mut ret: Vec<StringId> = before
+            .chain(after)
+            .flat_map(|x| x.string_ids.iter().copied())
+            .collect();
+        ret.sort();
+        ret
+    }
+
+    // Every string that could be read, referenced or not, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (StringId, &StringTableEntry)> + '_ {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, x)| Some((StringId(i as u32 + 1), &self.strings[*x.as_ref().ok()?])))
+    }
+
+    // Every string that could not be read, except for the ones whose offset is past the end of the section.
+    pub fn errors(&self) -> impl Iterator<Item = &StringError> + '_ {
+        self.entries.iter().filter_map(|x| x.as_ref().err())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EncodingMode, StringError, StringId, StringTable};
+    use crate::{
+        chk::{chk_str::ChkStr2, chk_strx::ChkStrx2},
+        chunk_name::ChunkName,
+        encoding::StringEncoding,
+        test::{make_sprp, make_str},
+        ChkWriter, ParsedChk,
+    };
+
+    // Strings 1 and 2 are the scenario name and description, string 3 is the name of force 1.
+    fn make_chk(strings: &[&[u8]]) -> Vec<u8> {
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(ChunkName::STR, make_str(strings));
+        writer.add_chunk(ChunkName::SPRP, make_sprp(1, 2));
+        writer.add_chunk(
+            ChunkName::FORC,
+            [[0u8; 8].as_slice(), &3u16.to_le_bytes(), &[0; 6], &[0; 4]].concat(),
+        );
+        writer.to_bytes()
+    }
+
+    fn windows_1252(s: &str) -> Vec<u8> {
+        encoding_rs::WINDOWS_1252.encode(s).0.into_owned()
+    }
+
+    #[test]
+    fn map_decides_short_strings() {
+        let strings = [
+            windows_1252("JØNÎ$  ßøûñÐ"),
+            windows_1252("Ð Ñ Ø © ñ"),
+            windows_1252("Àú"),
+        ];
+        let chk = make_chk(&strings.iter().map(|x| x.as_slice()).collect::<Vec<_>>());
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        // On its own the short string looks like Korean.
+        assert_eq!(parsed_chk.get_string(3).unwrap(), "저");
+
+        let mut string_table = StringTable::new(&parsed_chk).unwrap();
+
+        assert_eq!(
+            string_table.dominant_encoding(),
+            StringEncoding::Windows1252
+        );
+        assert!(string_table.confidence() > 0.0);
+        assert_eq!(
+            string_table
+                .iter()
+                .map(|(id, x)| (id, x.text.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (StringId(1), "JØNÎ$  ßøûñÐ"),
+                (StringId(2), "Ð Ñ Ø © ñ"),
+                (StringId(3), "Àú"),
+            ]
+        );
+
+        string_table.set_mode(EncodingMode::PerString { prior_weight: 0.0 });
+        assert_eq!(
+            string_table.text(StringId(3)).unwrap(),
+            parsed_chk.get_string(3).unwrap()
+        );
+
+        string_table.set_mode(EncodingMode::PerString { prior_weight: 10.0 });
+        assert_eq!(string_table.text(StringId(3)).unwrap(), "Àú");
+
+        string_table.set_override(StringId(1), StringEncoding::Utf8);
+        assert_eq!(
+            string_table.get(StringId(1)).unwrap().encoding,
+            StringEncoding::Utf8
+        );
+        string_table.clear_override(StringId(1));
+        assert_eq!(string_table.text(StringId(1)).unwrap(), "JØNÎ$  ßøûñÐ");
+    }
+
+    #[test]
+    fn korean_map() {
+        let strings = [
+            encoding_rs::EUC_KR
+                .encode("마린키우기 Let It Snow")
+                .0
+                .into_owned(),
+            encoding_rs::EUC_KR
+                .encode("적 데몬 3.5 정식버전")
+                .0
+                .into_owned(),
+            b"Snow".to_vec(),
+        ];
+        let chk = make_chk(&strings.iter().map(|x| x.as_slice()).collect::<Vec<_>>());
+        let string_table = StringTable::new(&ParsedChk::from_bytes(chk.as_slice())).unwrap();
+
+        assert_eq!(string_table.dominant_encoding(), StringEncoding::EucKr);
+        assert_eq!(
+            string_table.text(StringId(2)).unwrap(),
+            "적 데몬 3.5 정식버전"
+        );
+        assert_eq!(string_table.text(StringId(3)).unwrap(), "Snow");
+    }
+
+    #[test]
+    fn shared_overlapping_and_invalid_strings() {
+        // 6 strings, the offset table ends at 14 where "Hello\0" starts.
+        let mut string_data = 6u16.to_le_bytes().to_vec();
+        for offset in [14u16, 14, 16, 0xFFFF, 19, 14] {
+            string_data.extend(offset.to_le_bytes());
+        }
+        string_data.extend(b"Hello\0");
+
+        let string_table = StringTable::from_str_section(&ChkStr2 { string_data });
+
+        assert_eq!(string_table.number_of_strings(), 6);
+        assert_eq!(string_table.text(StringId(1)).unwrap(), "Hello");
+        assert_eq!(string_table.text(StringId(3)).unwrap(), "llo");
+        assert_eq!(string_table.text(StringId(5)).unwrap(), "");
+        assert_eq!(
+            string_table.shared_with(StringId(1)),
+            vec![StringId(2), StringId(6)]
+        );
+        assert_eq!(
+            string_table.get(StringId(2)).unwrap().string_ids,
+            vec![StringId(1), StringId(2), StringId(6)]
+        );
+        assert_eq!(
+            string_table.overlaps_with(StringId(1)),
+            vec![StringId(3), StringId(5)]
+        );
+        assert_eq!(
+            string_table.overlaps_with(StringId(5)),
+            vec![StringId(1), StringId(2), StringId(3), StringId(6)]
+        );
+        assert_eq!(string_table.overlaps_with(StringId(4)), vec![]);
+        assert_eq!(
+            string_table.get(StringId(4)).unwrap_err(),
+            StringError::OffsetOutOfBounds {
+                string_id: StringId(4),
+                offset: 0xFFFF,
+                section_len: 20,
+            }
+        );
+        assert_eq!(
+            string_table.get(StringId(0)).unwrap_err(),
+            StringError::NoString
+        );
+        assert_eq!(
+            string_table.get(StringId(7)).unwrap_err(),
+            StringError::OutOfRange {
+                string_id: StringId(7),
+                number_of_strings: 6,
+            }
+        );
+        assert_eq!(string_table.iter().count(), 5);
+        assert_eq!(string_table.errors().count(), 1);
+
+        let truncated = StringTable::from_str_section(&ChkStr2 {
+            string_data: [2u16, 6].iter().flat_map(|x| x.to_le_bytes()).collect(),
+        });
+
+        assert_eq!(
+            truncated.get(StringId(2)).unwrap_err(),
+            StringError::OffsetTableTruncated {
+                string_id: StringId(2)
+            }
+        );
+    }
+
+    #[test]
+    fn many_strings_at_one_offset() {
+        // STRx with 100000 strings that all point at the same "Hi\0", the offset table ends at 400004.
+        let number_of_strings = 100000u32;
+        let mut string_data = number_of_strings.to_le_bytes().to_vec();
+        for _ in 0..number_of_strings {
+            string_data.extend((4 + number_of_strings * 4).to_le_bytes());
+        }
+        string_data.extend(b"Hi\0");
+
+        let string_table = StringTable::from_strx(&ChkStrx2 { string_data });
+
+        assert_eq!(string_table.iter().count(), number_of_strings as usize);
+        assert_eq!(
+            string_table.text(StringId(number_of_strings)).unwrap(),
+            "Hi"
+        );
+        assert_eq!(
+            string_table.get(StringId(1)).unwrap().string_ids.len(),
+            number_of_strings as usize
+        );
+        assert_eq!(
+            string_table.shared_with(StringId(1)).len(),
+            number_of_strings as usize - 1
+        );
+        assert_eq!(string_table.overlaps_with(StringId(1)), vec![]);
+    }
+}
@@ -3,8 +3,10 @@ use crate::{
         chk_mbrf::{ChkMbrfAction, ChkMbrfCondition, ChkMbrfIndividual},
         chk_trig::{ChkTrigAction, ChkTrigCondition, ChkTrigIndividual},
     },
+    compile_trigedit, parse_triggers,
     test::get_chk,
-    ParsedChk,
+    triggers_to_trigedit, Action, Condition, Group, MaskFlag, NumericComparison, ParsedChk,
+    TrigEditNames, UnitType,
 };
 
 #[tokio::test]
@@ -13,6 +15,9 @@ async fn test_specific_map_files_for_known_values() {
         .await
         .unwrap();
     let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+    let triggers = parse_triggers(&parsed_chk);
+    let trigedit = triggers_to_trigedit(&parsed_chk);
+    let compiled = compile_trigedit(&trigedit, &TrigEditNames::new(&parsed_chk)).unwrap();
 
     if let Ok(x) = parsed_chk.ver {
         assert_eq!(*x.file_format_version, 206);
@@ -535,10 +540,69 @@ async fn test_specific_map_files_for_known_values() {
     if let Ok(x) = parsed_chk.trig {
         assert_eq!(x.triggers.len(), 1, "{x:?}");
         assert_eq!(x.triggers, [ChkTrigIndividual { conditions: [ChkTrigCondition { location: 1, group: 0, qualified_number: 17, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 3, resource_type_or_score_type_or_switch_number: 0, flags: 16, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }, ChkTrigCondition { location: 0, group: 0, qualified_number: 0, unit_id: 0, numeric_comparison_or_switch_state: 0, condition: 0, resource_type_or_score_type_or_switch_number: 0, flags: 0, mask_flag: 0 }], actions: [ChkTrigAction { location: 1, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 1, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 11, number_of_units_or_action_state_or_unit_order_or_number_modifier: 1, flags: 24, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }, ChkTrigAction { location: 0, string_number: 0, wav_string_number: 0, seconds_or_milliseconds: 0, first_or_only_group_or_player_affected: 0, second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 0, unit_type_or_score_type_or_resource_type_or_alliance_status: 0, action: 0, number_of_units_or_action_state_or_unit_order_or_number_modifier: 0, flags: 0, padding: 0, mask_flag: 0 }], execution_flags: 0, executed_for_player: [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], current_action: 0 }], "{x:?}");
+        assert_eq!(compiled.triggers, x.triggers);
+        assert!(compiled.new_strings.is_empty());
     } else {
         unreachable!();
     }
 
+    assert_eq!(triggers.len(), 1, "{triggers:?}");
+    assert_eq!(triggers[0].conditions.len(), 1, "{triggers:?}");
+    assert!(
+        triggers[0].conditions[0].flags.unit_type_used,
+        "{triggers:?}"
+    );
+    assert_eq!(triggers[0].conditions[0].mask_flag, MaskFlag::None);
+    assert!(
+        matches!(
+            triggers[0].conditions[0].condition,
+            Condition::Bring {
+                player: Group::Player1,
+                comparison: NumericComparison::AtLeast,
+                unit_type: UnitType::TerranMarine,
+                number: 17,
+                ..
+            }
+        ),
+        "{triggers:?}"
+    );
+    assert_eq!(triggers[0].actions.len(), 1, "{triggers:?}");
+    assert!(
+        triggers[0].actions[0].flags.unit_properties_used,
+        "{triggers:?}"
+    );
+    assert!(triggers[0].actions[0].flags.unit_type_used, "{triggers:?}");
+    if let Action::CreateUnitWithProperties {
+        player: Group::Player1,
+        unit_type: UnitType::TerranMarine,
+        number,
+        unit_prop,
+        properties: Some(properties),
+        ..
+    } = triggers[0].actions[0].action
+    {
+        assert_eq!(number, 1);
+        assert_eq!(unit_prop, 1);
+        assert_eq!({ properties.resource_amount }, 123);
+        assert_eq!({ properties.number_of_units_in_hangar }, 52);
+    } else {
+        unreachable!("{triggers:?}");
+    }
+
+    assert!(
+        trigedit.starts_with(
+            r#"Trigger("Player 1"){
+Conditions:
+	Bring("Player 1", "Terran Marine", "location test string", At least, 17);
+
+Actions:
+	Create Unit with Properties("Player 1", "Terran Marine", 1, "location test string", 1);
+}
+"#
+        ),
+        "{trigedit}"
+    );
+
     if let Ok(x) = parsed_chk.uprp {
         assert_eq!(x.cuwp_slots.len(), 64, "{x:?}");
 
@@ -1,6 +1,9 @@
 use crate::test::get_all_test_chks;
 use crate::test::get_chk;
+use crate::ChkError;
+use crate::ChkWriter;
 use crate::ParsedChk;
+use crate::{parse_mission_briefing, parse_triggers};
 use futures::pin_mut;
 use futures::TryStreamExt;
 
@@ -77,8 +80,65 @@ async fn test_get_string_on_all_maps() {
         let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
         let string_refs = parsed_chk.get_all_string_references().unwrap();
 
+        // Protected maps reference strings that do not exist, the only error get_string may return.
         for string_ref in string_refs {
-            parsed_chk.get_string(string_ref as usize).unwrap();
+            if let Err(err) = parsed_chk.get_string(string_ref as usize) {
+                assert!(matches!(err, ChkError::InvalidString { .. }), "{err}");
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_write_and_reparse_all_maps() {
+    let stream = get_all_test_chks();
+
+    pin_mut!(stream);
+
+    while let Some(chk) = stream.try_next().await.unwrap() {
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+        let written = ChkWriter::from(&parsed_chk);
+
+        let written_bytes = written.to_bytes();
+        let reparsed_chk = ParsedChk::from_bytes(written_bytes.as_slice());
+        let rewritten = ChkWriter::from(&reparsed_chk);
+
+        assert_eq!(written.chunks().len(), rewritten.chunks().len());
+        for ((a_name, a_data), (b_name, b_data)) in written.chunks().iter().zip(rewritten.chunks())
+        {
+            assert_eq!(a_name, b_name);
+            assert!(
+                a_data == b_data,
+                "section {:?} changed after round trip",
+                a_name
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_trigger_round_trip_on_all_maps() {
+    let stream = get_all_test_chks();
+
+    pin_mut!(stream);
+
+    while let Some(chk) = stream.try_next().await.unwrap() {
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        if let Ok(trig) = &parsed_chk.trig {
+            let triggers = parse_triggers(&parsed_chk);
+            assert_eq!(triggers.len(), trig.triggers.len());
+            for (trigger, raw) in triggers.iter().zip(&trig.triggers) {
+                assert!(trigger.to_raw() == *raw, "{trigger:?}");
+            }
+        }
+
+        if let Ok(mbrf) = &parsed_chk.mbrf {
+            let mission_briefings = parse_mission_briefing(&parsed_chk);
+            assert_eq!(mission_briefings.len(), mbrf.triggers.len());
+            for (mission_briefing, raw) in mission_briefings.iter().zip(&mbrf.triggers) {
+                assert!(mission_briefing.to_raw() == *raw, "{mission_briefing:?}");
+            }
         }
     }
 }
@@ -26,3 +26,11 @@ mod general;
 
 pub(crate) use util::get_all_test_chks;
 pub(crate) use util::get_chk;
+#[cfg(feature = "full")]
+pub(crate) use util::make_mrgn;
+#[cfg(feature = "full")]
+pub(crate) use util::make_sprp;
+pub(crate) use util::make_str;
+#[cfg(feature = "full")]
+pub(crate) use util::make_string_section;
+pub(crate) use util::make_trig;
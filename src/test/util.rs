@@ -1,3 +1,9 @@
+#[cfg(feature = "full")]
+use crate::chk::chk_mrgn::ChkMrgnIndividual;
+use crate::{
+    chk::chk_trig::{ChkTrigAction, ChkTrigCondition},
+    util::reinterpret_as_bytes,
+};
 use anyhow::Result;
 use async_stream::stream;
 use futures::Stream;
@@ -60,6 +66,70 @@ pub(crate) fn get_all_test_chks() -> impl Stream<Item = Result<Vec<u8>>> {
     }
 }
 
+// A STR section with the strings laid out one after the other right after the offset table.
+pub(crate) fn make_str<T: AsRef<[u8]>>(strings: &[T]) -> Vec<u8> {
+    make_string_section(&(strings.len() as u16).to_le_bytes(), strings, false)
+}
+
+// The header is everything before the offsets. Without properties it is laid out like STR, with them like
+// KSTR: 32 bit offsets followed by a zeroed u32 of properties for each string.
+pub(crate) fn make_string_section<T: AsRef<[u8]>>(
+    header: &[u8],
+    strings: &[T],
+    properties: bool,
+) -> Vec<u8> {
+    let offset_size = if properties { 4 } else { 2 };
+    let mut offset = header.len() + strings.len() * offset_size * (1 + properties as usize);
+    let mut ret = header.to_vec();
+
+    for string in strings {
+        ret.extend(&(offset as u32).to_le_bytes()[..offset_size]);
+        offset += string.as_ref().len() + 1;
+    }
+
+    if properties {
+        ret.extend(vec![0; strings.len() * 4]);
+    }
+
+    for string in strings {
+        ret.extend(string.as_ref());
+        ret.push(0);
+    }
+
+    ret
+}
+
+// An SPRP section with the given scenario name and description string numbers.
+#[cfg(feature = "full")]
+pub(crate) fn make_sprp(scenario_name: u16, description: u16) -> Vec<u8> {
+    [scenario_name, description]
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect()
+}
+
+// An MRGN section with the given locations first and the rest of the 64 locations zeroed.
+#[cfg(feature = "full")]
+pub(crate) fn make_mrgn(locations: &[ChkMrgnIndividual]) -> Vec<u8> {
+    assert!(locations.len() <= 64);
+
+    let mut ret = reinterpret_as_bytes(locations).to_vec();
+    ret.resize(64 * 20, 0);
+    ret
+}
+
+// A TRIG section with a single trigger made of the given conditions and actions. Everything else is zeroed,
+// so the trigger runs for no player until its player bytes at 2372 are set.
+pub(crate) fn make_trig(conditions: &[ChkTrigCondition], actions: &[ChkTrigAction]) -> Vec<u8> {
+    assert!(conditions.len() <= 16 && actions.len() <= 64);
+
+    let mut ret = reinterpret_as_bytes(conditions).to_vec();
+    ret.resize(16 * 20, 0);
+    ret.extend(reinterpret_as_bytes(actions));
+    ret.resize(2400, 0);
+    ret
+}
+
 fn hash(bytes: &[u8]) -> String {
     let mut hasher = sha2::Sha256::new();
 
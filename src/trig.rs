@@ -42,11 +42,12 @@ use serde::Serialize;
 
 use crate::{
     chk::{
-        chk_mbrf::{ChkMbrfAction, ChkMbrfCondition},
-        chk_trig::{ChkTrigAction, ChkTrigCondition},
+        chk_mbrf::{ChkMbrfAction, ChkMbrfCondition, ChkMbrfIndividual},
+        chk_trig::{ChkTrigAction, ChkTrigCondition, ChkTrigIndividual},
+        chk_uprp::ChkUprpIndividual,
     },
-    util::reinterpret_as_slice,
-    ParsedChk,
+    util::reinterpret_as_bytes,
+    ParsedChk, StringId,
 };
 use tracing::instrument;
 
@@ -346,7 +347,7 @@ static AI_SCRIPT_MAP: phf::Map<&'static [u8], &'static str> = phf::phf_map! {
     b"ZB0F" => "Brood Wars Zerg 10 - Town F",
 };
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub enum ResourceType {
     Unknown(i64),
     Ore,
@@ -355,7 +356,7 @@ pub enum ResourceType {
 }
 
 #[instrument(level = "trace")]
-fn parse_resource_type(resource_type: u16) -> ResourceType {
+pub(crate) fn parse_resource_type(resource_type: u16) -> ResourceType {
     match resource_type {
         0 => ResourceType::Ore,
         1 => ResourceType::Gas,
@@ -364,7 +365,17 @@ fn parse_resource_type(resource_type: u16) -> ResourceType {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[instrument(level = "trace")]
+fn serialize_resource_type(resource_type: &ResourceType) -> u16 {
+    match resource_type {
+        ResourceType::Ore => 0,
+        ResourceType::Gas => 1,
+        ResourceType::OreAndGas => 10,
+        ResourceType::Unknown(x) => *x as u16,
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub enum NumericComparison {
     Unknown(i64),
     AtLeast,
@@ -373,7 +384,7 @@ pub enum NumericComparison {
 }
 
 #[instrument(level = "trace")]
-fn parse_numeric_comparison(numeric_comparison: u8) -> NumericComparison {
+pub(crate) fn parse_numeric_comparison(numeric_comparison: u8) -> NumericComparison {
     match numeric_comparison {
         0 => NumericComparison::AtLeast,
         1 => NumericComparison::AtMost,
@@ -382,7 +393,17 @@ fn parse_numeric_comparison(numeric_comparison: u8) -> NumericComparison {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[instrument(level = "trace")]
+fn serialize_numeric_comparison(numeric_comparison: &NumericComparison) -> u8 {
+    match numeric_comparison {
+        NumericComparison::AtLeast => 0,
+        NumericComparison::AtMost => 1,
+        NumericComparison::Exactly => 10,
+        NumericComparison::Unknown(x) => *x as u8,
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub enum NumberModifier {
     Unknown(i64),
     SetTo,
@@ -391,7 +412,7 @@ pub enum NumberModifier {
 }
 
 #[instrument(level = "trace")]
-fn parse_number_modifier(modifier: u8) -> NumberModifier {
+pub(crate) fn parse_number_modifier(modifier: u8) -> NumberModifier {
     match modifier {
         7 => NumberModifier::SetTo,
         8 => NumberModifier::Add,
@@ -400,7 +421,17 @@ fn parse_number_modifier(modifier: u8) -> NumberModifier {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[instrument(level = "trace")]
+fn serialize_number_modifier(modifier: &NumberModifier) -> u8 {
+    match modifier {
+        NumberModifier::SetTo => 7,
+        NumberModifier::Add => 8,
+        NumberModifier::Subtract => 9,
+        NumberModifier::Unknown(x) => *x as u8,
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub enum ScoreType {
     Unknown(i64),
     Total,
@@ -413,7 +444,7 @@ pub enum ScoreType {
     Custom,
 }
 
-fn parse_score_type(score_type: u16) -> ScoreType {
+pub(crate) fn parse_score_type(score_type: u16) -> ScoreType {
     match score_type {
         0 => ScoreType::Total,
         1 => ScoreType::Units,
@@ -427,7 +458,21 @@ fn parse_score_type(score_type: u16) -> ScoreType {
     }
 }
 
-#[derive(Debug, Serialize)]
+fn serialize_score_type(score_type: &ScoreType) -> u16 {
+    match score_type {
+        ScoreType::Total => 0,
+        ScoreType::Units => 1,
+        ScoreType::Buildings => 2,
+        ScoreType::UnitsAndBuildings => 3,
+        ScoreType::Kills => 4,
+        ScoreType::Razings => 5,
+        ScoreType::KillsAndRazings => 6,
+        ScoreType::Custom => 7,
+        ScoreType::Unknown(x) => *x as u16,
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub enum SwitchState {
     Unknown(i64),
     Set,
@@ -435,7 +480,7 @@ pub enum SwitchState {
 }
 
 #[instrument(level = "trace")]
-fn parse_switch_state(switch_state: u8) -> SwitchState {
+pub(crate) fn parse_switch_state(switch_state: u8) -> SwitchState {
     match switch_state {
         2 => SwitchState::Set,
         3 => SwitchState::Cleared,
@@ -443,7 +488,16 @@ fn parse_switch_state(switch_state: u8) -> SwitchState {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[instrument(level = "trace")]
+fn serialize_switch_state(switch_state: &SwitchState) -> u8 {
+    match switch_state {
+        SwitchState::Set => 2,
+        SwitchState::Cleared => 3,
+        SwitchState::Unknown(x) => *x as u8,
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub enum Order {
     Unknown(i64),
     Move,
@@ -452,7 +506,7 @@ pub enum Order {
 }
 
 #[instrument(level = "trace")]
-fn parse_order(order: u8) -> Order {
+pub(crate) fn parse_order(order: u8) -> Order {
     match order {
         0 => Order::Move,
         1 => Order::Patrol,
@@ -461,7 +515,17 @@ fn parse_order(order: u8) -> Order {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[instrument(level = "trace")]
+fn serialize_order(order: &Order) -> u8 {
+    match order {
+        Order::Move => 0,
+        Order::Patrol => 1,
+        Order::Attack => 2,
+        Order::Unknown(x) => *x as u8,
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub enum ActionState {
     Unknown(i64),
     EnabledOrSet,
@@ -471,7 +535,7 @@ pub enum ActionState {
 }
 
 #[instrument(level = "trace")]
-fn parse_action_state(action_state: u8) -> ActionState {
+pub(crate) fn parse_action_state(action_state: u8) -> ActionState {
     match action_state {
         4 => ActionState::EnabledOrSet,
         5 => ActionState::DisabledOrClear,
@@ -482,20 +546,29 @@ fn parse_action_state(action_state: u8) -> ActionState {
 }
 
 #[instrument(level = "trace")]
-fn parse_ai_script(aiscript_id: u32) -> &'static str {
-    if let Ok(x) = reinterpret_as_slice(&aiscript_id) {
-        AI_SCRIPT_MAP
-            .get(x)
-            .cloned()
-            .unwrap_or("Failed to find ai script")
-    } else {
-        ""
+fn serialize_action_state(action_state: &ActionState) -> u8 {
+    match action_state {
+        ActionState::EnabledOrSet => 4,
+        ActionState::DisabledOrClear => 5,
+        ActionState::ToggleOrToggle => 6,
+        ActionState::RandomizeSwitch => 11,
+        ActionState::Unknown(x) => *x as u8,
+    }
+}
+
+// The 4 byte id of an AI script, e.g. "TMCu".
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct AiScript(pub u32);
+
+impl AiScript {
+    pub fn name(&self) -> Option<&'static str> {
+        AI_SCRIPT_MAP.get(&self.0.to_le_bytes()[..]).cloned()
     }
 }
 
 //AI_SCRIPT_MAP
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub enum AllianceStatus {
     Unknown(i64),
     Enemy,
@@ -503,7 +576,7 @@ pub enum AllianceStatus {
     AlliedVictory,
 }
 
-fn parse_alliance_status(alliance_status: u16) -> AllianceStatus {
+pub(crate) fn parse_alliance_status(alliance_status: u16) -> AllianceStatus {
     match alliance_status {
         0 => AllianceStatus::Enemy,
         1 => AllianceStatus::Allied,
@@ -512,7 +585,16 @@ fn parse_alliance_status(alliance_status: u16) -> AllianceStatus {
     }
 }
 
-#[derive(Debug, Serialize)]
+fn serialize_alliance_status(alliance_status: &AllianceStatus) -> u16 {
+    match alliance_status {
+        AllianceStatus::Enemy => 0,
+        AllianceStatus::Allied => 1,
+        AllianceStatus::AlliedVictory => 2,
+        AllianceStatus::Unknown(x) => *x as u16,
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub enum UnitType {
     Unknown(i64),
     TerranMarine,
@@ -750,7 +832,7 @@ pub enum UnitType {
     Factories,
 }
 
-fn parse_unit_type(unit_type: u16) -> UnitType {
+pub(crate) fn parse_unit_type(unit_type: u16) -> UnitType {
     match unit_type {
         0 => UnitType::TerranMarine,
         1 => UnitType::TerranGhost,
@@ -989,7 +1071,246 @@ fn parse_unit_type(unit_type: u16) -> UnitType {
     }
 }
 
-#[derive(Debug, Serialize)]
+pub(crate) fn serialize_unit_type(unit_type: &UnitType) -> u16 {
+    match unit_type {
+        UnitType::TerranMarine => 0,
+        UnitType::TerranGhost => 1,
+        UnitType::TerranVulture => 2,
+        UnitType::TerranGoliath => 3,
+        UnitType::GoliathTurret => 4,
+        UnitType::TerranSiegeTankTankMode => 5,
+        UnitType::TankTurretTankMode => 6,
+        UnitType::TerranSCV => 7,
+        UnitType::TerranWraith => 8,
+        UnitType::TerranScienceVessel => 9,
+        UnitType::GuiMontangFirebat => 10,
+        UnitType::TerranDropship => 11,
+        UnitType::TerranBattlecruiser => 12,
+        UnitType::VultureSpiderMine => 13,
+        UnitType::NuclearMissile => 14,
+        UnitType::TerranCivilian => 15,
+        UnitType::SarahKerriganGhost => 16,
+        UnitType::AlanSchezarGoliath => 17,
+        UnitType::AlanSchezarTurret => 18,
+        UnitType::JimRaynorVulture => 19,
+        UnitType::JimRaynorMarine => 20,
+        UnitType::TomKazanskyWraith => 21,
+        UnitType::MagellanScienceVessel => 22,
+        UnitType::EdmundDukeSiegeTank => 23,
+        UnitType::EdmundDukeTurretTankMode => 24,
+        UnitType::EdmundDukeSiegeMode => 25,
+        UnitType::EdmundDukeTurretSiegeMode => 26,
+        UnitType::ArcturusMengskBattlecruiser => 27,
+        UnitType::HyperionBattlecruiser => 28,
+        UnitType::NoradIIBattlecruiser => 29,
+        UnitType::TerranSiegeTankSiegeMode => 30,
+        UnitType::TankTurretSiegeMode => 31,
+        UnitType::Firebat => 32,
+        UnitType::ScannerSweep => 33,
+        UnitType::TerranMedic => 34,
+        UnitType::ZergLarva => 35,
+        UnitType::ZergEgg => 36,
+        UnitType::ZergZergling => 37,
+        UnitType::ZergHydralisk => 38,
+        UnitType::ZergUltralisk => 39,
+        UnitType::ZergBroodling => 40,
+        UnitType::ZergDrone => 41,
+        UnitType::ZergOverlord => 42,
+        UnitType::ZergMutalisk => 43,
+        UnitType::ZergGuardian => 44,
+        UnitType::ZergQueen => 45,
+        UnitType::ZergDefiler => 46,
+        UnitType::ZergScourge => 47,
+        UnitType::TorrarsqueUltralisk => 48,
+        UnitType::MatriarchQueen => 49,
+        UnitType::InfestedTerran => 50,
+        UnitType::InfestedKerrigan => 51,
+        UnitType::UncleanOneDefiler => 52,
+        UnitType::HunterKillerHydralisk => 53,
+        UnitType::DevouringOneZergling => 54,
+        UnitType::KukulzaMutalisk => 55,
+        UnitType::KukulzaGuardian => 56,
+        UnitType::YggdrasillOverlord => 57,
+        UnitType::TerranValkyrie => 58,
+        UnitType::MutaliskOrGuardianCocoon => 59,
+        UnitType::ProtossCorsair => 60,
+        UnitType::ProtossDarkTemplar => 61,
+        UnitType::ZergDevourer => 62,
+        UnitType::ProtossDarkArchon => 63,
+        UnitType::ProtossProbe => 64,
+        UnitType::ProtossZealot => 65,
+        UnitType::ProtossDragoon => 66,
+        UnitType::ProtossHighTemplar => 67,
+        UnitType::ProtossArchon => 68,
+        UnitType::ProtossShuttle => 69,
+        UnitType::ProtossScout => 70,
+        UnitType::ProtossArbiter => 71,
+        UnitType::ProtossCarrier => 72,
+        UnitType::ProtossInterceptor => 73,
+        UnitType::DarkTemplarHero => 74,
+        UnitType::ZeratulDarkTemplar => 75,
+        UnitType::TassadarAndZeratulArchon => 76,
+        UnitType::FenixZealot => 77,
+        UnitType::FenixDragoon => 78,
+        UnitType::TassadarTemplar => 79,
+        UnitType::MojoScout => 80,
+        UnitType::WarbringerReaver => 81,
+        UnitType::GantrithorCarrier => 82,
+        UnitType::ProtossReaver => 83,
+        UnitType::ProtossObserver => 84,
+        UnitType::ProtossScarab => 85,
+        UnitType::DanimothArbiter => 86,
+        UnitType::AldarisTemplar => 87,
+        UnitType::ArtanisScout => 88,
+        UnitType::RhynadonBadlandsCritter => 89,
+        UnitType::BengalaasJungleCritter => 90,
+        UnitType::UnusedWasCargoShip => 91,
+        UnitType::UnusedWasMercenaryGunship => 92,
+        UnitType::ScantidDesertCritter => 93,
+        UnitType::KakaruTwilightCritter => 94,
+        UnitType::RagnasaurAshworldCritter => 95,
+        UnitType::UrsadonIceWorldCritter => 96,
+        UnitType::LurkerEgg => 97,
+        UnitType::Raszagal => 98,
+        UnitType::SamirDuranGhost => 99,
+        UnitType::AlexeiStukovGhost => 100,
+        UnitType::MapRevealer => 101,
+        UnitType::GerardDuGalle => 102,
+        UnitType::ZergLurker => 103,
+        UnitType::InfestedDuran => 104,
+        UnitType::DisruptionWeb => 105,
+        UnitType::TerranCommandCenter => 106,
+        UnitType::TerranComsatStation => 107,
+        UnitType::TerranNuclearSilo => 108,
+        UnitType::TerranSupplyDepot => 109,
+        UnitType::TerranRefinery => 110,
+        UnitType::TerranBarracks => 111,
+        UnitType::TerranAcademy => 112,
+        UnitType::TerranFactory => 113,
+        UnitType::TerranStarport => 114,
+        UnitType::TerranControlTower => 115,
+        UnitType::TerranScienceFacility => 116,
+        UnitType::TerranCovertOps => 117,
+        UnitType::TerranPhysicsLab => 118,
+        UnitType::UnusedWasStarbase => 119,
+        UnitType::TerranMachineShop => 120,
+        UnitType::UnusedWasRepairBay => 121,
+        UnitType::TerranEngineeringBay => 122,
+        UnitType::TerranArmory => 123,
+        UnitType::TerranMissileTurret => 124,
+        UnitType::TerranBunker => 125,
+        UnitType::NoradII => 126,
+        UnitType::IonCannon => 127,
+        UnitType::UrajCrystal => 128,
+        UnitType::KhalisCrystal => 129,
+        UnitType::InfestedCommandCenter => 130,
+        UnitType::ZergHatchery => 131,
+        UnitType::ZergLair => 132,
+        UnitType::ZergHive => 133,
+        UnitType::ZergNydusCanal => 134,
+        UnitType::ZergHydraliskDen => 135,
+        UnitType::ZergDefilerMound => 136,
+        UnitType::ZergGreaterSpire => 137,
+        UnitType::ZergQueensNest => 138,
+        UnitType::ZergEvolutionChamber => 139,
+        UnitType::ZergUltraliskCavern => 140,
+        UnitType::ZergSpire => 141,
+        UnitType::ZergSpawningPool => 142,
+        UnitType::ZergCreepColony => 143,
+        UnitType::ZergSporeColony => 144,
+        UnitType::UnusedZergBuilding => 145,
+        UnitType::ZergSunkenColony => 146,
+        UnitType::ZergOvermindWithShell => 147,
+        UnitType::ZergOvermind => 148,
+        UnitType::ZergExtractor => 149,
+        UnitType::MatureChrysalis => 150,
+        UnitType::ZergCerebrate => 151,
+        UnitType::ZergCerebrateDaggoth => 152,
+        UnitType::UnusedZergBuilding5 => 153,
+        UnitType::ProtossNexus => 154,
+        UnitType::ProtossRoboticsFacility => 155,
+        UnitType::ProtossPylon => 156,
+        UnitType::ProtossAssimilator => 157,
+        UnitType::UnusedProtossBuilding1 => 158,
+        UnitType::ProtossObservatory => 159,
+        UnitType::ProtossGateway => 160,
+        UnitType::UnusedProtossBuilding2 => 161,
+        UnitType::ProtossPhotonCannon => 162,
+        UnitType::ProtossCitadelofAdun => 163,
+        UnitType::ProtossCyberneticsCore => 164,
+        UnitType::ProtossTemplarArchives => 165,
+        UnitType::ProtossForge => 166,
+        UnitType::ProtossStargate => 167,
+        UnitType::StasisCellPrison => 168,
+        UnitType::ProtossFleetBeacon => 169,
+        UnitType::ProtossArbiterTribunal => 170,
+        UnitType::ProtossRoboticsSupportBay => 171,
+        UnitType::ProtossShieldBattery => 172,
+        UnitType::KhaydarinCrystalFormation => 173,
+        UnitType::ProtossTemple => 174,
+        UnitType::XelNagaTemple => 175,
+        UnitType::MineralFieldType1 => 176,
+        UnitType::MineralFieldType2 => 177,
+        UnitType::MineralFieldType3 => 178,
+        UnitType::Cave => 179,
+        UnitType::Cavein => 180,
+        UnitType::Cantina => 181,
+        UnitType::MiningPlatform => 182,
+        UnitType::IndependantCommandCenter => 183,
+        UnitType::IndependantStarport => 184,
+        UnitType::IndependantJumpGate => 185,
+        UnitType::Ruins => 186,
+        UnitType::KyadarinCrystalFormation => 187,
+        UnitType::VespeneGeyser => 188,
+        UnitType::WarpGate => 189,
+        UnitType::PSIDisruptor => 190,
+        UnitType::ZergMarker => 191,
+        UnitType::TerranMarker => 192,
+        UnitType::ProtossMarker => 193,
+        UnitType::ZergBeacon => 194,
+        UnitType::TerranBeacon => 195,
+        UnitType::ProtossBeacon => 196,
+        UnitType::ZergFlagBeacon => 197,
+        UnitType::TerranFlagBeacon => 198,
+        UnitType::ProtossFlagBeacon => 199,
+        UnitType::PowerGenerator => 200,
+        UnitType::OvermindCocoon => 201,
+        UnitType::DarkSwarm => 202,
+        UnitType::FloorMissileTrap => 203,
+        UnitType::FloorHatch => 204,
+        UnitType::LeftUpperLevelDoor => 205,
+        UnitType::RightUpperLevelDoor => 206,
+        UnitType::LeftPitDoor => 207,
+        UnitType::RightPitDoor => 208,
+        UnitType::FloorGunTrap => 209,
+        UnitType::LeftWallMissileTrap => 210,
+        UnitType::LeftWallFlameTrap => 211,
+        UnitType::RightWallMissileTrap => 212,
+        UnitType::RightWallFlameTrap => 213,
+        UnitType::StartLocation => 214,
+        UnitType::Flag => 215,
+        UnitType::YoungChrysalis => 216,
+        UnitType::PsiEmitter => 217,
+        UnitType::DataDisc => 218,
+        UnitType::KhaydarinCrystal => 219,
+        UnitType::MineralClusterType1 => 220,
+        UnitType::MineralClusterType2 => 221,
+        UnitType::ProtossVespeneGasOrbType1 => 222,
+        UnitType::ProtossVespeneGasOrbType2 => 223,
+        UnitType::ZergVespeneGasSacType1 => 224,
+        UnitType::ZergVespeneGasSacType2 => 225,
+        UnitType::TerranVespeneGasTankType1 => 226,
+        UnitType::TerranVespeneGasTankType2 => 227,
+        UnitType::None => 228,
+        UnitType::AnyUnit => 229,
+        UnitType::Men => 230,
+        UnitType::Buildings => 231,
+        UnitType::Factories => 232,
+        UnitType::Unknown(x) => *x as u16,
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub enum Group {
     Unknown(i64),
     Player1,
@@ -1022,7 +1343,7 @@ pub enum Group {
 }
 
 #[instrument(level = "trace")]
-fn parse_group(group: u32) -> Group {
+pub(crate) fn parse_group(group: u32) -> Group {
     match group {
         0 => Group::Player1,
         1 => Group::Player2,
@@ -1055,14 +1376,118 @@ fn parse_group(group: u32) -> Group {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[instrument(level = "trace")]
+pub(crate) fn serialize_group(group: &Group) -> u32 {
+    match group {
+        Group::Player1 => 0,
+        Group::Player2 => 1,
+        Group::Player3 => 2,
+        Group::Player4 => 3,
+        Group::Player5 => 4,
+        Group::Player6 => 5,
+        Group::Player7 => 6,
+        Group::Player8 => 7,
+        Group::Player9 => 8,
+        Group::Player10 => 9,
+        Group::Player11 => 10,
+        Group::Player12 => 11,
+        Group::None => 12,
+        Group::CurrentPlayer => 13,
+        Group::Foes => 14,
+        Group::Allies => 15,
+        Group::NeutralPlayers => 16,
+        Group::AllPlayers => 17,
+        Group::Force1 => 18,
+        Group::Force2 => 19,
+        Group::Force3 => 20,
+        Group::Force4 => 21,
+        Group::Unused1 => 22,
+        Group::Unused2 => 23,
+        Group::Unused3 => 24,
+        Group::Unused4 => 25,
+        Group::NonAlliedVictoryPlayers => 26,
+        Group::Unknown(x) => *x as u32,
+    }
+}
+
+// 1 based index into MRGN, 0 means no location.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LocationId(pub u32);
+
+// The flags byte of a condition or action.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TriggerFlags {
+    // Bit 0, only used by actions.
+    pub ignore_wait_once: bool,
+    // Bit 1
+    pub disabled: bool,
+    // Bit 2, text is shown even if subtitles are turned off.
+    pub always_display: bool,
+    // Bit 3
+    pub unit_properties_used: bool,
+    // Bit 4, cleared in "Offset + Mask" EUDs.
+    pub unit_type_used: bool,
+    // Bits 5 to 7 shifted down.
+    pub unknown: u8,
+}
+
+impl TriggerFlags {
+    pub fn bits(&self) -> u8 {
+        (self.ignore_wait_once as u8)
+            | (self.disabled as u8) << 1
+            | (self.always_display as u8) << 2
+            | (self.unit_properties_used as u8) << 3
+            | (self.unit_type_used as u8) << 4
+            | self.unknown << 5
+    }
+}
+
+#[instrument(level = "trace")]
+fn parse_trigger_flags(flags: u8) -> TriggerFlags {
+    TriggerFlags {
+        ignore_wait_once: flags & 0x01 != 0,
+        disabled: flags & 0x02 != 0,
+        always_display: flags & 0x04 != 0,
+        unit_properties_used: flags & 0x08 != 0,
+        unit_type_used: flags & 0x10 != 0,
+        unknown: flags >> 5,
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum MaskFlag {
+    Unknown(i64),
+    None,
+    // "SC", the location field of a Deaths condition or SetDeaths action is a bitmask.
+    Eud,
+}
+
+#[instrument(level = "trace")]
+fn parse_mask_flag(mask_flag: u16) -> MaskFlag {
+    match mask_flag {
+        0 => MaskFlag::None,
+        0x4353 => MaskFlag::Eud,
+        _ => MaskFlag::Unknown(mask_flag as i64),
+    }
+}
+
+#[instrument(level = "trace")]
+fn serialize_mask_flag(mask_flag: &MaskFlag) -> u16 {
+    match mask_flag {
+        MaskFlag::None => 0,
+        MaskFlag::Eud => 0x4353,
+        MaskFlag::Unknown(x) => *x as u16,
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 #[serde(tag = "type")]
 pub enum Condition {
     Unknown {
         id: i64,
         raw: ChkTrigCondition,
     },
-    // 0
+    // 0, only kept if the rest of the condition is not empty.
     NoCondition,
     // 1
     CountdownTimer {
@@ -1081,7 +1506,7 @@ pub enum Condition {
         player: Group,
         comparison: NumericComparison,
         unit_type: UnitType,
-        location: String,
+        location: LocationId,
         number: i64,
     },
     // 4
@@ -1106,7 +1531,7 @@ pub enum Condition {
     // 7
     CommandsTheMostAt {
         unit_type: UnitType,
-        location: String,
+        location: LocationId,
     },
     // 8
     MostKills {
@@ -1145,6 +1570,8 @@ pub enum Condition {
         unit_type: UnitType,
         number: i64,
         eud_offset: u32,
+        // Only set if the mask flag is "SC".
+        mask: Option<u32>,
     },
     // 16
     CommandsTheLeast {
@@ -1153,7 +1580,7 @@ pub enum Condition {
     // 17
     CommandsTheLeastAt {
         unit_type: UnitType,
-        location: String,
+        location: LocationId,
     },
     // 18
     LeastKills {
@@ -1180,14 +1607,14 @@ pub enum Condition {
     Never,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 #[serde(tag = "type")]
 pub enum Action {
     Unknown {
         id: i64,
         raw: ChkTrigAction,
     },
-    // 0
+    // 0, only kept if the rest of the action is not empty.
     NoAction,
     // 1
     Victory,
@@ -1205,38 +1632,41 @@ pub enum Action {
     UnpauseGame,
     // 7
     Transmission {
-        text: String,
+        text: StringId,
         unit_type: UnitType,
-        location: String,
+        location: LocationId,
+        // Milliseconds the duration of the transmission is modified by.
         time: i64,
         modifier: NumberModifier,
-        wave: String,
+        wave: StringId,
         wave_time: i64,
     },
     // 8
     PlayWav {
-        wave: String,
+        wave: StringId,
         wave_time: i64,
     },
     // 9
     DisplayTextMessage {
-        text: String,
+        text: StringId,
     },
     // 10
     CenterView {
-        location: String,
+        location: LocationId,
     },
     // 11
     CreateUnitWithProperties {
         player: Group,
         unit_type: UnitType,
         number: i64,
-        location: String,
+        location: LocationId,
+        // 1 based CUWP slot.
         unit_prop: i64,
+        properties: Option<ChkUprpIndividual>,
     },
     // 12
     SetMissionObjectives {
-        text: String,
+        text: StringId,
     },
     // 13
     SetSwitch {
@@ -1250,37 +1680,37 @@ pub enum Action {
     },
     // 15
     RunAIScript {
-        script: &'static str,
+        script: AiScript,
     },
     // 16
     RunAIScriptAtLocation {
-        script: &'static str,
-        location: String,
+        script: AiScript,
+        location: LocationId,
     },
     // 17
     LeaderBoardControl {
-        text: String,
+        text: StringId,
         unit_type: UnitType,
     },
     // 18
     LeaderBoardControlAtLocation {
-        text: String,
+        text: StringId,
         unit_type: UnitType,
-        location: String,
+        location: LocationId,
     },
     // 19
     LeaderBoardResources {
-        text: String,
+        text: StringId,
         resource_type: ResourceType,
     },
     // 20
     LeaderBoardKills {
-        text: String,
+        text: StringId,
         unit_type: UnitType,
     },
     // 21
     LeaderBoardPoints {
-        text: String,
+        text: StringId,
         score_type: ScoreType,
     },
     // 22
@@ -1289,11 +1719,12 @@ pub enum Action {
         unit_type: UnitType,
     },
     // 23
+    // A `number` of 0 means all units, here and in the other actions that affect some units.
     KillUnitAtLocation {
         player: Group,
         unit_type: UnitType,
         number: i64,
-        location: String,
+        location: LocationId,
     },
     // 24
     RemoveAllUnits {
@@ -1305,7 +1736,7 @@ pub enum Action {
         player: Group,
         unit_type: UnitType,
         number: i64,
-        location: String,
+        location: LocationId,
     },
     // 26
     SetResources {
@@ -1323,7 +1754,7 @@ pub enum Action {
     },
     // 28
     MinimapPing {
-        location: String,
+        location: LocationId,
     },
     // 29
     TalkingPortrait {
@@ -1339,45 +1770,52 @@ pub enum Action {
         action: ActionState,
     },
     // 33
+    // `number` is the goal, here and in the other LeaderBoardGoal actions.
     LeaderBoardGoalControl {
-        text: String,
+        text: StringId,
+        number: i64,
         unit_type: UnitType,
     },
     // 34
     LeaderBoardGoalControlAtLocation {
-        text: String,
+        text: StringId,
+        number: i64,
         unit_type: UnitType,
-        location: String,
+        location: LocationId,
     },
     // 35
     LeaderBoardGoalResources {
-        text: String,
+        text: StringId,
+        number: i64,
         resource_type: ResourceType,
     },
     // 36
     LeaderBoardGoalKills {
-        text: String,
+        text: StringId,
+        number: i64,
         unit_type: UnitType,
     },
     // 37
     LeaderBoardGoalPoints {
-        text: String,
+        text: StringId,
+        number: i64,
         score_type: ScoreType,
     },
     // 38
+    // Centers `destination_location` on the units at `source_location`.
     MoveLocation {
         player: Group,
         unit_type: UnitType,
-        source_location: String,
-        destination_location: String,
+        source_location: LocationId,
+        destination_location: LocationId,
     },
     // 39
     MoveUnit {
         player: Group,
         unit_type: UnitType,
         number: i64,
-        source_location: String,
-        destination_location: String,
+        source_location: LocationId,
+        destination_location: LocationId,
     },
     // 40
     LeaderboardGreed {
@@ -1385,20 +1823,20 @@ pub enum Action {
     },
     // 41
     SetNextScenario {
-        text: String,
+        text: StringId,
     },
     // 42
     SetDoodadState {
         player: Group,
         unit_type: UnitType,
-        location: String,
+        location: LocationId,
         state: ActionState,
     },
     // 43
     SetInvincibility {
         player: Group,
         unit_type: UnitType,
-        location: String,
+        location: LocationId,
         state: ActionState,
     },
     // 44
@@ -1406,7 +1844,7 @@ pub enum Action {
         player: Group,
         unit_type: UnitType,
         number: i64,
-        location: String,
+        location: LocationId,
     },
     // 45
     SetDeaths {
@@ -1415,18 +1853,20 @@ pub enum Action {
         number: i64,
         modifier: NumberModifier,
         eud_offset: u32,
+        // Only set if the mask flag is "SC".
+        mask: Option<u32>,
     },
     // 46
     Order {
         player: Group,
         unit_type: UnitType,
-        source_location: String,
-        destination_location: String,
+        source_location: LocationId,
+        destination_location: LocationId,
         order: Order,
     },
     // 47
     Comment {
-        text: String,
+        text: StringId,
     },
     // 48
     GiveUnitsToPlayer {
@@ -1434,47 +1874,49 @@ pub enum Action {
         destination_player: Group,
         unit_type: UnitType,
         number: i64,
-        location: String,
+        location: LocationId,
     },
     // 49
+    // The Modify actions set `amount` on `number` units. It is a percentage for hit points, energy and
+    // shields.
     ModifyUnitHitPoints {
         player: Group,
         unit_type: UnitType,
         number: i64,
-        mod_ammount: i64,
-        location: String,
+        amount: i64,
+        location: LocationId,
     },
     // 50
     ModifyUnitEnergy {
         player: Group,
         unit_type: UnitType,
         number: i64,
-        mod_ammount: i64,
-        location: String,
+        amount: i64,
+        location: LocationId,
     },
     // 51
     ModifyUnitShieldPoints {
         player: Group,
         unit_type: UnitType,
         number: i64,
-        mod_ammount: i64,
-        location: String,
+        amount: i64,
+        location: LocationId,
     },
     // 52
     ModifyUnitResource {
         player: Group,
         unit_type: UnitType,
         number: i64,
-        mod_ammount: i64,
-        location: String,
+        amount: i64,
+        location: LocationId,
     },
     // 53
     ModifyUnitHangarCount {
         player: Group,
         unit_type: UnitType,
         number: i64,
-        mod_ammount: i64,
-        location: String,
+        amount: i64,
+        location: LocationId,
     },
     // 54
     PauseTimer,
@@ -1493,7 +1935,7 @@ pub enum Action {
     EnableDebugMode,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 #[serde(tag = "type")]
 pub enum MissionBriefingCondition {
     Unknown { id: i64, raw: ChkMbrfCondition },
@@ -1503,7 +1945,7 @@ pub enum MissionBriefingCondition {
     DataIsAMissionBriefing,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 #[serde(tag = "type")]
 pub enum MissionBriefingAction {
     Unknown {
@@ -1518,16 +1960,16 @@ pub enum MissionBriefingAction {
     },
     // 2
     PlayWav {
-        wave: String,
+        wave: StringId,
         wave_time: i64,
     },
     // 3
     DisplayTextMessage {
-        text: String,
+        text: StringId,
     },
     // 4
     SetMissionObjectives {
-        text: String,
+        text: StringId,
     },
     // 5
     ShowPortrait {
@@ -1540,844 +1982,1813 @@ pub enum MissionBriefingAction {
     },
     // 7
     DisplaySpeakingPortrait {
-        unit_type: UnitType,
         slot: i64,
+        // Milliseconds.
+        time: i64,
     },
     // 8
     DisplayTransmission {
-        text: String,
+        text: StringId,
         slot: i64,
         time: i64,
         modifier: NumberModifier,
-        wave: String,
+        wave: StringId,
         wave_time: i64,
     },
     // 9
     SkipTutorialEnabled,
 }
 
-#[derive(Debug, Serialize)]
+// A condition along with the parts of its slot that are not specific to the condition. Slots that are
+// entirely zero are skipped, everything else is kept so `to_raw` gives back the exact bytes.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct MissionBriefingConditionEntry {
+    pub index: usize,
+    pub condition: MissionBriefingCondition,
+    pub flags: TriggerFlags,
+    pub mask_flag: MaskFlag,
+    // The slot as it was read, including the fields the condition does not use.
+    pub raw: ChkMbrfCondition,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct MissionBriefingActionEntry {
+    pub index: usize,
+    pub action: MissionBriefingAction,
+    pub flags: TriggerFlags,
+    pub mask_flag: MaskFlag,
+    pub raw: ChkMbrfAction,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 pub struct MissionBriefing {
-    pub conditions: Vec<MissionBriefingCondition>,
-    pub actions: Vec<MissionBriefingAction>,
+    pub conditions: Vec<MissionBriefingConditionEntry>,
+    pub actions: Vec<MissionBriefingActionEntry>,
     pub execution_flags: u32,
     pub activated_for_players: [u8; 27],
     pub index_of_current_action: u8,
 }
 
-#[instrument(skip_all)]
-pub fn parse_mission_briefing<'a>(parsed_chk: &ParsedChk<'a>) -> Vec<MissionBriefing> {
-    let mut ret = Vec::new();
-
-    if let Ok(trig) = &parsed_chk.mbrf {
-        for trigger in &trig.triggers {
-            let mut conditions = Vec::new();
-            for condition in trigger.conditions {
-                match condition.condition {
-                    0 => {
-                        //conditions.push(MissionBriefingCondition::NoCondition);
-                    }
-                    13 => {
-                        conditions.push(MissionBriefingCondition::DataIsAMissionBriefing);
-                    }
-                    _ => {
-                        conditions.push(MissionBriefingCondition::Unknown {
-                            id: condition.condition as i64,
-                            raw: condition,
-                        });
-                    }
-                }
+#[instrument(level = "trace", skip_all)]
+fn parse_mission_briefing_condition(condition: ChkMbrfCondition) -> MissionBriefingCondition {
+    match condition.condition {
+        0 => MissionBriefingCondition::NoCondition,
+        13 => MissionBriefingCondition::DataIsAMissionBriefing,
+        _ => MissionBriefingCondition::Unknown {
+            id: condition.condition as i64,
+            raw: condition,
+        },
+    }
+}
+
+fn serialize_mission_briefing_condition(
+    mission_briefing_condition: &MissionBriefingCondition,
+    raw: &mut ChkMbrfCondition,
+) {
+    match mission_briefing_condition {
+        MissionBriefingCondition::NoCondition => raw.condition = 0,
+        MissionBriefingCondition::DataIsAMissionBriefing => raw.condition = 13,
+        MissionBriefingCondition::Unknown { id, raw: x } => {
+            *raw = *x;
+            raw.condition = *id as u8;
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+pub(crate) fn parse_mission_briefing_action(action: ChkMbrfAction) -> MissionBriefingAction {
+    match action.action {
+        0 => MissionBriefingAction::NoAction,
+        1 => MissionBriefingAction::Wait {
+            time: action.seconds_or_milliseconds as i64,
+        },
+        2 => MissionBriefingAction::PlayWav {
+            wave: StringId(action.wav_string_number),
+            wave_time: action.seconds_or_milliseconds as i64,
+        },
+        3 => MissionBriefingAction::DisplayTextMessage {
+            text: StringId(action.string_number),
+        },
+        4 => MissionBriefingAction::SetMissionObjectives {
+            text: StringId(action.string_number),
+        },
+        5 => MissionBriefingAction::ShowPortrait {
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+            slot: action.first_or_only_group_or_player_affected as i64,
+        },
+        6 => MissionBriefingAction::HidePortrait {
+            slot: action.first_or_only_group_or_player_affected as i64,
+        },
+        7 => MissionBriefingAction::DisplaySpeakingPortrait {
+            slot: action.first_or_only_group_or_player_affected as i64,
+            time: action.seconds_or_milliseconds as i64,
+        },
+        8 => MissionBriefingAction::DisplayTransmission {
+            slot: action.first_or_only_group_or_player_affected as i64,
+            text: StringId(action.string_number),
+            time: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            modifier: parse_number_modifier(
+                action.number_of_units_or_action_state_or_unit_order_or_number_modifier,
+            ),
+            wave: StringId(action.wav_string_number),
+            wave_time: action.seconds_or_milliseconds as i64,
+        },
+        9 => MissionBriefingAction::SkipTutorialEnabled,
+        _ => MissionBriefingAction::Unknown {
+            id: action.action as i64,
+            raw: action,
+        },
+    }
+}
+
+fn serialize_mission_briefing_action(
+    mission_briefing_action: &MissionBriefingAction,
+    raw: &mut ChkMbrfAction,
+) {
+    match mission_briefing_action {
+        MissionBriefingAction::NoAction => raw.action = 0,
+        MissionBriefingAction::Wait { time } => {
+            raw.action = 1;
+            raw.seconds_or_milliseconds = *time as u32;
+        }
+        MissionBriefingAction::PlayWav { wave, wave_time } => {
+            raw.action = 2;
+            raw.wav_string_number = wave.0;
+            raw.seconds_or_milliseconds = *wave_time as u32;
+        }
+        MissionBriefingAction::DisplayTextMessage { text } => {
+            raw.action = 3;
+            raw.string_number = text.0;
+        }
+        MissionBriefingAction::SetMissionObjectives { text } => {
+            raw.action = 4;
+            raw.string_number = text.0;
+        }
+        MissionBriefingAction::ShowPortrait { unit_type, slot } => {
+            raw.action = 5;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.first_or_only_group_or_player_affected = *slot as u32;
+        }
+        MissionBriefingAction::HidePortrait { slot } => {
+            raw.action = 6;
+            raw.first_or_only_group_or_player_affected = *slot as u32;
+        }
+        MissionBriefingAction::DisplaySpeakingPortrait { slot, time } => {
+            raw.action = 7;
+            raw.first_or_only_group_or_player_affected = *slot as u32;
+            raw.seconds_or_milliseconds = *time as u32;
+        }
+        MissionBriefingAction::DisplayTransmission {
+            slot,
+            text,
+            time,
+            modifier,
+            wave,
+            wave_time,
+        } => {
+            raw.action = 8;
+            raw.first_or_only_group_or_player_affected = *slot as u32;
+            raw.string_number = text.0;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *time as u32;
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier =
+                serialize_number_modifier(modifier);
+            raw.wav_string_number = wave.0;
+            raw.seconds_or_milliseconds = *wave_time as u32;
+        }
+        MissionBriefingAction::SkipTutorialEnabled => raw.action = 9,
+        MissionBriefingAction::Unknown { id, raw: x } => {
+            *raw = *x;
+            raw.action = *id as u8;
+        }
+    }
+}
+
+impl MissionBriefingConditionEntry {
+    pub fn new(index: usize, condition: MissionBriefingCondition) -> MissionBriefingConditionEntry {
+        MissionBriefingConditionEntry {
+            index,
+            condition,
+            flags: TriggerFlags::default(),
+            mask_flag: MaskFlag::None,
+            raw: ChkMbrfCondition::default(),
+        }
+    }
+
+    pub fn to_raw(&self) -> ChkMbrfCondition {
+        let mut raw = self.raw;
+        serialize_mission_briefing_condition(&self.condition, &mut raw);
+        raw.flags = self.flags.bits();
+        raw.mask_flag = serialize_mask_flag(&self.mask_flag);
+        raw
+    }
+}
+
+impl MissionBriefingAction {
+    pub fn text(&self) -> Option<StringId> {
+        match self {
+            MissionBriefingAction::DisplayTextMessage { text }
+            | MissionBriefingAction::SetMissionObjectives { text }
+            | MissionBriefingAction::DisplayTransmission { text, .. } => Some(*text),
+            _ => None,
+        }
+    }
+
+    pub fn wave(&self) -> Option<StringId> {
+        match self {
+            MissionBriefingAction::PlayWav { wave, .. }
+            | MissionBriefingAction::DisplayTransmission { wave, .. } => Some(*wave),
+            _ => None,
+        }
+    }
+}
+
+impl MissionBriefingActionEntry {
+    pub fn new(index: usize, action: MissionBriefingAction) -> MissionBriefingActionEntry {
+        MissionBriefingActionEntry {
+            index,
+            action,
+            flags: TriggerFlags::default(),
+            mask_flag: MaskFlag::None,
+            raw: ChkMbrfAction::default(),
+        }
+    }
+
+    pub fn to_raw(&self) -> ChkMbrfAction {
+        let mut raw = self.raw;
+        serialize_mission_briefing_action(&self.action, &mut raw);
+        raw.flags = self.flags.bits();
+        raw.mask_flag = serialize_mask_flag(&self.mask_flag);
+        raw
+    }
+}
+
+impl MissionBriefing {
+    #[instrument(level = "trace", skip_all)]
+    pub fn from_raw(trigger: &ChkMbrfIndividual) -> MissionBriefing {
+        let mut conditions = Vec::new();
+        for (index, condition) in trigger.conditions.into_iter().enumerate() {
+            if reinterpret_as_bytes(&condition).iter().all(|&x| x == 0) {
+                continue;
             }
-            let mut actions = Vec::new();
-            for action in trigger.actions {
-                match action.action {
-                    0 => {
-                        //actions.push(MissionBriefingAction::NoAction);
-                    }
-                    1 => {
-                        actions.push(MissionBriefingAction::Wait {
-                            time: action.seconds_or_milliseconds as i64,
-                        });
-                    }
-                    2 => {
-                        actions.push(MissionBriefingAction::PlayWav {
-                            wave: parsed_chk
-                                .get_string(action.wav_string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            wave_time: action.seconds_or_milliseconds as i64,
-                        });
-                    }
-                    3 => {
-                        actions.push(MissionBriefingAction::DisplayTextMessage {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    4 => {
-                        actions.push(MissionBriefingAction::SetMissionObjectives {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    5 => {
-                        actions.push(MissionBriefingAction::ShowPortrait {
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                            slot: action.first_or_only_group_or_player_affected as i64,
-                        });
-                    }
-                    6 => {
-                        actions.push(MissionBriefingAction::HidePortrait {
-                            slot: action.first_or_only_group_or_player_affected as i64,
-                        });
-                    }
-                    7 => {
-                        actions.push(MissionBriefingAction::DisplaySpeakingPortrait {
-                            slot: action.first_or_only_group_or_player_affected as i64,
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    8 => {
-                        actions.push(MissionBriefingAction::DisplayTransmission {
-                            slot: action.first_or_only_group_or_player_affected as i64,
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            time: action.seconds_or_milliseconds as i64,
-                            modifier: parse_number_modifier(
-                                action.number_of_units_or_action_state_or_unit_order_or_number_modifier,
-                            ),
-                            wave: parsed_chk
-                                .get_string(action.wav_string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            wave_time: action.seconds_or_milliseconds as i64,
-                        });
-                    }
-                    9 => {
-                        actions.push(MissionBriefingAction::SkipTutorialEnabled);
-                    }
-                    _ => {
-                        actions.push(MissionBriefingAction::Unknown {
-                            id: action.action as i64,
-                            raw: action,
-                        });
-                    }
-                }
+
+            conditions.push(MissionBriefingConditionEntry {
+                index,
+                condition: parse_mission_briefing_condition(condition),
+                flags: parse_trigger_flags(condition.flags),
+                mask_flag: parse_mask_flag(condition.mask_flag),
+                raw: condition,
+            });
+        }
+
+        let mut actions = Vec::new();
+        for (index, action) in trigger.actions.into_iter().enumerate() {
+            if reinterpret_as_bytes(&action).iter().all(|&x| x == 0) {
+                continue;
             }
 
-            ret.push(MissionBriefing {
-                conditions,
-                actions,
-                execution_flags: trigger.execution_flags,
-                activated_for_players: trigger.executed_for_player,
-                index_of_current_action: trigger.current_action,
+            actions.push(MissionBriefingActionEntry {
+                index,
+                action: parse_mission_briefing_action(action),
+                flags: parse_trigger_flags(action.flags),
+                mask_flag: parse_mask_flag(action.mask_flag),
+                raw: action,
             });
         }
+
+        MissionBriefing {
+            conditions,
+            actions,
+            execution_flags: trigger.execution_flags,
+            activated_for_players: trigger.executed_for_player,
+            index_of_current_action: trigger.current_action,
+        }
     }
 
-    ret
+    // Entries are written to the slot at their index. Panics if an index is out of range.
+    #[instrument(level = "trace", skip_all)]
+    pub fn to_raw(&self) -> ChkMbrfIndividual {
+        let mut conditions = [ChkMbrfCondition::default(); 16];
+        for x in &self.conditions {
+            conditions[x.index] = x.to_raw();
+        }
+
+        let mut actions = [ChkMbrfAction::default(); 64];
+        for x in &self.actions {
+            actions[x.index] = x.to_raw();
+        }
+
+        ChkMbrfIndividual {
+            conditions,
+            actions,
+            execution_flags: self.execution_flags,
+            executed_for_player: self.activated_for_players,
+            current_action: self.index_of_current_action,
+        }
+    }
+}
+
+#[instrument(skip_all)]
+pub fn parse_mission_briefing<'a>(parsed_chk: &ParsedChk<'a>) -> Vec<MissionBriefing> {
+    if let Ok(mbrf) = &parsed_chk.mbrf {
+        mbrf.triggers
+            .iter()
+            .map(MissionBriefing::from_raw)
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
+// A condition along with the parts of its slot that are not specific to the condition. Slots that are
+// entirely zero are skipped, everything else is kept so `to_raw` gives back the exact bytes.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct ConditionEntry {
+    // 0 to 15.
+    pub index: usize,
+    pub condition: Condition,
+    pub flags: TriggerFlags,
+    pub mask_flag: MaskFlag,
+    // The slot as it was read, including the fields the condition does not use.
+    pub raw: ChkTrigCondition,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct ActionEntry {
+    // 0 to 63.
+    pub index: usize,
+    pub action: Action,
+    pub flags: TriggerFlags,
+    pub mask_flag: MaskFlag,
+    pub raw: ChkTrigAction,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 pub struct Trigger {
-    pub conditions: Vec<Condition>,
-    pub actions: Vec<Action>,
+    pub conditions: Vec<ConditionEntry>,
+    pub actions: Vec<ActionEntry>,
     pub execution_flags: u32,
     pub activated_for_players: [u8; 27],
     pub index_of_current_action: u8,
 }
 
+#[instrument(level = "trace", skip_all)]
+fn parse_condition(condition: ChkTrigCondition) -> Condition {
+    match condition.condition {
+        0 => Condition::NoCondition,
+        1 => Condition::CountdownTimer {
+            comparison: parse_numeric_comparison(condition.numeric_comparison_or_switch_state),
+            number: condition.qualified_number as i64,
+        },
+        2 => Condition::Command {
+            player: parse_group(condition.group),
+            comparison: parse_numeric_comparison(condition.numeric_comparison_or_switch_state),
+            unit_type: parse_unit_type(condition.unit_id),
+            number: condition.qualified_number as i64,
+        },
+        3 => Condition::Bring {
+            player: parse_group(condition.group),
+            comparison: parse_numeric_comparison(condition.numeric_comparison_or_switch_state),
+            unit_type: parse_unit_type(condition.unit_id),
+            location: LocationId(condition.location),
+            number: condition.qualified_number as i64,
+        },
+        4 => Condition::Accumulate {
+            player: parse_group(condition.group),
+            comparison: parse_numeric_comparison(condition.numeric_comparison_or_switch_state),
+            unit_type: parse_unit_type(condition.unit_id),
+            number: condition.qualified_number as i64,
+            resource_type: parse_resource_type(
+                condition.resource_type_or_score_type_or_switch_number as u16,
+            ),
+        },
+        5 => Condition::Kill {
+            player: parse_group(condition.group),
+            comparison: parse_numeric_comparison(condition.numeric_comparison_or_switch_state),
+            unit_type: parse_unit_type(condition.unit_id),
+            number: condition.qualified_number as i64,
+        },
+        6 => Condition::CommandsTheMost {
+            unit_type: parse_unit_type(condition.unit_id),
+        },
+        7 => Condition::CommandsTheMostAt {
+            unit_type: parse_unit_type(condition.unit_id),
+            location: LocationId(condition.location),
+        },
+        8 => Condition::MostKills {
+            unit_type: parse_unit_type(condition.unit_id),
+        },
+        9 => Condition::HighestScore {
+            score_type: parse_score_type(
+                condition.resource_type_or_score_type_or_switch_number as u16,
+            ),
+        },
+        10 => Condition::MostResources {
+            resource_type: parse_resource_type(
+                condition.resource_type_or_score_type_or_switch_number as u16,
+            ),
+        },
+        11 => Condition::Switch {
+            switch: condition.resource_type_or_score_type_or_switch_number as i64,
+            switch_state: parse_switch_state(condition.numeric_comparison_or_switch_state),
+        },
+        12 => Condition::ElapsedTime {
+            comparison: parse_numeric_comparison(condition.numeric_comparison_or_switch_state),
+            number: condition.qualified_number as i64,
+        },
+        13 => Condition::DataIsAMissionBriefing,
+        14 => Condition::Opponents {
+            player: parse_group(condition.group),
+            comparison: parse_numeric_comparison(condition.numeric_comparison_or_switch_state),
+            number: condition.qualified_number as i64,
+        },
+        15 => Condition::Deaths {
+            player: parse_group(condition.group),
+            comparison: parse_numeric_comparison(condition.numeric_comparison_or_switch_state),
+            unit_type: parse_unit_type(condition.unit_id),
+            number: condition.qualified_number as i64,
+            eud_offset: (condition.unit_id as u32)
+                .wrapping_mul(12)
+                .wrapping_add(condition.group)
+                .wrapping_mul(4)
+                .wrapping_add(0x58A364),
+            mask: (condition.mask_flag == 0x4353).then_some(condition.location),
+        },
+        16 => Condition::CommandsTheLeast {
+            unit_type: parse_unit_type(condition.unit_id),
+        },
+        17 => Condition::CommandsTheLeastAt {
+            unit_type: parse_unit_type(condition.unit_id),
+            location: LocationId(condition.location),
+        },
+        18 => Condition::LeastKills {
+            unit_type: parse_unit_type(condition.unit_id),
+        },
+        19 => Condition::LowestScore {
+            score_type: parse_score_type(
+                condition.resource_type_or_score_type_or_switch_number as u16,
+            ),
+        },
+        20 => Condition::LeastResources {
+            resource_type: parse_resource_type(
+                condition.resource_type_or_score_type_or_switch_number as u16,
+            ),
+        },
+        21 => Condition::Score {
+            player: parse_group(condition.group),
+            comparison: parse_numeric_comparison(condition.numeric_comparison_or_switch_state),
+            score_type: parse_score_type(
+                condition.resource_type_or_score_type_or_switch_number as u16,
+            ),
+            number: condition.qualified_number as i64,
+        },
+        22 => Condition::Always,
+        23 => Condition::Never,
+        _ => Condition::Unknown {
+            id: condition.condition as i64,
+            raw: condition,
+        },
+    }
+}
+
+// `eud_offset` is derived from the other fields and is not written.
+fn serialize_condition(condition: &Condition, raw: &mut ChkTrigCondition) {
+    match condition {
+        Condition::NoCondition => raw.condition = 0,
+        Condition::CountdownTimer { comparison, number } => {
+            raw.condition = 1;
+            raw.numeric_comparison_or_switch_state = serialize_numeric_comparison(comparison);
+            raw.qualified_number = *number as u32;
+        }
+        Condition::Command {
+            player,
+            comparison,
+            unit_type,
+            number,
+        } => {
+            raw.condition = 2;
+            raw.group = serialize_group(player);
+            raw.numeric_comparison_or_switch_state = serialize_numeric_comparison(comparison);
+            raw.unit_id = serialize_unit_type(unit_type);
+            raw.qualified_number = *number as u32;
+        }
+        Condition::Bring {
+            player,
+            comparison,
+            unit_type,
+            location,
+            number,
+        } => {
+            raw.condition = 3;
+            raw.group = serialize_group(player);
+            raw.numeric_comparison_or_switch_state = serialize_numeric_comparison(comparison);
+            raw.unit_id = serialize_unit_type(unit_type);
+            raw.location = location.0;
+            raw.qualified_number = *number as u32;
+        }
+        Condition::Accumulate {
+            player,
+            comparison,
+            unit_type,
+            number,
+            resource_type,
+        } => {
+            raw.condition = 4;
+            raw.group = serialize_group(player);
+            raw.numeric_comparison_or_switch_state = serialize_numeric_comparison(comparison);
+            raw.unit_id = serialize_unit_type(unit_type);
+            raw.qualified_number = *number as u32;
+            raw.resource_type_or_score_type_or_switch_number =
+                serialize_resource_type(resource_type) as u8;
+        }
+        Condition::Kill {
+            player,
+            comparison,
+            unit_type,
+            number,
+        } => {
+            raw.condition = 5;
+            raw.group = serialize_group(player);
+            raw.numeric_comparison_or_switch_state = serialize_numeric_comparison(comparison);
+            raw.unit_id = serialize_unit_type(unit_type);
+            raw.qualified_number = *number as u32;
+        }
+        Condition::CommandsTheMost { unit_type } => {
+            raw.condition = 6;
+            raw.unit_id = serialize_unit_type(unit_type);
+        }
+        Condition::CommandsTheMostAt {
+            unit_type,
+            location,
+        } => {
+            raw.condition = 7;
+            raw.unit_id = serialize_unit_type(unit_type);
+            raw.location = location.0;
+        }
+        Condition::MostKills { unit_type } => {
+            raw.condition = 8;
+            raw.unit_id = serialize_unit_type(unit_type);
+        }
+        Condition::HighestScore { score_type } => {
+            raw.condition = 9;
+            raw.resource_type_or_score_type_or_switch_number =
+                serialize_score_type(score_type) as u8;
+        }
+        Condition::MostResources { resource_type } => {
+            raw.condition = 10;
+            raw.resource_type_or_score_type_or_switch_number =
+                serialize_resource_type(resource_type) as u8;
+        }
+        Condition::Switch {
+            switch,
+            switch_state,
+        } => {
+            raw.condition = 11;
+            raw.resource_type_or_score_type_or_switch_number = *switch as u8;
+            raw.numeric_comparison_or_switch_state = serialize_switch_state(switch_state);
+        }
+        Condition::ElapsedTime { comparison, number } => {
+            raw.condition = 12;
+            raw.numeric_comparison_or_switch_state = serialize_numeric_comparison(comparison);
+            raw.qualified_number = *number as u32;
+        }
+        Condition::DataIsAMissionBriefing => raw.condition = 13,
+        Condition::Opponents {
+            player,
+            comparison,
+            number,
+        } => {
+            raw.condition = 14;
+            raw.group = serialize_group(player);
+            raw.numeric_comparison_or_switch_state = serialize_numeric_comparison(comparison);
+            raw.qualified_number = *number as u32;
+        }
+        Condition::Deaths {
+            player,
+            comparison,
+            unit_type,
+            number,
+            mask,
+            ..
+        } => {
+            raw.condition = 15;
+            raw.group = serialize_group(player);
+            raw.numeric_comparison_or_switch_state = serialize_numeric_comparison(comparison);
+            raw.unit_id = serialize_unit_type(unit_type);
+            raw.qualified_number = *number as u32;
+            if let Some(mask) = mask {
+                raw.location = *mask;
+            }
+        }
+        Condition::CommandsTheLeast { unit_type } => {
+            raw.condition = 16;
+            raw.unit_id = serialize_unit_type(unit_type);
+        }
+        Condition::CommandsTheLeastAt {
+            unit_type,
+            location,
+        } => {
+            raw.condition = 17;
+            raw.unit_id = serialize_unit_type(unit_type);
+            raw.location = location.0;
+        }
+        Condition::LeastKills { unit_type } => {
+            raw.condition = 18;
+            raw.unit_id = serialize_unit_type(unit_type);
+        }
+        Condition::LowestScore { score_type } => {
+            raw.condition = 19;
+            raw.resource_type_or_score_type_or_switch_number =
+                serialize_score_type(score_type) as u8;
+        }
+        Condition::LeastResources { resource_type } => {
+            raw.condition = 20;
+            raw.resource_type_or_score_type_or_switch_number =
+                serialize_resource_type(resource_type) as u8;
+        }
+        Condition::Score {
+            player,
+            comparison,
+            score_type,
+            number,
+        } => {
+            raw.condition = 21;
+            raw.group = serialize_group(player);
+            raw.numeric_comparison_or_switch_state = serialize_numeric_comparison(comparison);
+            raw.resource_type_or_score_type_or_switch_number =
+                serialize_score_type(score_type) as u8;
+            raw.qualified_number = *number as u32;
+        }
+        Condition::Always => raw.condition = 22,
+        Condition::Never => raw.condition = 23,
+        Condition::Unknown { id, raw: x } => {
+            *raw = *x;
+            raw.condition = *id as u8;
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+pub(crate) fn parse_action(action: ChkTrigAction, cuwp_slots: &[ChkUprpIndividual]) -> Action {
+    match action.action {
+        0 => Action::NoAction,
+        1 => Action::Victory,
+        2 => Action::Defeat,
+        3 => Action::PreserveTrigger,
+        4 => Action::Wait {
+            time: action.seconds_or_milliseconds as i64,
+        },
+        5 => Action::PauseGame,
+        6 => Action::UnpauseGame,
+        7 => Action::Transmission {
+            text: StringId(action.string_number),
+            unit_type: parse_unit_type(action
+                .unit_type_or_score_type_or_resource_type_or_alliance_status),
+            location: LocationId(action.location),
+            time: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            modifier: parse_number_modifier(
+                action.number_of_units_or_action_state_or_unit_order_or_number_modifier,
+            ),
+            wave: StringId(action.wav_string_number),
+            wave_time: action.seconds_or_milliseconds as i64,
+        },
+        8 => Action::PlayWav {
+            wave: StringId(action.wav_string_number),
+            wave_time: action.seconds_or_milliseconds as i64,
+        },
+        9 => Action::DisplayTextMessage {
+            text: StringId(action.string_number),
+        },
+        10 => Action::CenterView {
+            location: LocationId(action.location),
+        },
+        11 => Action::CreateUnitWithProperties {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(action
+                .unit_type_or_score_type_or_resource_type_or_alliance_status),
+            number: action.number_of_units_or_action_state_or_unit_order_or_number_modifier as i64,
+            location: LocationId(action.location),
+            unit_prop: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            properties: cuwp_slots
+                .get((action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as usize).wrapping_sub(1))
+                .copied(),
+        },
+        12 => Action::SetMissionObjectives {
+            text: StringId(action.string_number),
+        },
+        13 => Action::SetSwitch {
+            switch: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            switch_action: parse_action_state(action.number_of_units_or_action_state_or_unit_order_or_number_modifier),
+        },
+        14 => Action::SetCountdownTimer {
+        time: action.seconds_or_milliseconds as i64,
+        modifier: parse_number_modifier(
+            action.number_of_units_or_action_state_or_unit_order_or_number_modifier,
+        ),
+        },
+        15 => Action::RunAIScript {
+        script: AiScript(action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number),
+        },
+        16 => Action::RunAIScriptAtLocation {
+            script: AiScript(action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number),
+            location: LocationId(action.location),
+        },
+        17 => Action::LeaderBoardControl {
+            text: StringId(action.string_number),
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        18 => Action::LeaderBoardControlAtLocation {
+            text: StringId(action.string_number),
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+            location: LocationId(action.location),
+        },
+        19 => Action::LeaderBoardResources {
+            text: StringId(action.string_number),
+            resource_type: parse_resource_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        20 => Action::LeaderBoardKills {
+            text: StringId(action.string_number),
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        21 => Action::LeaderBoardPoints {
+            text: StringId(action.string_number),
+            score_type: parse_score_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        22 => Action::KillAllUnits {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        23 => Action::KillUnitAtLocation {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+            number: action.number_of_units_or_action_state_or_unit_order_or_number_modifier as i64,
+            location: LocationId(action.location),
+        },
+        24 => Action::RemoveAllUnits {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        25 => Action::RemoveUnitAtLocation {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+            number: action.number_of_units_or_action_state_or_unit_order_or_number_modifier as i64,
+            location: LocationId(action.location),
+        },
+        26 => Action::SetResources {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            modifier: parse_number_modifier(action.number_of_units_or_action_state_or_unit_order_or_number_modifier),
+            resource_type: parse_resource_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        27 => Action::SetScore {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            modifier: parse_number_modifier(action.number_of_units_or_action_state_or_unit_order_or_number_modifier),
+            score_type: parse_score_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        28 => Action::MinimapPing {
+            location: LocationId(action.location),
+        },
+        29 => Action::TalkingPortrait {
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+            time: action.seconds_or_milliseconds as i64,
+        },
+        30 => Action::MuteUnitSpeech,
+        31 => Action::UnmuteUnitSpeech,
+        32 => Action::LeaderBoardComputerPlayers {
+            action: parse_action_state(action.number_of_units_or_action_state_or_unit_order_or_number_modifier),
+        },
+        33 => Action::LeaderBoardGoalControl {
+            text: StringId(action.string_number),
+            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        34 => Action::LeaderBoardGoalControlAtLocation {
+            text: StringId(action.string_number),
+            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+            location: LocationId(action.location),
+        },
+        35 => Action::LeaderBoardGoalResources {
+            text: StringId(action.string_number),
+            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            resource_type: parse_resource_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        36 => Action::LeaderBoardGoalKills {
+            text: StringId(action.string_number),
+            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        37 => Action::LeaderBoardGoalPoints {
+            text: StringId(action.string_number),
+            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            score_type: parse_score_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        38 => Action::MoveLocation {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+            source_location: LocationId(action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number),
+            destination_location: LocationId(action.location),
+        },
+        39 => Action::MoveUnit {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+            source_location: LocationId(action.location),
+            destination_location: LocationId(action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number),
+            number: action.number_of_units_or_action_state_or_unit_order_or_number_modifier as i64,
+        },
+        40 => Action::LeaderboardGreed {
+            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+        },
+        41 => Action::SetNextScenario {
+            text: StringId(action.string_number),
+        },
+        42 => Action::SetDoodadState {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+            location: LocationId(action.location),
+            state: parse_action_state(action.number_of_units_or_action_state_or_unit_order_or_number_modifier), // TODO: split actionstate enum into doodad state + switch state + computer-player state.
+        },
+        43 => Action::SetInvincibility {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+            location: LocationId(action.location),
+            state: parse_action_state(action.number_of_units_or_action_state_or_unit_order_or_number_modifier), // TODO: split actionstate enum into doodad state + switch state + computer-player state.
+        },
+        44 => Action::CreateUnit {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+            number: action.number_of_units_or_action_state_or_unit_order_or_number_modifier as i64,
+            location: LocationId(action.location),
+        },
+        45 => Action::SetDeaths {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(action
+                .unit_type_or_score_type_or_resource_type_or_alliance_status),
+            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            modifier: parse_number_modifier(action.number_of_units_or_action_state_or_unit_order_or_number_modifier),
+            eud_offset:
+            (action
+                .unit_type_or_score_type_or_resource_type_or_alliance_status as u32).wrapping_mul(12).wrapping_add(action.first_or_only_group_or_player_affected).wrapping_mul(4).wrapping_add(0x58A364),
+            mask: (action.mask_flag == 0x4353).then_some(action.location),
+        },
+        46 => Action::Order {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(action
+                .unit_type_or_score_type_or_resource_type_or_alliance_status),
+            source_location: LocationId(action.location),
+            destination_location: LocationId(action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number),
+            order: parse_order(action.number_of_units_or_action_state_or_unit_order_or_number_modifier)
+        },
+        47 => Action::Comment {
+            text: StringId(action.string_number),
+        },
+        48 => Action::GiveUnitsToPlayer {
+            source_player: parse_group(action.first_or_only_group_or_player_affected),
+            destination_player: parse_group(action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number),
+            unit_type: parse_unit_type(action
+                .unit_type_or_score_type_or_resource_type_or_alliance_status),
+            number: action.number_of_units_or_action_state_or_unit_order_or_number_modifier as i64,
+            location: LocationId(action.location),
+        },
+        49 => Action::ModifyUnitHitPoints {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(action
+                .unit_type_or_score_type_or_resource_type_or_alliance_status),
+            number: action.number_of_units_or_action_state_or_unit_order_or_number_modifier as i64,
+            amount: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            location: LocationId(action.location),
+        },
+        50 => Action::ModifyUnitEnergy {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(action
+                .unit_type_or_score_type_or_resource_type_or_alliance_status),
+            number: action.number_of_units_or_action_state_or_unit_order_or_number_modifier as i64,
+            amount: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            location: LocationId(action.location),
+        },
+        51 => Action::ModifyUnitShieldPoints {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(action
+                .unit_type_or_score_type_or_resource_type_or_alliance_status),
+            number: action.number_of_units_or_action_state_or_unit_order_or_number_modifier as i64,
+            amount: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            location: LocationId(action.location),
+        },
+        52 => Action::ModifyUnitResource { // TOOD: ModifyUnitResourceAmount
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(action
+                .unit_type_or_score_type_or_resource_type_or_alliance_status),
+            number: action.number_of_units_or_action_state_or_unit_order_or_number_modifier as i64,
+            amount: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            location: LocationId(action.location),
+        },
+        53 => Action::ModifyUnitHangarCount {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            unit_type: parse_unit_type(action
+                .unit_type_or_score_type_or_resource_type_or_alliance_status),
+            number: action.number_of_units_or_action_state_or_unit_order_or_number_modifier as i64,
+            amount: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
+            location: LocationId(action.location),
+        },
+        54 => Action::PauseTimer,
+        55 => Action::UnpauseTimer,
+        56 => Action::Draw,
+        57 => Action::SetAllianceStatus {
+            player: parse_group(action.first_or_only_group_or_player_affected),
+            alliance_status: parse_alliance_status(
+                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
+            ),
+        },
+        58 => Action::DisableDebugMode,
+        59 => Action::EnableDebugMode,
+        _ => Action::Unknown {
+            id: action.action as i64,
+            raw: action,
+        },
+    }
+}
+
+// `eud_offset` and the CUWP `properties` are derived from the other fields and are not written.
+fn serialize_action(action: &Action, raw: &mut ChkTrigAction) {
+    match action {
+        Action::NoAction => raw.action = 0,
+        Action::Victory => raw.action = 1,
+        Action::Defeat => raw.action = 2,
+        Action::PreserveTrigger => raw.action = 3,
+        Action::Wait { time } => {
+            raw.action = 4;
+            raw.seconds_or_milliseconds = *time as u32;
+        }
+        Action::PauseGame => raw.action = 5,
+        Action::UnpauseGame => raw.action = 6,
+        Action::Transmission {
+            text,
+            unit_type,
+            location,
+            time,
+            modifier,
+            wave,
+            wave_time,
+        } => {
+            raw.action = 7;
+            raw.string_number = text.0;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.location = location.0;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *time as u32;
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier =
+                serialize_number_modifier(modifier);
+            raw.wav_string_number = wave.0;
+            raw.seconds_or_milliseconds = *wave_time as u32;
+        }
+        Action::PlayWav { wave, wave_time } => {
+            raw.action = 8;
+            raw.wav_string_number = wave.0;
+            raw.seconds_or_milliseconds = *wave_time as u32;
+        }
+        Action::DisplayTextMessage { text } => {
+            raw.action = 9;
+            raw.string_number = text.0;
+        }
+        Action::CenterView { location } => {
+            raw.action = 10;
+            raw.location = location.0;
+        }
+        Action::CreateUnitWithProperties {
+            player,
+            unit_type,
+            number,
+            location,
+            unit_prop,
+            ..
+        } => {
+            raw.action = 11;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier = *number as u8;
+            raw.location = location.0;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *unit_prop as u32;
+        }
+        Action::SetMissionObjectives { text } => {
+            raw.action = 12;
+            raw.string_number = text.0;
+        }
+        Action::SetSwitch {
+            switch,
+            switch_action,
+        } => {
+            raw.action = 13;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *switch as u32;
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier =
+                serialize_action_state(switch_action);
+        }
+        Action::SetCountdownTimer { time, modifier } => {
+            raw.action = 14;
+            raw.seconds_or_milliseconds = *time as u32;
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier =
+                serialize_number_modifier(modifier);
+        }
+        Action::RunAIScript { script } => {
+            raw.action = 15;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = script.0;
+        }
+        Action::RunAIScriptAtLocation { script, location } => {
+            raw.action = 16;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = script.0;
+            raw.location = location.0;
+        }
+        Action::LeaderBoardControl { text, unit_type } => {
+            raw.action = 17;
+            raw.string_number = text.0;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+        }
+        Action::LeaderBoardControlAtLocation {
+            text,
+            unit_type,
+            location,
+        } => {
+            raw.action = 18;
+            raw.string_number = text.0;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.location = location.0;
+        }
+        Action::LeaderBoardResources {
+            text,
+            resource_type,
+        } => {
+            raw.action = 19;
+            raw.string_number = text.0;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_resource_type(resource_type);
+        }
+        Action::LeaderBoardKills { text, unit_type } => {
+            raw.action = 20;
+            raw.string_number = text.0;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+        }
+        Action::LeaderBoardPoints { text, score_type } => {
+            raw.action = 21;
+            raw.string_number = text.0;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_score_type(score_type);
+        }
+        Action::KillAllUnits { player, unit_type } => {
+            raw.action = 22;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+        }
+        Action::KillUnitAtLocation {
+            player,
+            unit_type,
+            number,
+            location,
+        } => {
+            raw.action = 23;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier = *number as u8;
+            raw.location = location.0;
+        }
+        Action::RemoveAllUnits { player, unit_type } => {
+            raw.action = 24;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+        }
+        Action::RemoveUnitAtLocation {
+            player,
+            unit_type,
+            number,
+            location,
+        } => {
+            raw.action = 25;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier = *number as u8;
+            raw.location = location.0;
+        }
+        Action::SetResources {
+            player,
+            number,
+            modifier,
+            resource_type,
+        } => {
+            raw.action = 26;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *number as u32;
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier =
+                serialize_number_modifier(modifier);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_resource_type(resource_type);
+        }
+        Action::SetScore {
+            player,
+            number,
+            modifier,
+            score_type,
+        } => {
+            raw.action = 27;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *number as u32;
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier =
+                serialize_number_modifier(modifier);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_score_type(score_type);
+        }
+        Action::MinimapPing { location } => {
+            raw.action = 28;
+            raw.location = location.0;
+        }
+        Action::TalkingPortrait { unit_type, time } => {
+            raw.action = 29;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.seconds_or_milliseconds = *time as u32;
+        }
+        Action::MuteUnitSpeech => raw.action = 30,
+        Action::UnmuteUnitSpeech => raw.action = 31,
+        Action::LeaderBoardComputerPlayers { action } => {
+            raw.action = 32;
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier =
+                serialize_action_state(action);
+        }
+        Action::LeaderBoardGoalControl {
+            text,
+            number,
+            unit_type,
+        } => {
+            raw.action = 33;
+            raw.string_number = text.0;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *number as u32;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+        }
+        Action::LeaderBoardGoalControlAtLocation {
+            text,
+            number,
+            unit_type,
+            location,
+        } => {
+            raw.action = 34;
+            raw.string_number = text.0;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *number as u32;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.location = location.0;
+        }
+        Action::LeaderBoardGoalResources {
+            text,
+            number,
+            resource_type,
+        } => {
+            raw.action = 35;
+            raw.string_number = text.0;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *number as u32;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_resource_type(resource_type);
+        }
+        Action::LeaderBoardGoalKills {
+            text,
+            number,
+            unit_type,
+        } => {
+            raw.action = 36;
+            raw.string_number = text.0;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *number as u32;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+        }
+        Action::LeaderBoardGoalPoints {
+            text,
+            number,
+            score_type,
+        } => {
+            raw.action = 37;
+            raw.string_number = text.0;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *number as u32;
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_score_type(score_type);
+        }
+        Action::MoveLocation {
+            player,
+            unit_type,
+            source_location,
+            destination_location,
+        } => {
+            raw.action = 38;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.location = destination_location.0;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = source_location.0;
+        }
+        Action::MoveUnit {
+            player,
+            unit_type,
+            source_location,
+            destination_location,
+            number,
+        } => {
+            raw.action = 39;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.location = source_location.0;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = destination_location.0;
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier = *number as u8;
+        }
+        Action::LeaderboardGreed { number } => {
+            raw.action = 40;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *number as u32;
+        }
+        Action::SetNextScenario { text } => {
+            raw.action = 41;
+            raw.string_number = text.0;
+        }
+        Action::SetDoodadState {
+            player,
+            unit_type,
+            location,
+            state,
+        } => {
+            raw.action = 42;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.location = location.0;
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier =
+                serialize_action_state(state);
+        }
+        Action::SetInvincibility {
+            player,
+            unit_type,
+            location,
+            state,
+        } => {
+            raw.action = 43;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.location = location.0;
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier =
+                serialize_action_state(state);
+        }
+        Action::CreateUnit {
+            player,
+            unit_type,
+            number,
+            location,
+        } => {
+            raw.action = 44;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier = *number as u8;
+            raw.location = location.0;
+        }
+        Action::SetDeaths {
+            player,
+            unit_type,
+            number,
+            modifier,
+            mask,
+            ..
+        } => {
+            raw.action = 45;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *number as u32;
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier =
+                serialize_number_modifier(modifier);
+            if let Some(mask) = mask {
+                raw.location = *mask;
+            }
+        }
+        Action::Order {
+            player,
+            unit_type,
+            source_location,
+            destination_location,
+            order,
+        } => {
+            raw.action = 46;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.location = source_location.0;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = destination_location.0;
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier =
+                serialize_order(order);
+        }
+        Action::Comment { text } => {
+            raw.action = 47;
+            raw.string_number = text.0;
+        }
+        Action::GiveUnitsToPlayer {
+            source_player,
+            destination_player,
+            unit_type,
+            number,
+            location,
+        } => {
+            raw.action = 48;
+            raw.first_or_only_group_or_player_affected = serialize_group(source_player);
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = serialize_group(destination_player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier = *number as u8;
+            raw.location = location.0;
+        }
+        Action::ModifyUnitHitPoints {
+            player,
+            unit_type,
+            number,
+            amount,
+            location,
+        } => {
+            raw.action = 49;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier = *number as u8;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *amount as u32;
+            raw.location = location.0;
+        }
+        Action::ModifyUnitEnergy {
+            player,
+            unit_type,
+            number,
+            amount,
+            location,
+        } => {
+            raw.action = 50;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier = *number as u8;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *amount as u32;
+            raw.location = location.0;
+        }
+        Action::ModifyUnitShieldPoints {
+            player,
+            unit_type,
+            number,
+            amount,
+            location,
+        } => {
+            raw.action = 51;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier = *number as u8;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *amount as u32;
+            raw.location = location.0;
+        }
+        Action::ModifyUnitResource {
+            player,
+            unit_type,
+            number,
+            amount,
+            location,
+        } => {
+            raw.action = 52;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier = *number as u8;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *amount as u32;
+            raw.location = location.0;
+        }
+        Action::ModifyUnitHangarCount {
+            player,
+            unit_type,
+            number,
+            amount,
+            location,
+        } => {
+            raw.action = 53;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_unit_type(unit_type);
+            raw.number_of_units_or_action_state_or_unit_order_or_number_modifier = *number as u8;
+            raw.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number = *amount as u32;
+            raw.location = location.0;
+        }
+        Action::PauseTimer => raw.action = 54,
+        Action::UnpauseTimer => raw.action = 55,
+        Action::Draw => raw.action = 56,
+        Action::SetAllianceStatus {
+            player,
+            alliance_status,
+        } => {
+            raw.action = 57;
+            raw.first_or_only_group_or_player_affected = serialize_group(player);
+            raw.unit_type_or_score_type_or_resource_type_or_alliance_status =
+                serialize_alliance_status(alliance_status);
+        }
+        Action::DisableDebugMode => raw.action = 58,
+        Action::EnableDebugMode => raw.action = 59,
+        Action::Unknown { id, raw: x } => {
+            *raw = *x;
+            raw.action = *id as u8;
+        }
+    }
+}
+
+impl ConditionEntry {
+    pub fn new(index: usize, condition: Condition) -> ConditionEntry {
+        ConditionEntry {
+            index,
+            condition,
+            flags: TriggerFlags::default(),
+            mask_flag: MaskFlag::None,
+            raw: ChkTrigCondition::default(),
+        }
+    }
+
+    pub fn to_raw(&self) -> ChkTrigCondition {
+        let mut raw = self.raw;
+        serialize_condition(&self.condition, &mut raw);
+        raw.flags = self.flags.bits();
+        raw.mask_flag = serialize_mask_flag(&self.mask_flag);
+        raw
+    }
+}
+
+impl Action {
+    // The string the action shows, e.g. a text message or the label of a leaderboard.
+    pub fn text(&self) -> Option<StringId> {
+        match self {
+            Action::Transmission { text, .. }
+            | Action::DisplayTextMessage { text }
+            | Action::SetMissionObjectives { text }
+            | Action::LeaderBoardControl { text, .. }
+            | Action::LeaderBoardControlAtLocation { text, .. }
+            | Action::LeaderBoardResources { text, .. }
+            | Action::LeaderBoardKills { text, .. }
+            | Action::LeaderBoardPoints { text, .. }
+            | Action::LeaderBoardGoalControl { text, .. }
+            | Action::LeaderBoardGoalControlAtLocation { text, .. }
+            | Action::LeaderBoardGoalResources { text, .. }
+            | Action::LeaderBoardGoalKills { text, .. }
+            | Action::LeaderBoardGoalPoints { text, .. }
+            | Action::SetNextScenario { text }
+            | Action::Comment { text } => Some(*text),
+            _ => None,
+        }
+    }
+
+    // The string with the file name of the WAV the action plays.
+    pub fn wave(&self) -> Option<StringId> {
+        match self {
+            Action::Transmission { wave, .. } | Action::PlayWav { wave, .. } => Some(*wave),
+            _ => None,
+        }
+    }
+}
+
+impl ActionEntry {
+    pub fn new(index: usize, action: Action) -> ActionEntry {
+        ActionEntry {
+            index,
+            action,
+            flags: TriggerFlags::default(),
+            mask_flag: MaskFlag::None,
+            raw: ChkTrigAction::default(),
+        }
+    }
+
+    pub fn to_raw(&self) -> ChkTrigAction {
+        let mut raw = self.raw;
+        serialize_action(&self.action, &mut raw);
+        raw.flags = self.flags.bits();
+        raw.mask_flag = serialize_mask_flag(&self.mask_flag);
+        raw
+    }
+}
+
+impl Trigger {
+    // The CUWP slots are only used to fill in the properties of CreateUnitWithProperties.
+    #[instrument(level = "trace", skip_all)]
+    pub fn from_raw(trigger: &ChkTrigIndividual, cuwp_slots: &[ChkUprpIndividual]) -> Trigger {
+        let mut conditions = Vec::new();
+        for (index, condition) in trigger.conditions.into_iter().enumerate() {
+            if reinterpret_as_bytes(&condition).iter().all(|&x| x == 0) {
+                continue;
+            }
+
+            conditions.push(ConditionEntry {
+                index,
+                condition: parse_condition(condition),
+                flags: parse_trigger_flags(condition.flags),
+                mask_flag: parse_mask_flag(condition.mask_flag),
+                raw: condition,
+            });
+        }
+
+        let mut actions = Vec::new();
+        for (index, action) in trigger.actions.into_iter().enumerate() {
+            if reinterpret_as_bytes(&action).iter().all(|&x| x == 0) {
+                continue;
+            }
+
+            actions.push(ActionEntry {
+                index,
+                action: parse_action(action, cuwp_slots),
+                flags: parse_trigger_flags(action.flags),
+                mask_flag: parse_mask_flag(action.mask_flag),
+                raw: action,
+            });
+        }
+
+        Trigger {
+            conditions,
+            actions,
+            execution_flags: trigger.execution_flags,
+            activated_for_players: trigger.executed_for_player,
+            index_of_current_action: trigger.current_action,
+        }
+    }
+
+    // Entries are written to the slot at their index. Panics if an index is out of range.
+    #[instrument(level = "trace", skip_all)]
+    pub fn to_raw(&self) -> ChkTrigIndividual {
+        let mut conditions = [ChkTrigCondition::default(); 16];
+        for x in &self.conditions {
+            conditions[x.index] = x.to_raw();
+        }
+
+        let mut actions = [ChkTrigAction::default(); 64];
+        for x in &self.actions {
+            actions[x.index] = x.to_raw();
+        }
+
+        ChkTrigIndividual {
+            conditions,
+            actions,
+            execution_flags: self.execution_flags,
+            executed_for_player: self.activated_for_players,
+            current_action: self.index_of_current_action,
+        }
+    }
+}
+
 #[instrument(skip_all)]
 pub fn parse_triggers<'a>(parsed_chk: &ParsedChk<'a>) -> Vec<Trigger> {
-    let mut ret = Vec::new();
+    let cuwp_slots = parsed_chk
+        .uprp
+        .as_ref()
+        .map(|x| x.cuwp_slots)
+        .unwrap_or_default();
 
     if let Ok(trig) = &parsed_chk.trig {
-        for trigger in &trig.triggers {
-            let mut conditions = Vec::new();
-            for condition in trigger.conditions {
-                match condition.condition {
-                    0 => {
-                        //conditions.push(Condition::NoCondition)
-                    }
-                    1 => {
-                        conditions.push(Condition::CountdownTimer {
-                            comparison: parse_numeric_comparison(
-                                condition.numeric_comparison_or_switch_state,
-                            ),
-                            number: condition.qualified_number as i64,
-                        });
-                    }
-                    2 => {
-                        conditions.push(Condition::Command {
-                            player: parse_group(condition.group),
-                            comparison: parse_numeric_comparison(
-                                condition.numeric_comparison_or_switch_state,
-                            ),
-                            unit_type: parse_unit_type(condition.unit_id),
-                            number: condition.qualified_number as i64,
-                        });
-                    }
-                    3 => {
-                        conditions.push(Condition::Bring {
-                            player: parse_group(condition.group),
-                            comparison: parse_numeric_comparison(
-                                condition.numeric_comparison_or_switch_state,
-                            ),
-                            unit_type: parse_unit_type(condition.unit_id),
-                            location: parsed_chk
-                                .get_location_name(condition.location as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            number: condition.qualified_number as i64,
-                        });
-                    }
-                    4 => {
-                        conditions.push(Condition::Accumulate {
-                            player: parse_group(condition.group),
-                            comparison: parse_numeric_comparison(
-                                condition.numeric_comparison_or_switch_state,
-                            ),
-                            unit_type: parse_unit_type(condition.unit_id),
-                            number: condition.qualified_number as i64,
-                            resource_type: parse_resource_type(
-                                condition.resource_type_or_score_type_or_switch_number as u16,
-                            ),
-                        });
-                    }
-                    5 => {
-                        conditions.push(Condition::Kill {
-                            player: parse_group(condition.group),
-                            comparison: parse_numeric_comparison(
-                                condition.numeric_comparison_or_switch_state,
-                            ),
-                            unit_type: parse_unit_type(condition.unit_id),
-                            number: condition.qualified_number as i64,
-                        });
-                    }
-                    6 => {
-                        conditions.push(Condition::CommandsTheMost {
-                            unit_type: parse_unit_type(condition.unit_id),
-                        });
-                    }
-                    7 => {
-                        conditions.push(Condition::CommandsTheMostAt {
-                            unit_type: parse_unit_type(condition.unit_id),
-                            location: parsed_chk
-                                .get_location_name(condition.location as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    8 => {
-                        conditions.push(Condition::MostKills {
-                            unit_type: parse_unit_type(condition.unit_id),
-                        });
-                    }
-                    9 => {
-                        conditions.push(Condition::HighestScore {
-                            score_type: parse_score_type(
-                                condition.resource_type_or_score_type_or_switch_number as u16,
-                            ),
-                        });
-                    }
-                    10 => {
-                        conditions.push(Condition::MostResources {
-                            resource_type: parse_resource_type(
-                                condition.resource_type_or_score_type_or_switch_number as u16,
-                            ),
-                        });
-                    }
-                    11 => {
-                        conditions.push(Condition::Switch {
-                            switch: condition.resource_type_or_score_type_or_switch_number as i64,
-                            switch_state: parse_switch_state(
-                                condition.numeric_comparison_or_switch_state,
-                            ),
-                        });
-                    }
-                    12 => {
-                        conditions.push(Condition::ElapsedTime {
-                            comparison: parse_numeric_comparison(
-                                condition.numeric_comparison_or_switch_state,
-                            ),
-                            number: condition.qualified_number as i64,
-                        });
-                    }
-                    13 => {
-                        conditions.push(Condition::DataIsAMissionBriefing);
-                    }
-                    14 => {
-                        conditions.push(Condition::Opponents {
-                            player: parse_group(condition.group),
-                            comparison: parse_numeric_comparison(
-                                condition.numeric_comparison_or_switch_state,
-                            ),
-                            number: condition.qualified_number as i64,
-                        });
-                    }
-                    15 => {
-                        conditions.push(Condition::Deaths {
-                            player: parse_group(condition.group),
-                            comparison: parse_numeric_comparison(
-                                condition.numeric_comparison_or_switch_state,
-                            ),
-                            unit_type: parse_unit_type(condition.unit_id),
-                            number: condition.qualified_number as i64,
-                            eud_offset: (condition.unit_id as u32)
-                                .wrapping_mul(12)
-                                .wrapping_add(condition.group)
-                                .wrapping_mul(4)
-                                .wrapping_add(0x58A364),
-                        });
-                    }
-                    16 => {
-                        conditions.push(Condition::CommandsTheLeast {
-                            unit_type: parse_unit_type(condition.unit_id),
-                        });
-                    }
-                    17 => {
-                        conditions.push(Condition::CommandsTheLeastAt {
-                            unit_type: parse_unit_type(condition.unit_id),
-                            location: parsed_chk
-                                .get_location_name(condition.location as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    18 => {
-                        conditions.push(Condition::LeastKills {
-                            unit_type: parse_unit_type(condition.unit_id),
-                        });
-                    }
-                    19 => {
-                        conditions.push(Condition::LowestScore {
-                            score_type: parse_score_type(
-                                condition.resource_type_or_score_type_or_switch_number as u16,
-                            ),
-                        });
-                    }
-                    20 => {
-                        conditions.push(Condition::LeastResources {
-                            resource_type: parse_resource_type(
-                                condition.resource_type_or_score_type_or_switch_number as u16,
-                            ),
-                        });
-                    }
-                    21 => {
-                        conditions.push(Condition::Score {
-                            player: parse_group(condition.group),
-                            comparison: parse_numeric_comparison(
-                                condition.numeric_comparison_or_switch_state,
-                            ),
-                            score_type: parse_score_type(
-                                condition.resource_type_or_score_type_or_switch_number as u16,
-                            ),
-                            number: condition.qualified_number as i64,
-                        });
-                    }
-                    22 => {
-                        conditions.push(Condition::Always);
-                    }
-                    23 => {
-                        conditions.push(Condition::Never);
-                    }
-                    _ => {
-                        conditions.push(Condition::Unknown {
-                            id: condition.condition as i64,
-                            raw: condition,
-                        });
-                    }
-                }
+        trig.triggers
+            .iter()
+            .map(|x| Trigger::from_raw(x, cuwp_slots))
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        parse_trigger_flags, parse_triggers, Action, ActionEntry, Condition, Group, LocationId,
+        MaskFlag, MissionBriefing, Trigger, UnitType,
+    };
+    use crate::{chunk_name::ChunkName, ChkWriter, ParsedChk};
+
+    #[test]
+    fn decodes_every_field_of_conditions_and_actions() {
+        let mut uprp = vec![0u8; 64 * 20];
+        uprp[5] = 50;
+
+        let mut trig = vec![0u8; 2400];
+        // Deaths with an EUD mask.
+        trig[0..4].copy_from_slice(&0xFF00u32.to_le_bytes());
+        trig[4..8].copy_from_slice(&13u32.to_le_bytes());
+        trig[8..12].copy_from_slice(&7u32.to_le_bytes());
+        trig[15] = 15;
+        trig[17] = 0x02;
+        trig[18..20].copy_from_slice(b"SC");
+
+        let action = 320;
+        // Create 5 units with the properties in CUWP slot 1.
+        trig[action + 20..action + 24].copy_from_slice(&1u32.to_le_bytes());
+        trig[action + 26] = 11;
+        trig[action + 27] = 5;
+        trig[action + 28] = 0x18;
+        // Set the hit points of all units to 50%.
+        trig[action + 32 + 20..action + 32 + 24].copy_from_slice(&50u32.to_le_bytes());
+        trig[action + 32 + 26] = 49;
+        // An empty action that is not all zeroes.
+        trig[action + 3 * 32] = 1;
+
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(ChunkName::UPRP, uprp);
+        writer.add_chunk(ChunkName::TRIG, trig);
+        let chk = writer.to_bytes();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        let triggers = parse_triggers(&parsed_chk);
+        assert_eq!(triggers.len(), 1);
+
+        let conditions = &triggers[0].conditions;
+        assert_eq!(conditions.len(), 1);
+        assert!(conditions[0].flags.disabled);
+        assert_eq!(conditions[0].mask_flag, MaskFlag::Eud);
+        assert!(matches!(
+            conditions[0].condition,
+            Condition::Deaths {
+                number: 7,
+                eud_offset: 0x58A398,
+                mask: Some(0xFF00),
+                ..
             }
+        ));
 
-            let mut actions = Vec::new();
-
-            for action in trigger.actions {
-                match action.action {
-                    0 => {}
-                    1 => {
-                        actions.push(Action::Victory);
-                    }
-                    2 => {
-                        actions.push(Action::Defeat);
-                    }
-                    3 => {
-                        actions.push(Action::PreserveTrigger);
-                    }
-                    4 => {
-                        actions.push(Action::Wait {
-                            time: action.seconds_or_milliseconds as i64,
-                        });
-                    }
-                    5 => {
-                        actions.push(Action::PauseGame);
-                    }
-                    6 => {
-                        actions.push(Action::UnpauseGame);
-                    }
-                    7 => {
-                        actions.push(Action::Transmission {
-                            text: parsed_chk.get_string(action.string_number as usize).unwrap_or("couldn't get string".to_owned()),
-                            unit_type: parse_unit_type(action
-                                .unit_type_or_score_type_or_resource_type_or_alliance_status),
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                            time: action.seconds_or_milliseconds as i64,
-                            modifier: parse_number_modifier(
-                                action.number_of_units_or_action_state_or_unit_order_or_number_modifier,
-                            ),
-                            wave: parsed_chk.get_string(action.wav_string_number as usize).unwrap_or("couldn't get string".to_owned()),
-                            // TODO: not sure what this is supposed to be.
-                            wave_time: 0,
-                        });
-                    }
-                    8 => {
-                        actions.push(Action::PlayWav {
-                            wave: parsed_chk
-                                .get_string(action.wav_string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            // TODO: not sure what this is supposed to be.
-                            wave_time: 0,
-                        });
-                    }
-                    9 => {
-                        actions.push(Action::DisplayTextMessage {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    10 => {
-                        actions.push(Action::CenterView {
-                            location: parsed_chk
-                                .get_location_name(action.location as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    11 => {
-                        actions.push(Action::CreateUnitWithProperties {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(action
-                                .unit_type_or_score_type_or_resource_type_or_alliance_status),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                            unit_prop: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                        });
-                    }
-                    12 => {
-                        actions.push(Action::SetMissionObjectives {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    13 => {
-                        actions.push(Action::SetSwitch {
-                            switch: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            switch_action: parse_action_state(action.number_of_units_or_action_state_or_unit_order_or_number_modifier),
-                        });
-                    }
-                    14 => {
-                        actions.push(Action::SetCountdownTimer {
-                        time: action.seconds_or_milliseconds as i64,
-                        modifier: parse_number_modifier(
-                            action.number_of_units_or_action_state_or_unit_order_or_number_modifier,
-                        ),
-                    });
-                    }
-                    15 => {
-                        actions.push(Action::RunAIScript {
-                        script: parse_ai_script(action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number),
-                    });
-                    }
-                    16 => {
-                        actions.push(Action::RunAIScriptAtLocation {
-                            script: parse_ai_script(action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number),
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    17 => {
-                        actions.push(Action::LeaderBoardControl {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    18 => {
-                        actions.push(Action::LeaderBoardControlAtLocation {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                            location: parsed_chk
-                                .get_location_name(action.location as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    19 => {
-                        actions.push(Action::LeaderBoardResources {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            resource_type: parse_resource_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    20 => {
-                        actions.push(Action::LeaderBoardKills {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    21 => {
-                        actions.push(Action::LeaderBoardPoints {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            score_type: parse_score_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    22 => {
-                        actions.push(Action::KillAllUnits {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    23 => {
-                        actions.push(Action::KillUnitAtLocation {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    24 => {
-                        actions.push(Action::RemoveAllUnits {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    25 => {
-                        actions.push(Action::RemoveUnitAtLocation {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    26 => {
-                        actions.push(Action::SetResources {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            modifier: parse_number_modifier(action.number_of_units_or_action_state_or_unit_order_or_number_modifier),
-                            resource_type: parse_resource_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    27 => {
-                        actions.push(Action::SetScore {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            modifier: parse_number_modifier(action.number_of_units_or_action_state_or_unit_order_or_number_modifier),
-                            score_type: parse_score_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    28 => {
-                        actions.push(Action::MinimapPing {
-                            location: parsed_chk
-                                .get_location_name(action.location as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    29 => {
-                        actions.push(Action::TalkingPortrait {
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                            time: action.seconds_or_milliseconds as i64,
-                        });
-                    }
-                    30 => {
-                        actions.push(Action::MuteUnitSpeech);
-                    }
-                    31 => {
-                        actions.push(Action::UnmuteUnitSpeech);
-                    }
-                    32 => {
-                        actions.push(Action::LeaderBoardComputerPlayers {
-                            action: parse_action_state(action.number_of_units_or_action_state_or_unit_order_or_number_modifier),
-                        });
-                    }
-                    33 => {
-                        actions.push(Action::LeaderBoardGoalControl {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    34 => {
-                        actions.push(Action::LeaderBoardGoalControlAtLocation {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                            location: parsed_chk
-                                .get_location_name(action.location as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    35 => {
-                        actions.push(Action::LeaderBoardGoalResources {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            resource_type: parse_resource_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    36 => {
-                        actions.push(Action::LeaderBoardGoalKills {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    37 => {
-                        actions.push(Action::LeaderBoardGoalPoints {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                            score_type: parse_score_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    38 => {
-                        actions.push(Action::MoveLocation {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                            source_location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                            destination_location: parsed_chk.get_location_name(action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as usize).unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    39 => {
-                        actions.push(Action::MoveUnit {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                            source_location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                            destination_location: parsed_chk.get_location_name(action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as usize).unwrap_or("couldn't get string".to_owned()),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                        });
-                    }
-                    40 => {
-                        actions.push(Action::LeaderboardGreed {
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                        });
-                    }
-                    41 => {
-                        actions.push(Action::SetNextScenario {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    42 => {
-                        actions.push(Action::SetDoodadState {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                            state: parse_action_state(action.number_of_units_or_action_state_or_unit_order_or_number_modifier), // TODO: split actionstate enum into doodad state + switch state + computer-player state.
-                        });
-                    }
-                    43 => {
-                        actions.push(Action::SetInvincibility {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                            state: parse_action_state(action.number_of_units_or_action_state_or_unit_order_or_number_modifier), // TODO: split actionstate enum into doodad state + switch state + computer-player state.
-                        });
-                    }
-                    44 => {
-                        actions.push(Action::CreateUnit {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    45 => {
-                        actions.push(Action::SetDeaths {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(action
-                                .unit_type_or_score_type_or_resource_type_or_alliance_status),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            modifier: parse_number_modifier(action.number_of_units_or_action_state_or_unit_order_or_number_modifier),
-                            eud_offset:
-                            (action
-                                .unit_type_or_score_type_or_resource_type_or_alliance_status as u32).wrapping_mul(12).wrapping_add(action.first_or_only_group_or_player_affected).wrapping_mul(4).wrapping_add(0x58A364)
-                        });
-                    }
-                    46 => {
-                        actions.push(Action::Order {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(action
-                                .unit_type_or_score_type_or_resource_type_or_alliance_status),
-                            source_location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                            destination_location: parsed_chk.get_location_name(action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as usize).unwrap_or("couldn't get string".to_owned()),
-                            order: parse_order(action.number_of_units_or_action_state_or_unit_order_or_number_modifier)
-                        });
-                    }
-                    47 => {
-                        actions.push(Action::Comment {
-                            text: parsed_chk
-                                .get_string(action.string_number as usize)
-                                .unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    48 => {
-                        actions.push(Action::GiveUnitsToPlayer {
-                            source_player: parse_group(action.first_or_only_group_or_player_affected),
-                            destination_player: parse_group(action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number),
-                            unit_type: parse_unit_type(action
-                                .unit_type_or_score_type_or_resource_type_or_alliance_status),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    49 => {
-                        actions.push(Action::ModifyUnitHitPoints {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(action
-                                .unit_type_or_score_type_or_resource_type_or_alliance_status),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            mod_ammount: 0, // TODO: what is this.
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    50 => {
-                        actions.push(Action::ModifyUnitEnergy {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(action
-                                .unit_type_or_score_type_or_resource_type_or_alliance_status),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            mod_ammount: 0, // TODO: what is this.
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    51 => {
-                        actions.push(Action::ModifyUnitShieldPoints {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(action
-                                .unit_type_or_score_type_or_resource_type_or_alliance_status),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            mod_ammount: 0, // TODO: what is this.
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    52 => {
-                        actions.push(Action::ModifyUnitResource { // TOOD: ModifyUnitResourceAmount
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(action
-                                .unit_type_or_score_type_or_resource_type_or_alliance_status),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            mod_ammount: 0, // TODO: what is this.
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    53 => {
-                        actions.push(Action::ModifyUnitHangarCount {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            unit_type: parse_unit_type(action
-                                .unit_type_or_score_type_or_resource_type_or_alliance_status),
-                            number: action.second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number as i64,
-                            mod_ammount: 0, // TODO: what is this.
-                            location: parsed_chk.get_location_name(action.location as usize).unwrap_or("couldn't get string".to_owned()),
-                        });
-                    }
-                    54 => {
-                        actions.push(Action::PauseTimer);
-                    }
-                    55 => {
-                        actions.push(Action::UnpauseTimer);
-                    }
-                    56 => {
-                        actions.push(Action::Draw);
-                    }
-                    57 => {
-                        actions.push(Action::SetAllianceStatus {
-                            player: parse_group(action.first_or_only_group_or_player_affected),
-                            alliance_status: parse_alliance_status(
-                                action.unit_type_or_score_type_or_resource_type_or_alliance_status,
-                            ),
-                        });
-                    }
-                    58 => {
-                        actions.push(Action::DisableDebugMode);
-                    }
-                    59 => {
-                        actions.push(Action::EnableDebugMode);
-                    }
-                    _ => {
-                        actions.push(Action::Unknown {
-                            id: action.action as i64,
-                            raw: action,
-                        });
-                    }
-                }
+        let actions = &triggers[0].actions;
+        assert_eq!(
+            actions.iter().map(|x| x.index).collect::<Vec<_>>(),
+            vec![0, 1, 3]
+        );
+        assert!(actions[0].flags.unit_properties_used && actions[0].flags.unit_type_used);
+        match &actions[0].action {
+            Action::CreateUnitWithProperties {
+                number,
+                unit_prop,
+                properties,
+                ..
+            } => {
+                assert_eq!((*number, *unit_prop), (5, 1));
+                assert_eq!(properties.unwrap().hit_points_percent, 50);
             }
+            x => panic!("{x:?}"),
+        }
+        assert!(matches!(
+            actions[1].action,
+            Action::ModifyUnitHitPoints {
+                number: 0,
+                amount: 50,
+                ..
+            }
+        ));
+        assert!(matches!(actions[2].action, Action::NoAction));
+        assert_eq!({ actions[2].raw.location }, 1);
 
-            ret.push(Trigger {
-                conditions,
-                actions,
-                execution_flags: trigger.execution_flags,
-                activated_for_players: trigger.executed_for_player,
-                index_of_current_action: trigger.current_action,
-            });
+        assert_eq!(parse_trigger_flags(0xFF).bits(), 0xFF);
+    }
+
+    // Every condition and action id, with noise in the fields they do not use.
+    fn make_noisy_trigger() -> Vec<u8> {
+        let mut trig = (0..2400u32)
+            .map(|x| (x.wrapping_mul(2654435761) >> 24) as u8)
+            .collect::<Vec<_>>();
+
+        for i in 0..16 {
+            trig[i * 20 + 15] = i as u8 + 8;
         }
+        for i in 0..64 {
+            trig[320 + i * 32 + 26] = i as u8;
+        }
+
+        trig
+    }
+
+    #[test]
+    fn round_trips_every_byte() {
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(ChunkName::TRIG, make_noisy_trigger());
+        writer.add_chunk(ChunkName::MBRF, make_noisy_trigger());
+        let chk = writer.to_bytes();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        let trig = &parsed_chk.trig.as_ref().unwrap().triggers[0];
+        let trigger = Trigger::from_raw(trig, &[]);
+        assert!(trigger
+            .actions
+            .iter()
+            .any(|x| matches!(x.action, Action::ModifyUnitEnergy { .. })));
+        assert!(trigger
+            .actions
+            .iter()
+            .any(|x| matches!(x.action, Action::Unknown { id: 63, .. })));
+        assert_eq!(trigger.to_raw(), *trig);
+
+        let mbrf = &parsed_chk.mbrf.as_ref().unwrap().triggers[0];
+        assert_eq!(MissionBriefing::from_raw(mbrf).to_raw(), *mbrf);
     }
 
-    ret
+    #[test]
+    fn edits_typed_actions() {
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(ChunkName::TRIG, make_noisy_trigger());
+        let chk = writer.to_bytes();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        let mut trigger = parse_triggers(&parsed_chk).remove(0);
+        // Keep Wait and SetCountdownTimer.
+        trigger.actions.retain(|x| x.index == 4 || x.index == 14);
+        trigger.actions.push(ActionEntry::new(
+            5,
+            Action::CreateUnit {
+                player: Group::Player3,
+                unit_type: UnitType::TerranGhost,
+                number: 4,
+                location: LocationId(2),
+            },
+        ));
+        if let Action::Wait { time } = &mut trigger.actions[0].action {
+            *time = 1500;
+        } else {
+            unreachable!();
+        }
+
+        let raw = trigger.to_raw();
+        assert_eq!({ raw.actions[4].seconds_or_milliseconds }, 1500);
+        assert_eq!({ raw.actions[4].location }, {
+            trigger.actions[0].raw.location
+        });
+        assert_eq!(raw.actions[3].action, 0);
+
+        let create_unit = raw.actions[5];
+        assert_eq!(create_unit.action, 44);
+        assert_eq!({ create_unit.first_or_only_group_or_player_affected }, 2);
+        assert_eq!(
+            { create_unit.unit_type_or_score_type_or_resource_type_or_alliance_status },
+            1
+        );
+        assert_eq!(
+            create_unit.number_of_units_or_action_state_or_unit_order_or_number_modifier,
+            4
+        );
+        assert_eq!({ create_unit.location }, 2);
+
+        let reparsed = Trigger::from_raw(&raw, &[]);
+        assert!(matches!(
+            reparsed.actions[1].action,
+            Action::CreateUnit {
+                number: 4,
+                location: LocationId(2),
+                ..
+            }
+        ));
+        assert_eq!(reparsed.actions[2], trigger.actions[1]);
+    }
 }
@@ -0,0 +1,3165 @@
+use crate::{
+    chk::chk_trig::{ChkTrigAction, ChkTrigCondition, ChkTrigIndividual},
+    eud::{death_table_address, eud_player_and_unit},
+    trig::{
+        parse_action_state, parse_alliance_status, parse_group, parse_number_modifier,
+        parse_numeric_comparison, parse_order, parse_resource_type, parse_score_type,
+        parse_switch_state, parse_unit_type, serialize_group, serialize_unit_type,
+    },
+    util::{reinterpret_as_bytes, reinterpret_slice3},
+    Action, ActionEntry, ActionState, AiScript, AllianceStatus, Condition, ConditionEntry, Group,
+    LocationId, MaskFlag, MissionBriefing, MissionBriefingAction, MissionBriefingActionEntry,
+    MissionBriefingCondition, MissionBriefingConditionEntry, NumberModifier, NumericComparison,
+    Order, ParsedChk, ResourceType, ScoreType, StringId, StringTable, SwitchState, Trigger,
+    TriggerFlags, UnitType,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use tracing::instrument;
+
+// The names StarEdit and SCMDraft use for units in triggers, indexed by unit id.
+pub(crate) static UNIT_NAMES: [&str; 233] = [
+    "Terran Marine",
+    "Terran Ghost",
+    "Terran Vulture",
+    "Terran Goliath",
+    "Goliath Turret",
+    "Terran Siege Tank (Tank Mode)",
+    "Tank Turret type 1",
+    "Terran SCV",
+    "Terran Wraith",
+    "Terran Science Vessel",
+    "Gui Montag (Firebat)",
+    "Terran Dropship",
+    "Terran Battlecruiser",
+    "Vulture Spider Mine",
+    "Nuclear Missile",
+    "Terran Civilian",
+    "Sarah Kerrigan (Ghost)",
+    "Alan Schezar (Goliath)",
+    "Alan Schezar Turret",
+    "Jim Raynor (Vulture)",
+    "Jim Raynor (Marine)",
+    "Tom Kazansky (Wraith)",
+    "Magellan (Science Vessel)",
+    "Edmund Duke (Tank Mode)",
+    "Edmund Duke Turret type 1",
+    "Edmund Duke (Siege Mode)",
+    "Edmund Duke Turret type 2",
+    "Arcturus Mengsk (Battlecruiser)",
+    "Hyperion (Battlecruiser)",
+    "Norad II (Battlecruiser)",
+    "Terran Siege Tank (Siege Mode)",
+    "Tank Turret type 2",
+    "Terran Firebat",
+    "Scanner Sweep",
+    "Terran Medic",
+    "Zerg Larva",
+    "Zerg Egg",
+    "Zerg Zergling",
+    "Zerg Hydralisk",
+    "Zerg Ultralisk",
+    "Zerg Broodling",
+    "Zerg Drone",
+    "Zerg Overlord",
+    "Zerg Mutalisk",
+    "Zerg Guardian",
+    "Zerg Queen",
+    "Zerg Defiler",
+    "Zerg Scourge",
+    "Torrasque (Ultralisk)",
+    "Matriarch (Queen)",
+    "Infested Terran",
+    "Infested Kerrigan (Infested Terran)",
+    "Unclean One (Defiler)",
+    "Hunter Killer (Hydralisk)",
+    "Devouring One (Zergling)",
+    "Kukulza (Mutalisk)",
+    "Kukulza (Guardian)",
+    "Yggdrasill (Overlord)",
+    "Terran Valkyrie",
+    "Mutalisk Cocoon",
+    "Protoss Corsair",
+    "Protoss Dark Templar",
+    "Zerg Devourer",
+    "Protoss Dark Archon",
+    "Protoss Probe",
+    "Protoss Zealot",
+    "Protoss Dragoon",
+    "Protoss High Templar",
+    "Protoss Archon",
+    "Protoss Shuttle",
+    "Protoss Scout",
+    "Protoss Arbiter",
+    "Protoss Carrier",
+    "Protoss Interceptor",
+    "Dark Templar (Hero)",
+    "Zeratul (Dark Templar)",
+    "Tassadar/Zeratul (Archon)",
+    "Fenix (Zealot)",
+    "Fenix (Dragoon)",
+    "Tassadar (Templar)",
+    "Mojo (Scout)",
+    "Warbringer (Reaver)",
+    "Gantrithor (Carrier)",
+    "Protoss Reaver",
+    "Protoss Observer",
+    "Protoss Scarab",
+    "Danimoth (Arbiter)",
+    "Aldaris (Templar)",
+    "Artanis (Scout)",
+    "Rhynadon (Badlands Critter)",
+    "Bengalaas (Jungle Critter)",
+    "Cargo Ship (Unused)",
+    "Mercenary Gunship (Unused)",
+    "Scantid (Desert Critter)",
+    "Kakaru (Twilight Critter)",
+    "Ragnasaur (Ashworld Critter)",
+    "Ursadon (Ice World Critter)",
+    "Lurker Egg",
+    "Raszagal (Corsair)",
+    "Samir Duran (Ghost)",
+    "Alexei Stukov (Ghost)",
+    "Map Revealer",
+    "Gerard DuGalle (BattleCruiser)",
+    "Zerg Lurker",
+    "Infested Duran (Infested Terran)",
+    "Disruption Web",
+    "Terran Command Center",
+    "Terran Comsat Station",
+    "Terran Nuclear Silo",
+    "Terran Supply Depot",
+    "Terran Refinery",
+    "Terran Barracks",
+    "Terran Academy",
+    "Terran Factory",
+    "Terran Starport",
+    "Terran Control Tower",
+    "Terran Science Facility",
+    "Terran Covert Ops",
+    "Terran Physics Lab",
+    "Starbase (Unused)",
+    "Terran Machine Shop",
+    "Repair Bay (Unused)",
+    "Terran Engineering Bay",
+    "Terran Armory",
+    "Terran Missile Turret",
+    "Terran Bunker",
+    "Norad II (Crashed Battlecruiser)",
+    "Ion Cannon",
+    "Uraj Crystal",
+    "Khalis Crystal",
+    "Infested Command Center",
+    "Zerg Hatchery",
+    "Zerg Lair",
+    "Zerg Hive",
+    "Zerg Nydus Canal",
+    "Zerg Hydralisk Den",
+    "Zerg Defiler Mound",
+    "Zerg Greater Spire",
+    "Zerg Queen's Nest",
+    "Zerg Evolution Chamber",
+    "Zerg Ultralisk Cavern",
+    "Zerg Spire",
+    "Zerg Spawning Pool",
+    "Zerg Creep Colony",
+    "Zerg Spore Colony",
+    "Unused Zerg Building",
+    "Zerg Sunken Colony",
+    "Zerg Overmind (With Shell)",
+    "Zerg Overmind",
+    "Zerg Extractor",
+    "Mature Chrysalis",
+    "Zerg Cerebrate",
+    "Zerg Cerebrate Daggoth",
+    "Unused Zerg Building 5",
+    "Protoss Nexus",
+    "Protoss Robotics Facility",
+    "Protoss Pylon",
+    "Protoss Assimilator",
+    "Unused Protoss Building",
+    "Protoss Observatory",
+    "Protoss Gateway",
+    "Unused Protoss Building 2",
+    "Protoss Photon Cannon",
+    "Protoss Citadel of Adun",
+    "Protoss Cybernetics Core",
+    "Protoss Templar Archives",
+    "Protoss Forge",
+    "Protoss Stargate",
+    "Stasis Cell/Prison",
+    "Protoss Fleet Beacon",
+    "Protoss Arbiter Tribunal",
+    "Protoss Robotics Support Bay",
+    "Protoss Shield Battery",
+    "Khaydarin Crystal Formation",
+    "Protoss Temple",
+    "Xel'Naga Temple",
+    "Mineral Field (Type 1)",
+    "Mineral Field (Type 2)",
+    "Mineral Field (Type 3)",
+    "Cave (Unused)",
+    "Cave-in (Unused)",
+    "Cantina (Unused)",
+    "Mining Platform (Unused)",
+    "Independent Command Center (Unused)",
+    "Independent Starport (Unused)",
+    "Independent Jump Gate (Unused)",
+    "Ruins (Unused)",
+    "Khaydarin Crystal Formation (Unused)",
+    "Vespene Geyser",
+    "Warp Gate",
+    "Psi Disrupter",
+    "Zerg Marker",
+    "Terran Marker",
+    "Protoss Marker",
+    "Zerg Beacon",
+    "Terran Beacon",
+    "Protoss Beacon",
+    "Zerg Flag Beacon",
+    "Terran Flag Beacon",
+    "Protoss Flag Beacon",
+    "Power Generator",
+    "Overmind Cocoon",
+    "Dark Swarm",
+    "Floor Missile Trap",
+    "Floor Hatch (Unused)",
+    "Left Upper Level Door",
+    "Right Upper Level Door",
+    "Left Pit Door",
+    "Right Pit Door",
+    "Floor Gun Trap",
+    "Left Wall Missile Trap",
+    "Left Wall Flame Trap",
+    "Right Wall Missile Trap",
+    "Right Wall Flame Trap",
+    "Start Location",
+    "Flag",
+    "Young Chrysalis",
+    "Psi Emitter",
+    "Data Disc",
+    "Khaydarin Crystal",
+    "Mineral Cluster Type 1",
+    "Mineral Cluster Type 2",
+    "Protoss Gas Orb Type 1",
+    "Protoss Gas Orb Type 2",
+    "Zerg Gas Sac Type 1",
+    "Zerg Gas Sac Type 2",
+    "Terran Gas Tank Type 1",
+    "Terran Gas Tank Type 2",
+    "None",
+    "Any unit",
+    "Men",
+    "Buildings",
+    "Factories",
+];
+
+// Indexed by the value of Group.
+pub(crate) static GROUP_NAMES: [&str; 27] = [
+    "Player 1",
+    "Player 2",
+    "Player 3",
+    "Player 4",
+    "Player 5",
+    "Player 6",
+    "Player 7",
+    "Player 8",
+    "Player 9",
+    "Player 10",
+    "Player 11",
+    "Player 12",
+    "None",
+    "Current Player",
+    "Foes",
+    "Allies",
+    "Neutral Players",
+    "All players",
+    "Force 1",
+    "Force 2",
+    "Force 3",
+    "Force 4",
+    "Unused 1",
+    "Unused 2",
+    "Unused 3",
+    "Unused 4",
+    "Non Allied Victory Players",
+];
+
+// Map specific names used in TrigEdit text. Names that are not unique are written as numbers so they can be
+// read back.
+#[derive(Debug, Clone)]
+pub struct TrigEditNames {
+    strings: Option<StringTable>,
+    string_ids: HashMap<String, StringId>,
+    // Index 0 is location 1.
+    locations: Vec<String>,
+    location_ids: HashMap<String, LocationId>,
+    switches: Vec<String>,
+    switch_ids: HashMap<String, usize>,
+}
+
+impl TrigEditNames {
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(parsed_chk: &ParsedChk) -> TrigEditNames {
+        let strings = StringTable::new(parsed_chk).ok();
+
+        let mut string_ids = HashMap::new();
+        for (string_id, entry) in strings.iter().flat_map(|x| x.iter()) {
+            string_ids.entry(entry.text.clone()).or_insert(string_id);
+        }
+
+        // Unnamed locations are called "Location N" like in the editors.
+        let mut locations = Vec::new();
+        if let Ok(mrgn) = &parsed_chk.mrgn {
+            for (index, location) in mrgn.locations.iter().enumerate() {
+                let editor_name = parsed_chk
+                    .ostr
+                    .as_ref()
+                    .ok()
+                    .and_then(|x| x.location_name.get(index))
+                    .and_then(|&x| parsed_chk.get_editor_string(x as usize));
+
+                let name = editor_name.or_else(|| {
+                    strings
+                        .as_ref()?
+                        .text(StringId(location.name_string_number as u32))
+                        .ok()
+                        .map(|x| x.to_owned())
+                });
+
+                locations.push(name.unwrap_or_else(|| format!("Location {}", index + 1)));
+            }
+        }
+
+        let mut location_ids = HashMap::new();
+        location_ids.insert("No Location".to_owned(), LocationId(0));
+        for (index, name) in locations.iter().enumerate() {
+            location_ids
+                .entry(name.clone())
+                .or_insert(LocationId(index as u32 + 1));
+        }
+
+        let switches: Vec<String> = (0..256)
+            .map(|switch| {
+                parsed_chk
+                    .get_switch_name(switch)
+                    .ok()
+                    .flatten()
+                    .unwrap_or_else(|| format!("Switch{}", switch + 1))
+            })
+            .collect();
+
+        let mut switch_ids = HashMap::new();
+        for (switch, name) in switches.iter().enumerate() {
+            switch_ids.entry(name.clone()).or_insert(switch);
+        }
+
+        TrigEditNames {
+            strings,
+            string_ids,
+            locations,
+            location_ids,
+            switches,
+            switch_ids,
+        }
+    }
+
+    // location is 1 based.
+    pub fn location_name(&self, location: LocationId) -> Option<&str> {
+        if location.0 == 0 {
+            return Some("No Location");
+        }
+
+        self.locations
+            .get(location.0 as usize - 1)
+            .map(|x| x.as_str())
+    }
+
+    // The first location with the name wins, here and for switches and strings.
+    pub fn find_location(&self, name: &str) -> Option<LocationId> {
+        self.location_ids.get(name).copied()
+    }
+
+    // switch is 0 based.
+    pub fn switch_name(&self, switch: usize) -> Option<&str> {
+        self.switches.get(switch).map(|x| x.as_str())
+    }
+
+    pub fn find_switch(&self, name: &str) -> Option<usize> {
+        self.switch_ids.get(name).copied()
+    }
+
+    pub fn find_string(&self, text: &str) -> Option<StringId> {
+        self.string_ids.get(text).copied()
+    }
+
+    pub fn strings(&self) -> Option<&StringTable> {
+        self.strings.as_ref()
+    }
+
+    fn location(&self, location: LocationId) -> String {
+        match self.location_name(location) {
+            Some(name) if self.find_location(name) == Some(location) => quote(name),
+            _ => location.0.to_string(),
+        }
+    }
+
+    fn switch(&self, switch: i64) -> String {
+        match self.switch_name(switch as usize) {
+            Some(name) if self.find_switch(name) == Some(switch as usize) => quote(name),
+            _ => switch.to_string(),
+        }
+    }
+
+    // String 0 is the empty string, strings that cannot be read, are empty or that have the same text as an earlier
+    // string are written as their number.
+    fn string(&self, string_id: StringId) -> String {
+        if string_id.is_none() {
+            return quote("");
+        }
+
+        match self.strings.as_ref().map(|x| x.text(string_id)) {
+            Some(Ok(text)) if !text.is_empty() && self.find_string(text) == Some(string_id) => {
+                quote(text)
+            }
+            _ => string_id.0.to_string(),
+        }
+    }
+}
+
+// Quotes and backslashes are escaped with a backslash, line breaks as \r and \n and other control
+// characters, e.g. colors, as their hex value in angle brackets like SCMDraft.
+pub fn escape_trigedit_string(text: &str) -> String {
+    let mut ret = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            '\r' => ret.push_str("\\r"),
+            '\n' => ret.push_str("\\n"),
+            c if c.is_ascii_control() => ret.push_str(&format!("<{:02X}>", c as u32)),
+            c => ret.push(c),
+        }
+    }
+
+    ret
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", escape_trigedit_string(text))
+}
+
+fn player(player: &Group) -> String {
+    match player {
+        Group::Unknown(x) => x.to_string(),
+        _ => quote(GROUP_NAMES[serialize_group(player) as usize]),
+    }
+}
+
+fn unit(unit_type: &UnitType) -> String {
+    match UNIT_NAMES.get(serialize_unit_type(unit_type) as usize) {
+        Some(name) => quote(name),
+        None => serialize_unit_type(unit_type).to_string(),
+    }
+}
+
+// 0 means all units.
+fn count(number: i64) -> String {
+    if number == 0 {
+        "All".to_owned()
+    } else {
+        number.to_string()
+    }
+}
+
+fn comparison(comparison: &NumericComparison) -> String {
+    match comparison {
+        NumericComparison::AtLeast => "At least".to_owned(),
+        NumericComparison::AtMost => "At most".to_owned(),
+        NumericComparison::Exactly => "Exactly".to_owned(),
+        NumericComparison::Unknown(x) => x.to_string(),
+    }
+}
+
+fn modifier(modifier: &NumberModifier) -> String {
+    match modifier {
+        NumberModifier::SetTo => "Set To".to_owned(),
+        NumberModifier::Add => "Add".to_owned(),
+        NumberModifier::Subtract => "Subtract".to_owned(),
+        NumberModifier::Unknown(x) => x.to_string(),
+    }
+}
+
+fn resource(resource_type: &ResourceType) -> String {
+    match resource_type {
+        ResourceType::Ore => "ore".to_owned(),
+        ResourceType::Gas => "gas".to_owned(),
+        ResourceType::OreAndGas => "ore and gas".to_owned(),
+        ResourceType::Unknown(x) => x.to_string(),
+    }
+}
+
+fn score(score_type: &ScoreType) -> String {
+    match score_type {
+        ScoreType::Total => "Total".to_owned(),
+        ScoreType::Units => "Units".to_owned(),
+        ScoreType::Buildings => "Buildings".to_owned(),
+        ScoreType::UnitsAndBuildings => "Units and buildings".to_owned(),
+        ScoreType::Kills => "Kills".to_owned(),
+        ScoreType::Razings => "Razings".to_owned(),
+        ScoreType::KillsAndRazings => "Kills and razings".to_owned(),
+        ScoreType::Custom => "Custom".to_owned(),
+        ScoreType::Unknown(x) => x.to_string(),
+    }
+}
+
+fn switch_state(switch_state: &SwitchState) -> String {
+    match switch_state {
+        SwitchState::Set => "set".to_owned(),
+        SwitchState::Cleared => "not set".to_owned(),
+        SwitchState::Unknown(x) => x.to_string(),
+    }
+}
+
+fn switch_action(switch_action: &ActionState) -> String {
+    match switch_action {
+        ActionState::EnabledOrSet => "set".to_owned(),
+        ActionState::DisabledOrClear => "clear".to_owned(),
+        ActionState::ToggleOrToggle => "toggle".to_owned(),
+        ActionState::RandomizeSwitch => "randomize".to_owned(),
+        ActionState::Unknown(x) => x.to_string(),
+    }
+}
+
+fn state(state: &ActionState) -> String {
+    match state {
+        ActionState::EnabledOrSet => "enable".to_owned(),
+        ActionState::DisabledOrClear => "disable".to_owned(),
+        ActionState::ToggleOrToggle => "toggle".to_owned(),
+        ActionState::RandomizeSwitch => "11".to_owned(),
+        ActionState::Unknown(x) => x.to_string(),
+    }
+}
+
+fn order(order: &Order) -> String {
+    match order {
+        Order::Move => "move".to_owned(),
+        Order::Patrol => "patrol".to_owned(),
+        Order::Attack => "attack".to_owned(),
+        Order::Unknown(x) => x.to_string(),
+    }
+}
+
+fn alliance(alliance_status: &AllianceStatus) -> String {
+    match alliance_status {
+        AllianceStatus::Enemy => "Enemy".to_owned(),
+        AllianceStatus::Allied => "Ally".to_owned(),
+        AllianceStatus::AlliedVictory => "Allied Victory".to_owned(),
+        AllianceStatus::Unknown(x) => x.to_string(),
+    }
+}
+
+// Scripts are written as their 4 character id, e.g. "TMCu".
+fn ai_script(script: &AiScript) -> String {
+    let bytes = script.0.to_le_bytes();
+    if bytes.iter().all(|x| x.is_ascii_graphic() || *x == b' ') {
+        quote(&String::from_utf8_lossy(&bytes))
+    } else {
+        script.0.to_string()
+    }
+}
+
+fn display(flags: &TriggerFlags) -> &'static str {
+    if flags.always_display {
+        "Always Display"
+    } else {
+        "Don't Always Display"
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    quote(&bytes.iter().map(|x| format!("{x:02X}")).collect::<String>())
+}
+
+// Deaths and SetDeaths outside of the death table are written as the address they touch, as long as the
+// address gives back the same player and unit.
+fn eud_address(player: &Group, unit_type: &UnitType, eud_offset: u32) -> Option<u32> {
+    let player = serialize_group(player);
+    let unit = serialize_unit_type(unit_type);
+
+    if (unit < 228 && player < 27) || eud_player_and_unit(eud_offset) != Some((player, unit)) {
+        return None;
+    }
+
+    Some(eud_offset)
+}
+
+fn condition(names: &TrigEditNames, entry: &ConditionEntry) -> String {
+    match &entry.condition {
+        Condition::Unknown { .. } | Condition::NoCondition => {
+            format!(
+                "Raw Condition({})",
+                hex(reinterpret_as_bytes(&entry.to_raw()))
+            )
+        }
+        Condition::CountdownTimer {
+            comparison: c,
+            number,
+        } => format!("Countdown Timer({}, {number})", comparison(c)),
+        Condition::Command {
+            player: p,
+            comparison: c,
+            unit_type,
+            number,
+        } => format!(
+            "Command({}, {}, {}, {number})",
+            player(p),
+            unit(unit_type),
+            comparison(c)
+        ),
+        Condition::Bring {
+            player: p,
+            comparison: c,
+            unit_type,
+            location,
+            number,
+        } => format!(
+            "Bring({}, {}, {}, {}, {number})",
+            player(p),
+            unit(unit_type),
+            names.location(*location),
+            comparison(c)
+        ),
+        Condition::Accumulate {
+            player: p,
+            comparison: c,
+            number,
+            resource_type,
+            ..
+        } => format!(
+            "Accumulate({}, {}, {number}, {})",
+            player(p),
+            comparison(c),
+            resource(resource_type)
+        ),
+        Condition::Kill {
+            player: p,
+            comparison: c,
+            unit_type,
+            number,
+        } => format!(
+            "Kill({}, {}, {}, {number})",
+            player(p),
+            unit(unit_type),
+            comparison(c)
+        ),
+        Condition::CommandsTheMost { unit_type } => {
+            format!("Command the Most({})", unit(unit_type))
+        }
+        Condition::CommandsTheMostAt {
+            unit_type,
+            location,
+        } => format!(
+            "Command the Most At({}, {})",
+            unit(unit_type),
+            names.location(*location)
+        ),
+        Condition::MostKills { unit_type } => format!("Most Kills({})", unit(unit_type)),
+        Condition::HighestScore { score_type } => format!("Highest Score({})", score(score_type)),
+        Condition::MostResources { resource_type } => {
+            format!("Most Resources({})", resource(resource_type))
+        }
+        Condition::Switch {
+            switch,
+            switch_state: s,
+        } => format!("Switch({}, {})", names.switch(*switch), switch_state(s)),
+        Condition::ElapsedTime {
+            comparison: c,
+            number,
+        } => format!("Elapsed Time({}, {number})", comparison(c)),
+        Condition::DataIsAMissionBriefing => "Mission Briefing()".to_owned(),
+        Condition::Opponents {
+            player: p,
+            comparison: c,
+            number,
+        } => format!("Opponents({}, {}, {number})", player(p), comparison(c)),
+        Condition::Deaths {
+            player: p,
+            comparison: c,
+            unit_type,
+            number,
+            eud_offset,
+            mask,
+        } => match (eud_address(p, unit_type, *eud_offset), mask) {
+            (Some(address), None) => {
+                format!("Memory(0x{address:08X}, {}, {number})", comparison(c))
+            }
+            (Some(address), Some(mask)) => format!(
+                "Masked Memory(0x{address:08X}, {}, {number}, 0x{mask:08X})",
+                comparison(c)
+            ),
+            (None, None) => format!(
+                "Deaths({}, {}, {}, {number})",
+                player(p),
+                unit(unit_type),
+                comparison(c)
+            ),
+            (None, Some(mask)) => format!(
+                "Masked Deaths({}, {}, {}, {number}, 0x{mask:08X})",
+                player(p),
+                unit(unit_type),
+                comparison(c)
+            ),
+        },
+        Condition::CommandsTheLeast { unit_type } => {
+            format!("Command the Least({})", unit(unit_type))
+        }
+        Condition::CommandsTheLeastAt {
+            unit_type,
+            location,
+        } => format!(
+            "Command the Least At({}, {})",
+            unit(unit_type),
+            names.location(*location)
+        ),
+        Condition::LeastKills { unit_type } => format!("Least Kills({})", unit(unit_type)),
+        Condition::LowestScore { score_type } => format!("Lowest Score({})", score(score_type)),
+        Condition::LeastResources { resource_type } => {
+            format!("Least Resources({})", resource(resource_type))
+        }
+        Condition::Score {
+            player: p,
+            comparison: c,
+            score_type,
+            number,
+        } => format!(
+            "Score({}, {}, {}, {number})",
+            player(p),
+            score(score_type),
+            comparison(c)
+        ),
+        Condition::Always => "Always()".to_owned(),
+        Condition::Never => "Never()".to_owned(),
+    }
+}
+
+fn action(names: &TrigEditNames, entry: &ActionEntry) -> String {
+    match &entry.action {
+        Action::Unknown { .. } | Action::NoAction => {
+            format!("Raw Action({})", hex(reinterpret_as_bytes(&entry.to_raw())))
+        }
+        Action::Victory => "Victory()".to_owned(),
+        Action::Defeat => "Defeat()".to_owned(),
+        Action::PreserveTrigger => "Preserve Trigger()".to_owned(),
+        Action::Wait { time } => format!("Wait({time})"),
+        Action::PauseGame => "Pause Game()".to_owned(),
+        Action::UnpauseGame => "Unpause Game()".to_owned(),
+        Action::Transmission {
+            text,
+            unit_type,
+            location,
+            time,
+            modifier: m,
+            wave,
+            wave_time,
+        } => format!(
+            "Transmission({}, {}, {}, {}, {}, {time}, {}, {wave_time})",
+            display(&entry.flags),
+            names.string(*text),
+            unit(unit_type),
+            names.location(*location),
+            modifier(m),
+            names.string(*wave)
+        ),
+        Action::PlayWav { wave, wave_time } => {
+            format!("Play WAV({}, {wave_time})", names.string(*wave))
+        }
+        Action::DisplayTextMessage { text } => format!(
+            "Display Text Message({}, {})",
+            display(&entry.flags),
+            names.string(*text)
+        ),
+        Action::CenterView { location } => format!("Center View({})", names.location(*location)),
+        Action::CreateUnitWithProperties {
+            player: p,
+            unit_type,
+            number,
+            location,
+            unit_prop,
+            ..
+        } => format!(
+            "Create Unit with Properties({}, {}, {number}, {}, {unit_prop})",
+            player(p),
+            unit(unit_type),
+            names.location(*location)
+        ),
+        Action::SetMissionObjectives { text } => {
+            format!("Set Mission Objectives({})", names.string(*text))
+        }
+        Action::SetSwitch {
+            switch,
+            switch_action: s,
+        } => format!(
+            "Set Switch({}, {})",
+            names.switch(*switch),
+            switch_action(s)
+        ),
+        Action::SetCountdownTimer { time, modifier: m } => {
+            format!("Set Countdown Timer({}, {time})", modifier(m))
+        }
+        Action::RunAIScript { script } => format!("Run AI Script({})", ai_script(script)),
+        Action::RunAIScriptAtLocation { script, location } => format!(
+            "Run AI Script At Location({}, {})",
+            ai_script(script),
+            names.location(*location)
+        ),
+        Action::LeaderBoardControl { text, unit_type } => format!(
+            "Leader Board Control({}, {})",
+            names.string(*text),
+            unit(unit_type)
+        ),
+        Action::LeaderBoardControlAtLocation {
+            text,
+            unit_type,
+            location,
+        } => format!(
+            "Leader Board Control At Location({}, {}, {})",
+            names.string(*text),
+            unit(unit_type),
+            names.location(*location)
+        ),
+        Action::LeaderBoardResources {
+            text,
+            resource_type,
+        } => format!(
+            "Leader Board Resources({}, {})",
+            names.string(*text),
+            resource(resource_type)
+        ),
+        Action::LeaderBoardKills { text, unit_type } => format!(
+            "Leader Board Kills({}, {})",
+            names.string(*text),
+            unit(unit_type)
+        ),
+        Action::LeaderBoardPoints { text, score_type } => format!(
+            "Leader Board Points({}, {})",
+            names.string(*text),
+            score(score_type)
+        ),
+        Action::KillAllUnits {
+            player: p,
+            unit_type,
+        } => format!("Kill Unit({}, {})", player(p), unit(unit_type)),
+        Action::KillUnitAtLocation {
+            player: p,
+            unit_type,
+            number,
+            location,
+        } => format!(
+            "Kill Unit At Location({}, {}, {}, {})",
+            player(p),
+            unit(unit_type),
+            count(*number),
+            names.location(*location)
+        ),
+        Action::RemoveAllUnits {
+            player: p,
+            unit_type,
+        } => format!("Remove Unit({}, {})", player(p), unit(unit_type)),
+        Action::RemoveUnitAtLocation {
+            player: p,
+            unit_type,
+            number,
+            location,
+        } => format!(
+            "Remove Unit At Location({}, {}, {}, {})",
+            player(p),
+            unit(unit_type),
+            count(*number),
+            names.location(*location)
+        ),
+        Action::SetResources {
+            player: p,
+            number,
+            modifier: m,
+            resource_type,
+        } => format!(
+            "Set Resources({}, {}, {number}, {})",
+            player(p),
+            modifier(m),
+            resource(resource_type)
+        ),
+        Action::SetScore {
+            player: p,
+            number,
+            modifier: m,
+            score_type,
+        } => format!(
+            "Set Score({}, {}, {number}, {})",
+            player(p),
+            modifier(m),
+            score(score_type)
+        ),
+        Action::MinimapPing { location } => {
+            format!("Minimap Ping({})", names.location(*location))
+        }
+        Action::TalkingPortrait { unit_type, time } => {
+            format!("Talking Portrait({}, {time})", unit(unit_type))
+        }
+        Action::MuteUnitSpeech => "Mute Unit Speech()".to_owned(),
+        Action::UnmuteUnitSpeech => "Unmute Unit Speech()".to_owned(),
+        Action::LeaderBoardComputerPlayers { action } => {
+            format!("Leaderboard Computer Players({})", state(action))
+        }
+        Action::LeaderBoardGoalControl {
+            text,
+            number,
+            unit_type,
+        } => format!(
+            "Leaderboard Goal Control({}, {}, {number})",
+            names.string(*text),
+            unit(unit_type)
+        ),
+        Action::LeaderBoardGoalControlAtLocation {
+            text,
+            number,
+            unit_type,
+            location,
+        } => format!(
+            "Leaderboard Goal Control At Location({}, {}, {number}, {})",
+            names.string(*text),
+            unit(unit_type),
+            names.location(*location)
+        ),
+        Action::LeaderBoardGoalResources {
+            text,
+            number,
+            resource_type,
+        } => format!(
+            "Leaderboard Goal Resources({}, {number}, {})",
+            names.string(*text),
+            resource(resource_type)
+        ),
+        Action::LeaderBoardGoalKills {
+            text,
+            number,
+            unit_type,
+        } => format!(
+            "Leaderboard Goal Kills({}, {}, {number})",
+            names.string(*text),
+            unit(unit_type)
+        ),
+        Action::LeaderBoardGoalPoints {
+            text,
+            number,
+            score_type,
+        } => format!(
+            "Leaderboard Goal Points({}, {}, {number})",
+            names.string(*text),
+            score(score_type)
+        ),
+        Action::MoveLocation {
+            player: p,
+            unit_type,
+            source_location,
+            destination_location,
+        } => format!(
+            "Move Location({}, {}, {}, {})",
+            player(p),
+            unit(unit_type),
+            names.location(*source_location),
+            names.location(*destination_location)
+        ),
+        Action::MoveUnit {
+            player: p,
+            unit_type,
+            number,
+            source_location,
+            destination_location,
+        } => format!(
+            "Move Unit({}, {}, {}, {}, {})",
+            player(p),
+            unit(unit_type),
+            count(*number),
+            names.location(*source_location),
+            names.location(*destination_location)
+        ),
+        Action::LeaderboardGreed { number } => format!("Leader Board Greed({number})"),
+        Action::SetNextScenario { text } => {
+            format!("Set Next Scenario({})", names.string(*text))
+        }
+        Action::SetDoodadState {
+            player: p,
+            unit_type,
+            location,
+            state: s,
+        } => format!(
+            "Set Doodad State({}, {}, {}, {})",
+            player(p),
+            unit(unit_type),
+            names.location(*location),
+            state(s)
+        ),
+        Action::SetInvincibility {
+            player: p,
+            unit_type,
+            location,
+            state: s,
+        } => format!(
+            "Set Invincibility({}, {}, {}, {})",
+            player(p),
+            unit(unit_type),
+            names.location(*location),
+            state(s)
+        ),
+        Action::CreateUnit {
+            player: p,
+            unit_type,
+            number,
+            location,
+        } => format!(
+            "Create Unit({}, {}, {number}, {})",
+            player(p),
+            unit(unit_type),
+            names.location(*location)
+        ),
+        Action::SetDeaths {
+            player: p,
+            unit_type,
+            number,
+            modifier: m,
+            eud_offset,
+            mask,
+        } => match (eud_address(p, unit_type, *eud_offset), mask) {
+            (Some(address), None) => {
+                format!("Set Memory(0x{address:08X}, {}, {number})", modifier(m))
+            }
+            (Some(address), Some(mask)) => format!(
+                "Set Masked Memory(0x{address:08X}, {}, {number}, 0x{mask:08X})",
+                modifier(m)
+            ),
+            (None, None) => format!(
+                "Set Deaths({}, {}, {}, {number})",
+                player(p),
+                unit(unit_type),
+                modifier(m)
+            ),
+            (None, Some(mask)) => format!(
+                "Set Masked Deaths({}, {}, {}, {number}, 0x{mask:08X})",
+                player(p),
+                unit(unit_type),
+                modifier(m)
+            ),
+        },
+        Action::Order {
+            player: p,
+            unit_type,
+            source_location,
+            destination_location,
+            order: o,
+        } => format!(
+            "Order({}, {}, {}, {}, {})",
+            player(p),
+            unit(unit_type),
+            names.location(*source_location),
+            names.location(*destination_location),
+            order(o)
+        ),
+        Action::Comment { text } => format!("Comment({})", names.string(*text)),
+        Action::GiveUnitsToPlayer {
+            source_player,
+            destination_player,
+            unit_type,
+            number,
+            location,
+        } => format!(
+            "Give Units to Player({}, {}, {}, {}, {})",
+            player(source_player),
+            player(destination_player),
+            unit(unit_type),
+            count(*number),
+            names.location(*location)
+        ),
+        Action::ModifyUnitHitPoints {
+            player: p,
+            unit_type,
+            number,
+            amount,
+            location,
+        } => format!(
+            "Modify Unit Hit Points({}, {}, {amount}, {}, {})",
+            player(p),
+            unit(unit_type),
+            count(*number),
+            names.location(*location)
+        ),
+        Action::ModifyUnitEnergy {
+            player: p,
+            unit_type,
+            number,
+            amount,
+            location,
+        } => format!(
+            "Modify Unit Energy({}, {}, {amount}, {}, {})",
+            player(p),
+            unit(unit_type),
+            count(*number),
+            names.location(*location)
+        ),
+        Action::ModifyUnitShieldPoints {
+            player: p,
+            unit_type,
+            number,
+            amount,
+            location,
+        } => format!(
+            "Modify Unit Shield Points({}, {}, {amount}, {}, {})",
+            player(p),
+            unit(unit_type),
+            count(*number),
+            names.location(*location)
+        ),
+        // The unit is always a resource, so the tools do not show it.
+        Action::ModifyUnitResource {
+            player: p,
+            number,
+            amount,
+            location,
+            ..
+        } => format!(
+            "Modify Unit Resource Amount({}, {amount}, {}, {})",
+            player(p),
+            count(*number),
+            names.location(*location)
+        ),
+        Action::ModifyUnitHangarCount {
+            player: p,
+            unit_type,
+            number,
+            amount,
+            location,
+        } => format!(
+            "Modify Unit Hanger Count({}, {}, {amount}, {}, {})",
+            player(p),
+            unit(unit_type),
+            count(*number),
+            names.location(*location)
+        ),
+        Action::PauseTimer => "Pause Timer()".to_owned(),
+        Action::UnpauseTimer => "Unpause Timer()".to_owned(),
+        Action::Draw => "Draw()".to_owned(),
+        Action::SetAllianceStatus {
+            player: p,
+            alliance_status,
+        } => format!(
+            "Set Alliance Status({}, {})",
+            player(p),
+            alliance(alliance_status)
+        ),
+        Action::DisableDebugMode => "Disable Debug Mode()".to_owned(),
+        Action::EnableDebugMode => "Enable Debug Mode()".to_owned(),
+    }
+}
+
+fn mission_briefing_condition(entry: &MissionBriefingConditionEntry) -> String {
+    match &entry.condition {
+        MissionBriefingCondition::DataIsAMissionBriefing => "Mission Briefing()".to_owned(),
+        MissionBriefingCondition::Unknown { .. } | MissionBriefingCondition::NoCondition => {
+            format!(
+                "Raw Condition({})",
+                hex(reinterpret_as_bytes(&entry.to_raw()))
+            )
+        }
+    }
+}
+
+// Portrait slots are 0 based in the file and 1 based in the editors.
+fn mission_briefing_action(names: &TrigEditNames, entry: &MissionBriefingActionEntry) -> String {
+    match &entry.action {
+        MissionBriefingAction::Unknown { .. } | MissionBriefingAction::NoAction => {
+            format!("Raw Action({})", hex(reinterpret_as_bytes(&entry.to_raw())))
+        }
+        MissionBriefingAction::Wait { time } => format!("Wait({time})"),
+        MissionBriefingAction::PlayWav { wave, wave_time } => {
+            format!("Play WAV({}, {wave_time})", names.string(*wave))
+        }
+        MissionBriefingAction::DisplayTextMessage { text } => {
+            format!("Text Message({})", names.string(*text))
+        }
+        MissionBriefingAction::SetMissionObjectives { text } => {
+            format!("Mission Objectives({})", names.string(*text))
+        }
+        MissionBriefingAction::ShowPortrait { unit_type, slot } => {
+            format!("Show Portrait({}, {})", unit(unit_type), slot + 1)
+        }
+        MissionBriefingAction::HidePortrait { slot } => format!("Hide Portrait({})", slot + 1),
+        MissionBriefingAction::DisplaySpeakingPortrait { slot, time } => {
+            format!("Display Speaking Portrait({}, {time})", slot + 1)
+        }
+        MissionBriefingAction::DisplayTransmission {
+            text,
+            slot,
+            time,
+            modifier: m,
+            wave,
+            wave_time,
+        } => format!(
+            "Transmission({}, {}, {}, {time}, {}, {wave_time})",
+            names.string(*text),
+            slot + 1,
+            modifier(m),
+            names.string(*wave)
+        ),
+        MissionBriefingAction::SkipTutorialEnabled => "Skip Tutorial Enabled()".to_owned(),
+    }
+}
+
+// Disabled conditions and actions are commented out with a semicolon.
+fn entry(disabled: bool, text: String) -> String {
+    format!("\t{}{text};\n", if disabled { ";" } else { "" })
+}
+
+fn commented_entry(disabled: bool, text: String, comment: &str) -> String {
+    format!(
+        "\t{}{text}; // {comment}\n",
+        if disabled { ";" } else { "" }
+    )
+}
+
+// Conditions that would not compile back to the same bytes, e.g. because of fields or flags the editors do
+// not write, are written raw with the readable text as a comment.
+fn checked_condition(names: &TrigEditNames, x: &ConditionEntry) -> String {
+    let text = condition(names, x);
+    let raw = x.to_raw();
+
+    if matches!(
+        x.condition,
+        Condition::Unknown { .. } | Condition::NoCondition
+    ) || compiles_to(names, &text, x.flags.disabled, |compiler, call| {
+        compiler.condition(call)
+    }) == Some(raw)
+    {
+        return entry(x.flags.disabled, text);
+    }
+
+    commented_entry(
+        x.flags.disabled,
+        format!("Raw Condition({})", hex(reinterpret_as_bytes(&raw))),
+        &text,
+    )
+}
+
+fn checked_action(names: &TrigEditNames, x: &ActionEntry) -> String {
+    let text = action(names, x);
+    let raw = x.to_raw();
+
+    if matches!(x.action, Action::Unknown { .. } | Action::NoAction)
+        || compiles_to(names, &text, x.flags.disabled, |compiler, call| {
+            compiler.action(call)
+        }) == Some(raw)
+    {
+        return entry(x.flags.disabled, text);
+    }
+
+    commented_entry(
+        x.flags.disabled,
+        format!("Raw Action({})", hex(reinterpret_as_bytes(&raw))),
+        &text,
+    )
+}
+
+// Text that needs a new string does not compile back to the original.
+fn compiles_to<T>(
+    names: &TrigEditNames,
+    text: &str,
+    disabled: bool,
+    compile: impl Fn(&mut Compiler, &Call) -> Result<T, TrigEditError>,
+) -> Option<T> {
+    let text = format!("{text};");
+    let mut scanner = Scanner::new(&text);
+    let mut compiler = Compiler::new(names);
+
+    let name = scanner.word().ok()?;
+    let ret = compile(&mut compiler, &scanner.call(disabled, name).ok()?).ok()?;
+
+    compiler.new_strings.is_empty().then_some(ret)
+}
+
+// Players are listed with how often the trigger has run for them if that is not 1.
+fn players(activated_for_players: &[u8; 27]) -> String {
+    activated_for_players
+        .iter()
+        .enumerate()
+        .filter(|(_, &x)| x != 0)
+        .map(|(i, &x)| {
+            let name = player(&parse_group(i as u32));
+            if x == 1 {
+                name
+            } else {
+                format!("{name} = {x}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn trigger(
+    activated_for_players: &[u8; 27],
+    conditions: Vec<String>,
+    actions: Vec<String>,
+    execution_flags: u32,
+    current_action: u8,
+) -> String {
+    let mut ret = format!(
+        "Trigger({}){{\nConditions:\n",
+        players(activated_for_players)
+    );
+    for x in conditions {
+        ret.push_str(&x);
+    }
+
+    ret.push_str("\nActions:\n");
+    for x in actions {
+        ret.push_str(&x);
+    }
+
+    if execution_flags != 0 {
+        ret.push_str(&format!("\nFlags:\n\t{execution_flags:032b};\n"));
+    }
+
+    if current_action != 0 {
+        ret.push_str(&format!("\nCurrent Action:\n\t{current_action};\n"));
+    }
+
+    ret.push_str("}\n\n//-----------------------------------------------------------------//\n\n");
+    ret
+}
+
+impl Trigger {
+    #[instrument(level = "trace", skip_all)]
+    pub fn to_trigedit(&self, names: &TrigEditNames) -> String {
+        trigger(
+            &self.activated_for_players,
+            self.conditions
+                .iter()
+                .map(|x| checked_condition(names, x))
+                .collect(),
+            self.actions
+                .iter()
+                .map(|x| checked_action(names, x))
+                .collect(),
+            self.execution_flags,
+            self.index_of_current_action,
+        )
+    }
+}
+
+impl MissionBriefing {
+    #[instrument(level = "trace", skip_all)]
+    pub fn to_trigedit(&self, names: &TrigEditNames) -> String {
+        trigger(
+            &self.activated_for_players,
+            self.conditions
+                .iter()
+                .map(|x| entry(x.flags.disabled, mission_briefing_condition(x)))
+                .collect(),
+            self.actions
+                .iter()
+                .map(|x| entry(x.flags.disabled, mission_briefing_action(names, x)))
+                .collect(),
+            self.execution_flags,
+            self.index_of_current_action,
+        )
+    }
+}
+
+// All of the triggers of the map in TrigEdit syntax.
+#[instrument(skip_all)]
+pub fn triggers_to_trigedit(parsed_chk: &ParsedChk) -> String {
+    let names = TrigEditNames::new(parsed_chk);
+
+    crate::parse_triggers(parsed_chk)
+        .iter()
+        .map(|x| x.to_trigedit(&names))
+        .collect()
+}
+
+#[instrument(skip_all)]
+pub fn mission_briefing_to_trigedit(parsed_chk: &ParsedChk) -> String {
+    let names = TrigEditNames::new(parsed_chk);
+
+    crate::parse_mission_briefing(parsed_chk)
+        .iter()
+        .map(|x| x.to_trigedit(&names))
+        .collect()
+}
+
+// Where TrigEdit text could not be compiled. line and column are 1 based and count characters.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct TrigEditError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for TrigEditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for TrigEditError {}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct CompiledTriggers {
+    pub triggers: Vec<ChkTrigIndividual>,
+    // Text that is not in the string table yet, numbered after its last string. Each text is only added once.
+    pub new_strings: Vec<(StringId, String)>,
+}
+
+// A name, number or quoted string with where it starts.
+#[derive(Debug, Clone)]
+struct Word {
+    text: String,
+    quoted: bool,
+    line: usize,
+    column: usize,
+}
+
+impl Word {
+    fn error(&self, message: impl Into<String>) -> TrigEditError {
+        TrigEditError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    fn describe(&self) -> String {
+        if self.quoted {
+            quote(&self.text)
+        } else {
+            format!("'{}'", self.text)
+        }
+    }
+}
+
+struct Call {
+    disabled: bool,
+    name: Word,
+    args: Vec<Word>,
+}
+
+impl Call {
+    fn args<const N: usize>(&self) -> Result<&[Word; N], TrigEditError> {
+        self.args.as_slice().try_into().map_err(|_| {
+            self.name.error(format!(
+                "{} takes {N} arguments, found {}",
+                self.name.text,
+                self.args.len()
+            ))
+        })
+    }
+}
+
+struct Scanner {
+    chars: Vec<char>,
+    position: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Scanner {
+    fn new(text: &str) -> Scanner {
+        Scanner {
+            chars: text.chars().collect(),
+            position: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn at_comment(&self) -> bool {
+        self.chars[self.position..].starts_with(&['/', '/'])
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(c)
+    }
+
+    fn error(&self, message: impl Into<String>) -> TrigEditError {
+        TrigEditError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    // Comments run from // to the end of the line.
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('/') if self.at_comment() => {
+                    while !matches!(self.peek(), None | Some('\n')) {
+                        self.bump();
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        self.peek().is_none()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+
+        if self.peek() == Some(c) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), TrigEditError> {
+        if self.eat(c) {
+            return Ok(());
+        }
+
+        Err(self.error(match self.peek() {
+            Some(x) => format!("expected '{c}', found '{x}'"),
+            None => format!("expected '{c}', found the end of the text"),
+        }))
+    }
+
+    // Unquoted words end at punctuation, line breaks and comments and may contain spaces, e.g. At least.
+    fn word(&mut self) -> Result<Word, TrigEditError> {
+        self.skip_whitespace();
+        let (line, column) = (self.line, self.column);
+
+        if self.eat('"') {
+            let mut text = String::new();
+            loop {
+                match self.bump() {
+                    Some('"') => break,
+                    Some('\\') => {
+                        text.push('\\');
+                        text.extend(self.bump());
+                    }
+                    Some('\n') | None => {
+                        return Err(TrigEditError {
+                            line,
+                            column,
+                            message: "unterminated string".to_owned(),
+                        })
+                    }
+                    Some(c) => text.push(c),
+                }
+            }
+
+            return Ok(Word {
+                text: unescape_trigedit_string(&text),
+                quoted: true,
+                line,
+                column,
+            });
+        }
+
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            if "(),;{}:=\"\n".contains(c) || (c == '/' && self.at_comment()) {
+                break;
+            }
+            text.push(c);
+            self.bump();
+        }
+
+        let text = text.trim_end().to_owned();
+        if text.is_empty() {
+            return Err(self.error(match self.peek() {
+                Some(x) => format!("expected a value, found '{x}'"),
+                None => "expected a value, found the end of the text".to_owned(),
+            }));
+        }
+
+        Ok(Word {
+            text,
+            quoted: false,
+            line,
+            column,
+        })
+    }
+
+    // The rest of a condition or action after its name, up to and including the semicolon.
+    fn call(&mut self, disabled: bool, name: Word) -> Result<Call, TrigEditError> {
+        if name.quoted {
+            return Err(name.error(format!(
+                "expected a condition or action, found {}",
+                name.describe()
+            )));
+        }
+
+        self.expect('(')?;
+        let mut args = Vec::new();
+        if !self.eat(')') {
+            loop {
+                args.push(self.word()?);
+                if self.eat(')') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+        self.expect(';')?;
+
+        Ok(Call {
+            disabled,
+            name,
+            args,
+        })
+    }
+}
+
+// The reverse of escape_trigedit_string. Unknown escapes are kept as they are.
+pub fn unescape_trigedit_string(text: &str) -> String {
+    let mut ret = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let hex_escape = rest
+            .get(..4)
+            .filter(|x| x.starts_with('<') && x.ends_with('>'))
+            .and_then(|x| u8::from_str_radix(&x[1..3], 16).ok());
+
+        if let Some(x) = hex_escape {
+            ret.push(x as char);
+            rest = &rest[4..];
+            continue;
+        }
+
+        match (c, rest.chars().nth(1)) {
+            ('\\', Some('"')) => ret.push('"'),
+            ('\\', Some('\\')) => ret.push('\\'),
+            ('\\', Some('r')) => ret.push('\r'),
+            ('\\', Some('n')) => ret.push('\n'),
+            _ => {
+                ret.push(c);
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+        }
+        rest = &rest[2..];
+    }
+
+    ret
+}
+
+// Keywords ignore case and spaces, so "Leaderboard" and "Leader Board" are the same.
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|x| !x.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// Decimal or hex with a 0x prefix. Negative numbers wrap around like in the editors.
+fn read_number(word: &Word) -> Result<i64, TrigEditError> {
+    let number = if word.quoted {
+        None
+    } else if let Some(x) = word
+        .text
+        .strip_prefix("0x")
+        .or_else(|| word.text.strip_prefix("0X"))
+    {
+        u32::from_str_radix(x, 16).ok().map(|x| x as i64)
+    } else if word.text.starts_with('-') {
+        word.text.parse::<i32>().ok().map(|x| x as u32 as i64)
+    } else {
+        word.text.parse::<u32>().ok().map(|x| x as i64)
+    };
+
+    number.ok_or_else(|| word.error(format!("expected a number, found {}", word.describe())))
+}
+
+fn read_bounded(word: &Word, max: u32) -> Result<u32, TrigEditError> {
+    match read_number(word)? {
+        x if x <= max as i64 => Ok(x as u32),
+        x => Err(word.error(format!("{x} is larger than {max}"))),
+    }
+}
+
+// The keyword the exporter writes for one of the values, or the number of any value.
+fn read_keyword<T>(
+    word: &Word,
+    values: &[u32],
+    max: u32,
+    parse: impl Fn(u32) -> T,
+    render: impl Fn(&T) -> String,
+    what: &str,
+) -> Result<T, TrigEditError> {
+    if !word.quoted {
+        let text = normalize(&word.text);
+        for &x in values {
+            let value = parse(x);
+            if normalize(&render(&value)) == text {
+                return Ok(value);
+            }
+        }
+
+        if let Ok(x) = read_bounded(word, max) {
+            return Ok(parse(x));
+        }
+    }
+
+    Err(word.error(format!("expected {what}, found {}", word.describe())))
+}
+
+fn read_comparison(word: &Word) -> Result<NumericComparison, TrigEditError> {
+    read_keyword(
+        word,
+        &[0, 1, 10],
+        u8::MAX as u32,
+        |x| parse_numeric_comparison(x as u8),
+        comparison,
+        "At least, At most or Exactly",
+    )
+}
+
+fn read_modifier(word: &Word) -> Result<NumberModifier, TrigEditError> {
+    read_keyword(
+        word,
+        &[7, 8, 9],
+        u8::MAX as u32,
+        |x| parse_number_modifier(x as u8),
+        modifier,
+        "Set To, Add or Subtract",
+    )
+}
+
+// Conditions only have a byte for the resource and score type, actions have two.
+fn read_resource(word: &Word, max: u32) -> Result<ResourceType, TrigEditError> {
+    read_keyword(
+        word,
+        &[0, 1, 10],
+        max,
+        |x| parse_resource_type(x as u16),
+        resource,
+        "ore, gas or ore and gas",
+    )
+}
+
+fn read_score(word: &Word, max: u32) -> Result<ScoreType, TrigEditError> {
+    read_keyword(
+        word,
+        &[0, 1, 2, 3, 4, 5, 6, 7],
+        max,
+        |x| parse_score_type(x as u16),
+        score,
+        "a score type",
+    )
+}
+
+fn read_switch_state(word: &Word) -> Result<SwitchState, TrigEditError> {
+    read_keyword(
+        word,
+        &[2, 3],
+        u8::MAX as u32,
+        |x| parse_switch_state(x as u8),
+        switch_state,
+        "set or not set",
+    )
+}
+
+fn read_switch_action(word: &Word) -> Result<ActionState, TrigEditError> {
+    read_keyword(
+        word,
+        &[4, 5, 6, 11],
+        u8::MAX as u32,
+        |x| parse_action_state(x as u8),
+        switch_action,
+        "set, clear, toggle or randomize",
+    )
+}
+
+fn read_state(word: &Word) -> Result<ActionState, TrigEditError> {
+    read_keyword(
+        word,
+        &[4, 5, 6],
+        u8::MAX as u32,
+        |x| parse_action_state(x as u8),
+        state,
+        "enable, disable or toggle",
+    )
+}
+
+fn read_order(word: &Word) -> Result<Order, TrigEditError> {
+    read_keyword(
+        word,
+        &[0, 1, 2],
+        u8::MAX as u32,
+        |x| parse_order(x as u8),
+        order,
+        "move, patrol or attack",
+    )
+}
+
+fn read_alliance(word: &Word) -> Result<AllianceStatus, TrigEditError> {
+    read_keyword(
+        word,
+        &[0, 1, 2],
+        u16::MAX as u32,
+        |x| parse_alliance_status(x as u16),
+        alliance,
+        "Enemy, Ally or Allied Victory",
+    )
+}
+
+fn read_display(word: &Word) -> Result<bool, TrigEditError> {
+    match normalize(&word.text).as_str() {
+        _ if word.quoted => Err(word.error("expected Always Display or Don't Always Display")),
+        "alwaysdisplay" => Ok(true),
+        "don'talwaysdisplay" => Ok(false),
+        _ => Err(word.error(format!(
+            "expected Always Display or Don't Always Display, found {}",
+            word.describe()
+        ))),
+    }
+}
+
+fn read_player(word: &Word) -> Result<Group, TrigEditError> {
+    if !word.quoted {
+        return Ok(parse_group(read_number(word)? as u32));
+    }
+
+    GROUP_NAMES
+        .iter()
+        .position(|x| x.eq_ignore_ascii_case(&word.text))
+        .map(|x| parse_group(x as u32))
+        .ok_or_else(|| word.error(format!("unknown player {}", word.describe())))
+}
+
+fn read_unit(word: &Word) -> Result<UnitType, TrigEditError> {
+    if !word.quoted {
+        return Ok(parse_unit_type(read_bounded(word, u16::MAX as u32)? as u16));
+    }
+
+    UNIT_NAMES
+        .iter()
+        .position(|x| x.eq_ignore_ascii_case(&word.text))
+        .map(|x| parse_unit_type(x as u16))
+        .ok_or_else(|| word.error(format!("unknown unit {}", word.describe())))
+}
+
+// All is 0.
+fn read_count(word: &Word) -> Result<i64, TrigEditError> {
+    if !word.quoted && word.text.eq_ignore_ascii_case("All") {
+        return Ok(0);
+    }
+
+    Ok(read_bounded(word, u8::MAX as u32)? as i64)
+}
+
+fn read_byte(word: &Word) -> Result<i64, TrigEditError> {
+    Ok(read_bounded(word, u8::MAX as u32)? as i64)
+}
+
+fn read_ai_script(word: &Word) -> Result<AiScript, TrigEditError> {
+    if !word.quoted {
+        return Ok(AiScript(read_number(word)? as u32));
+    }
+
+    let bytes: [u8; 4] = word.text.as_bytes().try_into().map_err(|_| {
+        word.error(format!(
+            "expected a 4 character script id, found {}",
+            word.describe()
+        ))
+    })?;
+
+    Ok(AiScript(u32::from_le_bytes(bytes)))
+}
+
+// An address in the death table, the player and unit it is made of have to give back the same address.
+fn read_address(word: &Word) -> Result<(Group, UnitType, u32), TrigEditError> {
+    let address = read_number(word)? as u32;
+
+    let (player, unit) = eud_player_and_unit(address)
+        .ok_or_else(|| word.error(format!("0x{address:08X} is not 4 byte aligned")))?;
+
+    Ok((parse_group(player), parse_unit_type(unit), address))
+}
+
+// The bytes of a raw condition or action, as hex.
+fn read_raw<T: Copy>(word: &Word) -> Result<T, TrigEditError> {
+    let bytes = (0..word.text.len())
+        .step_by(2)
+        .map(|i| {
+            word.text
+                .get(i..i + 2)
+                .and_then(|x| u8::from_str_radix(x, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>();
+
+    bytes
+        .and_then(|x| reinterpret_slice3(&x).ok())
+        .ok_or_else(|| {
+            word.error(format!(
+                "expected {} bytes of hex, found {}",
+                std::mem::size_of::<T>(),
+                word.describe()
+            ))
+        })
+}
+
+struct Compiler<'a> {
+    names: &'a TrigEditNames,
+    new_strings: Vec<(StringId, String)>,
+}
+
+impl Compiler<'_> {
+    fn new(names: &TrigEditNames) -> Compiler<'_> {
+        Compiler {
+            names,
+            new_strings: Vec::new(),
+        }
+    }
+
+    fn location(&self, word: &Word) -> Result<LocationId, TrigEditError> {
+        if !word.quoted {
+            return Ok(LocationId(read_number(word)? as u32));
+        }
+
+        self.names
+            .find_location(&word.text)
+            .ok_or_else(|| word.error(format!("unknown location {}", word.describe())))
+    }
+
+    fn switch(&self, word: &Word) -> Result<i64, TrigEditError> {
+        if !word.quoted {
+            return read_number(word);
+        }
+
+        self.names
+            .find_switch(&word.text)
+            .map(|x| x as i64)
+            .ok_or_else(|| word.error(format!("unknown switch {}", word.describe())))
+    }
+
+    // Numbers are string ids, text that is not in the map becomes a new string.
+    fn string(&mut self, word: &Word) -> Result<StringId, TrigEditError> {
+        if !word.quoted {
+            return Ok(StringId(read_number(word)? as u32));
+        }
+
+        if word.text.is_empty() {
+            return Ok(StringId(0));
+        }
+
+        if let Some(x) = self.names.find_string(&word.text) {
+            return Ok(x);
+        }
+
+        if let Some((x, _)) = self.new_strings.iter().find(|(_, x)| *x == word.text) {
+            return Ok(*x);
+        }
+
+        let number_of_strings = self
+            .names
+            .strings()
+            .map(|x| x.number_of_strings())
+            .unwrap_or_default();
+        let string_id = StringId(number_of_strings + self.new_strings.len() as u32 + 1);
+        self.new_strings.push((string_id, word.text.clone()));
+
+        Ok(string_id)
+    }
+
+    fn condition(&mut self, call: &Call) -> Result<ChkTrigCondition, TrigEditError> {
+        let name = normalize(&call.name.text);
+        let condition = match name.as_str() {
+            "rawcondition" => {
+                let [bytes] = call.args()?;
+                let mut raw: ChkTrigCondition = read_raw(bytes)?;
+                raw.flags = raw.flags & !0x02 | (call.disabled as u8) << 1;
+                return Ok(raw);
+            }
+            "countdowntimer" => {
+                let [c, number] = call.args()?;
+                Condition::CountdownTimer {
+                    comparison: read_comparison(c)?,
+                    number: read_number(number)?,
+                }
+            }
+            "command" => {
+                let [p, u, c, number] = call.args()?;
+                Condition::Command {
+                    player: read_player(p)?,
+                    comparison: read_comparison(c)?,
+                    unit_type: read_unit(u)?,
+                    number: read_number(number)?,
+                }
+            }
+            "bring" => {
+                let [p, u, l, c, number] = call.args()?;
+                Condition::Bring {
+                    player: read_player(p)?,
+                    comparison: read_comparison(c)?,
+                    unit_type: read_unit(u)?,
+                    location: self.location(l)?,
+                    number: read_number(number)?,
+                }
+            }
+            "accumulate" => {
+                let [p, c, number, r] = call.args()?;
+                Condition::Accumulate {
+                    player: read_player(p)?,
+                    comparison: read_comparison(c)?,
+                    unit_type: parse_unit_type(0),
+                    number: read_number(number)?,
+                    resource_type: read_resource(r, u8::MAX as u32)?,
+                }
+            }
+            "kill" => {
+                let [p, u, c, number] = call.args()?;
+                Condition::Kill {
+                    player: read_player(p)?,
+                    comparison: read_comparison(c)?,
+                    unit_type: read_unit(u)?,
+                    number: read_number(number)?,
+                }
+            }
+            "commandthemost" => {
+                let [u] = call.args()?;
+                Condition::CommandsTheMost {
+                    unit_type: read_unit(u)?,
+                }
+            }
+            "commandthemostat" => {
+                let [u, l] = call.args()?;
+                Condition::CommandsTheMostAt {
+                    unit_type: read_unit(u)?,
+                    location: self.location(l)?,
+                }
+            }
+            "mostkills" => {
+                let [u] = call.args()?;
+                Condition::MostKills {
+                    unit_type: read_unit(u)?,
+                }
+            }
+            "highestscore" => {
+                let [s] = call.args()?;
+                Condition::HighestScore {
+                    score_type: read_score(s, u8::MAX as u32)?,
+                }
+            }
+            "mostresources" => {
+                let [r] = call.args()?;
+                Condition::MostResources {
+                    resource_type: read_resource(r, u8::MAX as u32)?,
+                }
+            }
+            "switch" => {
+                let [sw, s] = call.args()?;
+                Condition::Switch {
+                    switch: self.switch(sw)?,
+                    switch_state: read_switch_state(s)?,
+                }
+            }
+            "elapsedtime" => {
+                let [c, number] = call.args()?;
+                Condition::ElapsedTime {
+                    comparison: read_comparison(c)?,
+                    number: read_number(number)?,
+                }
+            }
+            "missionbriefing" => {
+                let [] = call.args()?;
+                Condition::DataIsAMissionBriefing
+            }
+            "opponents" => {
+                let [p, c, number] = call.args()?;
+                Condition::Opponents {
+                    player: read_player(p)?,
+                    comparison: read_comparison(c)?,
+                    number: read_number(number)?,
+                }
+            }
+            "deaths" | "maskeddeaths" => {
+                let (p, u, c, number, mask) = match name.as_str() {
+                    "deaths" => {
+                        let [p, u, c, number] = call.args()?;
+                        (p, u, c, number, None)
+                    }
+                    _ => {
+                        let [p, u, c, number, mask] = call.args()?;
+                        (p, u, c, number, Some(read_number(mask)? as u32))
+                    }
+                };
+                let player = read_player(p)?;
+                let unit_type = read_unit(u)?;
+                Condition::Deaths {
+                    eud_offset: eud_offset(&player, &unit_type),
+                    player,
+                    comparison: read_comparison(c)?,
+                    unit_type,
+                    number: read_number(number)?,
+                    mask,
+                }
+            }
+            "memory" | "maskedmemory" => {
+                let (address, c, number, mask) = match name.as_str() {
+                    "memory" => {
+                        let [address, c, number] = call.args()?;
+                        (address, c, number, None)
+                    }
+                    _ => {
+                        let [address, c, number, mask] = call.args()?;
+                        (address, c, number, Some(read_number(mask)? as u32))
+                    }
+                };
+                let (player, unit_type, eud_offset) = read_address(address)?;
+                Condition::Deaths {
+                    player,
+                    comparison: read_comparison(c)?,
+                    unit_type,
+                    number: read_number(number)?,
+                    eud_offset,
+                    mask,
+                }
+            }
+            "commandtheleast" => {
+                let [u] = call.args()?;
+                Condition::CommandsTheLeast {
+                    unit_type: read_unit(u)?,
+                }
+            }
+            "commandtheleastat" => {
+                let [u, l] = call.args()?;
+                Condition::CommandsTheLeastAt {
+                    unit_type: read_unit(u)?,
+                    location: self.location(l)?,
+                }
+            }
+            "leastkills" => {
+                let [u] = call.args()?;
+                Condition::LeastKills {
+                    unit_type: read_unit(u)?,
+                }
+            }
+            "lowestscore" => {
+                let [s] = call.args()?;
+                Condition::LowestScore {
+                    score_type: read_score(s, u8::MAX as u32)?,
+                }
+            }
+            "leastresources" => {
+                let [r] = call.args()?;
+                Condition::LeastResources {
+                    resource_type: read_resource(r, u8::MAX as u32)?,
+                }
+            }
+            "score" => {
+                let [p, s, c, number] = call.args()?;
+                Condition::Score {
+                    player: read_player(p)?,
+                    comparison: read_comparison(c)?,
+                    score_type: read_score(s, u8::MAX as u32)?,
+                    number: read_number(number)?,
+                }
+            }
+            "always" => {
+                let [] = call.args()?;
+                Condition::Always
+            }
+            "never" => {
+                let [] = call.args()?;
+                Condition::Never
+            }
+            _ => {
+                return Err(call
+                    .name
+                    .error(format!("unknown condition {}", call.name.describe())))
+            }
+        };
+
+        // The editors set the unit flag on every condition with a unit, except masked deaths.
+        let unit_type_used = matches!(
+            condition,
+            Condition::Command { .. }
+                | Condition::Bring { .. }
+                | Condition::Kill { .. }
+                | Condition::CommandsTheMost { .. }
+                | Condition::CommandsTheMostAt { .. }
+                | Condition::MostKills { .. }
+                | Condition::Deaths { mask: None, .. }
+                | Condition::CommandsTheLeast { .. }
+                | Condition::CommandsTheLeastAt { .. }
+                | Condition::LeastKills { .. }
+        );
+        let mask_flag = match condition {
+            Condition::Deaths { mask: Some(_), .. } => MaskFlag::Eud,
+            _ => MaskFlag::None,
+        };
+
+        let mut entry = ConditionEntry::new(0, condition);
+        entry.flags.disabled = call.disabled;
+        entry.flags.unit_type_used = unit_type_used;
+        entry.mask_flag = mask_flag;
+        Ok(entry.to_raw())
+    }
+
+    fn action(&mut self, call: &Call) -> Result<ChkTrigAction, TrigEditError> {
+        let mut always_display = false;
+
+        let name = normalize(&call.name.text);
+        let action = match name.as_str() {
+            "rawaction" => {
+                let [bytes] = call.args()?;
+                let mut raw: ChkTrigAction = read_raw(bytes)?;
+                raw.flags = raw.flags & !0x02 | (call.disabled as u8) << 1;
+                return Ok(raw);
+            }
+            "victory" => {
+                let [] = call.args()?;
+                Action::Victory
+            }
+            "defeat" => {
+                let [] = call.args()?;
+                Action::Defeat
+            }
+            "preservetrigger" => {
+                let [] = call.args()?;
+                Action::PreserveTrigger
+            }
+            "wait" => {
+                let [time] = call.args()?;
+                Action::Wait {
+                    time: read_number(time)?,
+                }
+            }
+            "pausegame" => {
+                let [] = call.args()?;
+                Action::PauseGame
+            }
+            "unpausegame" => {
+                let [] = call.args()?;
+                Action::UnpauseGame
+            }
+            "transmission" => {
+                let [d, text, u, l, m, time, wave, wave_time] = call.args()?;
+                always_display = read_display(d)?;
+                Action::Transmission {
+                    text: self.string(text)?,
+                    unit_type: read_unit(u)?,
+                    location: self.location(l)?,
+                    time: read_number(time)?,
+                    modifier: read_modifier(m)?,
+                    wave: self.string(wave)?,
+                    wave_time: read_number(wave_time)?,
+                }
+            }
+            "playwav" => {
+                let [wave, wave_time] = call.args()?;
+                Action::PlayWav {
+                    wave: self.string(wave)?,
+                    wave_time: read_number(wave_time)?,
+                }
+            }
+            "displaytextmessage" => {
+                let [d, text] = call.args()?;
+                always_display = read_display(d)?;
+                Action::DisplayTextMessage {
+                    text: self.string(text)?,
+                }
+            }
+            "centerview" => {
+                let [l] = call.args()?;
+                Action::CenterView {
+                    location: self.location(l)?,
+                }
+            }
+            "createunitwithproperties" => {
+                let [p, u, number, l, unit_prop] = call.args()?;
+                Action::CreateUnitWithProperties {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    number: read_byte(number)?,
+                    location: self.location(l)?,
+                    unit_prop: read_number(unit_prop)?,
+                    properties: None,
+                }
+            }
+            "setmissionobjectives" => {
+                let [text] = call.args()?;
+                Action::SetMissionObjectives {
+                    text: self.string(text)?,
+                }
+            }
+            "setswitch" => {
+                let [sw, s] = call.args()?;
+                Action::SetSwitch {
+                    switch: self.switch(sw)?,
+                    switch_action: read_switch_action(s)?,
+                }
+            }
+            "setcountdowntimer" => {
+                let [m, time] = call.args()?;
+                Action::SetCountdownTimer {
+                    time: read_number(time)?,
+                    modifier: read_modifier(m)?,
+                }
+            }
+            "runaiscript" => {
+                let [script] = call.args()?;
+                Action::RunAIScript {
+                    script: read_ai_script(script)?,
+                }
+            }
+            "runaiscriptatlocation" => {
+                let [script, l] = call.args()?;
+                Action::RunAIScriptAtLocation {
+                    script: read_ai_script(script)?,
+                    location: self.location(l)?,
+                }
+            }
+            "leaderboardcontrol" => {
+                let [text, u] = call.args()?;
+                Action::LeaderBoardControl {
+                    text: self.string(text)?,
+                    unit_type: read_unit(u)?,
+                }
+            }
+            "leaderboardcontrolatlocation" => {
+                let [text, u, l] = call.args()?;
+                Action::LeaderBoardControlAtLocation {
+                    text: self.string(text)?,
+                    unit_type: read_unit(u)?,
+                    location: self.location(l)?,
+                }
+            }
+            "leaderboardresources" => {
+                let [text, r] = call.args()?;
+                Action::LeaderBoardResources {
+                    text: self.string(text)?,
+                    resource_type: read_resource(r, u16::MAX as u32)?,
+                }
+            }
+            "leaderboardkills" => {
+                let [text, u] = call.args()?;
+                Action::LeaderBoardKills {
+                    text: self.string(text)?,
+                    unit_type: read_unit(u)?,
+                }
+            }
+            "leaderboardpoints" => {
+                let [text, s] = call.args()?;
+                Action::LeaderBoardPoints {
+                    text: self.string(text)?,
+                    score_type: read_score(s, u16::MAX as u32)?,
+                }
+            }
+            "killunit" => {
+                let [p, u] = call.args()?;
+                Action::KillAllUnits {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                }
+            }
+            "killunitatlocation" => {
+                let [p, u, number, l] = call.args()?;
+                Action::KillUnitAtLocation {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    number: read_count(number)?,
+                    location: self.location(l)?,
+                }
+            }
+            "removeunit" => {
+                let [p, u] = call.args()?;
+                Action::RemoveAllUnits {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                }
+            }
+            "removeunitatlocation" => {
+                let [p, u, number, l] = call.args()?;
+                Action::RemoveUnitAtLocation {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    number: read_count(number)?,
+                    location: self.location(l)?,
+                }
+            }
+            "setresources" => {
+                let [p, m, number, r] = call.args()?;
+                Action::SetResources {
+                    player: read_player(p)?,
+                    number: read_number(number)?,
+                    modifier: read_modifier(m)?,
+                    resource_type: read_resource(r, u16::MAX as u32)?,
+                }
+            }
+            "setscore" => {
+                let [p, m, number, s] = call.args()?;
+                Action::SetScore {
+                    player: read_player(p)?,
+                    number: read_number(number)?,
+                    modifier: read_modifier(m)?,
+                    score_type: read_score(s, u16::MAX as u32)?,
+                }
+            }
+            "minimapping" => {
+                let [l] = call.args()?;
+                Action::MinimapPing {
+                    location: self.location(l)?,
+                }
+            }
+            "talkingportrait" => {
+                let [u, time] = call.args()?;
+                Action::TalkingPortrait {
+                    unit_type: read_unit(u)?,
+                    time: read_number(time)?,
+                }
+            }
+            "muteunitspeech" => {
+                let [] = call.args()?;
+                Action::MuteUnitSpeech
+            }
+            "unmuteunitspeech" => {
+                let [] = call.args()?;
+                Action::UnmuteUnitSpeech
+            }
+            "leaderboardcomputerplayers" => {
+                let [s] = call.args()?;
+                Action::LeaderBoardComputerPlayers {
+                    action: read_state(s)?,
+                }
+            }
+            "leaderboardgoalcontrol" => {
+                let [text, u, number] = call.args()?;
+                Action::LeaderBoardGoalControl {
+                    text: self.string(text)?,
+                    number: read_number(number)?,
+                    unit_type: read_unit(u)?,
+                }
+            }
+            "leaderboardgoalcontrolatlocation" => {
+                let [text, u, number, l] = call.args()?;
+                Action::LeaderBoardGoalControlAtLocation {
+                    text: self.string(text)?,
+                    number: read_number(number)?,
+                    unit_type: read_unit(u)?,
+                    location: self.location(l)?,
+                }
+            }
+            "leaderboardgoalresources" => {
+                let [text, number, r] = call.args()?;
+                Action::LeaderBoardGoalResources {
+                    text: self.string(text)?,
+                    number: read_number(number)?,
+                    resource_type: read_resource(r, u16::MAX as u32)?,
+                }
+            }
+            "leaderboardgoalkills" => {
+                let [text, u, number] = call.args()?;
+                Action::LeaderBoardGoalKills {
+                    text: self.string(text)?,
+                    number: read_number(number)?,
+                    unit_type: read_unit(u)?,
+                }
+            }
+            "leaderboardgoalpoints" => {
+                let [text, s, number] = call.args()?;
+                Action::LeaderBoardGoalPoints {
+                    text: self.string(text)?,
+                    number: read_number(number)?,
+                    score_type: read_score(s, u16::MAX as u32)?,
+                }
+            }
+            "movelocation" => {
+                let [p, u, source, destination] = call.args()?;
+                Action::MoveLocation {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    source_location: self.location(source)?,
+                    destination_location: self.location(destination)?,
+                }
+            }
+            "moveunit" => {
+                let [p, u, number, source, destination] = call.args()?;
+                Action::MoveUnit {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    number: read_count(number)?,
+                    source_location: self.location(source)?,
+                    destination_location: self.location(destination)?,
+                }
+            }
+            "leaderboardgreed" => {
+                let [number] = call.args()?;
+                Action::LeaderboardGreed {
+                    number: read_number(number)?,
+                }
+            }
+            "setnextscenario" => {
+                let [text] = call.args()?;
+                Action::SetNextScenario {
+                    text: self.string(text)?,
+                }
+            }
+            "setdoodadstate" => {
+                let [p, u, l, s] = call.args()?;
+                Action::SetDoodadState {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    location: self.location(l)?,
+                    state: read_state(s)?,
+                }
+            }
+            "setinvincibility" => {
+                let [p, u, l, s] = call.args()?;
+                Action::SetInvincibility {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    location: self.location(l)?,
+                    state: read_state(s)?,
+                }
+            }
+            "createunit" => {
+                let [p, u, number, l] = call.args()?;
+                Action::CreateUnit {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    number: read_byte(number)?,
+                    location: self.location(l)?,
+                }
+            }
+            "setdeaths" | "setmaskeddeaths" => {
+                let (p, u, m, number, mask) = match name.as_str() {
+                    "setdeaths" => {
+                        let [p, u, m, number] = call.args()?;
+                        (p, u, m, number, None)
+                    }
+                    _ => {
+                        let [p, u, m, number, mask] = call.args()?;
+                        (p, u, m, number, Some(read_number(mask)? as u32))
+                    }
+                };
+                let player = read_player(p)?;
+                let unit_type = read_unit(u)?;
+                Action::SetDeaths {
+                    eud_offset: eud_offset(&player, &unit_type),
+                    player,
+                    unit_type,
+                    number: read_number(number)?,
+                    modifier: read_modifier(m)?,
+                    mask,
+                }
+            }
+            "setmemory" | "setmaskedmemory" => {
+                let (address, m, number, mask) = match name.as_str() {
+                    "setmemory" => {
+                        let [address, m, number] = call.args()?;
+                        (address, m, number, None)
+                    }
+                    _ => {
+                        let [address, m, number, mask] = call.args()?;
+                        (address, m, number, Some(read_number(mask)? as u32))
+                    }
+                };
+                let (player, unit_type, eud_offset) = read_address(address)?;
+                Action::SetDeaths {
+                    player,
+                    unit_type,
+                    number: read_number(number)?,
+                    modifier: read_modifier(m)?,
+                    eud_offset,
+                    mask,
+                }
+            }
+            "order" => {
+                let [p, u, source, destination, o] = call.args()?;
+                Action::Order {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    source_location: self.location(source)?,
+                    destination_location: self.location(destination)?,
+                    order: read_order(o)?,
+                }
+            }
+            "comment" => {
+                let [text] = call.args()?;
+                Action::Comment {
+                    text: self.string(text)?,
+                }
+            }
+            "giveunitstoplayer" => {
+                let [source, destination, u, number, l] = call.args()?;
+                Action::GiveUnitsToPlayer {
+                    source_player: read_player(source)?,
+                    destination_player: read_player(destination)?,
+                    unit_type: read_unit(u)?,
+                    number: read_count(number)?,
+                    location: self.location(l)?,
+                }
+            }
+            "modifyunithitpoints" => {
+                let [p, u, amount, number, l] = call.args()?;
+                Action::ModifyUnitHitPoints {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    number: read_count(number)?,
+                    amount: read_number(amount)?,
+                    location: self.location(l)?,
+                }
+            }
+            "modifyunitenergy" => {
+                let [p, u, amount, number, l] = call.args()?;
+                Action::ModifyUnitEnergy {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    number: read_count(number)?,
+                    amount: read_number(amount)?,
+                    location: self.location(l)?,
+                }
+            }
+            "modifyunitshieldpoints" => {
+                let [p, u, amount, number, l] = call.args()?;
+                Action::ModifyUnitShieldPoints {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    number: read_count(number)?,
+                    amount: read_number(amount)?,
+                    location: self.location(l)?,
+                }
+            }
+            "modifyunitresourceamount" => {
+                let [p, amount, number, l] = call.args()?;
+                Action::ModifyUnitResource {
+                    player: read_player(p)?,
+                    unit_type: parse_unit_type(0),
+                    number: read_count(number)?,
+                    amount: read_number(amount)?,
+                    location: self.location(l)?,
+                }
+            }
+            "modifyunithangercount" => {
+                let [p, u, amount, number, l] = call.args()?;
+                Action::ModifyUnitHangarCount {
+                    player: read_player(p)?,
+                    unit_type: read_unit(u)?,
+                    number: read_count(number)?,
+                    amount: read_number(amount)?,
+                    location: self.location(l)?,
+                }
+            }
+            "pausetimer" => {
+                let [] = call.args()?;
+                Action::PauseTimer
+            }
+            "unpausetimer" => {
+                let [] = call.args()?;
+                Action::UnpauseTimer
+            }
+            "draw" => {
+                let [] = call.args()?;
+                Action::Draw
+            }
+            "setalliancestatus" => {
+                let [p, a] = call.args()?;
+                Action::SetAllianceStatus {
+                    player: read_player(p)?,
+                    alliance_status: read_alliance(a)?,
+                }
+            }
+            "disabledebugmode" => {
+                let [] = call.args()?;
+                Action::DisableDebugMode
+            }
+            "enabledebugmode" => {
+                let [] = call.args()?;
+                Action::EnableDebugMode
+            }
+            _ => {
+                return Err(call
+                    .name
+                    .error(format!("unknown action {}", call.name.describe())))
+            }
+        };
+
+        // Like for conditions, masked deaths and resources do not get the unit flag.
+        let unit_type_used = matches!(
+            action,
+            Action::Transmission { .. }
+                | Action::CreateUnitWithProperties { .. }
+                | Action::LeaderBoardControl { .. }
+                | Action::LeaderBoardControlAtLocation { .. }
+                | Action::LeaderBoardKills { .. }
+                | Action::KillAllUnits { .. }
+                | Action::KillUnitAtLocation { .. }
+                | Action::RemoveAllUnits { .. }
+                | Action::RemoveUnitAtLocation { .. }
+                | Action::TalkingPortrait { .. }
+                | Action::LeaderBoardGoalControl { .. }
+                | Action::LeaderBoardGoalControlAtLocation { .. }
+                | Action::LeaderBoardGoalKills { .. }
+                | Action::MoveLocation { .. }
+                | Action::MoveUnit { .. }
+                | Action::SetDoodadState { .. }
+                | Action::SetInvincibility { .. }
+                | Action::CreateUnit { .. }
+                | Action::SetDeaths { mask: None, .. }
+                | Action::Order { .. }
+                | Action::GiveUnitsToPlayer { .. }
+                | Action::ModifyUnitHitPoints { .. }
+                | Action::ModifyUnitEnergy { .. }
+                | Action::ModifyUnitShieldPoints { .. }
+                | Action::ModifyUnitHangarCount { .. }
+        );
+        let unit_properties_used = matches!(action, Action::CreateUnitWithProperties { .. });
+        let mask_flag = match action {
+            Action::SetDeaths { mask: Some(_), .. } => MaskFlag::Eud,
+            _ => MaskFlag::None,
+        };
+
+        let mut entry = ActionEntry::new(0, action);
+        entry.flags.disabled = call.disabled;
+        entry.flags.always_display = always_display;
+        entry.flags.unit_type_used = unit_type_used;
+        entry.flags.unit_properties_used = unit_properties_used;
+        entry.mask_flag = mask_flag;
+        Ok(entry.to_raw())
+    }
+
+    // Trigger(players){ Conditions: ... Actions: ... Flags: ... Current Action: ... }, every section is
+    // optional. Entries are put in the slots in order.
+    fn trigger(&mut self, scanner: &mut Scanner) -> Result<ChkTrigIndividual, TrigEditError> {
+        let keyword = scanner.word()?;
+        if keyword.quoted || normalize(&keyword.text) != "trigger" {
+            return Err(keyword.error(format!("expected Trigger, found {}", keyword.describe())));
+        }
+
+        let mut executed_for_player = [0u8; 27];
+        scanner.expect('(')?;
+        if !scanner.eat(')') {
+            loop {
+                let word = scanner.word()?;
+                let player = serialize_group(&read_player(&word)?) as usize;
+                if player >= executed_for_player.len() {
+                    return Err(word.error(format!("{} is not a player or group", word.describe())));
+                }
+
+                executed_for_player[player] = if scanner.eat('=') {
+                    read_bounded(&scanner.word()?, u8::MAX as u32)? as u8
+                } else {
+                    1
+                };
+
+                if scanner.eat(')') {
+                    break;
+                }
+                scanner.expect(',')?;
+            }
+        }
+        scanner.expect('{')?;
+
+        let mut conditions = [ChkTrigCondition::default(); 16];
+        let mut number_of_conditions = 0;
+        let mut actions = [ChkTrigAction::default(); 64];
+        let mut number_of_actions = 0;
+        let mut execution_flags = 0;
+        let mut current_action = 0;
+        let mut section = None;
+
+        while !scanner.eat('}') {
+            if scanner.at_end() {
+                return Err(scanner.error("expected '}', found the end of the text"));
+            }
+
+            let disabled = scanner.eat(';');
+            let word = scanner.word()?;
+
+            if !disabled && !word.quoted && scanner.eat(':') {
+                section = match normalize(&word.text).as_str() {
+                    "conditions" => Some(Section::Conditions),
+                    "actions" => Some(Section::Actions),
+                    "flags" => Some(Section::Flags),
+                    "currentaction" => Some(Section::CurrentAction),
+                    _ => return Err(word.error(format!("unknown section {}", word.describe()))),
+                };
+                continue;
+            }
+
+            match section {
+                Some(Section::Conditions) => {
+                    let call = scanner.call(disabled, word)?;
+                    let condition = self.condition(&call)?;
+                    *conditions
+                        .get_mut(number_of_conditions)
+                        .ok_or_else(|| call.name.error("a trigger has at most 16 conditions"))? =
+                        condition;
+                    number_of_conditions += 1;
+                }
+                Some(Section::Actions) => {
+                    let call = scanner.call(disabled, word)?;
+                    let action = self.action(&call)?;
+                    *actions
+                        .get_mut(number_of_actions)
+                        .ok_or_else(|| call.name.error("a trigger has at most 64 actions"))? =
+                        action;
+                    number_of_actions += 1;
+                }
+                Some(Section::Flags) => {
+                    execution_flags = u32::from_str_radix(&word.text, 2).map_err(|_| {
+                        word.error(format!(
+                            "expected 32 binary digits, found {}",
+                            word.describe()
+                        ))
+                    })?;
+                    scanner.expect(';')?;
+                }
+                Some(Section::CurrentAction) => {
+                    current_action = read_bounded(&word, u8::MAX as u32)? as u8;
+                    scanner.expect(';')?;
+                }
+                None => {
+                    return Err(word.error(format!(
+                        "expected Conditions: or Actions:, found {}",
+                        word.describe()
+                    )))
+                }
+            }
+        }
+
+        Ok(ChkTrigIndividual {
+            conditions,
+            actions,
+            execution_flags,
+            executed_for_player,
+            current_action,
+        })
+    }
+}
+
+enum Section {
+    Conditions,
+    Actions,
+    Flags,
+    CurrentAction,
+}
+
+// The offset the Deaths entry of a player and unit touches.
+fn eud_offset(player: &Group, unit_type: &UnitType) -> u32 {
+    death_table_address(serialize_group(player), serialize_unit_type(unit_type))
+}
+
+// Compiles TrigEdit text, as written by triggers_to_trigedit or SCMDraft, into TRIG entries. Names are looked
+// up in the map the names are from, strings that are not in it yet are returned as new strings.
+#[instrument(skip_all)]
+pub fn compile_trigedit(
+    text: &str,
+    names: &TrigEditNames,
+) -> Result<CompiledTriggers, TrigEditError> {
+    let mut scanner = Scanner::new(text);
+    let mut compiler = Compiler::new(names);
+
+    let mut triggers = Vec::new();
+    while !scanner.at_end() {
+        triggers.push(compiler.trigger(&mut scanner)?);
+    }
+
+    Ok(CompiledTriggers {
+        triggers,
+        new_strings: compiler.new_strings,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compile_trigedit, triggers_to_trigedit, TrigEditNames};
+    use crate::{
+        chk::{
+            chk_mrgn::ChkMrgnIndividual,
+            chk_trig::{ChkTrigAction, ChkTrigCondition},
+        },
+        chunk_name::ChunkName,
+        test::{make_mrgn, make_str, make_trig},
+        ChkWriter, ParsedChk, StringId,
+    };
+
+    fn make_chk() -> Vec<u8> {
+        let mut swnm = vec![0u8; 256 * 4];
+        swnm[0..4].copy_from_slice(&2u32.to_le_bytes());
+
+        // Location 1 is named, location 2 is not.
+        let mrgn = make_mrgn(&[ChkMrgnIndividual {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+            name_string_number: 1,
+            elevation_flags: 0,
+        }]);
+
+        let mut trig = make_trig(
+            &[
+                ChkTrigCondition {
+                    location: 1,
+                    qualified_number: 5,
+                    condition: 3,
+                    flags: 0x10,
+                    ..Default::default()
+                },
+                // A disabled switch condition.
+                ChkTrigCondition {
+                    numeric_comparison_or_switch_state: 2,
+                    condition: 11,
+                    flags: 0x02,
+                    ..Default::default()
+                },
+                // Deaths of unit 1000 is past the end of the death table.
+                ChkTrigCondition {
+                    group: 4,
+                    qualified_number: 7,
+                    unit_id: 1000,
+                    numeric_comparison_or_switch_state: 10,
+                    condition: 15,
+                    flags: 0x10,
+                    ..Default::default()
+                },
+            ],
+            &[
+                ChkTrigAction {
+                    string_number: 3,
+                    action: 9,
+                    flags: 0x04,
+                    ..Default::default()
+                },
+                ChkTrigAction {
+                    second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 1,
+                    action: 13,
+                    number_of_units_or_action_state_or_unit_order_or_number_modifier: 6,
+                    ..Default::default()
+                },
+                ChkTrigAction {
+                    location: 2,
+                    first_or_only_group_or_player_affected: 1,
+                    unit_type_or_score_type_or_resource_type_or_alliance_status: 37,
+                    action: 23,
+                    flags: 0x10,
+                    ..Default::default()
+                },
+                ChkTrigAction {
+                    second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: u32::from_le_bytes(*b"TMCu"),
+                    action: 15,
+                    ..Default::default()
+                },
+                ChkTrigAction {
+                    location: 0xFF,
+                    second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 3,
+                    action: 45,
+                    number_of_units_or_action_state_or_unit_order_or_number_modifier: 8,
+                    mask_flag: u16::from_le_bytes(*b"SC"),
+                    ..Default::default()
+                },
+                // Victory does not use the location, so it has to be written raw.
+                ChkTrigAction {
+                    location: 9,
+                    action: 1,
+                    ..Default::default()
+                },
+            ],
+        );
+        trig[2368..2372].copy_from_slice(&4u32.to_le_bytes());
+        trig[2372] = 1;
+        trig[2374] = 3;
+        trig[2399] = 2;
+
+        let mut writer = ChkWriter::new();
+        writer.add_chunk(
+            ChunkName::STR,
+            make_str(&[&b"Base"[..], b"Door", b"Say \"hi\"\r\n\x03red"]),
+        );
+        writer.add_chunk(ChunkName::SWNM, swnm);
+        writer.add_chunk(ChunkName::MRGN, mrgn);
+        writer.add_chunk(ChunkName::TRIG, trig);
+        writer.to_bytes()
+    }
+
+    #[test]
+    fn writes_triggers_like_scmdraft() {
+        let chk = make_chk();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        assert_eq!(
+            triggers_to_trigedit(&parsed_chk),
+            r#"Trigger("Player 1", "Player 3" = 3){
+Conditions:
+	Bring("Player 1", "Terran Marine", "Base", At least, 5);
+	;Switch("Door", set);
+	Memory(0x00595EF4, Exactly, 7);
+
+Actions:
+	Display Text Message(Always Display, "Say \"hi\"\r\n<03>red");
+	Set Switch("Switch2", toggle);
+	Kill Unit At Location("Player 2", "Zerg Zergling", All, "Location 2");
+	Run AI Script("TMCu");
+	Set Masked Deaths("Player 1", "Terran Marine", Add, 3, 0x000000FF);
+	Raw Action("0900000000000000000000000000000000000000000000000000010000000000"); // Victory()
+
+Flags:
+	00000000000000000000000000000100;
+
+Current Action:
+	2;
+}
+
+//-----------------------------------------------------------------//
+
+"#
+        );
+    }
+
+    #[test]
+    fn compiles_trigedit_back_to_the_same_bytes() {
+        let chk = make_chk();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+        let names = TrigEditNames::new(&parsed_chk);
+
+        let compiled = compile_trigedit(&triggers_to_trigedit(&parsed_chk), &names).unwrap();
+
+        assert_eq!(compiled.triggers, parsed_chk.trig.unwrap().triggers);
+        assert!(compiled.new_strings.is_empty());
+    }
+
+    #[test]
+    fn compiles_handwritten_trigedit() {
+        let chk = make_chk();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+        let names = TrigEditNames::new(&parsed_chk);
+
+        let compiled = compile_trigedit(
+            r#"
+// Keywords ignore case and spaces.
+TRIGGER("player 2", "All Players" = 2){
+Conditions:
+	Deaths("Current Player", "Zerg Zergling", at least, 0x10); // The current player.
+	Masked Memory(0x0058A364, Exactly, -1, 0xFF00);
+	Always();
+
+Actions:
+	LeaderBoard Computer Players(disable);
+	Display Text Message(Don't Always Display, "Base");
+	Display Text Message(Always Display, "New <03>text");
+	;Comment("New <03>text");
+	Set Resources("Foes", Subtract, 5, ore and gas);
+}
+"#,
+            &names,
+        )
+        .unwrap();
+
+        assert_eq!(compiled.triggers.len(), 1);
+        let trigger = &compiled.triggers[0];
+        assert_eq!(trigger.executed_for_player[1], 1);
+        assert_eq!(trigger.executed_for_player[17], 2);
+        assert_eq!(
+            trigger.conditions[0],
+            ChkTrigCondition {
+                group: 13,
+                qualified_number: 0x10,
+                unit_id: 37,
+                condition: 15,
+                flags: 0x10,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            trigger.conditions[1],
+            ChkTrigCondition {
+                location: 0xFF00,
+                qualified_number: u32::MAX,
+                numeric_comparison_or_switch_state: 10,
+                condition: 15,
+                mask_flag: 0x4353,
+                ..Default::default()
+            }
+        );
+        assert_eq!(trigger.conditions[2].condition, 22);
+        assert_eq!(trigger.conditions[3], ChkTrigCondition::default());
+
+        assert_eq!(
+            trigger.actions[0].number_of_units_or_action_state_or_unit_order_or_number_modifier,
+            5
+        );
+        assert_eq!({ trigger.actions[1].string_number }, 1);
+        assert_eq!(trigger.actions[1].flags, 0);
+        assert_eq!({ trigger.actions[2].string_number }, 4);
+        assert_eq!(trigger.actions[2].flags, 0x04);
+        assert_eq!({ trigger.actions[3].string_number }, 4);
+        assert_eq!(trigger.actions[3].flags, 0x02);
+        assert_eq!(
+            trigger.actions[4],
+            ChkTrigAction {
+                first_or_only_group_or_player_affected: 14,
+                second_group_affected_or_secondary_location_or_cuwp_number_or_number_or_ai_script_or_switch_number: 5,
+                unit_type_or_score_type_or_resource_type_or_alliance_status: 10,
+                action: 26,
+                number_of_units_or_action_state_or_unit_order_or_number_modifier: 9,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            compiled.new_strings,
+            [(StringId(4), "New \x03text".to_owned())]
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_line_and_column() {
+        let chk = make_chk();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+        let names = TrigEditNames::new(&parsed_chk);
+
+        let error = |text: &str| compile_trigedit(text, &names).unwrap_err().to_string();
+
+        assert_eq!(
+            error("Trigger(\"Player 1\"){\nConditions:\n\tBring(\"Player 1\", \"Terran Marine\", \"Nowhere\", At least, 1);\n}"),
+            "3:37: unknown location \"Nowhere\""
+        );
+        assert_eq!(
+            error("Trigger(\"Player 1\"){\nActions:\n\tWait(1, 2);\n}"),
+            "3:2: Wait takes 1 arguments, found 2"
+        );
+        assert_eq!(
+            error("Trigger(\"Player 1\"){\nActions:\n\tComment(\"Hi);\n}"),
+            "3:10: unterminated string"
+        );
+        assert_eq!(
+            error("Trigger(\"Player 1\"){\nActions:\n\tVictory()\n}"),
+            "4:1: expected ';', found '}'"
+        );
+    }
+}
@@ -0,0 +1,643 @@
+use crate::{
+    eud::{death_table_address, symbolize_eud_address},
+    trig::{serialize_group, serialize_unit_type},
+    trigedit::UNIT_NAMES,
+    Action, ActionState, Condition, Group, SwitchState, Trigger, UnitType,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use tracing::instrument;
+
+// Something triggers communicate through. Players and switches are 0 based.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(tag = "type")]
+pub enum StateVariable {
+    Switch { switch: usize },
+    Deaths { player: usize, unit: u16 },
+    // Deaths and SetDeaths that are EUDs, keyed by the address they touch.
+    Memory { address: u32 },
+}
+
+impl std::fmt::Display for StateVariable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateVariable::Switch { switch } => write!(f, "Switch {}", switch + 1),
+            StateVariable::Deaths { player, unit } => match UNIT_NAMES.get(*unit as usize) {
+                Some(name) => write!(f, "Player {} {name} deaths", player + 1),
+                None => write!(f, "Player {} unit {unit} deaths", player + 1),
+            },
+            StateVariable::Memory { address } => {
+                write!(f, "Memory 0x{address:08X}")?;
+                if let Some(location) = symbolize_eud_address(*address) {
+                    write!(f, " ({location})")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VariableAccess {
+    // A condition.
+    Read,
+    // An action.
+    Write,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct GraphEdge {
+    pub trigger: usize,
+    pub variable: StateVariable,
+    pub access: VariableAccess,
+    // Index of the condition or action in the trigger.
+    pub index: usize,
+}
+
+// Why a trigger can never fire. Conditions are the index in the trigger.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum DeadReason {
+    // Not activated for any of players 1 to 8, the only players that run triggers.
+    NoPlayers,
+    Never { condition: usize },
+    // Switches start cleared, so a Set condition needs a live trigger that sets, toggles or randomizes it.
+    SwitchNeverSet { condition: usize, switch: usize },
+    // The switch has to be both set and cleared.
+    ContradictorySwitch { switch: usize },
+}
+
+impl std::fmt::Display for DeadReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeadReason::NoPlayers => write!(f, "no players"),
+            DeadReason::Never { condition } => write!(f, "condition {} is Never", condition + 1),
+            DeadReason::SwitchNeverSet { condition, switch } => write!(
+                f,
+                "condition {} needs Switch {} which is never set",
+                condition + 1,
+                switch + 1
+            ),
+            DeadReason::ContradictorySwitch { switch } => {
+                write!(f, "Switch {} has to be set and cleared", switch + 1)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct TriggerNode {
+    pub trigger: usize,
+    // The 0 based players the trigger runs for, forces count as every player.
+    pub players: Vec<usize>,
+    pub dead: Option<DeadReason>,
+}
+
+// Triggers and the state variables they read and write. Disabled conditions and actions are left out.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct TriggerGraph {
+    pub triggers: Vec<TriggerNode>,
+    // Sorted.
+    pub variables: Vec<StateVariable>,
+    // In trigger order, reads before writes.
+    pub edges: Vec<GraphEdge>,
+}
+
+fn trigger_players(trigger: &Trigger) -> Vec<usize> {
+    let activated = &trigger.activated_for_players;
+
+    if activated[17..22].iter().any(|&x| x != 0) {
+        (0..8).collect()
+    } else {
+        (0..8).filter(|&x| activated[x] != 0).collect()
+    }
+}
+
+// Without knowing forces and alliances, groups other than single players stand for every player they could
+// contain.
+fn group_players(group: &Group, players: &[usize]) -> Vec<usize> {
+    match group {
+        Group::CurrentPlayer => players.to_vec(),
+        Group::Foes
+        | Group::Allies
+        | Group::AllPlayers
+        | Group::Force1
+        | Group::Force2
+        | Group::Force3
+        | Group::Force4 => (0..8).collect(),
+        Group::NeutralPlayers => (8..12).collect(),
+        _ => match serialize_group(group) {
+            x if x < 12 => vec![x as usize],
+            _ => Vec::new(),
+        },
+    }
+}
+
+fn death_variables(
+    players: &[usize],
+    group: &Group,
+    unit_type: &UnitType,
+    mask: Option<u32>,
+) -> Vec<StateVariable> {
+    let player = serialize_group(group);
+    let unit = serialize_unit_type(unit_type);
+
+    if unit < 228 && player < 27 && mask.is_none() {
+        group_players(group, players)
+            .into_iter()
+            .map(|player| StateVariable::Deaths { player, unit })
+            .collect()
+    } else {
+        vec![StateVariable::Memory {
+            address: death_table_address(player, unit),
+        }]
+    }
+}
+
+fn condition_reads(players: &[usize], condition: &Condition) -> Vec<StateVariable> {
+    match condition {
+        Condition::Switch { switch, .. } => vec![StateVariable::Switch {
+            switch: *switch as usize,
+        }],
+        Condition::Deaths {
+            player,
+            unit_type,
+            mask,
+            ..
+        } => death_variables(players, player, unit_type, *mask),
+        _ => Vec::new(),
+    }
+}
+
+fn action_writes(players: &[usize], action: &Action) -> Vec<StateVariable> {
+    match action {
+        Action::SetSwitch { switch, .. } => vec![StateVariable::Switch {
+            switch: *switch as usize,
+        }],
+        Action::SetDeaths {
+            player,
+            unit_type,
+            mask,
+            ..
+        } => death_variables(players, player, unit_type, *mask),
+        _ => Vec::new(),
+    }
+}
+
+// Dead for reasons that do not depend on other triggers.
+fn dead_by_itself(trigger: &Trigger, players: &[usize]) -> Option<DeadReason> {
+    if players.is_empty() {
+        return Some(DeadReason::NoPlayers);
+    }
+
+    let mut states = BTreeMap::new();
+
+    for entry in trigger.conditions.iter().filter(|x| !x.flags.disabled) {
+        match &entry.condition {
+            Condition::Never => {
+                return Some(DeadReason::Never {
+                    condition: entry.index,
+                })
+            }
+            Condition::Switch {
+                switch,
+                switch_state,
+            } => {
+                if let Some(previous) = states.insert(*switch, *switch_state) {
+                    if previous != *switch_state {
+                        return Some(DeadReason::ContradictorySwitch {
+                            switch: *switch as usize,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+impl TriggerGraph {
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(triggers: &[Trigger]) -> TriggerGraph {
+        let mut nodes = Vec::with_capacity(triggers.len());
+        let mut edges = Vec::new();
+
+        for (index, trigger) in triggers.iter().enumerate() {
+            let players = trigger_players(trigger);
+
+            for entry in trigger.conditions.iter().filter(|x| !x.flags.disabled) {
+                for variable in condition_reads(&players, &entry.condition) {
+                    edges.push(GraphEdge {
+                        trigger: index,
+                        variable,
+                        access: VariableAccess::Read,
+                        index: entry.index,
+                    });
+                }
+            }
+
+            for entry in trigger.actions.iter().filter(|x| !x.flags.disabled) {
+                for variable in action_writes(&players, &entry.action) {
+                    edges.push(GraphEdge {
+                        trigger: index,
+                        variable,
+                        access: VariableAccess::Write,
+                        index: entry.index,
+                    });
+                }
+            }
+
+            nodes.push(TriggerNode {
+                trigger: index,
+                dead: dead_by_itself(trigger, &players),
+                players,
+            });
+        }
+
+        // Switches start cleared, so a trigger that needs a switch set is only live if a live trigger sets
+        // it. Start from the triggers that need no switch set and keep adding the triggers they unlock until
+        // nothing changes, so triggers that only unlock each other are dead too.
+        let needed_switches = |trigger: &Trigger| {
+            trigger
+                .conditions
+                .iter()
+                .filter(|x| !x.flags.disabled)
+                .filter_map(|x| match &x.condition {
+                    Condition::Switch {
+                        switch,
+                        switch_state: SwitchState::Set,
+                    } => Some((x.index, *switch)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut set_switches = BTreeSet::new();
+        let mut live = vec![false; nodes.len()];
+
+        loop {
+            let mut changed = false;
+
+            for ((trigger, node), live) in triggers.iter().zip(&nodes).zip(&mut live) {
+                if *live
+                    || node.dead.is_some()
+                    || !needed_switches(trigger)
+                        .iter()
+                        .all(|(_, switch)| set_switches.contains(switch))
+                {
+                    continue;
+                }
+
+                *live = true;
+                changed = true;
+
+                set_switches.extend(
+                    trigger
+                        .actions
+                        .iter()
+                        .filter(|x| !x.flags.disabled)
+                        .filter_map(|x| match &x.action {
+                            Action::SetSwitch {
+                                switch,
+                                switch_action,
+                            } if *switch_action != ActionState::DisabledOrClear => Some(*switch),
+                            _ => None,
+                        }),
+                );
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        for ((trigger, node), live) in triggers.iter().zip(&mut nodes).zip(&live) {
+            if *live || node.dead.is_some() {
+                continue;
+            }
+
+            node.dead = needed_switches(trigger)
+                .into_iter()
+                .find(|(_, switch)| !set_switches.contains(switch))
+                .map(|(condition, switch)| DeadReason::SwitchNeverSet {
+                    condition,
+                    switch: switch as usize,
+                });
+        }
+
+        TriggerGraph {
+            triggers: nodes,
+            variables: edges
+                .iter()
+                .map(|x| x.variable)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+            edges,
+        }
+    }
+
+    pub fn dead_triggers(&self) -> impl Iterator<Item = &TriggerNode> {
+        self.triggers.iter().filter(|x| x.dead.is_some())
+    }
+
+    pub fn readers(&self, variable: &StateVariable) -> BTreeSet<usize> {
+        self.accessors(variable, VariableAccess::Read)
+    }
+
+    pub fn writers(&self, variable: &StateVariable) -> BTreeSet<usize> {
+        self.accessors(variable, VariableAccess::Write)
+    }
+
+    fn accessors(&self, variable: &StateVariable, access: VariableAccess) -> BTreeSet<usize> {
+        self.edges
+            .iter()
+            .filter(|x| x.variable == *variable && x.access == access)
+            .map(|x| x.trigger)
+            .collect()
+    }
+
+    // Triggers are boxes, dashed if they are dead, and variables are ellipses. Edges point from writers to
+    // variables and from variables to readers.
+    #[instrument(level = "trace", skip_all)]
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph triggers {\n");
+
+        for node in &self.triggers {
+            let mut label = format!("Trigger #{}", node.trigger);
+            if !node.players.is_empty() {
+                let players = node
+                    .players
+                    .iter()
+                    .map(|x| format!("P{}", x + 1))
+                    .collect::<Vec<_>>();
+                write!(label, "\\n{}", players.join(", ")).unwrap();
+            }
+            if let Some(reason) = node.dead {
+                write!(label, "\\n{reason}").unwrap();
+            }
+
+            writeln!(
+                out,
+                "\tt{} [shape=box{}, label=\"{label}\"];",
+                node.trigger,
+                if node.dead.is_some() {
+                    ", style=dashed"
+                } else {
+                    ""
+                }
+            )
+            .unwrap();
+        }
+
+        for (index, variable) in self.variables.iter().enumerate() {
+            writeln!(out, "\tv{index} [shape=ellipse, label=\"{variable}\"];").unwrap();
+        }
+
+        let edges = self
+            .edges
+            .iter()
+            .map(|x| {
+                let variable = self.variables.binary_search(&x.variable).unwrap();
+                match x.access {
+                    VariableAccess::Read => format!("v{variable} -> t{}", x.trigger),
+                    VariableAccess::Write => format!("t{} -> v{variable}", x.trigger),
+                }
+            })
+            .collect::<BTreeSet<_>>();
+        for edge in edges {
+            writeln!(out, "\t{edge};").unwrap();
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DeadReason, StateVariable, TriggerGraph};
+    use crate::{compile_trigedit, ParsedChk, TrigEditNames, Trigger};
+
+    #[test]
+    fn builds_graph_and_finds_dead_triggers() {
+        let parsed_chk = ParsedChk::from_bytes(&[]);
+        let compiled = compile_trigedit(
+            r#"Trigger("Player 1"){
+Conditions:
+	Always();
+
+Actions:
+	Set Switch("Switch1", set);
+	Set Deaths("Current Player", "Terran Marine", Add, 1);
+}
+
+Trigger("Player 1"){
+Conditions:
+	Switch("Switch1", set);
+	Deaths("Player 1", "Terran Marine", At least, 1);
+
+Actions:
+	Set Switch("Switch2", set);
+}
+
+Trigger("Player 1"){
+Conditions:
+	Switch("Switch3", set);
+
+Actions:
+	Set Switch("Switch4", set);
+}
+
+Trigger("Player 1"){
+Conditions:
+	Switch("Switch4", set);
+
+Actions:
+	Victory();
+}
+
+Trigger("Player 1"){
+Conditions:
+	Switch("Switch2", set);
+	Switch("Switch2", not set);
+
+Actions:
+	Defeat();
+}
+
+Trigger(){
+Conditions:
+	Always();
+
+Actions:
+	Victory();
+}
+"#,
+            &TrigEditNames::new(&parsed_chk),
+        )
+        .unwrap();
+        let triggers: Vec<Trigger> = compiled
+            .triggers
+            .iter()
+            .map(|x| Trigger::from_raw(x, &[]))
+            .collect();
+
+        let graph = TriggerGraph::new(&triggers);
+
+        let marine_deaths = StateVariable::Deaths { player: 0, unit: 0 };
+        assert_eq!(
+            graph
+                .writers(&marine_deaths)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            [0]
+        );
+        assert_eq!(
+            graph
+                .readers(&marine_deaths)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            [1]
+        );
+        assert_eq!(
+            graph
+                .dead_triggers()
+                .map(|x| (x.trigger, x.dead.unwrap()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    2,
+                    DeadReason::SwitchNeverSet {
+                        condition: 0,
+                        switch: 2
+                    }
+                ),
+                (
+                    3,
+                    DeadReason::SwitchNeverSet {
+                        condition: 0,
+                        switch: 3
+                    }
+                ),
+                (4, DeadReason::ContradictorySwitch { switch: 1 }),
+                (5, DeadReason::NoPlayers),
+            ]
+        );
+
+        assert!(graph
+            .to_json()
+            .unwrap()
+            .starts_with(r#"{"triggers":[{"trigger":0,"players":[0],"dead":null}"#));
+    }
+
+    #[test]
+    fn triggers_that_only_unlock_each_other_are_dead() {
+        let parsed_chk = ParsedChk::from_bytes(&[]);
+        let compiled = compile_trigedit(
+            r#"Trigger("Player 1"){
+Conditions:
+	Switch("Switch1", set);
+
+Actions:
+	Set Switch("Switch2", set);
+}
+
+Trigger("Player 1"){
+Conditions:
+	Switch("Switch2", set);
+
+Actions:
+	Set Switch("Switch1", set);
+}
+"#,
+            &TrigEditNames::new(&parsed_chk),
+        )
+        .unwrap();
+        let triggers: Vec<Trigger> = compiled
+            .triggers
+            .iter()
+            .map(|x| Trigger::from_raw(x, &[]))
+            .collect();
+
+        let graph = TriggerGraph::new(&triggers);
+
+        assert_eq!(
+            graph
+                .dead_triggers()
+                .map(|x| (x.trigger, x.dead.unwrap()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    0,
+                    DeadReason::SwitchNeverSet {
+                        condition: 0,
+                        switch: 0
+                    }
+                ),
+                (
+                    1,
+                    DeadReason::SwitchNeverSet {
+                        condition: 0,
+                        switch: 1
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_dot() {
+        let parsed_chk = ParsedChk::from_bytes(&[]);
+        let compiled = compile_trigedit(
+            r#"Trigger("Player 1", "Player 2"){
+Conditions:
+	Switch("Switch1", not set);
+
+Actions:
+	Set Switch("Switch1", set);
+	Set Deaths("Current Player", "Terran Marine", Add, 1);
+}
+
+Trigger("Player 1"){
+Conditions:
+	Never();
+
+Actions:
+	Set Switch("Switch1", clear);
+}
+"#,
+            &TrigEditNames::new(&parsed_chk),
+        )
+        .unwrap();
+        let triggers: Vec<Trigger> = compiled
+            .triggers
+            .iter()
+            .map(|x| Trigger::from_raw(x, &[]))
+            .collect();
+
+        assert_eq!(
+            TriggerGraph::new(&triggers).to_dot(),
+            r#"digraph triggers {
+	t0 [shape=box, label="Trigger #0\nP1, P2"];
+	t1 [shape=box, style=dashed, label="Trigger #1\nP1\ncondition 1 is Never"];
+	v0 [shape=ellipse, label="Switch 1"];
+	v1 [shape=ellipse, label="Player 1 Terran Marine deaths"];
+	v2 [shape=ellipse, label="Player 2 Terran Marine deaths"];
+	t0 -> v0;
+	t0 -> v1;
+	t0 -> v2;
+	t1 -> v0;
+	v0 -> t0;
+}
+"#
+        );
+    }
+}
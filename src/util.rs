@@ -1,4 +1,5 @@
-use std::mem::MaybeUninit;
+use crate::{chunk_name::ChunkName, error::ChkError, riff::RiffChunk};
+use std::{borrow::Cow, mem::MaybeUninit};
 use tracing::instrument;
 
 #[instrument(level = "trace", skip_all)]
@@ -43,27 +44,29 @@ pub(crate) fn parse_slice<T: Copy>(s: &[u8]) -> T {
 // }
 
 #[instrument(level = "trace", skip_all)]
-#[cfg(feature = "full")]
-pub(crate) fn reinterpret_as_slice<T: Sized + Copy>(s: &T) -> Result<&[u8], anyhow::Error> {
-    anyhow::Ok(unsafe {
-        std::slice::from_raw_parts((s as *const T) as *const u8, std::mem::size_of::<T>())
-    })
+pub(crate) fn reinterpret_as_bytes<T: ?Sized>(s: &T) -> &[u8] {
+    unsafe { std::slice::from_raw_parts((s as *const T) as *const u8, std::mem::size_of_val(s)) }
 }
 
 #[instrument(level = "trace", skip_all)]
-pub(crate) fn reinterpret_slice2<T: Sized>(s: &[u8]) -> Result<&[T], anyhow::Error> {
-    anyhow::ensure!(
-        s.len() % std::mem::size_of::<T>() == 0,
-        "s.len(): {}, std::mem::size_of::<T>(): {}",
-        s.len(),
-        std::mem::size_of::<T>()
-    );
+pub(crate) fn reinterpret_slice2<T: Sized>(s: &[u8]) -> Option<&[T]> {
+    if !s.len().is_multiple_of(std::mem::size_of::<T>()) {
+        return None;
+    }
 
-    Ok(unsafe {
+    Some(unsafe {
         std::slice::from_raw_parts(s.as_ptr() as *const T, s.len() / std::mem::size_of::<T>())
     })
 }
 
+// PROTECTION: some map protectors make sections that are not a multiple of 2 bytes long, the odd byte at the end is zero padded.
+#[instrument(level = "trace", skip_all)]
+pub(crate) fn parse_u16_slice_zero_pad(s: &[u8]) -> Vec<u16> {
+    s.chunks(2)
+        .map(|x| u16::from_le_bytes([x[0], *x.get(1).unwrap_or(&0)]))
+        .collect()
+}
+
 #[instrument(level = "trace", skip_all)]
 pub(crate) fn reinterpret_slice3<T: Copy + Sized>(s: &[u8]) -> Result<T, anyhow::Error> {
     anyhow::ensure!(
@@ -76,8 +79,8 @@ pub(crate) fn reinterpret_slice3<T: Copy + Sized>(s: &[u8]) -> Result<T, anyhow:
     Ok(unsafe { std::ptr::read_unaligned(s.as_ptr() as *const T) })
 }
 
-trait Extract: Sized + Copy {
-    fn extract(data: &[u8]) -> anyhow::Result<(Self, &[u8])>;
+pub(crate) trait Extract: Sized + Copy {
+    fn extract(data: &[u8]) -> Option<(Self, &[u8])>;
 }
 
 trait ExtractLaxZeroPad: Sized + Copy {
@@ -103,34 +106,32 @@ impl<T: Extract> ExtractLaxZeroPad for T {
 }
 
 impl Extract for u8 {
-    fn extract(data: &[u8]) -> anyhow::Result<(Self, &[u8])> {
-        Ok((
-            Self::from_le_bytes(data[0..std::mem::size_of::<Self>()].try_into()?),
-            &data[std::mem::size_of::<Self>()..],
-        ))
+    fn extract(data: &[u8]) -> Option<(Self, &[u8])> {
+        let (bytes, rest) = data.split_first_chunk::<1>()?;
+        Some((Self::from_le_bytes(*bytes), rest))
     }
 }
 
 impl Extract for u16 {
-    fn extract(data: &[u8]) -> anyhow::Result<(Self, &[u8])> {
-        Ok((
-            Self::from_le_bytes(data[0..std::mem::size_of::<Self>()].try_into()?),
-            &data[std::mem::size_of::<Self>()..],
-        ))
+    fn extract(data: &[u8]) -> Option<(Self, &[u8])> {
+        let (bytes, rest) = data.split_first_chunk::<2>()?;
+        Some((Self::from_le_bytes(*bytes), rest))
     }
 }
 
 impl Extract for u32 {
-    fn extract(data: &[u8]) -> anyhow::Result<(Self, &[u8])> {
-        Ok((
-            Self::from_le_bytes(data[0..std::mem::size_of::<Self>()].try_into()?),
-            &data[std::mem::size_of::<Self>()..],
-        ))
+    fn extract(data: &[u8]) -> Option<(Self, &[u8])> {
+        let (bytes, rest) = data.split_first_chunk::<4>()?;
+        Some((Self::from_le_bytes(*bytes), rest))
     }
 }
 
 impl<T: Extract, const N: usize> Extract for [T; N] {
-    fn extract(data: &[u8]) -> anyhow::Result<(Self, &[u8])> {
+    fn extract(data: &[u8]) -> Option<(Self, &[u8])> {
+        if data.len() < std::mem::size_of::<Self>() {
+            return None;
+        }
+
         let mut ret: [MaybeUninit<T>; N] = unsafe { MaybeUninit::uninit().assume_init() };
 
         let mut remainder = data;
@@ -146,52 +147,60 @@ impl<T: Extract, const N: usize> Extract for [T; N] {
         #[allow(forgetting_copy_types)]
         std::mem::forget(ret);
 
-        Ok((ret2, remainder))
+        Some((ret2, remainder))
     }
 }
 
 pub(crate) struct CursorSlicer<'a> {
     s: &'a [u8],
     current_offset: usize,
+    chunk_name: ChunkName,
+    data_offset: usize,
 }
 
 impl<'a> CursorSlicer<'a> {
     #[instrument(level = "trace", skip_all)]
-    pub(crate) fn new(s: &'a [u8]) -> CursorSlicer<'a> {
+    pub(crate) fn new(chunk: &RiffChunk<'a>) -> CursorSlicer<'a> {
         CursorSlicer {
-            s,
+            s: chunk.data,
             current_offset: 0,
+            chunk_name: chunk.chunk_name.clone(),
+            data_offset: chunk.offset + 8,
         }
     }
 
-    #[instrument(level = "trace", skip_all)]
-    pub(crate) fn extract_slice<T>(&mut self, elements: usize) -> Result<&'a [T], anyhow::Error> {
-        anyhow::ensure!(self.s.len() >= self.current_offset + elements * std::mem::size_of::<T>());
-
-        let ret = reinterpret_slice2(
-            &self.s[self.current_offset..self.current_offset + elements * std::mem::size_of::<T>()],
-        )?;
-
-        self.current_offset += std::mem::size_of_val(ret);
+    fn remaining(&self) -> usize {
+        self.s.len().saturating_sub(self.current_offset)
+    }
 
-        Ok(ret)
+    fn truncated(&self, expected: usize) -> ChkError {
+        ChkError::Truncated {
+            chunk_name: self.chunk_name.clone(),
+            offset: self.data_offset + self.current_offset,
+            expected,
+            actual: self.remaining(),
+        }
     }
 
     #[instrument(level = "trace", skip_all)]
-    pub(crate) fn extract_slice_lax<T>(
-        &mut self,
-        elements: usize,
-    ) -> Result<&'a [T], anyhow::Error> {
-        anyhow::ensure!(self.s.len() >= self.current_offset);
+    pub(crate) fn extract_slice<T>(&mut self, elements: usize) -> Result<&'a [T], ChkError> {
+        let len = elements * std::mem::size_of::<T>();
 
-        let elements = std::cmp::min(
-            (self.s.len() - self.current_offset) / std::mem::size_of::<T>(),
-            elements,
-        );
+        if self.remaining() < len {
+            return Err(self.truncated(len));
+        }
+
+        // A reference to misaligned data is undefined behaviour, see extract_cow.
+        if len != 0 && !self.is_aligned::<T>() {
+            return Err(ChkError::Malformed {
+                chunk_name: self.chunk_name.clone(),
+                offset: self.data_offset + self.current_offset,
+                reason: format!("data is not aligned to {} bytes", std::mem::align_of::<T>()),
+            });
+        }
 
-        let ret = reinterpret_slice2(
-            &self.s[self.current_offset..self.current_offset + elements * std::mem::size_of::<T>()],
-        )?;
+        let ret = reinterpret_slice2(&self.s[self.current_offset..self.current_offset + len])
+            .ok_or_else(|| self.truncated(len))?;
 
         self.current_offset += std::mem::size_of_val(ret);
 
@@ -199,48 +208,86 @@ impl<'a> CursorSlicer<'a> {
     }
 
     #[instrument(level = "trace", skip_all)]
-    pub(crate) fn extract_rest_as_slice<T>(&mut self) -> Result<&'a [T], anyhow::Error> {
-        anyhow::ensure!(self.s.len() >= self.current_offset);
-        anyhow::ensure!((self.s.len() - self.current_offset) % std::mem::size_of::<T>() == 0);
+    pub(crate) fn extract_slice_lax<T>(&mut self, elements: usize) -> Result<&'a [T], ChkError> {
+        let elements = std::cmp::min(self.remaining() / std::mem::size_of::<T>(), elements);
 
-        let elements = (self.s.len() - self.current_offset) / std::mem::size_of::<T>();
+        self.extract_slice(elements)
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub(crate) fn extract_rest_as_slice<T>(&mut self) -> Result<&'a [T], ChkError> {
+        if !self.remaining().is_multiple_of(std::mem::size_of::<T>()) {
+            return Err(ChkError::Malformed {
+                chunk_name: self.chunk_name.clone(),
+                offset: self.data_offset + self.current_offset,
+                reason: format!(
+                    "{} bytes left is not a multiple of the element size {}",
+                    self.remaining(),
+                    std::mem::size_of::<T>()
+                ),
+            });
+        }
+
+        let elements = self.remaining() / std::mem::size_of::<T>();
 
         self.extract_slice(elements)
     }
 
     // If for example there is some kind of protection where one of the objects is mangled, such as [int, int, int, X] where X is 1 byte instead of 4, the lax variant will ignore the last one.
     #[instrument(level = "trace", skip_all)]
-    pub(crate) fn extract_rest_as_slice_lax<T>(&mut self) -> Result<&'a [T], anyhow::Error> {
-        anyhow::ensure!(self.s.len() >= self.current_offset);
-        //anyhow::ensure!((self.s.len() - self.current_offset) % std::mem::size_of::<T>() == 0);
-
-        let elements = (self.s.len() - self.current_offset) / std::mem::size_of::<T>();
+    pub(crate) fn extract_rest_as_slice_lax<T>(&mut self) -> Result<&'a [T], ChkError> {
+        let elements = self.remaining() / std::mem::size_of::<T>();
 
         self.extract_slice_lax(elements)
     }
 
     #[instrument(level = "trace", skip_all)]
-    pub(crate) fn extract_ref<T>(&mut self) -> Result<&'a T, anyhow::Error> {
-        anyhow::ensure!(self.s.len() >= self.current_offset + std::mem::size_of::<T>());
-
-        let ret = &reinterpret_slice2(
-            &self.s[self.current_offset..self.current_offset + std::mem::size_of::<T>()],
-        )?[0];
-        self.current_offset += std::mem::size_of::<T>();
-        Ok(ret)
+    pub(crate) fn extract_ref<T>(&mut self) -> Result<&'a T, ChkError> {
+        Ok(&self.extract_slice::<T>(1)?[0])
     }
 
+    // Sections start wherever the previous one ended, so multi byte data is only borrowed when it happens to be
+    // aligned and is copied out otherwise.
     #[instrument(level = "trace", skip_all)]
-    pub(crate) fn extract_ref_lax<T>(&mut self) -> Result<Option<&'a T>, anyhow::Error> {
-        anyhow::ensure!(self.s.len() >= self.current_offset);
+    pub(crate) fn extract_cow<T: Extract>(&mut self) -> Result<Cow<'a, T>, ChkError> {
+        if self.is_aligned::<T>() {
+            Ok(Cow::Borrowed(self.extract_ref()?))
+        } else {
+            Ok(Cow::Owned(self.extract()?))
+        }
+    }
 
-        if self.s.len() >= self.current_offset + std::mem::size_of::<T>() {
-            Ok(Some(self.extract_ref()?))
+    #[instrument(level = "trace", skip_all)]
+    pub(crate) fn extract_cow_lax<T: Extract>(&mut self) -> Result<Option<Cow<'a, T>>, ChkError> {
+        if self.remaining() >= std::mem::size_of::<T>() {
+            Ok(Some(self.extract_cow()?))
         } else {
             Ok(None)
         }
     }
 
+    #[instrument(level = "trace", skip_all)]
+    pub(crate) fn extract_rest_as_cow_slice_lax<T: Extract>(
+        &mut self,
+    ) -> Result<Cow<'a, [T]>, ChkError> {
+        if self.is_aligned::<T>() {
+            return Ok(Cow::Borrowed(self.extract_rest_as_slice_lax()?));
+        }
+
+        let elements = self.remaining() / std::mem::size_of::<T>();
+
+        (0..elements)
+            .map(|_| self.extract())
+            .collect::<Result<Vec<T>, ChkError>>()
+            .map(Cow::Owned)
+    }
+
+    fn is_aligned<T>(&self) -> bool {
+        self.s
+            .get(self.current_offset..)
+            .is_some_and(|x| x.as_ptr().align_offset(std::mem::align_of::<T>()) == 0)
+    }
+
     #[instrument(level = "trace", skip_all)]
     pub(crate) fn extract_u8_lax(&mut self) -> u8 {
         let ret = if self.s.len() > self.current_offset {
@@ -298,9 +345,10 @@ impl<'a> CursorSlicer<'a> {
     }
 
     #[instrument(level = "trace", skip_all)]
-    pub(crate) fn extract<T: Extract>(&mut self) -> anyhow::Result<T> {
-        let (ret, s) = T::extract(self.s)?;
-        self.s = s;
+    pub(crate) fn extract<T: Extract>(&mut self) -> Result<T, ChkError> {
+        let (ret, _) = T::extract(&self.s[self.current_offset..])
+            .ok_or_else(|| self.truncated(std::mem::size_of::<T>()))?;
+        self.current_offset += std::mem::size_of::<T>();
         Ok(ret)
     }
 
@@ -323,8 +371,8 @@ impl<'a> CursorSlicer<'a> {
 
     #[instrument(level = "trace", skip_all)]
     pub(crate) fn extract_lax_zero_pad<T: Extract>(&mut self) -> T {
-        let (ret, s) = T::extract_lax_zero_pad(self.s);
-        self.s = s;
+        let (ret, _) = T::extract_lax_zero_pad(&self.s[self.current_offset..]);
+        self.current_offset += std::cmp::min(std::mem::size_of::<T>(), self.remaining());
         ret
     }
 
@@ -343,7 +391,6 @@ impl<'a> CursorSlicer<'a> {
 }
 
 #[instrument(level = "trace", skip_all)]
-#[cfg(feature = "full")]
 pub(crate) fn parse_null_terminated_bytestring_unsigned(s: &[u8]) -> &[u8] {
     let mut index = 0;
     for &c in s {
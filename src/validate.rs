@@ -0,0 +1,410 @@
+use crate::{chunk_name::ChunkName, error::ChkError, ParsedChk};
+use serde::Serialize;
+use tracing::instrument;
+
+// The game that is going to load the map.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum Version {
+    // StarCraft 1.04 and above, or Brood War playing in Original mode.
+    Original,
+    // Brood War 1.16.1 playing in Expansion mode.
+    BroodWar,
+    // StarCraft: Remastered playing in Expansion mode.
+    Remastered,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum GameType {
+    Melee,
+    UseMapSettings,
+}
+
+// Something that stops StarCraft from loading the map, or that StarCraft silently drops while loading it.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub enum Violation {
+    // A section required for the version and game type is missing, has an invalid size or could not be parsed.
+    Section(ChkError),
+    // VER holds a file format version the game does not accept.
+    UnsupportedFileFormat {
+        file_format_version: u16,
+    },
+    // STRx is only read by Remastered.
+    UnsupportedSection {
+        chunk_name: ChunkName,
+    },
+    // MRGN has to hold 64 locations for Original maps and 255 otherwise.
+    LocationCount {
+        expected: usize,
+        actual: usize,
+    },
+    InvalidDimensions {
+        width: u16,
+        height: u16,
+    },
+    InvalidUnit {
+        index: usize,
+        reason: String,
+    },
+    // A string number that is larger than the number of strings in STR/STRx.
+    InvalidStringReference {
+        chunk_name: ChunkName,
+        string_number: u32,
+        number_of_strings: u32,
+    },
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::Section(err) => write!(f, "{err}"),
+            Violation::UnsupportedFileFormat {
+                file_format_version,
+            } => write!(f, "file format version {file_format_version} is not supported"),
+            Violation::UnsupportedSection { chunk_name } => {
+                write!(f, "section {chunk_name:?} is not supported")
+            }
+            Violation::LocationCount { expected, actual } => {
+                write!(f, "MRGN has {actual} locations, expected {expected}")
+            }
+            Violation::InvalidDimensions { width, height } => {
+                write!(f, "map dimensions {width}x{height} are invalid")
+            }
+            Violation::InvalidUnit { index, reason } => write!(f, "unit {index}: {reason}"),
+            Violation::InvalidStringReference {
+                chunk_name,
+                string_number,
+                number_of_strings,
+            } => write!(
+                f,
+                "section {chunk_name:?} references string {string_number} but there are only {number_of_strings} strings"
+            ),
+        }
+    }
+}
+
+// Whether a section must be present, following the "Required for ..." notes in the chk module.
+// expansion is true when the map is loaded in Expansion mode, i.e. a Hybrid or Brood War map in Brood War.
+fn is_required(
+    chunk_name: &ChunkName,
+    file_format_version: u16,
+    expansion: bool,
+    game_type: GameType,
+) -> bool {
+    let melee = game_type == GameType::Melee;
+    let brood_war = file_format_version == 205 || file_format_version == 206;
+
+    match chunk_name {
+        ChunkName::VER
+        | ChunkName::VCOD
+        | ChunkName::OWNR
+        | ChunkName::ERA
+        | ChunkName::DIM
+        | ChunkName::SIDE
+        | ChunkName::MTXM
+        | ChunkName::UNIT
+        | ChunkName::THG2
+        | ChunkName::SPRP
+        | ChunkName::FORC => true,
+        ChunkName::COLR => brood_war,
+        ChunkName::PUNI | ChunkName::UPRP | ChunkName::MRGN | ChunkName::TRIG | ChunkName::MBRF => {
+            !melee
+        }
+        ChunkName::MASK => !melee,
+        ChunkName::UPGR | ChunkName::PTEC | ChunkName::UNIS | ChunkName::UPGS | ChunkName::TECS => {
+            !melee && !expansion
+        }
+        ChunkName::PUPx | ChunkName::PTEx | ChunkName::UNIx | ChunkName::UPGx | ChunkName::TECx => {
+            !melee && expansion
+        }
+        _ => false,
+    }
+}
+
+// The number of strings declared at the start of STRx, or STR if there is no STRx.
+fn number_of_strings(parsed_chk: &ParsedChk) -> Option<u32> {
+    if let Ok(x) = &parsed_chk.strx {
+        Some(u32::from_le_bytes(
+            x.string_data.get(0..4)?.try_into().ok()?,
+        ))
+    } else if let Ok(x) = &parsed_chk.str {
+        Some(u16::from_le_bytes(x.string_data.get(0..2)?.try_into().ok()?) as u32)
+    } else {
+        None
+    }
+}
+
+impl ParsedChk<'_> {
+    // Checks the map against the rules StarCraft applies when loading it. Sections that fail to parse
+    // but are not required are not reported, the game does not read them either.
+    #[instrument(level = "trace", skip(self))]
+    pub fn validate(&self, version: Version, game_type: GameType) -> Vec<Violation> {
+        let mut ret = Vec::new();
+
+        let file_format_version = match &self.ver {
+            Ok(x) => *x.file_format_version,
+            Err(err) => return vec![Violation::Section(err.clone())],
+        };
+
+        let supported = match version {
+            Version::Original => matches!(file_format_version, 59 | 63),
+            Version::BroodWar => matches!(file_format_version, 59 | 63 | 205),
+            Version::Remastered => matches!(file_format_version, 59 | 63 | 64 | 205 | 206),
+        };
+
+        if !supported {
+            ret.push(Violation::UnsupportedFileFormat {
+                file_format_version,
+            });
+        }
+
+        let expansion = version != Version::Original && file_format_version != 59;
+
+        macro_rules! check {
+            ($field:ident, $chunk_name:ident) => {
+                if is_required(
+                    &ChunkName::$chunk_name,
+                    file_format_version,
+                    expansion,
+                    game_type,
+                ) {
+                    if let Err(err) = &self.$field {
+                        ret.push(Violation::Section(err.clone()));
+                    }
+                }
+            };
+        }
+
+        check!(vcod, VCOD);
+        check!(ownr, OWNR);
+        check!(era, ERA);
+        check!(dim, DIM);
+        check!(side, SIDE);
+        check!(mtxm, MTXM);
+        check!(puni, PUNI);
+        check!(upgr, UPGR);
+        check!(ptec, PTEC);
+        check!(unit, UNIT);
+        check!(thg2, THG2);
+        check!(mask, MASK);
+        check!(uprp, UPRP);
+        check!(mrgn, MRGN);
+        check!(trig, TRIG);
+        check!(mbrf, MBRF);
+        check!(sprp, SPRP);
+        check!(forc, FORC);
+        check!(unis, UNIS);
+        check!(upgs, UPGS);
+        check!(tecs, TECS);
+        check!(colr, COLR);
+        check!(pupx, PUPx);
+        check!(ptex, PTEx);
+        check!(unix, UNIx);
+        check!(upgx, UPGx);
+        check!(tecx, TECx);
+
+        if version == Version::Remastered {
+            if let (Err(err), Err(_)) = (&self.str, &self.strx) {
+                ret.push(Violation::Section(err.clone()));
+            }
+        } else {
+            if let Err(err) = &self.str {
+                ret.push(Violation::Section(err.clone()));
+            }
+            if self.strx.is_ok() {
+                ret.push(Violation::UnsupportedSection {
+                    chunk_name: ChunkName::STRx,
+                });
+            }
+        }
+
+        if let Ok(x) = &self.mrgn {
+            let expected = if file_format_version == 59 { 64 } else { 255 };
+
+            if x.locations.len() != expected {
+                ret.push(Violation::LocationCount {
+                    expected,
+                    actual: x.locations.len(),
+                });
+            }
+        }
+
+        if let Ok(x) = &self.dim {
+            if !(1..=256).contains(&*x.width) || !(1..=256).contains(&*x.height) {
+                ret.push(Violation::InvalidDimensions {
+                    width: *x.width,
+                    height: *x.height,
+                });
+            }
+        }
+
+        if let Ok(x) = &self.unit {
+            for (index, unit) in x.units.iter().enumerate() {
+                if unit.unit_id >= 228 {
+                    ret.push(Violation::InvalidUnit {
+                        index,
+                        reason: format!("unit id {} is out of range", { unit.unit_id }),
+                    });
+                }
+
+                if unit.owner >= 12 {
+                    ret.push(Violation::InvalidUnit {
+                        index,
+                        reason: format!("owner {} is out of range", unit.owner),
+                    });
+                }
+            }
+        }
+
+        if let Some(number_of_strings) = number_of_strings(self) {
+            let mut check_string = |chunk_name: ChunkName, string_number: u32| {
+                if string_number > number_of_strings {
+                    ret.push(Violation::InvalidStringReference {
+                        chunk_name,
+                        string_number,
+                        number_of_strings,
+                    });
+                }
+            };
+
+            if let Ok(x) = &self.sprp {
+                check_string(ChunkName::SPRP, *x.scenario_name_string_number as u32);
+                check_string(ChunkName::SPRP, *x.description_string_number as u32);
+            }
+
+            if let Ok(x) = &self.forc {
+                for string_number in x.force_name {
+                    check_string(ChunkName::FORC, string_number as u32);
+                }
+            }
+
+            if let Ok(x) = &self.mrgn {
+                for location in x.locations {
+                    check_string(ChunkName::MRGN, location.name_string_number as u32);
+                }
+            }
+
+            if let Ok(x) = &self.swnm {
+                for string_number in x.switch_name_string_number.iter() {
+                    check_string(ChunkName::SWNM, *string_number);
+                }
+            }
+
+            if let Ok(x) = &self.wav {
+                for string_number in x.wav_string_number.iter() {
+                    check_string(ChunkName::WAV, *string_number);
+                }
+            }
+
+            // Unused actions are left full of garbage by some editors, so only look at actions that do something.
+            if let Ok(x) = &self.trig {
+                for action in x.triggers.iter().flat_map(|x| x.actions.iter()) {
+                    if action.action != 0 {
+                        check_string(ChunkName::TRIG, action.string_number);
+                        check_string(ChunkName::TRIG, action.wav_string_number);
+                    }
+                }
+            }
+
+            if let Ok(x) = &self.mbrf {
+                for action in x.triggers.iter().flat_map(|x| x.actions.iter()) {
+                    if action.action != 0 {
+                        check_string(ChunkName::MBRF, action.string_number);
+                        check_string(ChunkName::MBRF, action.wav_string_number);
+                    }
+                }
+            }
+        }
+
+        ret
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GameType, Version, Violation};
+    use crate::{chunk_name::ChunkName, error::ChkError, test::make_str, ChkWriter, ParsedChk};
+
+    fn make_melee_chk(file_format_version: u16) -> ChkWriter {
+        let mut writer = ChkWriter::new();
+
+        writer.add_chunk(ChunkName::VER, file_format_version.to_le_bytes().to_vec());
+        writer.add_chunk(ChunkName::VCOD, vec![0; 1040]);
+        writer.add_chunk(ChunkName::OWNR, vec![6; 12]);
+        writer.add_chunk(ChunkName::ERA, vec![0, 0]);
+        writer.add_chunk(ChunkName::DIM, vec![64, 0, 64, 0]);
+        writer.add_chunk(ChunkName::SIDE, vec![0; 12]);
+        writer.add_chunk(ChunkName::MTXM, vec![0; 64 * 64 * 2]);
+        writer.add_chunk(ChunkName::UNIT, vec![]);
+        writer.add_chunk(ChunkName::THG2, vec![]);
+        writer.add_chunk(ChunkName::STR, make_str(&["a"]));
+        writer.add_chunk(ChunkName::SPRP, vec![1, 0, 0, 0]);
+        writer.add_chunk(ChunkName::FORC, vec![0; 20]);
+        writer.add_chunk(ChunkName::COLR, vec![0, 1, 2, 3, 4, 5, 6, 7]);
+
+        writer
+    }
+
+    #[test]
+    fn valid_melee_map_has_no_violations() {
+        let chk = make_melee_chk(205).to_bytes();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        assert_eq!(
+            parsed_chk.validate(Version::BroodWar, GameType::Melee),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn reports_version_and_game_type_specific_violations() {
+        let chk = make_melee_chk(205).to_bytes();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        assert_eq!(
+            parsed_chk.validate(Version::Original, GameType::Melee),
+            vec![Violation::UnsupportedFileFormat {
+                file_format_version: 205
+            }]
+        );
+
+        let violations = parsed_chk.validate(Version::BroodWar, GameType::UseMapSettings);
+        assert!(
+            violations.contains(&Violation::Section(ChkError::MissingSection {
+                chunk_name: ChunkName::UNIx
+            }))
+        );
+        assert!(
+            !violations.contains(&Violation::Section(ChkError::MissingSection {
+                chunk_name: ChunkName::UNIS
+            }))
+        );
+    }
+
+    #[test]
+    fn reports_cross_section_violations() {
+        let mut writer = make_melee_chk(205);
+        // Scenario name points past the single string in STR.
+        writer.add_chunk(ChunkName::SPRP, vec![2, 0, 0, 0]);
+        let mut unit = vec![0; 36];
+        unit[8] = 228;
+        writer.add_chunk(ChunkName::UNIT, unit);
+
+        let chk = writer.to_bytes();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+        assert_eq!(
+            parsed_chk.validate(Version::BroodWar, GameType::Melee),
+            vec![
+                Violation::InvalidUnit {
+                    index: 0,
+                    reason: "unit id 228 is out of range".to_owned()
+                },
+                Violation::InvalidStringReference {
+                    chunk_name: ChunkName::SPRP,
+                    string_number: 2,
+                    number_of_strings: 1
+                },
+            ]
+        );
+    }
+}
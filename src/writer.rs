@@ -0,0 +1,252 @@
+use crate::{
+    chk::{
+        chk_colr::serialize_colr, chk_crgb::serialize_crgb, chk_dd2::serialize_dd2,
+        chk_dim::serialize_dim, chk_era::serialize_era, chk_forc::serialize_forc,
+        chk_iown::serialize_iown, chk_isom::serialize_isom, chk_ive2::serialize_ive2,
+        chk_iver::serialize_iver, chk_kstr::serialize_kstr, chk_mask::serialize_mask,
+        chk_mbrf::serialize_mbrf, chk_mrgn::serialize_mrgn, chk_mtxm::serialize_mtxm,
+        chk_ostr::serialize_ostr, chk_ownr::serialize_ownr, chk_ptec::serialize_ptec,
+        chk_ptex::serialize_ptex, chk_puni::serialize_puni, chk_pupx::serialize_pupx,
+        chk_side::serialize_side, chk_sprp::serialize_sprp, chk_str::serialize_str,
+        chk_strx::serialize_strx, chk_swnm::serialize_swnm, chk_tecs::serialize_tecs,
+        chk_tecx::serialize_tecx, chk_thg2::serialize_thg2, chk_tile::serialize_tile,
+        chk_trig::serialize_trig, chk_type::serialize_type, chk_unis::serialize_unis,
+        chk_unit::serialize_unit, chk_unix::serialize_unix, chk_upgr::serialize_upgr,
+        chk_upgs::serialize_upgs, chk_upgx::serialize_upgx, chk_uprp::serialize_uprp,
+        chk_upus::serialize_upus, chk_vcod::serialize_vcod, chk_ver::serialize_ver,
+        chk_wav::serialize_wav,
+    },
+    chunk_name::{serialize_chunk_name, ChunkName},
+    ParsedChk, ParsedChkOwned,
+};
+use tracing::instrument;
+
+// Collects sections and emits them as a RIFF stream, i.e. a scenario.chk.
+// Every section is written exactly once as a single chunk with a correct header, in the order they
+// were added. Merged sections (MTXM, STR, STRx, KSTR, UNIT, THG2, TRIG, MBRF) are written back already
+// merged, so the output reads the same as the input but is not byte for byte identical to it. The
+// unparsed chunks of a ParsedChkOwned are the exception, they are written back one by one as they were.
+#[derive(Debug, Default, Clone)]
+pub struct ChkWriter {
+    chunks: Vec<(ChunkName, Vec<u8>)>,
+}
+
+impl ChkWriter {
+    pub fn new() -> ChkWriter {
+        ChkWriter::default()
+    }
+
+    // Adds a section. If a section with the same name was already added it is replaced in place.
+    pub fn add_chunk(&mut self, chunk_name: ChunkName, data: Vec<u8>) {
+        if let Some(existing) = self.chunks.iter_mut().find(|(name, _)| *name == chunk_name) {
+            existing.1 = data;
+        } else {
+            self.chunks.push((chunk_name, data));
+        }
+    }
+
+    pub fn remove_chunk(&mut self, chunk_name: &ChunkName) {
+        self.chunks.retain(|(name, _)| name != chunk_name);
+    }
+
+    pub fn chunks(&self) -> &[(ChunkName, Vec<u8>)] {
+        self.chunks.as_slice()
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut ret = Vec::with_capacity(self.chunks.iter().map(|(_, data)| data.len() + 8).sum());
+
+        for (chunk_name, data) in &self.chunks {
+            ret.extend_from_slice(&serialize_chunk_name(chunk_name));
+            ret.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            ret.extend_from_slice(data.as_slice());
+        }
+
+        ret
+    }
+}
+
+impl From<&ParsedChk<'_>> for ChkWriter {
+    #[instrument(level = "trace", skip_all)]
+    fn from(parsed_chk: &ParsedChk<'_>) -> ChkWriter {
+        ChkWriter::from(&ParsedChkOwned::from(parsed_chk))
+    }
+}
+
+impl From<&ParsedChkOwned> for ChkWriter {
+    // Sections are emitted in the order StarEdit writes them, followed by the sections introduced
+    // by later editors and then the unparsed chunks. Missing sections are left out.
+    #[instrument(level = "trace", skip_all)]
+    fn from(parsed_chk: &ParsedChkOwned) -> ChkWriter {
+        let mut writer = ChkWriter::new();
+
+        macro_rules! add {
+            ($field:ident, $chunk_name:ident, $serialize:ident) => {
+                if let Some(x) = &parsed_chk.$field {
+                    writer.add_chunk(ChunkName::$chunk_name, $serialize(x));
+                }
+            };
+        }
+
+        add!(type_, TYPE, serialize_type);
+        add!(ver, VER, serialize_ver);
+        add!(iver, IVER, serialize_iver);
+        add!(ive2, IVE2, serialize_ive2);
+        add!(vcod, VCOD, serialize_vcod);
+        add!(iown, IOWN, serialize_iown);
+        add!(ownr, OWNR, serialize_ownr);
+        add!(era, ERA, serialize_era);
+        add!(dim, DIM, serialize_dim);
+        add!(side, SIDE, serialize_side);
+        add!(mtxm, MTXM, serialize_mtxm);
+        add!(puni, PUNI, serialize_puni);
+        add!(upgr, UPGR, serialize_upgr);
+        add!(ptec, PTEC, serialize_ptec);
+        add!(unit, UNIT, serialize_unit);
+        add!(isom, ISOM, serialize_isom);
+        add!(tile, TILE, serialize_tile);
+        add!(dd2, DD2, serialize_dd2);
+        add!(thg2, THG2, serialize_thg2);
+        add!(mask, MASK, serialize_mask);
+        add!(str, STR, serialize_str);
+        add!(uprp, UPRP, serialize_uprp);
+        add!(upus, UPUS, serialize_upus);
+        add!(mrgn, MRGN, serialize_mrgn);
+        add!(trig, TRIG, serialize_trig);
+        add!(mbrf, MBRF, serialize_mbrf);
+        add!(sprp, SPRP, serialize_sprp);
+        add!(forc, FORC, serialize_forc);
+        add!(wav, WAV, serialize_wav);
+        add!(unis, UNIS, serialize_unis);
+        add!(upgs, UPGS, serialize_upgs);
+        add!(tecs, TECS, serialize_tecs);
+        add!(swnm, SWNM, serialize_swnm);
+        add!(colr, COLR, serialize_colr);
+        add!(pupx, PUPx, serialize_pupx);
+        add!(ptex, PTEx, serialize_ptex);
+        add!(unix, UNIx, serialize_unix);
+        add!(upgx, UPGx, serialize_upgx);
+        add!(tecx, TECx, serialize_tecx);
+        add!(crgb, CRGB, serialize_crgb);
+        add!(strx, STRx, serialize_strx);
+        add!(ostr, OSTR, serialize_ostr);
+        add!(kstr, KSTR, serialize_kstr);
+
+        // A section can be made of several chunks, so these are not merged with add_chunk.
+        let parsed_chunk_names: Vec<ChunkName> =
+            writer.chunks.iter().map(|(x, _)| x.clone()).collect();
+
+        for (chunk_name, data) in &parsed_chk.unparsed {
+            if !parsed_chunk_names.contains(chunk_name) {
+                writer.chunks.push((chunk_name.clone(), data.clone()));
+            }
+        }
+
+        writer
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ChkWriter;
+    use crate::{chunk_name::ChunkName, riff::parse_riff, ParsedChk};
+
+    fn make_test_chk() -> Vec<u8> {
+        let mut writer = ChkWriter::new();
+
+        writer.add_chunk(ChunkName::VER, 205u16.to_le_bytes().to_vec());
+        writer.add_chunk(ChunkName::OWNR, vec![6, 6, 6, 6, 6, 6, 6, 6, 0, 0, 0, 0]);
+        writer.add_chunk(ChunkName::ERA, 4u16.to_le_bytes().to_vec());
+        writer.add_chunk(
+            ChunkName::DIM,
+            [64u16.to_le_bytes(), 96u16.to_le_bytes()].concat(),
+        );
+        writer.add_chunk(ChunkName::MTXM, vec![0x11; 64 * 96 * 2]);
+        writer.add_chunk(ChunkName::UNIT, (0..36 * 3).map(|x| x as u8).collect());
+        writer.add_chunk(
+            ChunkName::STR,
+            [&3u16.to_le_bytes()[..], &[0; 6], b"abc\0"].concat(),
+        );
+        writer.add_chunk(ChunkName::SPRP, vec![1, 0, 0, 0]);
+        writer.add_chunk(ChunkName::UNKNOWN("ZZZZ".to_owned()), vec![1, 2, 3]);
+        // Too short, so IVE2 fails to parse.
+        writer.add_chunk(ChunkName::IVE2, vec![11]);
+
+        writer.to_bytes()
+    }
+
+    #[test]
+    fn writes_valid_riff_headers() {
+        let chk = make_test_chk();
+        let chunks = parse_riff(chk.as_slice());
+
+        assert_eq!(chunks.len(), 10);
+        assert_eq!(chunks[0].chunk_name, ChunkName::VER);
+        assert_eq!(chunks[0].size, 2);
+        assert_eq!(chunks[4].chunk_name, ChunkName::MTXM);
+        assert_eq!(chunks[4].size, 64 * 96 * 2);
+        assert_eq!(chunks[8].chunk_name, ChunkName::UNKNOWN("ZZZZ".to_owned()));
+        assert_eq!(chunks[8].data, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn add_chunk_replaces_existing_chunk() {
+        let mut writer = ChkWriter::new();
+
+        writer.add_chunk(ChunkName::VER, vec![59, 0]);
+        writer.add_chunk(ChunkName::ERA, vec![0, 0]);
+        writer.add_chunk(ChunkName::VER, vec![205, 0]);
+
+        assert_eq!(writer.chunks().len(), 2);
+        assert_eq!(writer.chunks()[0], (ChunkName::VER, vec![205, 0]));
+    }
+
+    #[test]
+    fn round_trip_synthetic_chk() {
+        let chk = make_test_chk();
+        let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+        let written = parsed_chk.to_bytes();
+        let reparsed_chk = ParsedChk::from_bytes(written.as_slice());
+
+        // Every section is made of one chunk and they are in the order the writer emits them, so nothing
+        // moves.
+        let chunks = parse_riff(chk.as_slice());
+        let written_chunks = parse_riff(written.as_slice());
+
+        assert_eq!(written_chunks.len(), chunks.len());
+        for (written_chunk, chunk) in written_chunks.iter().zip(&chunks) {
+            assert_eq!(written_chunk.chunk_name, chunk.chunk_name);
+            assert_eq!(written_chunk.data, chunk.data);
+        }
+
+        assert_eq!(*reparsed_chk.ver.as_ref().unwrap().file_format_version, 205);
+        assert_eq!(
+            reparsed_chk.ownr.as_ref().unwrap().player_owner,
+            [6, 6, 6, 6, 6, 6, 6, 6, 0, 0, 0, 0]
+        );
+        assert_eq!(*reparsed_chk.era.as_ref().unwrap().tileset, 4);
+        assert_eq!(*reparsed_chk.dim.as_ref().unwrap().width, 64);
+        assert_eq!(*reparsed_chk.dim.as_ref().unwrap().height, 96);
+        assert_eq!(
+            reparsed_chk.mtxm.as_ref().unwrap().data,
+            vec![0x1111; 64 * 96]
+        );
+        assert_eq!(reparsed_chk.unit.as_ref().unwrap().units.len(), 3);
+        assert_eq!(
+            reparsed_chk.str.as_ref().unwrap().string_data,
+            parsed_chk.str.as_ref().unwrap().string_data
+        );
+        assert_eq!(
+            *reparsed_chk
+                .sprp
+                .as_ref()
+                .unwrap()
+                .scenario_name_string_number,
+            1
+        );
+
+        // Writing is stable once the sections have been merged.
+        assert_eq!(reparsed_chk.to_bytes(), written);
+    }
+}
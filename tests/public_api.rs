@@ -0,0 +1,122 @@
+use bwmap::{
+    chk::{ChkDimOwned, ChkMrgnIndividual, ChkUnit, ChkUnitIndividual, ChkVerOwned},
+    parse_riff,
+    riff::{validate_and_group_riff_chunks, ChunkName, RiffChunk},
+    ChkWriter, ParsedChk, ParsedChkOwned,
+};
+
+fn make_unit(unit_id: u16, owner: u8, x: u16, y: u16) -> ChkUnitIndividual {
+    ChkUnitIndividual {
+        class_instance: 0,
+        x,
+        y,
+        unit_id,
+        type_of_relation_to_other_building: 0,
+        properties_that_can_be_applied: 0,
+        properties_that_can_be_changed: 0,
+        owner,
+        hit_points_percent: 100,
+        shield_points_percent: 100,
+        energy_points_percent: 100,
+        resource_amount: 0,
+        number_of_units_in_hangar: 0,
+        unit_state_flags: 0,
+        unused: 0,
+        class_instance_related_to: 0,
+    }
+}
+
+fn make_test_chk() -> Vec<u8> {
+    let mut writer = ChkWriter::from(&ParsedChkOwned {
+        ver: Some(ChkVerOwned {
+            file_format_version: 205,
+        }),
+        dim: Some(ChkDimOwned {
+            width: 64,
+            height: 64,
+        }),
+        unit: Some(ChkUnit {
+            units: vec![make_unit(0, 0, 100, 200), make_unit(214, 1, 300, 400)],
+        }),
+        ..Default::default()
+    });
+
+    writer.add_chunk(ChunkName::MRGN, vec![0; 5100]);
+
+    writer.to_bytes()
+}
+
+fn count_units_for_player(unit: &ChkUnit, player: u8) -> usize {
+    unit.units.iter().filter(|x| x.owner == player).count()
+}
+
+fn chunk_names(chunks: &[RiffChunk]) -> Vec<ChunkName> {
+    chunks.iter().map(|x| x.chunk_name.clone()).collect()
+}
+
+fn is_empty_location(location: &ChkMrgnIndividual) -> bool {
+    *location
+        == ChkMrgnIndividual {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+            name_string_number: 0,
+            elevation_flags: 0,
+        }
+}
+
+#[test]
+fn riff_chunks_can_be_named() {
+    let chk = make_test_chk();
+    let chunks = parse_riff(chk.as_slice());
+
+    assert_eq!(
+        chunk_names(chunks.as_slice()),
+        vec![
+            ChunkName::VER,
+            ChunkName::DIM,
+            ChunkName::UNIT,
+            ChunkName::MRGN
+        ]
+    );
+
+    let grouped = validate_and_group_riff_chunks(chunks.as_slice());
+    assert_eq!(grouped[&ChunkName::UNIT][0].size, 72);
+}
+
+#[test]
+fn chunk_types_can_be_named() {
+    let chk = make_test_chk();
+    let parsed_chk = ParsedChk::from_bytes(chk.as_slice());
+
+    let unit = parsed_chk.unit.as_ref().unwrap();
+    assert_eq!(count_units_for_player(unit, 0), 1);
+    assert_eq!(count_units_for_player(unit, 1), 1);
+    assert_eq!(unit.units[1], make_unit(214, 1, 300, 400));
+
+    let mrgn = parsed_chk.mrgn.as_ref().unwrap();
+    assert_eq!(mrgn.locations.len(), 255);
+    assert!(mrgn.locations.iter().all(is_empty_location));
+}
+
+#[test]
+fn owned_chk_can_be_edited_from_outside() {
+    let chk = make_test_chk();
+    let mut owned = ParsedChkOwned::from_bytes(chk.as_slice());
+
+    owned
+        .unit
+        .as_mut()
+        .unwrap()
+        .units
+        .push(make_unit(1, 2, 500, 600));
+
+    let written = owned.to_bytes();
+    let reparsed_chk = ParsedChk::from_bytes(written.as_slice());
+
+    assert_eq!(
+        count_units_for_player(reparsed_chk.unit.as_ref().unwrap(), 2),
+        1
+    );
+}